@@ -0,0 +1,47 @@
+pragma circom 2.0.0;
+
+// Prove your ISO 3166-1 numeric country is in a set you disclose, without
+// revealing which member it is. The set comes out as the four words of
+// contracts/lib/verifier/core/src/jurisdiction.rs: code c is bit c % 250 of
+// word c \ 250.
+//
+// Public signals, outputs first: words[0..4], subject, issuedAt. Bind the key
+// with bind_attribute(id, attribute, validity, 4, 5) and
+// set_jurisdiction_input(id, 0). Like multiplier.circom, nothing here checks
+// who attested the country; an issuer signature check would sit in front.
+template Residency() {
+    var CODES = 1000;
+    var PER_WORD = 250;
+
+    signal input subject;
+    signal input issuedAt;
+    // Private: the holder's country and the set disclosed in its place (0 or 1 per code)
+    signal input country;
+    signal input members[CODES];
+    signal output words[4];
+
+    // One-hot encoding of country; it must be a member
+    signal hit[CODES];
+    var hits = 0;
+    var index = 0;
+    for (var i = 0; i < CODES; i++) {
+        hit[i] <-- i == country ? 1 : 0;
+        hit[i] * (hit[i] - 1) === 0;
+        members[i] * (members[i] - 1) === 0;
+        hit[i] * (1 - members[i]) === 0;
+        hits += hit[i];
+        index += hit[i] * i;
+    }
+    hits === 1;
+    index === country;
+
+    for (var w = 0; w < 4; w++) {
+        var word = 0;
+        for (var j = 0; j < PER_WORD; j++) {
+            word += members[w * PER_WORD + j] * 2 ** j;
+        }
+        words[w] <== word;
+    }
+}
+
+component main {public [subject, issuedAt]} = Residency();
@@ -0,0 +1,33 @@
+#!/bin/bash
+# bench.sh - Ink/gas and size regression check for the deployable WASM
+#
+# Builds each program as deploy.sh does, runs every entrypoint through the
+# metered host in bench/ and fails if any case is over bench/budget.json or
+# any program is over Stylus's 24 KB compressed size limit.
+# Pass --write-budget to accept the current costs after an intended change.
+
+set -e
+
+cd "$(dirname "$0")"
+
+# Wasm file stem and cargo feature of each program; ZKVerifier is the default build
+PROGRAMS="verifier: gateway:gateway records:records delegations:delegations policies:policies assets:assets bridge:bridge"
+OUT=target/stylus
+mkdir -p "$OUT"
+
+echo "📦 Building optimized WASM..."
+for program in $PROGRAMS; do
+    name=${program%%:*}
+    feature=${program#*:}
+    cargo build --release --target wasm32-unknown-unknown --lib --no-default-features ${feature:+--features $feature}
+    cp target/wasm32-unknown-unknown/release/arbshield_verifier.wasm "$OUT/$name.wasm"
+done
+
+echo "⛽ Measuring entrypoints..."
+cargo run --release -p arbshield-verifier-bench -- \
+    --wasm-dir "$OUT" \
+    --budget bench/budget.json \
+    --report target/bench/report.json \
+    "$@"
+
+echo "✅ Within budget; report in target/bench/report.json"
@@ -0,0 +1,601 @@
+//! METERED STYLUS HOST - the contracts' release wasm, run in wasmi
+//!
+//! Supplies the `vm_hooks` imports the deployed programs link against and
+//! prices one call the way Stylus bills it: ink for wasm execution and
+//! hostio overhead, gas for the EVM work behind storage, logs, calls and
+//! precompiles. A fresh instance runs each call, as on-chain, and a call
+//! from one program to another runs the callee in its own instance, charged
+//! to the caller; a callee that reverts leaves storage as it found it.
+//!
+//! Execution is charged from wasmi fuel (about one unit per executed
+//! instruction) at a flat `INK_PER_FUEL`, standing in for Stylus's
+//! per-opcode table. Absolute numbers are estimates to compare against
+//! `cargo stylus check --estimate-gas`; differences between two builds are
+//! exact, which is what the budgets check.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::Rc,
+};
+
+use alloy_primitives::{Address, B256};
+use wasmi::{Caller, Config, Engine, Error, Extern, Linker, Memory, Module, Store, TypedFunc};
+
+use crate::{precompiles, BenchError};
+
+/// Ink per gas (Arbitrum's default ink price)
+pub const INK_PRICE: u64 = 10_000;
+/// Ink per unit of wasmi fuel
+pub const INK_PER_FUEL: u64 = 200;
+/// Base ink of every hostio
+pub const HOSTIO_INK: u64 = 8_400;
+/// Ink per 32-byte word copied between wasm memory and the host
+pub const PTR_INK: u64 = 5_040;
+/// Extra ink of hostios that reach EVM state (storage, logs, calls)
+pub const EVM_API_INK: u64 = 59_673;
+
+const COLD_SLOAD_GAS: u64 = 2_100;
+const WARM_STORAGE_READ_GAS: u64 = 100;
+const SSTORE_SET_GAS: u64 = 20_000;
+const SSTORE_RESET_GAS: u64 = 2_900;
+const COLD_ACCOUNT_ACCESS_GAS: u64 = 2_600;
+const LOG_GAS: u64 = 375;
+const LOG_TOPIC_GAS: u64 = 375;
+const LOG_DATA_GAS: u64 = 8;
+const KECCAK_GAS: u64 = 30;
+const KECCAK_WORD_GAS: u64 = 6;
+const PAGE_GAS: u64 = 1_000;
+const TX_DATA_ZERO_GAS: u64 = 4;
+const TX_DATA_NONZERO_GAS: u64 = 16;
+
+/// Fuel each program instance starts with; far above anything a block could hold
+const FUEL_LIMIT: u64 = 1 << 48;
+
+/// Caller a fresh host starts with
+pub const DEFAULT_SENDER: Address = Address::repeat_byte(0xaa);
+/// Block timestamp a fresh host starts with
+pub const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+
+/// Cost and outcome of one call
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    pub success: bool,
+    /// Return data, or revert data if the call failed
+    pub output: Vec<u8>,
+    /// Wasm execution plus hostio overhead, of every program the call ran
+    pub ink: u64,
+    /// Storage, log, call and precompile gas
+    pub evm_gas: u64,
+    /// Calldata bytes at the EIP-2028 rates; the 21000 base is left out
+    pub calldata_gas: u64,
+}
+
+impl Receipt {
+    /// Gas the call itself burns: ink at `INK_PRICE` plus EVM work
+    pub fn execution_gas(&self) -> u64 {
+        self.ink.div_ceil(INK_PRICE) + self.evm_gas
+    }
+
+    /// Execution and calldata gas
+    pub fn gas(&self) -> u64 {
+        self.execution_gas() + self.calldata_gas
+    }
+}
+
+/// Programs by address, and the linker that gives them their hostios
+struct Chain {
+    engine: Engine,
+    linker: Linker<Tx>,
+    programs: HashMap<Address, Module>,
+}
+
+impl Chain {
+    fn new(programs: &[(Address, &[u8])]) -> Result<Self, Error> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let linker = vm_hooks(&engine)?;
+        let programs = programs
+            .iter()
+            .map(|(address, wasm)| Ok((*address, Module::new(&engine, wasm)?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { engine, linker, programs })
+    }
+}
+
+/// Account state every frame of a transaction shares
+#[derive(Clone, Default)]
+struct World {
+    /// Committed storage by (account, slot), with the writes of frames still running
+    storage: HashMap<(Address, B256), B256>,
+    /// Value of each slot written in this transaction before its first write
+    original: HashMap<(Address, B256), B256>,
+    warm: HashSet<(Address, B256)>,
+    warm_accounts: HashSet<Address>,
+    /// Accounts with code: the programs and stand-ins deployed with `Host::deploy`
+    accounts: HashSet<Address>,
+}
+
+impl World {
+    fn load(&self, slot: &(Address, B256)) -> B256 {
+        self.storage.get(slot).copied().unwrap_or_default()
+    }
+
+    /// EIP-2929 access charge for a slot, warming it
+    fn access(&mut self, slot: (Address, B256)) -> u64 {
+        match self.warm.insert(slot) {
+            true => COLD_SLOAD_GAS,
+            false => 0,
+        }
+    }
+
+    /// EIP-2929 call charge for an account, warming it
+    fn access_account(&mut self, address: Address) -> u64 {
+        match self.warm_accounts.insert(address) {
+            true => COLD_ACCOUNT_ACCESS_GAS,
+            false => WARM_STORAGE_READ_GAS,
+        }
+    }
+
+    /// Write `address`'s cached slots; returns their EIP-2200 cost, without refunds
+    fn flush(&mut self, address: Address, cache: BTreeMap<B256, B256>) -> u64 {
+        let mut gas = 0;
+        for (key, value) in cache {
+            let slot = (address, key);
+            let current = self.load(&slot);
+            let original = *self.original.entry(slot).or_insert(current);
+            let cost = if value == current || original != current {
+                WARM_STORAGE_READ_GAS
+            } else if original.is_zero() {
+                SSTORE_SET_GAS
+            } else {
+                SSTORE_RESET_GAS
+            };
+            gas += self.access(slot) + cost;
+            self.storage.insert(slot, value);
+        }
+        gas
+    }
+}
+
+/// One program instance's view of the call: what its hostios read and write
+struct Tx {
+    chain: Rc<Chain>,
+    world: World,
+    /// Program running in this frame
+    address: Address,
+    sender: Address,
+    origin: Address,
+    timestamp: u64,
+    block_number: u64,
+    calldata: Vec<u8>,
+    result: Vec<u8>,
+    return_data: Vec<u8>,
+    /// `storage_cache_bytes32` writes not yet flushed
+    cache: BTreeMap<B256, B256>,
+    ink: u64,
+    evm_gas: u64,
+}
+
+impl Tx {
+    fn load(&self, key: &B256) -> B256 {
+        self.cache.get(key).copied().unwrap_or_else(|| self.world.load(&(self.address, *key)))
+    }
+
+    fn flush(&mut self) {
+        let cache = std::mem::take(&mut self.cache);
+        self.evm_gas += self.world.flush(self.address, cache);
+    }
+
+    /// Top frame of a transaction from `sender` to `address`
+    fn new(chain: Rc<Chain>, world: World, address: Address, sender: Address, calldata: Vec<u8>) -> Self {
+        Self {
+            chain,
+            world,
+            address,
+            sender,
+            origin: sender,
+            timestamp: GENESIS_TIMESTAMP,
+            block_number: 1,
+            calldata,
+            result: Vec::new(),
+            return_data: Vec::new(),
+            cache: BTreeMap::new(),
+            ink: 0,
+            evm_gas: 0,
+        }
+    }
+
+    /// Frame for a call from this one to `address`
+    fn frame(&mut self, address: Address, calldata: Vec<u8>) -> Self {
+        let world = std::mem::take(&mut self.world);
+        Self {
+            origin: self.origin,
+            timestamp: self.timestamp,
+            block_number: self.block_number,
+            ..Self::new(self.chain.clone(), world, address, self.address, calldata)
+        }
+    }
+
+    /// Run the program at `self.address`, charging its execution to the
+    /// frame; whether it succeeded, and the frame after
+    ///
+    /// Storage written by a program that reverts is rolled back, including
+    /// writes of the programs it called.
+    fn execute(self, module: &Module) -> Result<(bool, Self), Error> {
+        let chain = self.chain.clone();
+        let snapshot = self.world.storage.clone();
+        let len = self.calldata.len() as i32;
+        let mut store = Store::new(&chain.engine, self);
+        store.set_fuel(FUEL_LIMIT)?;
+        let status = chain
+            .linker
+            .instantiate_and_start(&mut store, module)
+            .and_then(|instance| instance.get_typed_func::<i32, i32>(&store, "user_entrypoint"))
+            .and_then(|entrypoint: TypedFunc<i32, i32>| entrypoint.call(&mut store, len));
+        let fuel = FUEL_LIMIT - store.get_fuel()?;
+        let mut tx = store.into_data();
+        tx.ink += fuel * INK_PER_FUEL;
+
+        // A trap is a revert as far as the chain is concerned, but never expected here
+        let success = status? == 0;
+        if !success {
+            tx.world.storage = snapshot;
+        }
+        Ok((success, tx))
+    }
+}
+
+/// The programs deployed on an otherwise empty chain
+pub struct Host {
+    chain: Rc<Chain>,
+    world: World,
+    sender: Address,
+    timestamp: u64,
+    block_number: u64,
+}
+
+impl Host {
+    /// Deploy each wasm at its address
+    pub fn new(programs: &[(Address, &[u8])]) -> Result<Self, BenchError> {
+        let chain = Chain::new(programs).map_err(wasm_error)?;
+        let accounts = chain.programs.keys().copied().collect();
+        Ok(Self {
+            chain: Rc::new(chain),
+            world: World { accounts, ..World::default() },
+            sender: DEFAULT_SENDER,
+            timestamp: GENESIS_TIMESTAMP,
+            block_number: 1,
+        })
+    }
+
+    pub fn set_sender(&mut self, sender: Address) {
+        self.sender = sender;
+    }
+
+    /// Give `address` code; calls to it still succeed with no return data
+    /// and only the call itself is charged, not the callee's execution
+    pub fn deploy(&mut self, address: Address) {
+        self.world.accounts.insert(address);
+    }
+
+    /// Move block time forward, starting a new block
+    pub fn advance(&mut self, secs: u64) {
+        self.timestamp += secs;
+        self.block_number += 1;
+    }
+
+    /// Number of the block calls currently run in
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Run the program at `address` on ABI calldata, as a transaction;
+    /// storage changes stick only if it succeeds
+    pub fn call(&mut self, address: Address, calldata: &[u8]) -> Result<Receipt, BenchError> {
+        let module = self
+            .chain
+            .programs
+            .get(&address)
+            .ok_or_else(|| BenchError::Wasm(format!("no program at {address}")))?;
+        let mut world = std::mem::take(&mut self.world);
+        world.original.clear();
+        world.warm.clear();
+        world.warm_accounts = HashSet::from([address]);
+        let tx = Tx {
+            timestamp: self.timestamp,
+            block_number: self.block_number,
+            ..Tx::new(self.chain.clone(), world, address, self.sender, calldata.to_vec())
+        };
+        let (success, tx) = tx.execute(module).map_err(wasm_error)?;
+        self.world = tx.world;
+
+        Ok(Receipt {
+            success,
+            output: tx.result,
+            ink: tx.ink,
+            evm_gas: tx.evm_gas,
+            calldata_gas: calldata_gas(calldata),
+        })
+    }
+}
+
+/// EIP-2028 calldata cost
+pub fn calldata_gas(calldata: &[u8]) -> u64 {
+    calldata
+        .iter()
+        .map(|byte| match byte {
+            0 => TX_DATA_ZERO_GAS,
+            _ => TX_DATA_NONZERO_GAS,
+        })
+        .sum()
+}
+
+fn words(len: usize) -> u64 {
+    len.div_ceil(32) as u64
+}
+
+fn wasm_error(err: Error) -> BenchError {
+    BenchError::Wasm(err.to_string())
+}
+
+// ============================================================================
+// VM HOOKS
+// ============================================================================
+
+fn memory(caller: &Caller<'_, Tx>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("program exports no memory"))
+}
+
+fn read(caller: &Caller<'_, Tx>, ptr: i32, len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0u8; len];
+    memory(caller)?
+        .read(caller, ptr as u32 as usize, &mut bytes)
+        .map_err(|err| Error::new(err.to_string()))?;
+    Ok(bytes)
+}
+
+fn write(caller: &mut Caller<'_, Tx>, ptr: i32, bytes: &[u8]) -> Result<(), Error> {
+    memory(caller)?
+        .write(caller, ptr as u32 as usize, bytes)
+        .map_err(|err| Error::new(err.to_string()))
+}
+
+/// Shared body of the call hostios: precompiles answer, programs run in
+/// a frame of their own, other accounts succeed with no return data
+///
+/// The frame's storage cache is flushed first, as the Stylus host does.
+fn call(
+    caller: &mut Caller<'_, Tx>,
+    contract: i32,
+    calldata: i32,
+    calldata_len: i32,
+    return_data_len: i32,
+) -> Result<i32, Error> {
+    let address = Address::from_slice(&read(caller, contract, 20)?);
+    let input = read(caller, calldata, calldata_len as u32 as usize)?;
+    let tx = caller.data_mut();
+    tx.flush();
+    tx.ink += HOSTIO_INK + EVM_API_INK + PTR_INK * words(input.len());
+    let (status, output) = match precompiles::run(address, &input) {
+        Some((output, gas)) => {
+            tx.evm_gas += WARM_STORAGE_READ_GAS + gas;
+            match output {
+                Ok(output) => (0, output),
+                Err(()) => (1, Vec::new()),
+            }
+        }
+        None => {
+            tx.evm_gas += tx.world.access_account(address);
+            match tx.chain.clone().programs.get(&address) {
+                Some(module) => {
+                    let (success, frame) = tx.frame(address, input).execute(module)?;
+                    tx.world = frame.world;
+                    tx.ink += frame.ink;
+                    tx.evm_gas += frame.evm_gas;
+                    (!success as i32, frame.result)
+                }
+                None => (0, Vec::new()),
+            }
+        }
+    };
+    write(caller, return_data_len, &(output.len() as u32).to_le_bytes())?;
+    caller.data_mut().return_data = output;
+    Ok(status)
+}
+
+fn vm_hooks(engine: &Engine) -> Result<Linker<Tx>, Error> {
+    let mut linker = Linker::<Tx>::new(engine);
+    linker
+        .func_wrap("vm_hooks", "read_args", |mut caller: Caller<'_, Tx>, dest: i32| {
+            let calldata = caller.data().calldata.clone();
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK * words(calldata.len());
+            write(&mut caller, dest, &calldata)
+        })?
+        .func_wrap("vm_hooks", "write_result", |mut caller: Caller<'_, Tx>, data: i32, len: i32| {
+            let result = read(&caller, data, len as u32 as usize)?;
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK * words(result.len());
+            caller.data_mut().result = result;
+            Ok(())
+        })?
+        .func_wrap("vm_hooks", "storage_load_bytes32", |mut caller: Caller<'_, Tx>, key: i32, dest: i32| {
+            let key = B256::from_slice(&read(&caller, key, 32)?);
+            let tx = caller.data_mut();
+            let gas = match tx.world.access((tx.address, key)) {
+                0 => WARM_STORAGE_READ_GAS,
+                cold => cold,
+            };
+            tx.ink += HOSTIO_INK + EVM_API_INK + 2 * PTR_INK;
+            tx.evm_gas += gas;
+            let value = tx.load(&key);
+            write(&mut caller, dest, value.as_slice())
+        })?
+        .func_wrap("vm_hooks", "storage_cache_bytes32", |mut caller: Caller<'_, Tx>, key: i32, value: i32| {
+            let key = B256::from_slice(&read(&caller, key, 32)?);
+            let value = B256::from_slice(&read(&caller, value, 32)?);
+            let tx = caller.data_mut();
+            tx.ink += HOSTIO_INK + 2 * PTR_INK;
+            tx.cache.insert(key, value);
+            Ok(())
+        })?
+        .func_wrap("vm_hooks", "storage_flush_cache", |mut caller: Caller<'_, Tx>, _clear: i32| {
+            let tx = caller.data_mut();
+            tx.ink += HOSTIO_INK + EVM_API_INK;
+            tx.flush();
+        })?
+        .func_wrap("vm_hooks", "native_keccak256", |mut caller: Caller<'_, Tx>, bytes: i32, len: i32, output: i32| {
+            use sha3::{Digest, Keccak256};
+            let bytes = read(&caller, bytes, len as u32 as usize)?;
+            let gas = KECCAK_GAS + KECCAK_WORD_GAS * words(bytes.len());
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK * (words(bytes.len()) + 1) + gas * INK_PRICE;
+            write(&mut caller, output, &Keccak256::digest(&bytes))
+        })?
+        .func_wrap("vm_hooks", "emit_log", |mut caller: Caller<'_, Tx>, _data: i32, len: i32, topics: i32| {
+            let len = len as u32 as usize;
+            let topics = topics as u32 as u64;
+            let data_len = (len as u64).saturating_sub(32 * topics);
+            let tx = caller.data_mut();
+            tx.ink += HOSTIO_INK + EVM_API_INK + PTR_INK * words(len);
+            tx.evm_gas += LOG_GAS + LOG_TOPIC_GAS * topics + LOG_DATA_GAS * data_len;
+            Ok(())
+        })?
+        .func_wrap(
+            "vm_hooks",
+            "static_call_contract",
+            |mut caller: Caller<'_, Tx>, contract: i32, calldata: i32, calldata_len: i32, _gas: i64, return_data_len: i32| {
+                call(&mut caller, contract, calldata, calldata_len, return_data_len)
+            },
+        )?
+        .func_wrap(
+            "vm_hooks",
+            "call_contract",
+            |mut caller: Caller<'_, Tx>,
+             contract: i32,
+             calldata: i32,
+             calldata_len: i32,
+             _value: i32,
+             _gas: i64,
+             return_data_len: i32| { call(&mut caller, contract, calldata, calldata_len, return_data_len) },
+        )?
+        .func_wrap(
+            "vm_hooks",
+            "delegate_call_contract",
+            |mut caller: Caller<'_, Tx>, contract: i32, calldata: i32, calldata_len: i32, _gas: i64, return_data_len: i32| {
+                call(&mut caller, contract, calldata, calldata_len, return_data_len)
+            },
+        )?
+        .func_wrap("vm_hooks", "read_return_data", |mut caller: Caller<'_, Tx>, dest: i32, offset: i32, size: i32| {
+            let data = &caller.data().return_data;
+            let available = data.get(offset as u32 as usize..).unwrap_or_default();
+            let copied = available[..(size as u32 as usize).min(available.len())].to_vec();
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK * words(copied.len());
+            write(&mut caller, dest, &copied)?;
+            Ok(copied.len() as i32)
+        })?
+        .func_wrap("vm_hooks", "return_data_size", |mut caller: Caller<'_, Tx>| {
+            caller.data_mut().ink += HOSTIO_INK;
+            caller.data().return_data.len() as i32
+        })?
+        .func_wrap("vm_hooks", "msg_sender", |mut caller: Caller<'_, Tx>, sender: i32| {
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK;
+            let address = caller.data().sender;
+            write(&mut caller, sender, address.as_slice())
+        })?
+        .func_wrap("vm_hooks", "tx_origin", |mut caller: Caller<'_, Tx>, origin: i32| {
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK;
+            let address = caller.data().origin;
+            write(&mut caller, origin, address.as_slice())
+        })?
+        .func_wrap("vm_hooks", "account_codehash", |mut caller: Caller<'_, Tx>, address: i32, dest: i32| {
+            use sha3::{Digest, Keccak256};
+            let address = Address::from_slice(&read(&caller, address, 20)?);
+            let tx = caller.data_mut();
+            tx.ink += HOSTIO_INK + EVM_API_INK + 2 * PTR_INK;
+            tx.evm_gas += COLD_ACCOUNT_ACCESS_GAS;
+            let hash = match tx.world.accounts.contains(&address) {
+                true => B256::from_slice(&Keccak256::digest(address)),
+                false => B256::ZERO,
+            };
+            write(&mut caller, dest, hash.as_slice())
+        })?
+        .func_wrap("vm_hooks", "contract_address", |mut caller: Caller<'_, Tx>, address: i32| {
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK;
+            let own = caller.data().address;
+            write(&mut caller, address, own.as_slice())
+        })?
+        .func_wrap("vm_hooks", "msg_value", |mut caller: Caller<'_, Tx>, value: i32| {
+            caller.data_mut().ink += HOSTIO_INK + PTR_INK;
+            write(&mut caller, value, &[0u8; 32])
+        })?
+        .func_wrap("vm_hooks", "msg_reentrant", |mut caller: Caller<'_, Tx>| {
+            caller.data_mut().ink += HOSTIO_INK;
+            0i32
+        })?
+        .func_wrap("vm_hooks", "block_timestamp", |mut caller: Caller<'_, Tx>| {
+            caller.data_mut().ink += HOSTIO_INK;
+            caller.data().timestamp as i64
+        })?
+        .func_wrap("vm_hooks", "block_number", |mut caller: Caller<'_, Tx>| {
+            caller.data_mut().ink += HOSTIO_INK;
+            caller.data().block_number as i64
+        })?
+        .func_wrap("vm_hooks", "chainid", |mut caller: Caller<'_, Tx>| {
+            caller.data_mut().ink += HOSTIO_INK;
+            // Arbitrum Sepolia
+            421_614i64
+        })?
+        .func_wrap("vm_hooks", "pay_for_memory_grow", |mut caller: Caller<'_, Tx>, pages: i32| {
+            let tx = caller.data_mut();
+            tx.ink += HOSTIO_INK;
+            tx.evm_gas += PAGE_GAS * (pages as u32 as u64);
+        })?
+        .func_wrap("vm_hooks", "log_txt", |_: Caller<'_, Tx>, _text: i32, _len: i32| {})?;
+    Ok(linker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn test_sstore_pricing() {
+        const ACCOUNT: Address = Address::repeat_byte(0xcc);
+        let write = |world: &mut World, slot: u8, value: u8| {
+            world.flush(ACCOUNT, BTreeMap::from([(key(slot), key(value))]))
+        };
+        let mut world = World::default();
+        world.storage.insert((ACCOUNT, key(2)), key(9));
+
+        // Fresh slot, then the same slot again in the same transaction
+        assert_eq!(write(&mut world, 1, 5), COLD_SLOAD_GAS + SSTORE_SET_GAS);
+        assert_eq!(write(&mut world, 1, 6), WARM_STORAGE_READ_GAS);
+
+        // Overwriting a committed value, and writing back what is there
+        assert_eq!(write(&mut world, 2, 8), COLD_SLOAD_GAS + SSTORE_RESET_GAS);
+        assert_eq!(write(&mut world, 2, 8), WARM_STORAGE_READ_GAS);
+
+        // Slots are per account, and unflushed and flushed writes shadow committed storage
+        assert_eq!(world.load(&(Address::ZERO, key(2))), B256::ZERO);
+        let chain = Rc::new(Chain::new(&[]).unwrap());
+        let mut caller = Tx::new(chain, world, Address::ZERO, DEFAULT_SENDER, Vec::new());
+        let mut tx = caller.frame(ACCOUNT, Vec::new());
+        assert_eq!(tx.sender, Address::ZERO);
+        assert_eq!(tx.load(&key(2)), key(8));
+        tx.cache.insert(key(2), key(7));
+        assert_eq!(tx.load(&key(2)), key(7));
+        assert_eq!(tx.load(&key(3)), B256::ZERO);
+    }
+
+    #[test]
+    fn test_calldata_gas() {
+        assert_eq!(calldata_gas(&[]), 0);
+        assert_eq!(calldata_gas(&[0, 1, 0, 0xff]), 2 * TX_DATA_ZERO_GAS + 2 * TX_DATA_NONZERO_GAS);
+    }
+}
@@ -0,0 +1,300 @@
+//! INK AND GAS BENCHMARKS - every entrypoint of every program on a metered host
+//!
+//! Deploys the release wasm of each program (the bytes `cargo stylus deploy`
+//! ships) to [`host::Host`], wired together as `deploy.sh` does, runs every
+//! entrypoint once per backend and public-input count, and compares the
+//! cost of each call with a checked-in budget.
+//!
+//! ```text
+//! arbshield-bench --wasm-dir target/stylus \
+//!                 --budget bench/budget.json --report target/bench/report.json
+//! ```
+//!
+//! Cases are keyed `entrypoint/backend/inputs`, with `xN` appended for calls
+//! covering N proofs. A case over its ink or gas budget, a case with no
+//! budget and a budget with no case all fail the run, as does a
+//! `verify_aggregate` case of N proofs costing at least N separate
+//! [`SEPARATE_VERIFICATION_CASE`] calls, and any program whose brotli
+//! compressed wasm is over [`MAX_WASM_BYTES`], which Stylus refuses to
+//! deploy.
+
+use std::{collections::BTreeMap, fmt, io, io::Write, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+pub mod host;
+mod precompiles;
+pub mod scenarios;
+
+/// Version of the report layout, bumped when fields change meaning
+pub const REPORT_SCHEMA: u32 = 2;
+/// Room `--write-budget` leaves above the measured cost
+pub const HEADROOM_PERCENT: u64 = 5;
+/// Single-proof case an aggregate of N proofs must cost less than N of
+pub const SEPARATE_VERIFICATION_CASE: &str = "verify_with_precompile/precompile/1";
+/// Largest compressed program Stylus activates (24 KiB)
+pub const MAX_WASM_BYTES: usize = 24 * 1024;
+
+#[derive(Debug)]
+pub enum BenchError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+    /// The program could not be loaded or trapped
+    Wasm(String),
+    /// A measured call reverted or rejected its proof: (case, reason)
+    Reverted(String, String),
+    Fixture(String),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Json(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Wasm(err) => write!(f, "wasm: {err}"),
+            Self::Reverted(case, reason) => write!(f, "{case}: call failed: {reason}"),
+            Self::Fixture(err) => write!(f, "fixture: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+// ============================================================================
+// MEASUREMENTS
+// ============================================================================
+
+/// Cost of one entrypoint call
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Measurement {
+    pub case: String,
+    pub entrypoint: &'static str,
+    pub backend: String,
+    pub inputs: usize,
+    pub proofs: usize,
+    pub ink: u64,
+    /// `execution_gas + calldata_gas`
+    pub gas: u64,
+    pub execution_gas: u64,
+    pub calldata_gas: u64,
+}
+
+impl Measurement {
+    pub fn new(entrypoint: &'static str, backend: &str, inputs: usize, proofs: usize) -> Self {
+        let case = match proofs {
+            0 | 1 => format!("{entrypoint}/{backend}/{inputs}"),
+            _ => format!("{entrypoint}/{backend}/{inputs}x{proofs}"),
+        };
+        Self {
+            case,
+            entrypoint,
+            backend: backend.into(),
+            inputs,
+            proofs,
+            ink: 0,
+            gas: 0,
+            execution_gas: 0,
+            calldata_gas: 0,
+        }
+    }
+
+    pub fn with(self, receipt: &host::Receipt) -> Self {
+        Self {
+            ink: receipt.ink,
+            gas: receipt.gas(),
+            execution_gas: receipt.execution_gas(),
+            calldata_gas: receipt.calldata_gas,
+            ..self
+        }
+    }
+}
+
+// ============================================================================
+// PROGRAM SIZE
+// ============================================================================
+
+/// Size of `wasm` brotli compressed as `cargo stylus` does before deploying
+pub fn compressed_size(wasm: &[u8]) -> usize {
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer.write_all(wasm).expect("writing to a Vec");
+    }
+    compressed.len()
+}
+
+// ============================================================================
+// BUDGETS
+// ============================================================================
+
+/// Most a case may cost
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limit {
+    pub ink: u64,
+    pub gas: u64,
+}
+
+/// Limits by case, as kept in `bench/budget.json`
+pub type Budget = BTreeMap<String, Limit>;
+
+/// Budget admitting `measurements` with `HEADROOM_PERCENT` to spare
+pub fn budget_for(measurements: &[Measurement]) -> Budget {
+    let headroom = |value: u64| value + (value * HEADROOM_PERCENT).div_ceil(100);
+    measurements
+        .iter()
+        .map(|m| (m.case.clone(), Limit { ink: headroom(m.ink), gas: headroom(m.gas) }))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Ok,
+    OverBudget,
+    Unbudgeted,
+    /// An aggregate no cheaper than verifying its proofs one by one, or with
+    /// no `SEPARATE_VERIFICATION_CASE` measured to compare against
+    NoCheaperThanSeparate,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Entry {
+    #[serde(flatten)]
+    pub measurement: Measurement,
+    pub budget: Option<Limit>,
+    pub verdict: Verdict,
+}
+
+/// Machine-readable result of a run, for the gas dashboard
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub schema: u32,
+    pub ink_price: u64,
+    pub ink_per_fuel: u64,
+    /// Brotli compressed size of each program's wasm, by program
+    pub wasm_bytes: BTreeMap<String, usize>,
+    /// Programs over `MAX_WASM_BYTES`
+    pub oversized: Vec<String>,
+    pub passed: bool,
+    pub entries: Vec<Entry>,
+    /// Budgeted cases the run no longer measures
+    pub stale: Vec<String>,
+}
+
+/// Compare each measurement with its limit, and each program's compressed
+/// size with `MAX_WASM_BYTES`
+pub fn check(measurements: Vec<Measurement>, budget: &Budget, wasm_bytes: BTreeMap<String, usize>) -> Report {
+    let separate_gas = measurements.iter().find(|m| m.case == SEPARATE_VERIFICATION_CASE).map(|m| m.gas);
+    let entries: Vec<Entry> = measurements
+        .into_iter()
+        .map(|measurement| {
+            let limit = budget.get(&measurement.case).copied();
+            let aggregate_gas_cap = separate_gas.map(|gas| gas.saturating_mul(measurement.proofs as u64));
+            let verdict = match limit {
+                _ if measurement.entrypoint == "verify_aggregate"
+                    && aggregate_gas_cap.is_none_or(|cap| measurement.gas >= cap) =>
+                {
+                    Verdict::NoCheaperThanSeparate
+                }
+                None => Verdict::Unbudgeted,
+                Some(limit) if measurement.ink > limit.ink || measurement.gas > limit.gas => Verdict::OverBudget,
+                Some(_) => Verdict::Ok,
+            };
+            Entry { measurement, budget: limit, verdict }
+        })
+        .collect();
+    let stale: Vec<String> = budget
+        .keys()
+        .filter(|case| !entries.iter().any(|entry| &entry.measurement.case == *case))
+        .cloned()
+        .collect();
+    let oversized: Vec<String> =
+        wasm_bytes.iter().filter(|(_, size)| **size > MAX_WASM_BYTES).map(|(program, _)| program.clone()).collect();
+    Report {
+        schema: REPORT_SCHEMA,
+        ink_price: host::INK_PRICE,
+        ink_per_fuel: host::INK_PER_FUEL,
+        wasm_bytes,
+        passed: stale.is_empty()
+            && oversized.is_empty()
+            && entries.iter().all(|entry| entry.verdict == Verdict::Ok),
+        oversized,
+        entries,
+        stale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measured(case: (&'static str, &str, usize), ink: u64, gas: u64) -> Measurement {
+        Measurement { ink, gas, ..Measurement::new(case.0, case.1, case.2, 1) }
+    }
+
+    #[test]
+    fn test_budget_check() {
+        let measurements = vec![
+            measured(("verify", "native", 1), 1_000, 100),
+            measured(("verify_packed", "direct", 4), 2_000, 200),
+        ];
+        let mut budget = budget_for(&measurements);
+        assert_eq!(budget["verify/native/1"], Limit { ink: 1_050, gas: 105 });
+        assert!(check(measurements.clone(), &budget, BTreeMap::new()).passed);
+
+        // Gas alone over the limit fails the case
+        budget.insert("verify/native/1".into(), Limit { ink: 1_000, gas: 99 });
+        let report = check(measurements.clone(), &budget, BTreeMap::new());
+        assert!(!report.passed);
+        assert_eq!(report.entries[0].verdict, Verdict::OverBudget);
+        assert_eq!(report.entries[1].verdict, Verdict::Ok);
+
+        // Cases and budgets must match one to one
+        budget = budget_for(&measurements[..1]);
+        budget.insert("verify_hashed/sha256/4".into(), Limit { ink: 1, gas: 1 });
+        let report = check(measurements, &budget, BTreeMap::new());
+        assert!(!report.passed);
+        assert_eq!(report.entries[1].verdict, Verdict::Unbudgeted);
+        assert_eq!(report.stale, ["verify_hashed/sha256/4"]);
+    }
+
+    #[test]
+    fn test_aggregate_must_beat_separate_proofs() {
+        let aggregate = |gas| Measurement { gas, ..Measurement::new("verify_aggregate", "snarkpack", 1, 8) };
+        let separate = measured(("verify_with_precompile", "precompile", 1), 1_000, 100);
+
+        let measurements = vec![separate.clone(), aggregate(799)];
+        assert!(check(measurements.clone(), &budget_for(&measurements), BTreeMap::new()).passed);
+
+        let measurements = vec![separate, aggregate(800)];
+        let report = check(measurements.clone(), &budget_for(&measurements), BTreeMap::new());
+        assert!(!report.passed);
+        assert_eq!(report.entries[1].verdict, Verdict::NoCheaperThanSeparate);
+
+        // With nothing to compare against, an aggregate cannot pass
+        let measurements = vec![aggregate(1)];
+        assert!(!check(measurements.clone(), &budget_for(&measurements), BTreeMap::new()).passed);
+    }
+
+    #[test]
+    fn test_program_size_limit() {
+        let measurements = vec![measured(("verify", "native", 1), 1_000, 100)];
+        let budget = budget_for(&measurements);
+        let sizes = |gateway| BTreeMap::from([("verifier".into(), MAX_WASM_BYTES), ("gateway".into(), gateway)]);
+        assert!(check(measurements.clone(), &budget, sizes(MAX_WASM_BYTES - 1)).passed);
+
+        let report = check(measurements, &budget, sizes(MAX_WASM_BYTES + 1));
+        assert!(!report.passed);
+        assert_eq!(report.oversized, ["gateway"]);
+        assert_eq!(report.entries[0].verdict, Verdict::Ok);
+
+        // Compression is what counts, not the raw size
+        assert!(compressed_size(&vec![0; 4 * MAX_WASM_BYTES]) < MAX_WASM_BYTES);
+    }
+
+    #[test]
+    fn test_case_keys() {
+        assert_eq!(Measurement::new("verify_packed", "keccak256", 16, 1).case, "verify_packed/keccak256/16");
+        assert_eq!(Measurement::new("verify_aggregate", "snarkpack", 4, 8).case, "verify_aggregate/snarkpack/4x8");
+    }
+}
@@ -0,0 +1,104 @@
+//! `arbshield-bench --wasm-dir target/stylus --budget bench/budget.json --report target/bench/report.json`
+//!
+//! Exits non-zero when any case is over budget, unbudgeted or stale, or any
+//! program is too large to deploy, so CI can run it right after the release
+//! wasm builds.
+
+use std::{collections::BTreeMap, fs, path::PathBuf, process::ExitCode};
+
+use arbshield_verifier_bench::{
+    budget_for, check, compressed_size, scenarios, BenchError, Budget, Report, Verdict, MAX_WASM_BYTES,
+};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(name = "arbshield-bench", version, about = "Ink and gas regression benchmarks for the Stylus programs")]
+struct Args {
+    /// Directory holding `<program>.wasm` for each program, as `bench.sh` builds them
+    #[arg(long, default_value = "target/stylus")]
+    wasm_dir: PathBuf,
+    #[arg(long, default_value = "bench/budget.json")]
+    budget: PathBuf,
+    /// Where to write the JSON report
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// Replace the budget with this run's costs plus headroom
+    #[arg(long)]
+    write_budget: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<bool, BenchError> {
+    let mut programs = Vec::new();
+    let mut wasm_bytes = BTreeMap::new();
+    for (name, address) in scenarios::PROGRAMS {
+        let path = args.wasm_dir.join(format!("{name}.wasm"));
+        let wasm = fs::read(&path).map_err(|err| BenchError::Io(path, err))?;
+        wasm_bytes.insert(name.to_string(), compressed_size(&wasm));
+        programs.push((address, wasm));
+    }
+    let programs: Vec<_> = programs.iter().map(|(address, wasm)| (*address, wasm.as_slice())).collect();
+    let measurements = scenarios::run(&programs)?;
+
+    if args.write_budget {
+        write_json(&args.budget, &budget_for(&measurements))?;
+        eprintln!("wrote {} budgets to {}", measurements.len(), args.budget.display());
+    }
+    let budget: Budget = {
+        let text = fs::read_to_string(&args.budget).map_err(|err| BenchError::Io(args.budget.clone(), err))?;
+        serde_json::from_str(&text).map_err(|err| BenchError::Json(args.budget.clone(), err))?
+    };
+
+    let report = check(measurements, &budget, wasm_bytes);
+    print_table(&report);
+    if let Some(path) = &args.report {
+        write_json(path, &report)?;
+    }
+    Ok(report.passed)
+}
+
+fn write_json(path: &PathBuf, value: &impl serde::Serialize) -> Result<(), BenchError> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| BenchError::Io(dir.to_path_buf(), err))?;
+    }
+    let mut json = serde_json::to_string_pretty(value).map_err(|err| BenchError::Json(path.clone(), err))?;
+    json.push('\n');
+    fs::write(path, json).map_err(|err| BenchError::Io(path.clone(), err))
+}
+
+fn print_table(report: &Report) {
+    eprintln!("{:<40} {:>14} {:>11} {:>11}  verdict", "case", "ink", "gas", "budget gas");
+    for entry in &report.entries {
+        let m = &entry.measurement;
+        let budget = entry.budget.map_or_else(|| "-".into(), |limit| limit.gas.to_string());
+        let verdict = match entry.verdict {
+            Verdict::Ok => "ok",
+            Verdict::OverBudget => "OVER BUDGET",
+            Verdict::Unbudgeted => "UNBUDGETED",
+            Verdict::NoCheaperThanSeparate => "NO CHEAPER THAN SEPARATE PROOFS",
+        };
+        eprintln!("{:<40} {:>14} {:>11} {:>11}  {verdict}", m.case, m.ink, m.gas, budget);
+    }
+    for case in &report.stale {
+        eprintln!("{case:<40} {:>14} {:>11} {:>11}  STALE (no longer measured)", "-", "-", "-");
+    }
+    eprintln!();
+    eprintln!("{:<40} {:>14} {:>11}  verdict", "program", "compressed", "limit");
+    for (program, size) in &report.wasm_bytes {
+        let verdict = match report.oversized.contains(program) {
+            true => "TOO LARGE TO DEPLOY",
+            false => "ok",
+        };
+        eprintln!("{program:<40} {size:>14} {MAX_WASM_BYTES:>11}  {verdict}");
+    }
+}
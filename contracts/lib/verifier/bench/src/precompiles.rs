@@ -0,0 +1,113 @@
+//! sha256 (0x02), priced per the Yellow Paper, and the BN254 precompiles
+//! 0x06 (add), 0x07 (mul) and 0x08 (pairing), computed with arkworks and
+//! priced per EIP-1108
+
+use alloy_primitives::Address;
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, One, PrimeField, Zero};
+
+const SHA256_GAS: u64 = 60;
+const SHA256_WORD_GAS: u64 = 12;
+const EC_ADD_GAS: u64 = 150;
+const EC_MUL_GAS: u64 = 6_000;
+const PAIRING_GAS: u64 = 45_000;
+const PAIRING_PAIR_GAS: u64 = 34_000;
+
+/// Output and gas of a precompile at `address`, `None` if there is none
+/// there; `Err` for inputs the EVM rejects (the call fails)
+pub fn run(address: Address, input: &[u8]) -> Option<(Result<Vec<u8>, ()>, u64)> {
+    let id = match address.0[..19].iter().all(|byte| *byte == 0) {
+        true => address.0[19],
+        false => return None,
+    };
+    Some(match id {
+        0x02 => {
+            use sha2::{Digest, Sha256};
+            let gas = SHA256_GAS + SHA256_WORD_GAS * input.len().div_ceil(32) as u64;
+            (Ok(Sha256::digest(input).to_vec()), gas)
+        }
+        0x06 => (ec_add(&padded::<128>(input)), EC_ADD_GAS),
+        0x07 => (ec_mul(&padded::<96>(input)), EC_MUL_GAS),
+        0x08 => (ec_pairing(input), PAIRING_GAS + PAIRING_PAIR_GAS * (input.len() / 192) as u64),
+        _ => return None,
+    })
+}
+
+/// EIP-196: missing input bytes read as zero, extra bytes are ignored
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let len = input.len().min(N);
+    bytes[..len].copy_from_slice(&input[..len]);
+    bytes
+}
+
+fn ec_add(input: &[u8; 128]) -> Result<Vec<u8>, ()> {
+    let sum = g1(&input[..64])? + g1(&input[64..])?;
+    Ok(encode_g1(&sum.into_affine()))
+}
+
+fn ec_mul(input: &[u8; 96]) -> Result<Vec<u8>, ()> {
+    // The scalar is a full 256-bit integer, not reduced mod r
+    let product = g1(&input[..64])?.mul_bigint(bigint(&input[64..]));
+    Ok(encode_g1(&product.into_affine()))
+}
+
+/// EIP-197: (G1 || G2) pairs with G2 as x.c1 || x.c0 || y.c1 || y.c0
+fn ec_pairing(input: &[u8]) -> Result<Vec<u8>, ()> {
+    if !input.len().is_multiple_of(192) {
+        return Err(());
+    }
+    let (g1s, g2s): (Vec<_>, Vec<_>) = input
+        .chunks_exact(192)
+        .map(|pair| Ok((g1(&pair[..64])?, g2(&pair[64..])?)))
+        .collect::<Result<Vec<_>, ()>>()?
+        .into_iter()
+        .unzip();
+    let valid = Bn254::multi_pairing(g1s, g2s).0.is_one();
+    let mut output = vec![0u8; 32];
+    output[31] = valid as u8;
+    Ok(output)
+}
+
+fn bigint(bytes: &[u8]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("8 bytes"));
+    }
+    BigInt::new(limbs)
+}
+
+/// Coordinates at or above p are rejected, not reduced
+fn fq(bytes: &[u8]) -> Result<Fq, ()> {
+    Fq::from_bigint(bigint(bytes)).ok_or(())
+}
+
+fn g1(bytes: &[u8]) -> Result<G1Affine, ()> {
+    let (x, y) = (fq(&bytes[..32])?, fq(&bytes[32..64])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    point.is_on_curve().then_some(point).ok_or(())
+}
+
+fn g2(bytes: &[u8]) -> Result<G2Affine, ()> {
+    let x = Fq2::new(fq(&bytes[32..64])?, fq(&bytes[..32])?);
+    let y = Fq2::new(fq(&bytes[96..128])?, fq(&bytes[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(());
+    }
+    Ok(point)
+}
+
+fn encode_g1(point: &G1Affine) -> Vec<u8> {
+    match point.xy() {
+        Some((x, y)) => [x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()].concat(),
+        None => vec![0u8; 64],
+    }
+}
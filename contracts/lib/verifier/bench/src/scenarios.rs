@@ -0,0 +1,772 @@
+//! Every entrypoint of every program, called through its ABI on a metered host
+//!
+//! The programs are deployed at fixed addresses and initialized as
+//! `deploy.sh` wires them, so calls that cross programs are charged for
+//! every program they run. Keys and proofs come from ark-groth16 with fixed
+//! seeds, so two runs on the same build produce the same numbers. Each
+//! measured call must succeed and, for verifications, accept its proof.
+
+use alloy_primitives::{Address, Bytes, FixedBytes, B256, U256};
+use alloy_sol_types::{sol, SolCall};
+use arbshield_verifier_core::{
+    jurisdiction::{self, JurisdictionSet},
+    policy, snarkjs::ProofJson, Groth16Proof, InputMode, VerifyingKey, MULTIPLIER_CIRCUIT_ID,
+};
+use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{Groth16, ProvingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_snark::SNARK;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    host::{Host, Receipt, DEFAULT_SENDER},
+    BenchError, Measurement,
+};
+
+/// Public input counts every per-circuit entrypoint is measured at; at least
+/// the subject and issue time an attribute statement carries
+pub const INPUT_COUNTS: [usize; 3] = [2, 4, 16];
+/// Terms per policy, each proven in the same `verify_for_policy` call
+pub const POLICY_SIZES: [usize; 2] = [2, 8];
+/// Record writes `is_compliant_at` searches through
+pub const CHECKPOINT_COUNTS: [usize; 2] = [1, 32];
+/// Steps in the longest implication chain; `ComplianceDelegations`' `MAX_IMPLICATION_DEPTH`
+const MAX_IMPLICATION_DEPTH: usize = 3;
+/// Delegation hops compliance checks follow; `ComplianceDelegations`' `MAX_DELEGATION_DEPTH`
+const MAX_DELEGATION_DEPTH: usize = 2;
+
+const VERIFIER: Address = Address::repeat_byte(0xcc);
+const GATEWAY: Address = Address::repeat_byte(0xe1);
+const RECORDS: Address = Address::repeat_byte(0xe2);
+const DELEGATIONS: Address = Address::repeat_byte(0xe3);
+const POLICIES: Address = Address::repeat_byte(0xe4);
+const ASSETS: Address = Address::repeat_byte(0xe5);
+const BRIDGE: Address = Address::repeat_byte(0xe6);
+/// Each program's wasm file stem, as `bench.sh` names it, and where it is deployed
+pub const PROGRAMS: [(&str, Address); 7] = [
+    ("verifier", VERIFIER),
+    ("gateway", GATEWAY),
+    ("records", RECORDS),
+    ("delegations", DELEGATIONS),
+    ("policies", POLICIES),
+    ("assets", ASSETS),
+    ("bridge", BRIDGE),
+];
+
+/// Address attribute proofs are made out to
+const SUBJECT: Address = Address::repeat_byte(0x5b);
+/// `ComplianceRegistry.sol` stand-in; its own execution is not measured
+const REGISTRY: Address = Address::repeat_byte(0x7e);
+/// Validity every attribute is bound with
+const VALIDITY_SECS: u64 = 365 * 24 * 60 * 60;
+
+const MULTIPLIER_PROOF_JSON: &str = include_str!("../../core/fixtures/multiplier/proof.json");
+
+sol! {
+    function initialize(address owner);
+    // gateway: (owner, verifier, records)
+    function initialize(address owner, address verifier, address records);
+    // records: (verifier, gateway); delegations: (owner, records); policies: (gateway, delegations)
+    function initialize(address first, address second);
+    // assets: (owner, records, delegations, policies); bridge: (owner, gateway, records, assets)
+    function initialize(address owner, address first, address second, address third);
+    function registerCircuit(bytes32 circuit_id, bytes vk_bytes, uint8 input_mode);
+    function verify(uint8[] proof_bytes, uint8[][] public_inputs) returns (bool);
+    function verifyWithPrecompile(uint8[] proof_bytes, uint8[][] public_inputs) returns (bool);
+    function verifyPacked(bytes packed) returns (bool);
+    function verifyHashed(bytes32 circuit_id, bytes proof_bytes, bytes inputs) returns (uint256[]);
+    function verifyStatement(bytes32 circuit_id, bytes proof_bytes, bytes inputs) returns (bytes32);
+    function getCircuit(bytes32 circuit_id) returns (uint8, uint32);
+    function startSession(bytes32 circuit_id, bytes proof_bytes) returns (uint256);
+    function addSessionInputs(uint256 session_id, bytes inputs) returns (uint32);
+    function finishSession(uint256 session_id) returns (bool);
+    function cancelSession(uint256 session_id);
+    function getSession(uint256 session_id) returns (address, bytes32, uint64, uint32, uint32);
+    function getKeyFingerprint(bytes32 circuit_id) returns (bytes32);
+    function getVerifiedCount() returns (uint256);
+    function bindAttribute(bytes32 circuit_id, string attribute, uint64 validity_secs, uint32 subject_input, uint32 issued_at_input);
+    // Stylus encodes a returned tuple holding a string as one tuple value
+    function getAttributeBinding(bytes32 circuit_id) returns ((string, uint64, uint32, uint32));
+    function verifyAttribute(bytes32 circuit_id, bytes proof_bytes, bytes inputs) returns (address, uint64);
+    function verifyClaimedAttribute(bytes32 circuit_id, string attribute, bytes proof_bytes, bytes inputs) returns (address, uint64);
+    function isCompliant(address user, string attribute_type) returns (bool);
+    function isCompliantWithin(address user, string attribute_type, uint64 max_age) returns (bool);
+    function getRecord(address user, string attribute_type) returns (bool, uint256, uint256, bytes32);
+    function getRecordSource(address user, string attribute_type) returns (bytes32, bytes32);
+    function setComplianceRegistry(address registry);
+    function getComplianceRegistry() returns (address);
+    function setAttributeCircuit(string attribute, bytes32 circuit_id);
+    function getAttributeCircuit(string attribute) returns (bytes32);
+    function verifyProof(bytes proof, string attribute_type) returns (bool);
+    function createPolicy(string[] attributes, uint64[] max_ages, bytes program) returns (bytes32);
+    function getPolicy(bytes32 policy_id) returns ((string[], uint64[], bytes));
+    function evaluatePolicy(address user, bytes32 policy_id) returns (bool);
+    function evaluatePolicyFor(address user, bytes32 policy_id, address asset) returns (bool);
+    function policyExists(bytes32 policy_id) returns (bool);
+    function verifyForPolicy(bytes32 policy_id, bytes32[] circuit_ids, bytes[] proofs, bytes[] inputs) returns (address);
+    function verifyAndRecord(bytes32 circuit_id, string attribute, bytes proof_bytes, bytes inputs) returns (address, uint64);
+    function setAssetIssuer(address asset, address issuer);
+    function setAssetPolicy(address asset, bytes32 policy_id);
+    function getAssetRules(address asset) returns (address, bytes32);
+    function canHold(address asset, address user) returns (bool);
+    function canTransfer(address asset, address from, address to, uint256 _amount) returns (bool);
+    function addImplication(string stronger, string weaker);
+    function removeImplication(string stronger, string weaker);
+    function getImpliedBy(string attribute) returns (string[]);
+    function isCompliantAt(address user, string attribute_type, uint64 timepoint) returns (bool);
+    function numCheckpoints(address user, string attribute_type) returns (uint32);
+    function getCheckpoint(address user, string attribute_type, uint32 index) returns (uint64, uint64, uint64);
+    function checkpointAtBlock(address user, string attribute_type, uint64 block) returns (uint64, uint64, uint64);
+    function clock() returns (uint64);
+    function CLOCK_MODE() returns (string);
+    function markKeyCompromised(bytes32 fingerprint);
+    function isKeyCompromised(bytes32 fingerprint) returns (bool);
+    function setJurisdictionInput(bytes32 circuit_id, uint32 first_input);
+    function getJurisdictionInput(bytes32 circuit_id) returns (bool, uint32);
+    function getProvenJurisdictions(address user, string attribute_type) returns (bytes32[]);
+    function setAssetJurisdictions(address asset, string attribute, uint16[] allowed, uint16[] denied, bool match_any);
+    function getAssetJurisdictions(address asset) returns ((string, uint16[], uint16[], bool));
+    function verifyForAsset(address asset, bytes32 circuit_id, bytes proof_bytes, bytes inputs) returns (address);
+    function delegateAttribute(address delegate, string attribute, address asset, uint64 expires_at);
+    function acceptDelegation(address principal, string attribute, address asset);
+    function revokeDelegation(address principal, address delegate, string attribute, address asset);
+    function getDelegations(address delegate, string attribute) returns ((address[], address[], uint64[], bool[]));
+    function getPrincipals(address delegate, string attribute, address asset) returns (address[]);
+    function holdsAttribute(address user, string attribute, uint64 max_age, address asset) returns (bool);
+}
+
+/// `ComplianceDelegations.is_compliant`, which also follows implications
+/// and delegations, unlike `ComplianceRecords.is_compliant`
+mod delegated {
+    alloy_sol_types::sol! {
+        function isCompliant(address user, string attribute_type) returns (bool);
+    }
+}
+
+/// Program an ABI call is sent to
+trait Routed: SolCall {
+    const TO: Address;
+}
+
+macro_rules! routed {
+    ($to:expr => $($call:ty),* $(,)?) => {
+        $(impl Routed for $call {
+            const TO: Address = $to;
+        })*
+    };
+}
+
+routed!(VERIFIER =>
+    registerCircuitCall, verifyCall, verifyWithPrecompileCall, verifyPackedCall, verifyHashedCall, startSessionCall,
+    addSessionInputsCall, finishSessionCall, cancelSessionCall, getSessionCall, getKeyFingerprintCall,
+    getVerifiedCountCall, markKeyCompromisedCall, isKeyCompromisedCall, verifyStatementCall, getCircuitCall,
+);
+routed!(GATEWAY =>
+    bindAttributeCall, getAttributeBindingCall, verifyAttributeCall, verifyClaimedAttributeCall,
+    setJurisdictionInputCall, getJurisdictionInputCall,
+);
+routed!(RECORDS =>
+    isCompliantCall, getRecordCall, getRecordSourceCall, isCompliantAtCall, numCheckpointsCall, getCheckpointCall,
+    checkpointAtBlockCall, clockCall, CLOCK_MODECall, isCompliantWithinCall, getProvenJurisdictionsCall,
+);
+routed!(DELEGATIONS =>
+    addImplicationCall, removeImplicationCall, getImpliedByCall, delegateAttributeCall, acceptDelegationCall,
+    revokeDelegationCall, getDelegationsCall, delegated::isCompliantCall, getPrincipalsCall, holdsAttributeCall,
+);
+routed!(POLICIES =>
+    createPolicyCall, getPolicyCall, evaluatePolicyCall, verifyForPolicyCall, evaluatePolicyForCall,
+    policyExistsCall,
+);
+routed!(ASSETS =>
+    setAssetIssuerCall, setAssetPolicyCall, getAssetRulesCall, canHoldCall, canTransferCall,
+    setAssetJurisdictionsCall, getAssetJurisdictionsCall,
+);
+routed!(BRIDGE =>
+    setComplianceRegistryCall, getComplianceRegistryCall, setAttributeCircuitCall, getAttributeCircuitCall,
+    verifyProofCall, verifyAndRecordCall, verifyForAssetCall,
+);
+
+/// x_i · x_i = w_i for each public input x_i
+#[derive(Clone)]
+struct Squares {
+    inputs: Vec<Option<Fr>>,
+}
+
+impl ConstraintSynthesizer<Fr> for Squares {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for input in self.inputs {
+            let x = cs.new_input_variable(|| input.ok_or(SynthesisError::AssignmentMissing))?;
+            let w = cs.new_witness_variable(|| input.map(|x| x * x).ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(x.into(), x.into(), w.into())?;
+        }
+        Ok(())
+    }
+}
+
+/// A key with `inputs` public inputs and its proving key
+struct Circuit {
+    pk: ProvingKey<Bn254>,
+    vk: VerifyingKey,
+}
+
+impl Circuit {
+    fn setup(inputs: usize, rng: &mut StdRng) -> Self {
+        let blank = Squares { inputs: vec![None; inputs] };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(blank, rng).expect("setup");
+        let vk = VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.gamma_abc_g1,
+        };
+        Self { pk, vk }
+    }
+
+    fn prove(&self, inputs: &[Fr], rng: &mut StdRng) -> Groth16Proof {
+        let witness = Squares { inputs: inputs.iter().copied().map(Some).collect() };
+        let proof = Groth16::<Bn254>::prove(&self.pk, witness, rng).expect("prove");
+        Groth16Proof { a: proof.a, b: proof.b, c: proof.c }
+    }
+}
+
+/// Circuit id for `inputs` public inputs under `mode`
+fn circuit_id(mode: InputMode, inputs: usize) -> FixedBytes<32> {
+    let mut id = [0u8; 32];
+    id[0] = 0xbe;
+    id[1] = mode.as_u8();
+    id[24..].copy_from_slice(&(inputs as u64).to_be_bytes());
+    FixedBytes(id)
+}
+
+fn random_inputs(count: usize, rng: &mut StdRng) -> Vec<Fr> {
+    (0..count).map(|_| Fr::rand(rng)).collect()
+}
+
+fn packed(inputs: &[Fr]) -> Vec<u8> {
+    inputs.iter().flat_map(|input| input.into_bigint().to_bytes_be()).collect()
+}
+
+fn address_input(address: Address) -> Fr {
+    Fr::from_be_bytes_mod_order(address.as_slice())
+}
+
+/// `SUBJECT` and an issue time: inputs 0 and 1 of every attribute statement
+fn statement(issued_at: u64) -> [Fr; 2] {
+    [address_input(SUBJECT), Fr::from(issued_at)]
+}
+
+fn proof_bytes(proof: &Groth16Proof) -> Bytes {
+    proof.to_uncompressed_bytes().to_vec().into()
+}
+
+/// Calls on one deployment, and what they cost
+struct Bench {
+    host: Host,
+    rng: StdRng,
+    measurements: Vec<Measurement>,
+}
+
+impl Bench {
+    /// Call that must succeed, e.g. setup for the call being measured
+    fn call<C: Routed>(&mut self, call: C, case: &str) -> Result<(C::Return, Receipt), BenchError> {
+        self.call_at(C::TO, call, case)
+    }
+
+    /// `call` to a program other than the one `C` is routed to
+    fn call_at<C: SolCall>(&mut self, to: Address, call: C, case: &str) -> Result<(C::Return, Receipt), BenchError> {
+        let receipt = self.host.call(to, &call.abi_encode())?;
+        if !receipt.success {
+            let reason = String::from_utf8_lossy(&receipt.output).into_owned();
+            return Err(BenchError::Reverted(case.into(), reason));
+        }
+        let output = C::abi_decode_returns(&receipt.output, true)
+            .map_err(|err| BenchError::Reverted(case.into(), err.to_string()))?;
+        Ok((output, receipt))
+    }
+
+    fn measure<C: Routed>(
+        &mut self,
+        entrypoint: &'static str,
+        backend: &str,
+        inputs: usize,
+        proofs: usize,
+        call: C,
+    ) -> Result<C::Return, BenchError> {
+        self.measure_at(C::TO, entrypoint, backend, inputs, proofs, call)
+    }
+
+    /// `measure` on a program other than the one `C` is routed to
+    fn measure_at<C: SolCall>(
+        &mut self,
+        to: Address,
+        entrypoint: &'static str,
+        backend: &str,
+        inputs: usize,
+        proofs: usize,
+        call: C,
+    ) -> Result<C::Return, BenchError> {
+        let measurement = Measurement::new(entrypoint, backend, inputs, proofs);
+        let (output, receipt) = self.call_at(to, call, &measurement.case)?;
+        self.measurements.push(measurement.with(&receipt));
+        Ok(output)
+    }
+
+    /// `measure` for calls returning whether a proof was accepted
+    fn expect_valid<C: Routed>(
+        &mut self,
+        entrypoint: &'static str,
+        backend: &str,
+        inputs: usize,
+        proofs: usize,
+        call: C,
+        valid: impl FnOnce(&C::Return) -> bool,
+    ) -> Result<(), BenchError> {
+        let output = self.measure(entrypoint, backend, inputs, proofs, call)?;
+        match valid(&output) {
+            true => Ok(()),
+            false => {
+                let case = Measurement::new(entrypoint, backend, inputs, proofs).case;
+                Err(BenchError::Reverted(case, "proof rejected".into()))
+            }
+        }
+    }
+
+    fn register(&mut self, mode: InputMode, inputs: usize, vk: &VerifyingKey) -> Result<FixedBytes<32>, BenchError> {
+        let id = circuit_id(mode, inputs);
+        let call = registerCircuitCall { circuit_id: id, vk_bytes: vk.to_bytes().into(), input_mode: mode.as_u8() };
+        self.measure("register_circuit", mode_name(mode), inputs, 1, call)?;
+        Ok(id)
+    }
+
+    /// Bind a circuit to an attribute read from inputs 0 and 1, then record
+    /// it for `SUBJECT` locally, through the registry and as the Solidity
+    /// wrapper would
+    ///
+    /// A record only takes a newer statement, so each call proves one issued
+    /// a second after the last: `prove` returns the proof and input words
+    /// for an issue time.
+    fn prove_attribute(
+        &mut self,
+        id: FixedBytes<32>,
+        mode: InputMode,
+        inputs: usize,
+        prove: impl Fn(u64, &mut StdRng) -> (Groth16Proof, Vec<u8>),
+    ) -> Result<(), BenchError> {
+        let attribute = format!("{}_{inputs}", mode_name(mode));
+        let call = bindAttributeCall {
+            circuit_id: id,
+            attribute: attribute.clone(),
+            validity_secs: VALIDITY_SECS,
+            subject_input: 0,
+            issued_at_input: 1,
+        };
+        self.measure("bind_attribute", mode_name(mode), inputs, 0, call)?;
+        let fresh = |bench: &mut Self| -> Result<(Groth16Proof, Vec<u8>), BenchError> {
+            bench.host.advance(1);
+            let now = bench.call(clockCall {}, "clock")?.0._0;
+            Ok(prove(now, &mut bench.rng))
+        };
+
+        let (proof, words) = fresh(self)?;
+        let call = verifyAttributeCall { circuit_id: id, proof_bytes: proof_bytes(&proof), inputs: words.into() };
+        self.measure("verify_attribute", mode_name(mode), inputs, 1, call)?;
+        let (proof, words) = fresh(self)?;
+        let call = verifyClaimedAttributeCall {
+            circuit_id: id,
+            attribute: attribute.clone(),
+            proof_bytes: proof_bytes(&proof),
+            inputs: words.into(),
+        };
+        self.measure("verify_claimed_attribute", mode_name(mode), inputs, 1, call)?;
+        let (proof, words) = fresh(self)?;
+        let call = verifyAndRecordCall {
+            circuit_id: id,
+            attribute: attribute.clone(),
+            proof_bytes: proof_bytes(&proof),
+            inputs: words.into(),
+        };
+        self.measure("verify_and_record", mode_name(mode), inputs, 1, call)?;
+        let call = setAttributeCircuitCall { attribute: attribute.clone(), circuit_id: id };
+        self.measure("set_attribute_circuit", mode_name(mode), inputs, 0, call)?;
+        let (proof, words) = fresh(self)?;
+        let wrapped = [proof.to_uncompressed_bytes().as_slice(), &words].concat();
+        let call = verifyProofCall { proof: wrapped.into(), attribute_type: attribute };
+        self.expect_valid("verify_proof", mode_name(mode), inputs, 1, call, |output| output._0)
+    }
+}
+
+fn mode_name(mode: InputMode) -> &'static str {
+    match mode {
+        InputMode::Direct => "direct",
+        InputMode::Keccak256 => "keccak256",
+        InputMode::Sha256 => "sha256",
+    }
+}
+
+/// Deploy each of `PROGRAMS` and measure every entrypoint
+pub fn run(programs: &[(Address, &[u8])]) -> Result<Vec<Measurement>, BenchError> {
+    let mut bench = Bench { host: Host::new(programs)?, rng: StdRng::seed_from_u64(40), measurements: Vec::new() };
+    initialize(&mut bench)?;
+    bench.host.deploy(REGISTRY);
+    bench.measure("set_compliance_registry", "-", 0, 0, setComplianceRegistryCall { registry: REGISTRY })?;
+
+    multiplier(&mut bench)?;
+    for inputs in INPUT_COUNTS {
+        direct_circuit(&mut bench, inputs)?;
+        for mode in [InputMode::Keccak256, InputMode::Sha256] {
+            hashed_circuit(&mut bench, mode, inputs)?;
+        }
+    }
+    policies(&mut bench)?;
+    implications(&mut bench)?;
+    delegations(&mut bench)?;
+    checkpoints(&mut bench)?;
+    jurisdictions(&mut bench)?;
+
+    bench.measure("get_verified_count", "-", 0, 0, getVerifiedCountCall {})?;
+    let attribute = format!("{}_2", mode_name(InputMode::Direct));
+    let call = isCompliantCall { user: SUBJECT, attribute_type: attribute.clone() };
+    bench.expect_valid("is_compliant", "-", 0, 0, call, |output| output._0)?;
+    bench.measure("get_record", "-", 0, 0, getRecordCall { user: SUBJECT, attribute_type: attribute.clone() })?;
+    let call = getRecordCall { user: SUBJECT, attribute_type: attribute.clone() };
+    bench.measure_at(BRIDGE, "get_record", "bridge", 0, 0, call)?;
+    bench.measure("get_record_source", "-", 0, 0, getRecordSourceCall { user: SUBJECT, attribute_type: attribute })?;
+    let call = getAttributeBindingCall { circuit_id: circuit_id(InputMode::Direct, 2) };
+    bench.measure("get_attribute_binding", "-", 0, 0, call)?;
+    bench.measure("get_compliance_registry", "-", 0, 0, getComplianceRegistryCall {})?;
+    let call = getAttributeCircuitCall { attribute: format!("{}_2", mode_name(InputMode::Direct)) };
+    bench.measure("get_attribute_circuit", "-", 0, 0, call)?;
+
+    // Last, as it voids records: a key no circuit here uses
+    let fingerprint = B256::repeat_byte(0xdd);
+    bench.measure("mark_key_compromised", "-", 0, 0, markKeyCompromisedCall { fingerprint })?;
+    let call = isKeyCompromisedCall { fingerprint };
+    bench.expect_valid("is_key_compromised", "-", 0, 0, call, |output| output._0)?;
+    Ok(bench.measurements)
+}
+
+/// Wire the programs together as `deploy.sh` does, owned by `DEFAULT_SENDER`
+fn initialize(bench: &mut Bench) -> Result<(), BenchError> {
+    let owner = DEFAULT_SENDER;
+    bench.measure_at(VERIFIER, "initialize", "verifier", 0, 0, initialize_0Call { owner })?;
+    let call = initialize_1Call { owner, verifier: VERIFIER, records: RECORDS };
+    bench.measure_at(GATEWAY, "initialize", "gateway", 0, 0, call)?;
+    let call = initialize_2Call { first: VERIFIER, second: GATEWAY };
+    bench.measure_at(RECORDS, "initialize", "records", 0, 0, call)?;
+    let call = initialize_2Call { first: owner, second: RECORDS };
+    bench.measure_at(DELEGATIONS, "initialize", "delegations", 0, 0, call)?;
+    let call = initialize_2Call { first: GATEWAY, second: DELEGATIONS };
+    bench.measure_at(POLICIES, "initialize", "policies", 0, 0, call)?;
+    let call = initialize_3Call { owner, first: RECORDS, second: DELEGATIONS, third: POLICIES };
+    bench.measure_at(ASSETS, "initialize", "assets", 0, 0, call)?;
+    let call = initialize_3Call { owner, first: GATEWAY, second: RECORDS, third: ASSETS };
+    bench.measure_at(BRIDGE, "initialize", "bridge", 0, 0, call)?;
+    Ok(())
+}
+
+/// `verify` and `verify_with_precompile` only take the key registered under
+/// the multiplier's circuit id: here the development key the fixture was
+/// proven with
+fn multiplier(bench: &mut Bench) -> Result<(), BenchError> {
+    let vk_bytes = VerifyingKey::load_real().to_bytes().into();
+    let call = registerCircuitCall { circuit_id: FixedBytes(MULTIPLIER_CIRCUIT_ID), vk_bytes, input_mode: 0 };
+    bench.call(call, "register_circuit multiplier")?;
+
+    let proof = ProofJson::from_json(MULTIPLIER_PROOF_JSON)
+        .and_then(|json| json.to_uncompressed_bytes())
+        .map_err(|err| BenchError::Fixture(err.to_string()))?
+        .to_vec();
+    let public_inputs = vec![U256::from(15).to_be_bytes::<32>().to_vec()];
+
+    let call = verifyCall { proof_bytes: proof.clone(), public_inputs: public_inputs.clone() };
+    bench.expect_valid("verify", "precompile", 1, 1, call, |output| output._0)?;
+    let call = verifyWithPrecompileCall { proof_bytes: proof.clone(), public_inputs };
+    bench.expect_valid("verify_with_precompile", "precompile", 1, 1, call, |output| output._0)?;
+
+    let packed = [&MULTIPLIER_CIRCUIT_ID[..], &proof, &U256::from(15).to_be_bytes::<32>()].concat();
+    let call = verifyPackedCall { packed: packed.into() };
+    bench.expect_valid("verify_packed", "multiplier", 1, 1, call, |output| output._0)
+}
+
+/// Packed, attribute, staged and view calls on a registered direct input circuit
+fn direct_circuit(bench: &mut Bench, inputs: usize) -> Result<(), BenchError> {
+    let circuit = Circuit::setup(inputs, &mut bench.rng);
+    let id = bench.register(InputMode::Direct, inputs, &circuit.vk)?;
+    bench.measure("get_key_fingerprint", "direct", inputs, 0, getKeyFingerprintCall { circuit_id: id })?;
+    bench.measure("get_circuit", "direct", inputs, 0, getCircuitCall { circuit_id: id })?;
+
+    let values = random_inputs(inputs, &mut bench.rng);
+    let proof = circuit.prove(&values, &mut bench.rng);
+    let packed_call = [id.as_slice(), &proof.to_uncompressed_bytes(), &packed(&values)].concat();
+    let call = verifyPackedCall { packed: packed_call.into() };
+    bench.expect_valid("verify_packed", "direct", inputs, 1, call, |output| output._0)?;
+    let call = verifyStatementCall { circuit_id: id, proof_bytes: proof_bytes(&proof), inputs: packed(&values).into() };
+    bench.measure("verify_statement", "direct", inputs, 1, call)?;
+    bench.prove_attribute(id, InputMode::Direct, inputs, |issued_at, rng| {
+        let mut values = values.clone();
+        values[..2].copy_from_slice(&statement(issued_at));
+        (circuit.prove(&values, rng), packed(&values))
+    })?;
+
+    // The whole statement in one chunk; the chunk size is the caller's choice
+    let call = startSessionCall { circuit_id: id, proof_bytes: proof_bytes(&proof) };
+    let session_id = bench.measure("start_session", "direct", inputs, 1, call)?._0;
+    bench.measure("get_session", "direct", inputs, 1, getSessionCall { session_id })?;
+    let call = addSessionInputsCall { session_id, inputs: packed(&values).into() };
+    bench.measure("add_session_inputs", "direct", inputs, 1, call)?;
+    let call = finishSessionCall { session_id };
+    bench.expect_valid("finish_session", "direct", inputs, 1, call, |output| output._0)?;
+
+    let call = startSessionCall { circuit_id: id, proof_bytes: proof_bytes(&proof) };
+    let session_id = bench.call(call, "start_session")?.0._0;
+    bench.measure("cancel_session", "direct", inputs, 1, cancelSessionCall { session_id })?;
+    Ok(())
+}
+
+/// `verify_hashed`, `verify_packed` and `verify_attribute` on a circuit
+/// whose one input is the hash of `inputs` words
+fn hashed_circuit(bench: &mut Bench, mode: InputMode, inputs: usize) -> Result<(), BenchError> {
+    let circuit = Circuit::setup(1, &mut bench.rng);
+    let id = bench.register(mode, inputs, &circuit.vk)?;
+
+    let values = random_inputs(inputs, &mut bench.rng);
+    let prove = |values: &[Fr], rng: &mut StdRng| {
+        let words = packed(values);
+        let digest = mode.digest(&words).expect("hashed mode");
+        (circuit.prove(&[digest], rng), words)
+    };
+    let (proof, words) = prove(&values, &mut bench.rng);
+
+    let call = verifyHashedCall { circuit_id: id, proof_bytes: proof_bytes(&proof), inputs: words.clone().into() };
+    bench.measure("verify_hashed", mode_name(mode), inputs, 1, call)?;
+    let call = verifyStatementCall { circuit_id: id, proof_bytes: proof_bytes(&proof), inputs: words.clone().into() };
+    bench.measure("verify_statement", mode_name(mode), inputs, 1, call)?;
+    let packed_call = [id.as_slice(), &proof.to_uncompressed_bytes(), &words].concat();
+    let call = verifyPackedCall { packed: packed_call.into() };
+    bench.expect_valid("verify_packed", mode_name(mode), inputs, 1, call, |output| output._0)?;
+    bench.prove_attribute(id, mode, inputs, |issued_at, rng| {
+        let mut values = values.clone();
+        values[..2].copy_from_slice(&statement(issued_at));
+        prove(&values, rng)
+    })
+}
+
+/// `create_policy`, `verify_for_policy` and `evaluate_policy` on policies
+/// requiring every one of `terms` attributes, each proven by its own circuit
+fn policies(bench: &mut Bench) -> Result<(), BenchError> {
+    let max_terms = POLICY_SIZES.iter().copied().max().unwrap_or(2);
+    let circuits: Vec<(FixedBytes<32>, Circuit)> = (0..max_terms)
+        .map(|term| (B256::repeat_byte(0xb0 + term as u8), Circuit::setup(2, &mut bench.rng)))
+        .collect();
+    for (term, (id, circuit)) in circuits.iter().enumerate() {
+        let call = registerCircuitCall { circuit_id: *id, vk_bytes: circuit.vk.to_bytes().into(), input_mode: 0 };
+        bench.call(call, "register_circuit")?;
+        let attribute = format!("policy_{term}");
+        let call = bindAttributeCall {
+            circuit_id: *id,
+            attribute,
+            validity_secs: VALIDITY_SECS,
+            subject_input: 0,
+            issued_at_input: 1,
+        };
+        bench.call(call, "bind_attribute")?;
+    }
+
+    for terms in POLICY_SIZES {
+        // TERM 0, (TERM i, AND)*
+        let mut program = vec![policy::OP_TERM, 0];
+        program.extend((1..terms as u8).flat_map(|term| [policy::OP_TERM, term, policy::OP_AND]));
+        let call = createPolicyCall {
+            attributes: (0..terms).map(|term| format!("policy_{term}")).collect(),
+            max_ages: vec![VALIDITY_SECS; terms],
+            program: program.into(),
+        };
+        let policy_id = bench.measure("create_policy", "-", terms, 0, call)?._0;
+
+        // Policy sizes share terms, so each size proves a newer statement
+        bench.host.advance(1);
+        let values = statement(bench.call(clockCall {}, "clock")?.0._0);
+        let words = packed(&values);
+        let proofs = circuits[..terms]
+            .iter()
+            .map(|(_, circuit)| proof_bytes(&circuit.prove(&values, &mut bench.rng)))
+            .collect();
+        let call = verifyForPolicyCall {
+            policy_id,
+            circuit_ids: circuits[..terms].iter().map(|(id, _)| *id).collect(),
+            proofs,
+            inputs: vec![words.clone().into(); terms],
+        };
+        bench.measure("verify_for_policy", "direct", 2, terms, call)?;
+        let call = evaluatePolicyCall { user: SUBJECT, policy_id };
+        bench.expect_valid("evaluate_policy", "-", terms, 0, call, |output| output._0)?;
+        bench.measure("get_policy", "-", terms, 0, getPolicyCall { policy_id })?;
+        bench.expect_valid("policy_exists", "-", terms, 0, policyExistsCall { policy_id }, |output| output._0)?;
+
+        // The token asks on every transfer; price it against each policy size
+        let asset = Address::repeat_byte(0xa5 + terms as u8);
+        let call = setAssetIssuerCall { asset, issuer: DEFAULT_SENDER };
+        bench.measure("set_asset_issuer", "-", terms, 0, call)?;
+        bench.measure("set_asset_policy", "-", terms, 0, setAssetPolicyCall { asset, policy_id })?;
+        bench.measure("get_asset_rules", "-", terms, 0, getAssetRulesCall { asset })?;
+        let call = evaluatePolicyForCall { user: SUBJECT, policy_id, asset };
+        bench.expect_valid("evaluate_policy_for", "-", terms, 0, call, |output| output._0)?;
+        let call = canHoldCall { asset, user: SUBJECT };
+        bench.expect_valid("can_hold", "-", terms, 0, call, |output| output._0)?;
+        let call = canTransferCall { asset, from: SUBJECT, to: SUBJECT, _amount: U256::from(1) };
+        bench.expect_valid("can_transfer", "-", terms, 0, call, |output| output._0)?;
+    }
+    Ok(())
+}
+
+/// Chain tiers below an attribute `SUBJECT` holds, as deep as the contract
+/// allows, and check the weakest through every step
+fn implications(bench: &mut Bench) -> Result<(), BenchError> {
+    let held = format!("{}_2", mode_name(InputMode::Direct));
+    let tiers: Vec<String> = (1..=MAX_IMPLICATION_DEPTH).map(|tier| format!("tier_{tier}")).collect();
+    for (step, weaker) in tiers.iter().enumerate() {
+        let stronger = if step == 0 { held.clone() } else { tiers[step - 1].clone() };
+        bench.measure("add_implication", "-", step + 1, 0, addImplicationCall { stronger, weaker: weaker.clone() })?;
+    }
+    let weakest = tiers[MAX_IMPLICATION_DEPTH - 1].clone();
+    let call = delegated::isCompliantCall { user: SUBJECT, attribute_type: weakest.clone() };
+    bench.expect_valid("is_compliant", "implied", MAX_IMPLICATION_DEPTH, 0, call, |output| output._0)?;
+    bench.measure("get_implied_by", "-", 0, 0, getImpliedByCall { attribute: weakest.clone() })?;
+
+    let call = addImplicationCall { stronger: held.clone(), weaker: weakest.clone() };
+    bench.call(call, "add_implication")?;
+    let call = removeImplicationCall { stronger: held, weaker: weakest };
+    bench.measure("remove_implication", "-", 0, 0, call)?;
+    Ok(())
+}
+
+/// Delegate an attribute `SUBJECT` holds down a chain as long as checks
+/// follow, and check the last delegate through every hop
+fn delegations(bench: &mut Bench) -> Result<(), BenchError> {
+    let attribute = format!("{}_2", mode_name(InputMode::Direct));
+    let expires_at = bench.call(clockCall {}, "clock")?.0._0 + VALIDITY_SECS;
+    let mut principal = SUBJECT;
+    for hop in 1..=MAX_DELEGATION_DEPTH {
+        let delegate = Address::repeat_byte(0xf0 + hop as u8);
+        bench.host.set_sender(principal);
+        let call = delegateAttributeCall { delegate, attribute: attribute.clone(), asset: Address::ZERO, expires_at };
+        bench.measure("delegate_attribute", "-", hop, 0, call)?;
+        bench.host.set_sender(delegate);
+        let call = acceptDelegationCall { principal, attribute: attribute.clone(), asset: Address::ZERO };
+        bench.measure("accept_delegation", "-", hop, 0, call)?;
+        principal = delegate;
+    }
+    bench.host.set_sender(DEFAULT_SENDER);
+    let call = delegated::isCompliantCall { user: principal, attribute_type: attribute.clone() };
+    bench.expect_valid("is_compliant", "delegated", MAX_DELEGATION_DEPTH, 0, call, |output| output._0)?;
+    bench.measure("get_delegations", "-", 0, 0, getDelegationsCall { delegate: principal, attribute: attribute.clone() })?;
+    let call = getPrincipalsCall { delegate: principal, attribute: attribute.clone(), asset: Address::ZERO };
+    bench.measure("get_principals", "-", 0, 0, call)?;
+    let call = holdsAttributeCall { user: principal, attribute: attribute.clone(), max_age: 0, asset: Address::ZERO };
+    bench.expect_valid("holds_attribute", "delegated", MAX_DELEGATION_DEPTH, 0, call, |output| output._0)?;
+
+    bench.host.set_sender(principal);
+    let call = revokeDelegationCall {
+        principal: Address::repeat_byte(0xf1),
+        delegate: principal,
+        attribute,
+        asset: Address::ZERO,
+    };
+    bench.measure("revoke_delegation", "-", 0, 0, call)?;
+    bench.host.set_sender(DEFAULT_SENDER);
+    Ok(())
+}
+
+/// Renew one record a minute apart and look up its first state by time
+/// and by block, so the binary search runs its full depth
+fn checkpoints(bench: &mut Bench) -> Result<(), BenchError> {
+    let id = B256::repeat_byte(0xc4);
+    let circuit = Circuit::setup(2, &mut bench.rng);
+    let call = registerCircuitCall { circuit_id: id, vk_bytes: circuit.vk.to_bytes().into(), input_mode: 0 };
+    bench.call(call, "register_circuit")?;
+    let attribute = String::from("history");
+    let call = bindAttributeCall {
+        circuit_id: id,
+        attribute: attribute.clone(),
+        validity_secs: VALIDITY_SECS,
+        subject_input: 0,
+        issued_at_input: 1,
+    };
+    bench.call(call, "bind_attribute")?;
+
+    let first = bench.call(clockCall {}, "clock")?.0._0;
+    let first_block = bench.host.block_number();
+    let mut written = 0;
+    for count in CHECKPOINT_COUNTS {
+        while written < count {
+            let values = statement(first + 60 * written as u64);
+            let proof = proof_bytes(&circuit.prove(&values, &mut bench.rng));
+            let call = verifyAttributeCall { circuit_id: id, proof_bytes: proof, inputs: packed(&values).into() };
+            bench.call(call, "verify_attribute")?;
+            bench.host.advance(60);
+            written += 1;
+        }
+        let call = isCompliantAtCall { user: SUBJECT, attribute_type: attribute.clone(), timepoint: first };
+        bench.expect_valid("is_compliant_at", "-", count, 0, call, |output| output._0)?;
+        let call = checkpointAtBlockCall { user: SUBJECT, attribute_type: attribute.clone(), block: first_block };
+        bench.expect_valid("checkpoint_at_block", "-", count, 0, call, |output| output._0 == first)?;
+    }
+
+    let call = numCheckpointsCall { user: SUBJECT, attribute_type: attribute.clone() };
+    bench.measure("num_checkpoints", "-", 0, 0, call)?;
+    let call = getCheckpointCall { user: SUBJECT, attribute_type: attribute, index: 0 };
+    bench.measure("get_checkpoint", "-", 0, 0, call)?;
+    let call = isCompliantWithinCall { user: SUBJECT, attribute_type: String::from("history"), max_age: VALIDITY_SECS };
+    bench.expect_valid("is_compliant_within", "-", 0, 0, call, |output| output._0)?;
+    bench.measure("clock", "-", 0, 0, clockCall {})?;
+    bench.measure("clock_mode", "-", 0, 0, CLOCK_MODECall {})?;
+    Ok(())
+}
+
+/// An asset open to the EU and US but not to sanctioned countries, and a
+/// holder proven to be in one of three EU countries
+fn jurisdictions(bench: &mut Bench) -> Result<(), BenchError> {
+    const EU: [u16; 27] = [
+        40, 56, 100, 191, 196, 203, 208, 233, 246, 250, 276, 300, 348, 372, 380, 428, 440, 442, 470, 528, 616, 620,
+        642, 703, 705, 724, 752,
+    ];
+    const SANCTIONED: [u16; 4] = [192, 364, 408, 760];
+    let id = B256::repeat_byte(0xd0);
+    let circuit = Circuit::setup(2 + jurisdiction::WORDS, &mut bench.rng);
+    let call = registerCircuitCall { circuit_id: id, vk_bytes: circuit.vk.to_bytes().into(), input_mode: 0 };
+    bench.call(call, "register_circuit")?;
+    let attribute = String::from("residency");
+    let call = bindAttributeCall {
+        circuit_id: id,
+        attribute: attribute.clone(),
+        validity_secs: VALIDITY_SECS,
+        subject_input: 0,
+        issued_at_input: 1,
+    };
+    bench.call(call, "bind_attribute")?;
+    bench.measure("set_jurisdiction_input", "-", 0, 0, setJurisdictionInputCall { circuit_id: id, first_input: 2 })?;
+    bench.measure("get_jurisdiction_input", "-", 0, 0, getJurisdictionInputCall { circuit_id: id })?;
+
+    let asset = Address::repeat_byte(0xd5);
+    bench.call(setAssetIssuerCall { asset, issuer: DEFAULT_SENDER }, "set_asset_issuer")?;
+    let call = createPolicyCall { attributes: vec![attribute.clone()], max_ages: vec![0], program: vec![policy::OP_TERM, 0].into() };
+    let policy_id = bench.call(call, "create_policy")?.0._0;
+    bench.call(setAssetPolicyCall { asset, policy_id }, "set_asset_policy")?;
+    let allowed = [&EU[..], &[840]].concat();
+    let call = setAssetJurisdictionsCall { asset, attribute, allowed, denied: SANCTIONED.to_vec(), match_any: false };
+    bench.measure("set_asset_jurisdictions", "-", 0, 0, call)?;
+    bench.measure("get_asset_jurisdictions", "-", 0, 0, getAssetJurisdictionsCall { asset })?;
+
+    let set = JurisdictionSet::from_codes(&EU[..3]).map_err(|err| BenchError::Fixture(String::from_utf8_lossy(&err).into_owned()))?;
+    let mut values = statement(bench.call(clockCall {}, "clock")?.0._0).to_vec();
+    values.extend(set.to_bytes().chunks(32).map(Fr::from_be_bytes_mod_order));
+    let proof = proof_bytes(&circuit.prove(&values, &mut bench.rng));
+    let call = verifyForAssetCall { asset, circuit_id: id, proof_bytes: proof, inputs: packed(&values).into() };
+    bench.expect_valid("verify_for_asset", "direct", 2 + jurisdiction::WORDS, 1, call, |output| output._0 == SUBJECT)?;
+    let call = getProvenJurisdictionsCall { user: SUBJECT, attribute_type: String::from("residency") };
+    bench.expect_valid("get_proven_jurisdictions", "-", 0, 0, call, |output| output._0.len() == jurisdiction::WORDS)?;
+    let call = canHoldCall { asset, user: SUBJECT };
+    bench.expect_valid("can_hold", "jurisdiction", 0, 0, call, |output| output._0)
+}
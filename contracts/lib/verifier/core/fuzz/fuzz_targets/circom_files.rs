@@ -0,0 +1,13 @@
+//! `.r1cs`, `.zkey` and `.wtns` files, as read by the CLI and the prover
+
+#![no_main]
+
+use arbshield_verifier_core::circom::{read_wtns, verifying_key_from_zkey, ProvingKey, R1cs};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = R1cs::from_bytes(data);
+    let _ = ProvingKey::from_bytes(data);
+    let _ = verifying_key_from_zkey(data);
+    let _ = read_wtns(data);
+});
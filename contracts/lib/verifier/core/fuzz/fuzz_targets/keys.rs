@@ -0,0 +1,21 @@
+//! Owner-supplied encodings: verifying keys, aggregation SRS and aggregate proofs
+
+#![no_main]
+
+use arbshield_verifier_core::{
+    snarkpack::{AggregateProof, VerifierSrs},
+    VerifyingKey,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(vk) = VerifyingKey::from_bytes(data) {
+        assert_eq!(vk.to_bytes(), data);
+    }
+    if let Ok(srs) = VerifierSrs::from_bytes(data) {
+        assert_eq!(srs.to_bytes(), data);
+    }
+    if let Ok(proof) = AggregateProof::from_bytes(data) {
+        assert_eq!(proof.to_bytes(), data);
+    }
+});
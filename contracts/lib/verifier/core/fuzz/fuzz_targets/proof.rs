@@ -0,0 +1,17 @@
+//! Proof calldata: uncompressed (256 bytes) and compressed (128 bytes)
+
+#![no_main]
+
+use arbshield_verifier_core::Groth16Proof;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(proof) = Groth16Proof::from_uncompressed_bytes(data) {
+        assert_eq!(proof.to_uncompressed_bytes(), data, "uncompressed encoding is not unique");
+    }
+    if let Ok(proof) = Groth16Proof::from_compressed_bytes(data) {
+        assert_eq!(proof.to_compressed_bytes(), data, "compressed encoding is not unique");
+        let bytes = proof.to_uncompressed_bytes();
+        assert!(Groth16Proof::from_uncompressed_array(&bytes).is_ok());
+    }
+});
@@ -0,0 +1,28 @@
+//! Public inputs: ABI `bytes[]` words and `verify_packed` calldata
+
+#![no_main]
+
+use arbshield_verifier_core::{parse_public_inputs, PackedCall};
+use ark_ff::{BigInteger, PrimeField};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // First byte sets the word length, so ragged input lists come up too
+    if let Some((&width, rest)) = data.split_first() {
+        let width = usize::from(width % 40).max(1);
+        let inputs: Vec<Vec<u8>> = rest.chunks(width).map(<[u8]>::to_vec).collect();
+        if let Ok(values) = parse_public_inputs(&inputs) {
+            for (value, word) in values.iter().zip(&inputs) {
+                assert_eq!(&value.into_bigint().to_bytes_be(), word, "input reduced instead of rejected");
+            }
+        }
+    }
+
+    if let Ok(call) = PackedCall::parse(data) {
+        let rebuilt = [&call.circuit_id[..], &call.proof[..], call.inputs.as_flattened()].concat();
+        assert_eq!(rebuilt, data);
+        if let Ok(values) = call.public_inputs() {
+            assert_eq!(values.len(), call.inputs.len());
+        }
+    }
+});
@@ -0,0 +1,22 @@
+//! snarkjs `proof.json`, `verification_key.json` and `public.json`
+
+#![no_main]
+
+use arbshield_verifier_core::snarkjs::{parse_decimal, parse_public_signals_json, to_decimal, ProofJson, VerificationKeyJson};
+use ark_bn254::Fr;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|json: &str| {
+    if let Ok(proof) = ProofJson::from_json(json).and_then(|proof| proof.to_proof()) {
+        let again = ProofJson::from_proof(&proof).to_proof().unwrap();
+        assert_eq!(again.to_uncompressed_bytes(), proof.to_uncompressed_bytes());
+    }
+    if let Ok(vk) = VerificationKeyJson::from_json(json).and_then(|vk| vk.to_verifying_key()) {
+        let again = VerificationKeyJson::from_verifying_key(&vk).to_verifying_key().unwrap();
+        assert_eq!(again.to_bytes(), vk.to_bytes());
+    }
+    let _ = parse_public_signals_json(json);
+    if let Some(value) = parse_decimal::<Fr>(json) {
+        assert_eq!(parse_decimal::<Fr>(&to_decimal(&value)), Some(value));
+    }
+});
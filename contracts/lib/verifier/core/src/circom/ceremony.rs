@@ -0,0 +1,668 @@
+//! Phase-2 ceremony transcripts, checked the way `snarkjs zkey verify` does
+//!
+//! A `.zkey` is honest for a circuit when (1) every section equals the
+//! initial key derived from the `.r1cs` and the phase-1 `.ptau`, except C
+//! and H, which are divided by the final δ; and (2) δ is the product of the
+//! contributions recorded in section 10, each proving knowledge of its
+//! factor against its transcript hash. The initial key is rebuilt and
+//! compared directly, so snarkjs's circuit hash (csHash) is only reported
+//! and used as the root of the transcript chain, not recomputed.
+//!
+//! Contribution records (section 10): csHash (64) || count, then per
+//! contribution δ-after (G1) || [s]₁ || [s·x]₁ || [sp·x]₂ || transcript (64)
+//! || type (0 contributed, 1 beacon) || params length || params, each param
+//! a one-byte id (1 name, 2 beacon iterations exponent, 3 beacon hash).
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{pairing::Pairing, short_weierstrass::SWCurveConfig, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInt, Field, PrimeField, UniformRand, Zero};
+use ark_std::rand::RngCore;
+use blake2::{Blake2b512, Digest};
+use core::fmt;
+
+use super::{
+    ptau::{PowersOfTau, LAGRANGE_ALPHA_TAU_G1, LAGRANGE_BETA_TAU_G1, LAGRANGE_TAU_G1},
+    zkey::open_zkey,
+    CircomError, Coefficient, Constraint, Matrix, ProvingKey, R1cs, Reader,
+};
+use crate::append_g1;
+
+/// Random-beacon parameters of a final contribution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Beacon {
+    pub hash: Vec<u8>,
+    /// The beacon key was derived with 2^iterations_exp hash iterations
+    pub iterations_exp: u8,
+}
+
+/// One phase-2 contribution as recorded in the `.zkey`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contribution {
+    pub name: Option<String>,
+    pub beacon: Option<Beacon>,
+    /// [δ]₁ after this contribution
+    pub delta_after: G1Affine,
+    /// Proof of knowledge of the factor x: [s]₁, [s·x]₁ and [sp·x]₂,
+    /// where [sp]₂ is hashed from the transcript
+    pub g1_s: G1Affine,
+    pub g1_sx: G1Affine,
+    pub g2_spx: G2Affine,
+    pub transcript: [u8; 64],
+}
+
+/// One line of the report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Outcome of `verify_ceremony`, printable as an archivable text report
+#[derive(Clone, Debug)]
+pub struct CeremonyReport {
+    pub n_vars: usize,
+    pub n_public: usize,
+    pub domain_size: usize,
+    pub ptau_power: u32,
+    pub ptau_ceremony_power: u32,
+    pub ptau_contributions: usize,
+    pub cs_hash: [u8; 64],
+    pub contributions: Vec<Contribution>,
+    /// Fingerprint of the final verifying key, as the contract reports it
+    pub fingerprint: [u8; 32],
+    pub checks: Vec<Check>,
+}
+
+impl CeremonyReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Record whether a deployed key (`vk_constants`, a registry entry) is this one
+    pub fn compare_deployed(&mut self, source: &str, fingerprint: [u8; 32]) {
+        let passed = fingerprint == self.fingerprint;
+        let detail = if passed { "same key".to_string() } else { format!("0x{}", hex(&fingerprint)) };
+        self.check(format!("deployed key ({source})"), passed, detail);
+    }
+
+    fn check(&mut self, name: impl Into<String>, passed: bool, detail: impl Into<String>) {
+        self.checks.push(Check { name: name.into(), passed, detail: detail.into() });
+    }
+}
+
+impl fmt::Display for CeremonyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Groth16 phase-2 ceremony report")?;
+        writeln!(f, "circuit:      {} wires, {} public, domain {}", self.n_vars, self.n_public, self.domain_size)?;
+        writeln!(
+            f,
+            "ptau:         power {} (ceremony power {}), {} phase-1 contributions",
+            self.ptau_power, self.ptau_ceremony_power, self.ptau_contributions
+        )?;
+        writeln!(f, "csHash:       0x{}", hex(&self.cs_hash))?;
+        writeln!(f, "fingerprint:  0x{}", hex(&self.fingerprint))?;
+
+        writeln!(f, "\ncontributions:")?;
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            let name = contribution.name.as_deref().unwrap_or("(unnamed)");
+            writeln!(f, "  #{} {name}", i + 1)?;
+            writeln!(f, "     transcript 0x{}", hex(&contribution.transcript))?;
+            if let Some(beacon) = &contribution.beacon {
+                writeln!(
+                    f,
+                    "     beacon 0x{}, 2^{} iterations (beacon key not re-derived)",
+                    hex(&beacon.hash),
+                    beacon.iterations_exp
+                )?;
+            }
+        }
+
+        writeln!(f, "\nchecks:")?;
+        for check in &self.checks {
+            let status = if check.passed { "ok  " } else { "FAIL" };
+            writeln!(f, "  [{status}] {}: {}", check.name, check.detail)?;
+        }
+        write!(f, "\nresult: {}", if self.passed() { "PASS" } else { "FAIL" })
+    }
+}
+
+/// Check a phase-2 `.zkey` against its circuit and phase-1 file
+///
+/// Unreadable files are errors; a readable key that fails a check comes
+/// back as a report with `passed() == false`. `rng` draws the random linear
+/// combinations that batch the C and H checks into two pairings each.
+pub fn verify_ceremony<R: RngCore>(
+    r1cs: &[u8],
+    ptau: &[u8],
+    zkey: &[u8],
+    rng: &mut R,
+) -> Result<CeremonyReport, CircomError> {
+    let r1cs = R1cs::from_bytes(r1cs)?;
+    let ptau = PowersOfTau::from_bytes(ptau)?;
+    let pk = ProvingKey::from_bytes(zkey)?;
+    let (cs_hash, contributions) = read_contributions(zkey)?;
+
+    let n_public = r1cs.n_public();
+    let domain_size = (r1cs.constraints.len() + n_public + 1).next_power_of_two();
+    let mut report = CeremonyReport {
+        n_vars: pk.n_vars,
+        n_public: pk.n_public,
+        domain_size: pk.domain_size,
+        ptau_power: ptau.power,
+        ptau_ceremony_power: ptau.ceremony_power,
+        ptau_contributions: ptau.contributions,
+        cs_hash,
+        contributions,
+        fingerprint: pk.vk.fingerprint(),
+        checks: Vec::new(),
+    };
+
+    let shape = (pk.n_vars, pk.n_public, pk.domain_size) == (r1cs.n_wires, n_public, domain_size);
+    report.check(
+        "circuit shape",
+        shape,
+        format!("r1cs implies {} wires, {n_public} public, domain {domain_size}", r1cs.n_wires),
+    );
+    // H lives on the doubled domain
+    let fits = 2 * domain_size <= 1 << ptau.power;
+    report.check("ptau size", fits, format!("domain {} needs power {}", 2 * domain_size, (2 * domain_size).trailing_zeros()));
+    if !shape || !fits {
+        return Ok(report);
+    }
+
+    let phase1 = pk.vk.alpha_g1 == ptau.alpha_g1 && pk.beta_g1 == ptau.beta_g1 && pk.vk.beta_g2 == ptau.beta_g2;
+    report.check("phase-1 α, β", phase1, "taken from the ptau");
+    report.check("γ", pk.vk.gamma_g2 == G2Affine::generator(), "the G2 generator");
+
+    let initial = InitialKey::derive(&r1cs, &ptau, domain_size)?;
+    report.check("coefficients", sorted(&pk.coefficients) == sorted(&initial.coefficients), "A and B match the r1cs");
+    report.check("A", pk.a_query == initial.a, "matches r1cs + ptau");
+    report.check("B1", pk.b_g1_query == initial.b_g1, "matches r1cs + ptau");
+    report.check("B2", pk.b_g2_query == initial.b_g2, "matches r1cs + ptau");
+    report.check("IC", pk.vk.ic == initial.ic, "matches r1cs + ptau");
+    let delta_g2 = pk.vk.delta_g2;
+    report.check("C", scaled_by_delta(&initial.c, &pk.c_query, delta_g2, rng), "r1cs + ptau, divided by δ");
+    report.check("H", scaled_by_delta(&initial.h, &pk.h_query, delta_g2, rng), "ptau, divided by δ");
+    report.check(
+        "δ",
+        same_ratio(G1Affine::generator(), pk.delta_g1, G2Affine::generator(), delta_g2),
+        "[δ]₁ and [δ]₂ agree",
+    );
+
+    let mut delta = G1Affine::generator();
+    for i in 0..report.contributions.len() {
+        let contribution = &report.contributions[i];
+        let expected = contribution_transcript(
+            &report.cs_hash,
+            &report.contributions[..i],
+            &contribution.g1_s,
+            &contribution.g1_sx,
+        );
+        let transcript = expected == contribution.transcript;
+
+        let g2_sp = hash_to_g2(&contribution.transcript);
+        let knows_x = !contribution.g1_s.is_zero()
+            && same_ratio(contribution.g1_s, contribution.g1_sx, g2_sp, contribution.g2_spx);
+        let applied_x = !contribution.delta_after.is_zero()
+            && same_ratio(delta, contribution.delta_after, g2_sp, contribution.g2_spx);
+        delta = contribution.delta_after;
+
+        report.check(format!("contribution #{} transcript", i + 1), transcript, "chains from csHash");
+        report.check(
+            format!("contribution #{} δ update", i + 1),
+            knows_x && applied_x,
+            "proves knowledge of its factor",
+        );
+    }
+    report.check(
+        "contributions",
+        !report.contributions.is_empty(),
+        format!("{} recorded; with none, δ is the generator", report.contributions.len()),
+    );
+    report.check("final δ", delta == pk.delta_g1, "is the last contribution's δ");
+
+    Ok(report)
+}
+
+// ============================================================================
+// INITIAL KEY
+// ============================================================================
+
+/// What `snarkjs groth16 setup` writes before any contribution (δ = 1)
+struct InitialKey {
+    coefficients: Vec<Coefficient>,
+    a: Vec<G1Affine>,
+    b_g1: Vec<G1Affine>,
+    b_g2: Vec<G2Affine>,
+    ic: Vec<G1Affine>,
+    c: Vec<G1Affine>,
+    h: Vec<G1Affine>,
+}
+
+impl InitialKey {
+    fn derive(r1cs: &R1cs, ptau: &PowersOfTau<'_>, domain_size: usize) -> Result<Self, CircomError> {
+        let tau = ptau.lagrange_g1(LAGRANGE_TAU_G1, domain_size)?;
+        let tau_g2 = ptau.lagrange_g2(domain_size)?;
+        let alpha_tau = ptau.lagrange_g1(LAGRANGE_ALPHA_TAU_G1, domain_size)?;
+        let beta_tau = ptau.lagrange_g1(LAGRANGE_BETA_TAU_G1, domain_size)?;
+        let doubled = ptau.lagrange_g1(LAGRANGE_TAU_G1, 2 * domain_size)?;
+
+        let n_vars = r1cs.n_wires;
+        let n_public = r1cs.n_public();
+        let (mut a, mut b_g1, mut k) =
+            (vec![G1Projective::zero(); n_vars], vec![G1Projective::zero(); n_vars], vec![G1Projective::zero(); n_vars]);
+        let mut b_g2 = vec![G2Projective::zero(); n_vars];
+        let mut coefficients = Vec::new();
+
+        // Rows past the constraints bind each public wire (A = wᵢ)
+        let public_rows = (0..=n_public).map(|i| Constraint { a: vec![(i, Fr::ONE)], ..Default::default() });
+        for (row, constraint) in r1cs.constraints.iter().cloned().chain(public_rows).enumerate() {
+            for &(wire, value) in &constraint.a {
+                a[wire] += tau[row] * value;
+                k[wire] += beta_tau[row] * value;
+                coefficients.push(Coefficient { matrix: Matrix::A, constraint: row, signal: wire, value });
+            }
+            for &(wire, value) in &constraint.b {
+                b_g1[wire] += tau[row] * value;
+                b_g2[wire] += tau_g2[row] * value;
+                k[wire] += alpha_tau[row] * value;
+                coefficients.push(Coefficient { matrix: Matrix::B, constraint: row, signal: wire, value });
+            }
+            for &(wire, value) in &constraint.c {
+                k[wire] += tau[row] * value;
+            }
+        }
+
+        let mut k = G1Projective::normalize_batch(&k);
+        let c = k.split_off(n_public + 1);
+        Ok(Self {
+            coefficients,
+            a: G1Projective::normalize_batch(&a),
+            b_g1: G1Projective::normalize_batch(&b_g1),
+            b_g2: G2Projective::normalize_batch(&b_g2),
+            ic: k,
+            c,
+            h: doubled.into_iter().skip(1).step_by(2).collect(),
+        })
+    }
+}
+
+fn sorted(coefficients: &[Coefficient]) -> Vec<(bool, usize, usize, BigInt<4>)> {
+    let mut keys: Vec<_> = coefficients
+        .iter()
+        .filter(|coeff| !coeff.value.is_zero())
+        .map(|coeff| (coeff.matrix == Matrix::B, coeff.constraint, coeff.signal, coeff.value.into_bigint()))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Every final point is its initial point divided by δ, checked on one
+/// random linear combination
+fn scaled_by_delta<R: RngCore>(initial: &[G1Affine], last: &[G1Affine], delta_g2: G2Affine, rng: &mut R) -> bool {
+    if initial.len() != last.len() {
+        return false;
+    }
+    let weights: Vec<Fr> = (0..initial.len()).map(|_| Fr::rand(rng)).collect();
+    let initial = G1Projective::msm_unchecked(initial, &weights).into_affine();
+    let last = G1Projective::msm_unchecked(last, &weights).into_affine();
+    same_ratio(initial, last, delta_g2, G2Affine::generator())
+}
+
+/// b₁ / a₁ = b₂ / a₂ in the exponent: e(a₁, b₂) = e(b₁, a₂)
+fn same_ratio(a1: G1Affine, b1: G1Affine, a2: G2Affine, b2: G2Affine) -> bool {
+    Bn254::multi_pairing([a1, -b1], [b2, a2]).is_zero()
+}
+
+// ============================================================================
+// CONTRIBUTION CHAIN
+// ============================================================================
+
+pub(super) fn read_contributions(zkey: &[u8]) -> Result<([u8; 64], Vec<Contribution>), CircomError> {
+    let file = open_zkey(zkey)?;
+    let mut section = file.section(10)?;
+    let cs_hash = section.take(64)?.try_into().expect("64 bytes");
+    let count = section.len()?;
+    let contributions = (0..count)
+        .map(|i| read_contribution(&mut section, i + 1))
+        .collect::<Result<_, _>>()?;
+    Ok((cs_hash, contributions))
+}
+
+fn read_contribution(section: &mut Reader<'_>, number: usize) -> Result<Contribution, CircomError> {
+    let field = |name: &str| format!("contribution #{number} {name}");
+    let delta_after = section.g1(&field("deltaAfter"))?;
+    let g1_s = section.g1(&field("g1_s"))?;
+    let g1_sx = section.g1(&field("g1_sx"))?;
+    let g2_spx = section.g2(&field("g2_spx"))?;
+    let transcript = section.take(64)?.try_into().expect("64 bytes");
+    let kind = section.u32()?;
+    let params_len = section.len()?;
+    let mut params = Reader::new(section.take(params_len)?, "zkey");
+
+    let (mut name, mut iterations_exp, mut beacon_hash) = (None, None, None);
+    let mut last_id = 0;
+    while params.remaining() > 0 {
+        let id = params.take(1)?[0];
+        if id <= last_id {
+            return Err(CircomError::Format(field("parameters out of order")));
+        }
+        last_id = id;
+        match id {
+            1 => {
+                let len = params.take(1)?[0] as usize;
+                name = Some(String::from_utf8_lossy(params.take(len)?).into_owned());
+            }
+            2 => iterations_exp = Some(params.take(1)?[0]),
+            3 => {
+                let len = params.take(1)?[0] as usize;
+                beacon_hash = Some(params.take(len)?.to_vec());
+            }
+            other => return Err(CircomError::Format(field(&format!("parameter {other}")))),
+        }
+    }
+
+    let beacon = match (kind, beacon_hash, iterations_exp) {
+        (0, None, None) => None,
+        (1, Some(hash), Some(iterations_exp)) => Some(Beacon { hash, iterations_exp }),
+        _ => return Err(CircomError::Format(field(&format!("type {kind} with mismatched parameters")))),
+    };
+    Ok(Contribution { name, beacon, delta_after, g1_s, g1_sx, g2_spx, transcript })
+}
+
+/// BLAKE2b-512 of csHash, every earlier contribution's public key, then [s]₁, [s·x]₁
+pub(crate) fn contribution_transcript(
+    cs_hash: &[u8; 64],
+    previous: &[Contribution],
+    g1_s: &G1Affine,
+    g1_sx: &G1Affine,
+) -> [u8; 64] {
+    let mut hasher = Blake2b512::new();
+    hasher.update(cs_hash);
+    for contribution in previous {
+        let mut key = Vec::with_capacity(3 * 64 + 128 + 64);
+        for point in [&contribution.delta_after, &contribution.g1_s, &contribution.g1_sx] {
+            append_uncompressed_g1(point, &mut key);
+        }
+        append_uncompressed_g2(&contribution.g2_spx, &mut key);
+        key.extend(contribution.transcript);
+        hasher.update(key);
+    }
+    let mut key = Vec::with_capacity(128);
+    append_uncompressed_g1(g1_s, &mut key);
+    append_uncompressed_g1(g1_sx, &mut key);
+    hasher.update(key);
+
+    let mut transcript = [0u8; 64];
+    transcript.copy_from_slice(&hasher.finalize());
+    transcript
+}
+
+/// ffjavascript's uncompressed encoding: big-endian x || y, 0x40 flag for infinity
+fn append_uncompressed_g1(point: &G1Affine, buf: &mut Vec<u8>) {
+    if point.is_zero() {
+        buf.push(0x40);
+        buf.extend([0u8; 63]);
+    } else {
+        append_g1(point, buf);
+    }
+}
+
+fn append_uncompressed_g2(point: &G2Affine, buf: &mut Vec<u8>) {
+    if point.is_zero() {
+        buf.push(0x40);
+        buf.extend([0u8; 127]);
+    } else {
+        crate::append_g2(point, buf);
+    }
+}
+
+/// [sp]₂ for a transcript, as snarkjs's `hashToG2`: ChaCha20 seeded with
+/// the first 32 bytes (eight big-endian words) driving ffjavascript's
+/// `G2.fromRng`, then cleared of the cofactor
+pub(crate) fn hash_to_g2(transcript: &[u8; 64]) -> G2Affine {
+    let mut seed = [0u32; 8];
+    for (word, chunk) in seed.iter_mut().zip(transcript.chunks_exact(4)) {
+        *word = u32::from_be_bytes(chunk.try_into().expect("4 bytes"));
+    }
+    let mut rng = ChaCha::new(seed);
+
+    let (x, y, greatest) = loop {
+        let x = Fq2::new(fq_from_rng(&mut rng), fq_from_rng(&mut rng));
+        let greatest = rng.next_u32() & 1 == 1;
+        if let Some(y) = (x.square() * x + ark_bn254::g2::Config::COEFF_B).sqrt() {
+            break (x, y, greatest);
+        }
+    };
+    let y = if greatest ^ is_negative(&y) { -y } else { y };
+    G2Affine::new_unchecked(x, y).mul_by_cofactor_to_group().into_affine()
+}
+
+/// ffjavascript `F.fromRng`: four words, masked to the field's bit length,
+/// rejected until canonical, and taken as a Montgomery representation
+fn fq_from_rng(rng: &mut ChaCha) -> Fq {
+    loop {
+        let mut limbs = [0u64; 4];
+        for limb in &mut limbs {
+            *limb = (u64::from(rng.next_u32()) << 32) | u64::from(rng.next_u32());
+        }
+        limbs[3] &= (1 << (Fq::MODULUS_BIT_SIZE - 192)) - 1;
+        let repr = BigInt::new(limbs);
+        if repr < Fq::MODULUS {
+            return ark_ff::Fp(repr, core::marker::PhantomData);
+        }
+    }
+}
+
+/// ffjavascript's sign: above (q − 1)/2, judged on c1 unless it is zero
+fn is_negative(value: &Fq2) -> bool {
+    let part = if value.c1.is_zero() { value.c0 } else { value.c1 };
+    part.into_bigint() > Fq::MODULUS_MINUS_ONE_DIV_TWO
+}
+
+/// ChaCha20 keystream with a zero nonce, one 32-bit word at a time
+struct ChaCha {
+    state: [u32; 16],
+    block: [u32; 16],
+    index: usize,
+}
+
+impl ChaCha {
+    fn new(seed: [u32; 8]) -> Self {
+        let mut state = [0u32; 16];
+        state[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+        state[4..12].copy_from_slice(&seed);
+        Self { state, block: [0; 16], index: 16 }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index == 16 {
+            self.refill();
+        }
+        self.index += 1;
+        self.block[self.index - 1]
+    }
+
+    fn refill(&mut self) {
+        let mut x = self.state;
+        for _ in 0..10 {
+            for [a, b, c, d] in [[0, 4, 8, 12], [1, 5, 9, 13], [2, 6, 10, 14], [3, 7, 11, 15]] {
+                quarter_round(&mut x, a, b, c, d);
+            }
+            for [a, b, c, d] in [[0, 5, 10, 15], [1, 6, 11, 12], [2, 7, 8, 13], [3, 4, 9, 14]] {
+                quarter_round(&mut x, a, b, c, d);
+            }
+        }
+        for (out, (mixed, input)) in self.block.iter_mut().zip(x.iter().zip(&self.state)) {
+            *out = mixed.wrapping_add(*input);
+        }
+        self.index = 0;
+        // 128-bit block counter in words 12..16
+        for word in &mut self.state[12..] {
+            *word = word.wrapping_add(1);
+            if *word != 0 {
+                break;
+            }
+        }
+    }
+}
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_files::{self, ToxicWaste};
+    use super::*;
+    use crate::{circom::prove, verify_groth16, VerifyingKey};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    struct Files {
+        r1cs: Vec<u8>,
+        ptau: Vec<u8>,
+        /// Straight out of `groth16 setup`, before any contribution
+        initial: Vec<u8>,
+    }
+
+    fn setup(rng: &mut StdRng, power: u32) -> Files {
+        let waste = ToxicWaste { gamma: Fr::ONE, delta: Fr::ONE, ..ToxicWaste::random(rng) };
+        let r1cs = test_files::multiplier_r1cs();
+        Files {
+            r1cs: test_files::r1cs_bytes(&r1cs),
+            ptau: test_files::ptau_bytes(power, &waste),
+            initial: test_files::zkey_bytes(&r1cs, &waste),
+        }
+    }
+
+    fn two_contributions(files: &Files, rng: &mut StdRng) -> Vec<u8> {
+        let beacon = Beacon { hash: vec![0xbe; 32], iterations_exp: 10 };
+        let zkey = test_files::contribute(&files.initial, Fr::rand(rng), Some("alice"), None, rng);
+        test_files::contribute(&zkey, Fr::rand(rng), None, Some(&beacon), rng)
+    }
+
+    fn failed(report: &CeremonyReport) -> Vec<&str> {
+        report.checks.iter().filter(|check| !check.passed).map(|check| check.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_honest_ceremony() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let files = setup(&mut rng, 3);
+        let zkey = two_contributions(&files, &mut rng);
+
+        let mut report = verify_ceremony(&files.r1cs, &files.ptau, &zkey, &mut rng).unwrap();
+        assert!(report.passed(), "{report}");
+        assert_eq!(report.contributions.len(), 2);
+        assert_eq!(report.contributions[0].name.as_deref(), Some("alice"));
+        assert_eq!(report.contributions[1].beacon.as_ref().unwrap().iterations_exp, 10);
+
+        // The audited key is the one that proves, and what the contract would fingerprint
+        let pk = ProvingKey::from_bytes(&zkey).unwrap();
+        assert_eq!(report.fingerprint, pk.vk.fingerprint());
+        let witness = [1u64, 15, 3, 5].map(Fr::from);
+        let (proof, inputs) = prove(&pk, &witness, &mut rng).unwrap();
+        assert!(verify_groth16(&proof, &inputs, &pk.vk).unwrap());
+
+        report.compare_deployed("registry", pk.vk.fingerprint());
+        assert!(report.passed());
+        report.compare_deployed("vk_constants", VerifyingKey::load_real().fingerprint());
+        assert_eq!(failed(&report), ["deployed key (vk_constants)"]);
+        assert!(report.to_string().ends_with("result: FAIL"));
+    }
+
+    #[test]
+    fn test_broken_ceremonies() {
+        let mut rng = StdRng::seed_from_u64(37);
+        let files = setup(&mut rng, 3);
+        let honest = two_contributions(&files, &mut rng);
+        let verify = |zkey: &[u8], rng: &mut StdRng| verify_ceremony(&files.r1cs, &files.ptau, zkey, rng).unwrap();
+
+        // No contributions: δ = 1 and anyone can forge
+        assert_eq!(failed(&verify(&files.initial, &mut rng)), ["contributions"]);
+
+        // A setup over a different τ than the ptau
+        let other = setup(&mut rng, 3);
+        let forged = test_files::contribute(&other.initial, Fr::rand(&mut rng), None, None, &mut rng);
+        assert_eq!(failed(&verify(&forged, &mut rng)), ["phase-1 α, β", "A", "B1", "B2", "IC", "C", "H"]);
+
+        // δ rescaled without a recorded contribution
+        let unrecorded = test_files::scale_delta(&honest, Fr::from(7u8), true);
+        assert_eq!(failed(&verify(&unrecorded, &mut rng)), ["final δ"]);
+
+        // δ rescaled in the header only: C and H no longer match it
+        let header_only = test_files::scale_delta(&honest, Fr::from(7u8), false);
+        assert_eq!(failed(&verify(&header_only, &mut rng)), ["C", "H", "final δ"]);
+
+        // A rewritten transcript breaks its own proof and every later link
+        let mut sections = test_files::sections(&honest);
+        let records = &mut sections.iter_mut().find(|(id, _)| *id == 10).unwrap().1;
+        records[64 + 4 + 3 * 64 + 128] ^= 1;
+        let tampered = test_files::bin_file(b"zkey", 1, &sections);
+        assert_eq!(
+            failed(&verify(&tampered, &mut rng)),
+            ["contribution #1 transcript", "contribution #1 δ update", "contribution #2 transcript"]
+        );
+
+        // A ptau too small for the doubled domain stops before deriving anything;
+        // a file that is not a ptau at all is an error, not a report
+        let small = setup(&mut rng, 2);
+        let report = verify_ceremony(&small.r1cs, &small.ptau, &small.initial, &mut rng).unwrap();
+        assert_eq!(failed(&report), ["ptau size"]);
+        assert!(matches!(
+            verify_ceremony(&files.r1cs, &honest, &honest, &mut rng),
+            Err(CircomError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn test_snarkjs_zkey_new() {
+        // circom's multiplier.r1cs and the key `snarkjs zkey new` made from it and
+        // the Hermez ptau, which is not in the tree; checked against another τ
+        let r1cs = include_bytes!("../../fixtures/circom/multiplier.r1cs");
+        let zkey = include_bytes!("../../fixtures/circom/multiplier.zkey");
+        let mut rng = StdRng::seed_from_u64(38);
+        let files = setup(&mut rng, 3);
+
+        let report = verify_ceremony(r1cs, &files.ptau, zkey, &mut rng).unwrap();
+        assert_eq!((report.n_vars, report.n_public, report.domain_size), (4, 1, 4));
+        assert!(report.contributions.is_empty());
+        assert_eq!(report.fingerprint, ProvingKey::from_bytes(zkey).unwrap().vk.fingerprint());
+        // Everything tied to the r1cs alone holds; a key nobody contributed to is flagged
+        assert_eq!(
+            failed(&report),
+            ["phase-1 α, β", "A", "B1", "B2", "IC", "C", "H", "contributions"],
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn test_chacha_keystream() {
+        // RFC 8439 A.1, test vector 1: all-zero key, nonce and counter
+        let mut rng = ChaCha::new([0; 8]);
+        assert_eq!([rng.next_u32(), rng.next_u32()], [0xade0_b876, 0x903d_f1a0]);
+
+        let point = hash_to_g2(&[7; 64]);
+        assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(point, hash_to_g2(&[7; 64]));
+        assert_ne!(point, hash_to_g2(&[8; 64]));
+    }
+}
@@ -0,0 +1,255 @@
+//! CIRCOM / SNARKJS BINARY FILES AND NATIVE PROVING
+//!
+//! Readers for the iden3 binary formats (`.r1cs`, `.wtns`, `.zkey`, `.ptau`) and a
+//! Groth16 prover that consumes them the way `snarkjs groth16 prove` does,
+//! so test and CLI proofs need no Node toolchain. With `witness-wasm`, the
+//! witness can also be computed from circom's `.wasm` generator; with
+//! `ceremony`, a `.zkey` can be audited against its `.r1cs` and `.ptau`.
+//!
+//! All four formats share one container: a 4-byte magic, u32 version, u32
+//! section count, then sections of u32 id || u64 length || payload, all
+//! little-endian. Field elements are n8-byte little-endian words; `.zkey`
+//! and `.ptau` store curve points in Montgomery form, with all-zero bytes for infinity.
+
+#[cfg(feature = "ceremony")]
+mod ceremony;
+mod prover;
+#[cfg(feature = "ceremony")]
+mod ptau;
+mod r1cs;
+#[cfg(feature = "witness-wasm")]
+mod witness;
+mod wtns;
+mod zkey;
+
+#[cfg(test)]
+pub(crate) mod test_files;
+
+#[cfg(feature = "ceremony")]
+pub use ceremony::{verify_ceremony, Beacon, CeremonyReport, Check, Contribution};
+pub use prover::{prove, prove_with_randomness};
+pub use r1cs::{Constraint, R1cs};
+#[cfg(feature = "witness-wasm")]
+pub use witness::WitnessCalculator;
+pub use wtns::read_wtns;
+pub use zkey::{verifying_key_from_zkey, Coefficient, Matrix, PlonkVerifyingKey, ProvingKey, ZkeyVerifyingKey};
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use core::fmt;
+
+/// Why a circom/snarkjs file cannot be read or proven over
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircomError {
+    /// Truncated file, bad magic or version, missing section
+    Format(String),
+    /// Valid file for something this crate does not handle (curve, protocol)
+    Unsupported(String),
+    /// Field element that is not canonical
+    Encoding(String),
+    OffCurve(String),
+    NotInSubgroup(String),
+    /// Witness that does not fit the circuit or key
+    Witness(String),
+    /// Witness generator failed to load or run
+    Wasm(String),
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(reason) => write!(f, "malformed file: {reason}"),
+            Self::Unsupported(what) => write!(f, "unsupported {what}"),
+            Self::Encoding(field) => write!(f, "{field}: not a canonical field element"),
+            Self::OffCurve(field) => write!(f, "{field}: point not on curve"),
+            Self::NotInSubgroup(field) => write!(f, "{field}: point not in subgroup"),
+            Self::Witness(reason) => write!(f, "witness: {reason}"),
+            Self::Wasm(reason) => write!(f, "witness generator: {reason}"),
+        }
+    }
+}
+
+impl From<CircomError> for Vec<u8> {
+    fn from(err: CircomError) -> Self {
+        err.to_string().into_bytes()
+    }
+}
+
+// ============================================================================
+// SECTIONED CONTAINER
+// ============================================================================
+
+/// Sections of an iden3 binary file, by id
+pub(crate) struct BinFile<'a> {
+    kind: &'static str,
+    pub version: u32,
+    sections: BTreeMap<u32, Vec<&'a [u8]>>,
+}
+
+impl<'a> BinFile<'a> {
+    pub fn parse(bytes: &'a [u8], magic: &[u8; 4], kind: &'static str) -> Result<Self, CircomError> {
+        let mut reader = Reader::new(bytes, kind);
+        if reader.take(4)? != magic {
+            return Err(CircomError::Format(format!("not a {kind} file")));
+        }
+        let version = reader.u32()?;
+        let count = reader.u32()?;
+
+        let mut sections: BTreeMap<u32, Vec<&[u8]>> = BTreeMap::new();
+        for _ in 0..count {
+            let id = reader.u32()?;
+            let len = usize::try_from(reader.u64()?)
+                .map_err(|_| CircomError::Format(format!("{kind} section {id} too large")))?;
+            sections.entry(id).or_default().push(reader.take(len)?);
+        }
+        Ok(Self { kind, version, sections })
+    }
+
+    /// The single section with this id
+    pub fn section(&self, id: u32) -> Result<Reader<'a>, CircomError> {
+        match self.sections.get(&id).map(Vec::as_slice) {
+            Some([section]) => Ok(Reader::new(section, self.kind)),
+            Some(_) => Err(CircomError::Format(format!("{} section {id} repeated", self.kind))),
+            None => Err(CircomError::Format(format!("{} section {id} missing", self.kind))),
+        }
+    }
+}
+
+/// Little-endian cursor over one section
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    kind: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8], kind: &'static str) -> Self {
+        Self { bytes, kind }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], CircomError> {
+        if self.bytes.len() < len {
+            return Err(CircomError::Format(format!("{} file truncated", self.kind)));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub fn u32(&mut self) -> Result<u32, CircomError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("4 bytes")))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, CircomError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().expect("8 bytes")))
+    }
+
+    /// u32 count or index, as usize
+    pub fn len(&mut self) -> Result<usize, CircomError> {
+        Ok(self.u32()? as usize)
+    }
+
+    /// Check an `n8 || prime` header against the expected field
+    pub fn expect_prime<F: PrimeField>(&mut self, what: &str) -> Result<(), CircomError> {
+        let n8 = self.len()?;
+        let prime = self.take(n8)?;
+        if n8 != 32 || prime != F::MODULUS.to_bytes_le() {
+            return Err(CircomError::Unsupported(format!("{what} prime (only BN254 is supported)")));
+        }
+        Ok(())
+    }
+
+    /// Field element in standard form
+    pub fn fr(&mut self, field: &str) -> Result<Fr, CircomError> {
+        Fr::from_bigint(self.bigint()?).ok_or_else(|| CircomError::Encoding(field.to_string()))
+    }
+
+    /// Field element in Montgomery form
+    pub fn fr_montgomery(&mut self, field: &str) -> Result<Fr, CircomError> {
+        montgomery(self.bigint()?, field)
+    }
+
+    pub fn fq_montgomery(&mut self, field: &str) -> Result<Fq, CircomError> {
+        montgomery(self.bigint()?, field)
+    }
+
+    /// G1 point as Montgomery x || y
+    pub fn g1(&mut self, field: &str) -> Result<G1Affine, CircomError> {
+        let x = self.fq_montgomery(field)?;
+        let y = self.fq_montgomery(field)?;
+        if x == Fq::from(0u8) && y == Fq::from(0u8) {
+            return Ok(G1Affine::zero());
+        }
+        let point = G1Affine::new_unchecked(x, y);
+        if !point.is_on_curve() {
+            return Err(CircomError::OffCurve(field.to_string()));
+        }
+        Ok(point)
+    }
+
+    /// G2 point as Montgomery x.c0 || x.c1 || y.c0 || y.c1
+    pub fn g2(&mut self, field: &str) -> Result<G2Affine, CircomError> {
+        let x = Fq2::new(self.fq_montgomery(field)?, self.fq_montgomery(field)?);
+        let y = Fq2::new(self.fq_montgomery(field)?, self.fq_montgomery(field)?);
+        if x == Fq2::from(0u8) && y == Fq2::from(0u8) {
+            return Ok(G2Affine::zero());
+        }
+        let point = G2Affine::new_unchecked(x, y);
+        if !point.is_on_curve() {
+            return Err(CircomError::OffCurve(field.to_string()));
+        }
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(CircomError::NotInSubgroup(field.to_string()));
+        }
+        Ok(point)
+    }
+
+    /// A whole section of exactly `count` G1 points
+    pub fn g1_section(mut self, count: usize, field: &str) -> Result<Vec<G1Affine>, CircomError> {
+        self.expect_len(count, 64, field)?;
+        (0..count).map(|i| self.g1(&format!("{field}[{i}]"))).collect()
+    }
+
+    /// A whole section of exactly `count` G2 points
+    pub fn g2_section(mut self, count: usize, field: &str) -> Result<Vec<G2Affine>, CircomError> {
+        self.expect_len(count, 128, field)?;
+        (0..count).map(|i| self.g2(&format!("{field}[{i}]"))).collect()
+    }
+
+    fn expect_len(&self, count: usize, size: usize, field: &str) -> Result<(), CircomError> {
+        if self.remaining() != count * size {
+            return Err(CircomError::Format(format!(
+                "{field} section holds {} bytes, header implies {count} entries",
+                self.remaining()
+            )));
+        }
+        Ok(())
+    }
+
+    fn bigint(&mut self) -> Result<BigInt<4>, CircomError> {
+        let word = self.take(32)?;
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(word.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+        }
+        Ok(BigInt::new(limbs))
+    }
+}
+
+/// Interpret a Montgomery-form word, rejecting non-canonical ones
+fn montgomery<P: ark_ff::FpConfig<4>>(repr: BigInt<4>, field: &str) -> Result<ark_ff::Fp<P, 4>, CircomError> {
+    if repr >= P::MODULUS {
+        return Err(CircomError::Encoding(field.to_string()));
+    }
+    Ok(ark_ff::Fp(repr, core::marker::PhantomData))
+}
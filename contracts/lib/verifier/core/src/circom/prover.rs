@@ -0,0 +1,180 @@
+//! Groth16 proving over a `.zkey`, matching `snarkjs groth16 prove`
+//!
+//! The quotient is never divided out: A·B − C is evaluated on the odd
+//! powers of the doubled domain's generator (a coset where the vanishing
+//! polynomial is constant), and the zkey's H points are the matching
+//! Lagrange basis, already scaled by 1/δ.
+
+use alloc::{format, vec, vec::Vec};
+use ark_bn254::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::rand::RngCore;
+
+use super::{CircomError, Matrix, ProvingKey};
+use crate::Groth16Proof;
+
+/// Prove with fresh blinding; returns the proof and its public inputs
+///
+/// The witness is not checked against the constraints (see
+/// `R1cs::check_witness`), so an unsatisfying witness yields a
+/// well-formed proof that fails verification.
+pub fn prove<R: RngCore>(
+    pk: &ProvingKey,
+    witness: &[Fr],
+    rng: &mut R,
+) -> Result<(Groth16Proof, Vec<Fr>), CircomError> {
+    prove_with_randomness(pk, witness, Fr::rand(rng), Fr::rand(rng))
+}
+
+/// Prove with caller-chosen blinding factors r, s (deterministic proofs)
+pub fn prove_with_randomness(
+    pk: &ProvingKey,
+    witness: &[Fr],
+    r: Fr,
+    s: Fr,
+) -> Result<(Groth16Proof, Vec<Fr>), CircomError> {
+    if witness.len() != pk.n_vars {
+        return Err(CircomError::Witness(format!(
+            "{} values for a key over {} wires",
+            witness.len(),
+            pk.n_vars
+        )));
+    }
+    let h = quotient_evaluations(pk, witness)?;
+    let private = &witness[pk.n_public + 1..];
+
+    let a = pk.vk.alpha_g1 + msm_g1(&pk.a_query, witness) + pk.delta_g1 * r;
+    let b_g1 = pk.beta_g1 + msm_g1(&pk.b_g1_query, witness) + pk.delta_g1 * s;
+    let b = pk.vk.beta_g2 + msm_g2(&pk.b_g2_query, witness) + pk.vk.delta_g2 * s;
+    let c = msm_g1(&pk.c_query, private) + msm_g1(&pk.h_query, &h) + a * s + b_g1 * r
+        - pk.delta_g1 * (r * s);
+
+    let proof = Groth16Proof {
+        a: a.into_affine(),
+        b: b.into_affine(),
+        c: c.into_affine(),
+    };
+    Ok((proof, witness[1..=pk.n_public].to_vec()))
+}
+
+/// (A·B − C) on the coset ω₂ₙ·⟨ωₙ⟩, from the zkey's A and B coefficients
+fn quotient_evaluations(pk: &ProvingKey, witness: &[Fr]) -> Result<Vec<Fr>, CircomError> {
+    let n = pk.domain_size;
+    let domain = Radix2EvaluationDomain::<Fr>::new(n)
+        .ok_or_else(|| CircomError::Unsupported(format!("domain size {n}")))?;
+    let shift = Radix2EvaluationDomain::<Fr>::new(2 * n)
+        .ok_or_else(|| CircomError::Unsupported(format!("domain size {}", 2 * n)))?
+        .group_gen;
+
+    let mut a = vec![Fr::from(0u8); n];
+    let mut b = vec![Fr::from(0u8); n];
+    for coeff in &pk.coefficients {
+        let target = match coeff.matrix {
+            Matrix::A => &mut a,
+            Matrix::B => &mut b,
+        };
+        target[coeff.constraint] += coeff.value * witness[coeff.signal];
+    }
+    let mut c: Vec<Fr> = a.iter().zip(&b).map(|(a, b)| *a * b).collect();
+
+    for evals in [&mut a, &mut b, &mut c] {
+        domain.ifft_in_place(evals);
+        let mut power = Fr::ONE;
+        for coeff in evals.iter_mut() {
+            *coeff *= power;
+            power *= shift;
+        }
+        domain.fft_in_place(evals);
+    }
+
+    Ok(a.iter().zip(&b).zip(&c).map(|((a, b), c)| *a * b - c).collect())
+}
+
+fn msm_g1(bases: &[G1Affine], scalars: &[Fr]) -> G1Projective {
+    G1Projective::msm_unchecked(bases, scalars)
+}
+
+fn msm_g2(bases: &[G2Affine], scalars: &[Fr]) -> G2Projective {
+    G2Projective::msm_unchecked(bases, scalars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_files;
+    use super::*;
+    use crate::{circom::R1cs, verify_groth16};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_prove_from_zkey_and_wtns() {
+        let mut rng = StdRng::seed_from_u64(34);
+        let files = test_files::multiplier(&mut rng);
+        let pk = ProvingKey::from_bytes(&files.zkey).unwrap();
+        let r1cs = R1cs::from_bytes(&files.r1cs).unwrap();
+        assert_eq!((r1cs.n_wires, r1cs.n_public(), pk.n_public), (4, 1, 1));
+
+        let witness = crate::circom::read_wtns(&test_files::wtns(&files.witness(3, 5))).unwrap();
+        r1cs.check_witness(&witness).unwrap();
+        let (proof, inputs) = prove(&pk, &witness, &mut rng).unwrap();
+        assert_eq!(inputs, vec![Fr::from(15u64)]);
+        assert!(verify_groth16(&proof, &inputs, &pk.vk).unwrap());
+
+        // Fresh blinding gives a different, equally valid proof
+        let (again, _) = prove(&pk, &witness, &mut rng).unwrap();
+        assert_ne!(again.a, proof.a);
+        assert!(verify_groth16(&again, &inputs, &pk.vk).unwrap());
+
+        // A witness claiming 3 * 5 = 16 is caught by the r1cs, and its proof fails
+        let mut bad = witness.clone();
+        bad[1] = Fr::from(16u64);
+        assert!(r1cs.check_witness(&bad).is_err());
+        let (forged, inputs) = prove(&pk, &bad, &mut rng).unwrap();
+        assert!(!verify_groth16(&forged, &inputs, &pk.vk).unwrap());
+
+        assert!(prove(&pk, &witness[..3], &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_file_format_errors() {
+        let mut rng = StdRng::seed_from_u64(35);
+        let files = test_files::multiplier(&mut rng);
+
+        assert!(matches!(ProvingKey::from_bytes(&files.r1cs), Err(CircomError::Format(_))));
+        assert!(matches!(
+            ProvingKey::from_bytes(&files.zkey[..files.zkey.len() - 1]),
+            Err(CircomError::Format(_))
+        ));
+
+        let mut wtns = test_files::wtns(&files.witness(3, 5));
+        let last = wtns.len() - 1;
+        wtns[last] = 0xff;
+        assert!(matches!(crate::circom::read_wtns(&wtns), Err(CircomError::Encoding(_))));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        /// Corrupt headers, counts and lengths come back as errors, never as
+        /// panics or unbounded allocations
+        #[test]
+        fn test_corrupted_files_never_panic(index in any::<usize>(), mask in 1..=u8::MAX, cut in any::<usize>()) {
+            let mut rng = StdRng::seed_from_u64(36);
+            let files = test_files::multiplier(&mut rng);
+            let wtns = test_files::wtns(&files.witness(3, 5));
+
+            for file in [&files.r1cs, &files.zkey, &wtns] {
+                let mut mutated = file.clone();
+                mutated[index % file.len()] ^= mask;
+                for bytes in [&mutated[..], &file[..cut % file.len()]] {
+                    let _ = R1cs::from_bytes(bytes);
+                    let _ = ProvingKey::from_bytes(bytes);
+                    let _ = crate::circom::verifying_key_from_zkey(bytes);
+                    let _ = crate::circom::read_wtns(bytes);
+                }
+            }
+        }
+    }
+}
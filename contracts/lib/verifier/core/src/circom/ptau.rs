@@ -0,0 +1,111 @@
+//! snarkjs `.ptau` powers of tau (phase 1), prepared for phase 2
+//!
+//! Header (section 1): n8 || q || power || ceremonyPower. Sections 2-6 hold
+//! [τⁱ]₁, [τⁱ]₂, [ατⁱ]₁, [βτⁱ]₁ and [β]₂; 7 the phase-1 contributions.
+//! `snarkjs powersoftau prepare phase2` adds the same values in the
+//! Lagrange basis of every domain 2ᵏ, k ≤ power, concatenated in order of
+//! k: 12 [Lᵢ(τ)]₁, 13 [Lᵢ(τ)]₂, 14 [αLᵢ(τ)]₁, 15 [βLᵢ(τ)]₁.
+
+use alloc::{format, vec::Vec};
+use ark_bn254::{Fq, G1Affine, G2Affine};
+
+use super::{BinFile, CircomError, Reader};
+
+pub(crate) const LAGRANGE_TAU_G1: u32 = 12;
+pub(crate) const LAGRANGE_TAU_G2: u32 = 13;
+pub(crate) const LAGRANGE_ALPHA_TAU_G1: u32 = 14;
+pub(crate) const LAGRANGE_BETA_TAU_G1: u32 = 15;
+
+/// A phase-1 file, read lazily: only the points a check asks for are decoded
+pub(crate) struct PowersOfTau<'a> {
+    file: BinFile<'a>,
+    /// Largest domain is 2^power
+    pub power: u32,
+    pub ceremony_power: u32,
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub contributions: usize,
+}
+
+impl<'a> PowersOfTau<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CircomError> {
+        let file = BinFile::parse(bytes, b"ptau", "ptau")?;
+        if file.version != 1 {
+            return Err(CircomError::Unsupported(format!("ptau version {}", file.version)));
+        }
+        let mut header = file.section(1)?;
+        header.expect_prime::<Fq>("ptau")?;
+        let power = header.u32()?;
+        let ceremony_power = header.u32()?;
+        if power == 0 || power > 28 {
+            return Err(CircomError::Format(format!("ptau power {power}")));
+        }
+
+        Ok(Self {
+            power,
+            ceremony_power,
+            alpha_g1: file.section(4)?.g1("alphaTauG1[0]")?,
+            beta_g1: file.section(5)?.g1("betaTauG1[0]")?,
+            beta_g2: file.section(6)?.g2("betaG2")?,
+            contributions: file.section(7)?.len()?,
+            file,
+        })
+    }
+
+    /// The 2ᵏ Lagrange-basis G1 points of one domain from section 12, 14 or 15
+    pub fn lagrange_g1(&self, section: u32, domain_size: usize) -> Result<Vec<G1Affine>, CircomError> {
+        let field = format!("ptau section {section}");
+        self.lagrange(section, domain_size, 64)?.g1_section(domain_size, &field)
+    }
+
+    /// The 2ᵏ Lagrange-basis G2 points of one domain from section 13
+    pub fn lagrange_g2(&self, domain_size: usize) -> Result<Vec<G2Affine>, CircomError> {
+        let field = format!("ptau section {LAGRANGE_TAU_G2}");
+        self.lagrange(LAGRANGE_TAU_G2, domain_size, 128)?.g2_section(domain_size, &field)
+    }
+
+    fn lagrange(&self, section: u32, domain_size: usize, point_size: usize) -> Result<Reader<'a>, CircomError> {
+        if !domain_size.is_power_of_two() || domain_size > 1 << self.power {
+            return Err(CircomError::Unsupported(format!(
+                "domain {domain_size} from a ptau of power {}",
+                self.power
+            )));
+        }
+        let mut reader = self.file.section(section).map_err(|_| {
+            CircomError::Format(format!("ptau section {section} missing (run `snarkjs powersoftau prepare phase2`)"))
+        })?;
+        if reader.remaining() != ((2 << self.power) - 1) * point_size {
+            return Err(CircomError::Format(format!("ptau section {section} does not match power {}", self.power)));
+        }
+        reader.take((domain_size - 1) * point_size)?;
+        let points = reader.take(domain_size * point_size)?;
+        Ok(Reader::new(points, "ptau"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_files::{self, ToxicWaste};
+    use super::*;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_corrupted_ptau_never_panics(index in any::<usize>(), mask in 1..=u8::MAX, domain in 0usize..5) {
+            let waste = ToxicWaste::random(&mut StdRng::seed_from_u64(37));
+            let mut ptau = test_files::ptau_bytes(2, &waste);
+            let index = index % ptau.len();
+            ptau[index] ^= mask;
+
+            if let Ok(ptau) = PowersOfTau::from_bytes(&ptau) {
+                let _ = ptau.lagrange_g1(LAGRANGE_TAU_G1, domain);
+                let _ = ptau.lagrange_g1(LAGRANGE_BETA_TAU_G1, domain);
+                let _ = ptau.lagrange_g2(domain);
+            }
+        }
+    }
+}
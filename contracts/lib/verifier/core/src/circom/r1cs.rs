@@ -0,0 +1,106 @@
+//! circom `.r1cs` constraint systems
+//!
+//! Header (section 1): n8 || prime || nWires || nPubOut || nPubIn || nPrvIn
+//! || nLabels (u64) || nConstraints. Constraints (section 2): for each of
+//! A, B, C a term count followed by (wire u32, coefficient) pairs, with
+//! coefficients in standard form.
+
+use alloc::{format, vec::Vec};
+use ark_bn254::Fr;
+use ark_ff::Zero;
+
+use super::{BinFile, CircomError, Reader};
+
+/// One constraint A·w × B·w = C·w, as sparse (wire, coefficient) terms
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Constraint {
+    pub a: Vec<(usize, Fr)>,
+    pub b: Vec<(usize, Fr)>,
+    pub c: Vec<(usize, Fr)>,
+}
+
+/// A circom circuit's constraint system
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct R1cs {
+    /// Witness length, including the constant-one wire 0
+    pub n_wires: usize,
+    pub n_pub_out: usize,
+    pub n_pub_in: usize,
+    pub n_prv_in: usize,
+    pub constraints: Vec<Constraint>,
+}
+
+impl R1cs {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircomError> {
+        let file = BinFile::parse(bytes, b"r1cs", "r1cs")?;
+        if file.version != 1 {
+            return Err(CircomError::Unsupported(format!("r1cs version {}", file.version)));
+        }
+
+        let mut header = file.section(1)?;
+        header.expect_prime::<Fr>("r1cs")?;
+        let n_wires = header.len()?;
+        let n_pub_out = header.len()?;
+        let n_pub_in = header.len()?;
+        let n_prv_in = header.len()?;
+        let _n_labels = header.u64()?;
+        let n_constraints = header.len()?;
+        if 1 + n_pub_out + n_pub_in + n_prv_in > n_wires {
+            return Err(CircomError::Format("r1cs signal counts exceed wire count".into()));
+        }
+
+        let mut section = file.section(2)?;
+        let constraints = (0..n_constraints)
+            .map(|i| {
+                Ok(Constraint {
+                    a: read_terms(&mut section, n_wires, i)?,
+                    b: read_terms(&mut section, n_wires, i)?,
+                    c: read_terms(&mut section, n_wires, i)?,
+                })
+            })
+            .collect::<Result<_, CircomError>>()?;
+
+        Ok(Self { n_wires, n_pub_out, n_pub_in, n_prv_in, constraints })
+    }
+
+    /// Public signals: outputs, then public inputs (wires 1..=n_public)
+    pub fn n_public(&self) -> usize {
+        self.n_pub_out + self.n_pub_in
+    }
+
+    /// Check a full witness; on failure, the index of the first unsatisfied constraint
+    pub fn check_witness(&self, witness: &[Fr]) -> Result<(), CircomError> {
+        if witness.len() != self.n_wires {
+            return Err(CircomError::Witness(format!(
+                "{} values for {} wires",
+                witness.len(),
+                self.n_wires
+            )));
+        }
+        if witness[0] != Fr::from(1u8) {
+            return Err(CircomError::Witness("wire 0 must be 1".into()));
+        }
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let eval = |terms: &[(usize, Fr)]| -> Fr { terms.iter().map(|&(wire, coeff)| coeff * witness[wire]).sum() };
+            if eval(&constraint.a) * eval(&constraint.b) != eval(&constraint.c) {
+                return Err(CircomError::Witness(format!("constraint {index} not satisfied")));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_terms(section: &mut Reader<'_>, n_wires: usize, constraint: usize) -> Result<Vec<(usize, Fr)>, CircomError> {
+    let count = section.len()?;
+    (0..count)
+        .map(|_| {
+            let wire = section.len()?;
+            if wire >= n_wires {
+                return Err(CircomError::Format(format!("constraint {constraint} uses wire {wire}")));
+            }
+            let coeff = section.fr(&format!("constraint {constraint} coefficient"))?;
+            Ok((wire, coeff))
+        })
+        .filter(|term| !matches!(term, Ok((_, coeff)) if coeff.is_zero()))
+        .collect()
+}
@@ -0,0 +1,424 @@
+//! Test-only writers for `.r1cs`, `.wtns` and `.zkey`, plus a Groth16
+//! setup with known toxic waste laid out the way `snarkjs groth16 setup`
+//! lays it out. No snarkjs is needed to exercise the readers and prover.
+
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ff::{BigInteger, Field, PrimeField, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::rand::RngCore;
+
+use super::{
+    zkey::{GROTH16_PROTOCOL, PLONK_PROTOCOL},
+    Constraint, PlonkVerifyingKey, R1cs,
+};
+
+/// Secrets of a test setup
+pub struct ToxicWaste {
+    pub tau: Fr,
+    pub alpha: Fr,
+    pub beta: Fr,
+    pub gamma: Fr,
+    pub delta: Fr,
+}
+
+impl ToxicWaste {
+    pub fn random<R: RngCore>(rng: &mut R) -> Self {
+        Self {
+            tau: Fr::rand(rng),
+            alpha: Fr::rand(rng),
+            beta: Fr::rand(rng),
+            gamma: Fr::rand(rng),
+            delta: Fr::rand(rng),
+        }
+    }
+}
+
+/// multiplier.circom: wires [1, c, a, b], one constraint a × b = c
+pub struct MultiplierFiles {
+    pub r1cs: Vec<u8>,
+    pub zkey: Vec<u8>,
+}
+
+impl MultiplierFiles {
+    pub fn witness(&self, a: u64, b: u64) -> Vec<Fr> {
+        vec![Fr::ONE, Fr::from(a * b), Fr::from(a), Fr::from(b)]
+    }
+}
+
+pub fn multiplier_r1cs() -> R1cs {
+    R1cs {
+        n_wires: 4,
+        n_pub_out: 1,
+        n_pub_in: 0,
+        n_prv_in: 2,
+        constraints: vec![Constraint {
+            a: vec![(2, Fr::ONE)],
+            b: vec![(3, Fr::ONE)],
+            c: vec![(1, Fr::ONE)],
+        }],
+    }
+}
+
+pub fn multiplier<R: RngCore>(rng: &mut R) -> MultiplierFiles {
+    let r1cs = multiplier_r1cs();
+    let waste = ToxicWaste::random(rng);
+    MultiplierFiles { r1cs: r1cs_bytes(&r1cs), zkey: zkey_bytes(&r1cs, &waste) }
+}
+
+// ============================================================================
+// ENCODING
+// ============================================================================
+
+pub fn bin_file(magic: &[u8; 4], version: u32, sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.extend(version.to_le_bytes());
+    out.extend((sections.len() as u32).to_le_bytes());
+    for (id, data) in sections {
+        out.extend(id.to_le_bytes());
+        out.extend((data.len() as u64).to_le_bytes());
+        out.extend(data);
+    }
+    out
+}
+
+pub fn put_u32(buf: &mut Vec<u8>, value: usize) {
+    buf.extend((value as u32).to_le_bytes());
+}
+
+pub fn put_prime<F: PrimeField>(buf: &mut Vec<u8>) {
+    put_u32(buf, 32);
+    buf.extend(F::MODULUS.to_bytes_le());
+}
+
+pub fn put_fr(buf: &mut Vec<u8>, value: &Fr) {
+    buf.extend(value.into_bigint().to_bytes_le());
+}
+
+fn put_fq_montgomery(buf: &mut Vec<u8>, value: &Fq) {
+    buf.extend(value.0.to_bytes_le());
+}
+
+pub fn put_g1(buf: &mut Vec<u8>, point: &G1Affine) {
+    match point.xy() {
+        Some((x, y)) => [x, y].iter().for_each(|c| put_fq_montgomery(buf, c)),
+        None => buf.extend([0u8; 64]),
+    }
+}
+
+pub fn put_g2(buf: &mut Vec<u8>, point: &G2Affine) {
+    match point.xy() {
+        Some((x, y)) => [x.c0, x.c1, y.c0, y.c1].iter().for_each(|c| put_fq_montgomery(buf, c)),
+        None => buf.extend([0u8; 128]),
+    }
+}
+
+pub fn r1cs_bytes(r1cs: &R1cs) -> Vec<u8> {
+    let mut header = Vec::new();
+    put_prime::<Fr>(&mut header);
+    for count in [r1cs.n_wires, r1cs.n_pub_out, r1cs.n_pub_in, r1cs.n_prv_in] {
+        put_u32(&mut header, count);
+    }
+    header.extend((r1cs.n_wires as u64).to_le_bytes());
+    put_u32(&mut header, r1cs.constraints.len());
+
+    let mut constraints = Vec::new();
+    for constraint in &r1cs.constraints {
+        for terms in [&constraint.a, &constraint.b, &constraint.c] {
+            put_u32(&mut constraints, terms.len());
+            for (wire, coeff) in terms {
+                put_u32(&mut constraints, *wire);
+                put_fr(&mut constraints, coeff);
+            }
+        }
+    }
+    bin_file(b"r1cs", 1, &[(1, header), (2, constraints)])
+}
+
+pub fn wtns(witness: &[Fr]) -> Vec<u8> {
+    let mut header = Vec::new();
+    put_prime::<Fr>(&mut header);
+    put_u32(&mut header, witness.len());
+    let mut values = Vec::new();
+    witness.iter().for_each(|value| put_fr(&mut values, value));
+    bin_file(b"wtns", 2, &[(1, header), (2, values)])
+}
+
+// ============================================================================
+// SETUP
+// ============================================================================
+
+/// QAP rows: the circuit's constraints, then A = w_i for each public wire i
+fn qap_rows(r1cs: &R1cs) -> Vec<Constraint> {
+    let mut rows = r1cs.constraints.clone();
+    rows.extend((0..=r1cs.n_public()).map(|i| Constraint { a: vec![(i, Fr::ONE)], ..Default::default() }));
+    rows
+}
+
+pub fn domain_size(r1cs: &R1cs) -> usize {
+    (r1cs.constraints.len() + r1cs.n_public() + 1).next_power_of_two()
+}
+
+/// A `.zkey` for the circuit with the given secrets and no contributions
+pub fn zkey_bytes(r1cs: &R1cs, waste: &ToxicWaste) -> Vec<u8> {
+    let n = domain_size(r1cs);
+    let n_vars = r1cs.n_wires;
+    let n_public = r1cs.n_public();
+    let rows = qap_rows(r1cs);
+    let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+    let lagrange = domain.evaluate_all_lagrange_coefficients(waste.tau);
+
+    let (mut u, mut v, mut w) = (vec![Fr::ZERO; n_vars], vec![Fr::ZERO; n_vars], vec![Fr::ZERO; n_vars]);
+    for (row, constraint) in rows.iter().enumerate() {
+        for (polys, terms) in [(&mut u, &constraint.a), (&mut v, &constraint.b), (&mut w, &constraint.c)] {
+            for (wire, coeff) in terms {
+                polys[*wire] += *coeff * lagrange[row];
+            }
+        }
+    }
+    let combined = |i: usize| waste.beta * u[i] + waste.alpha * v[i] + w[i];
+
+    let g1 = |scalar: Fr| (G1Projective::generator() * scalar).into_affine();
+    let g2 = |scalar: Fr| (G2Projective::generator() * scalar).into_affine();
+    let (gamma_inv, delta_inv) = (waste.gamma.inverse().unwrap(), waste.delta.inverse().unwrap());
+
+    let mut header = Vec::new();
+    put_prime::<Fq>(&mut header);
+    put_prime::<Fr>(&mut header);
+    for count in [n_vars, n_public, n] {
+        put_u32(&mut header, count);
+    }
+    put_g1(&mut header, &g1(waste.alpha));
+    put_g1(&mut header, &g1(waste.beta));
+    put_g2(&mut header, &g2(waste.beta));
+    put_g2(&mut header, &g2(waste.gamma));
+    put_g1(&mut header, &g1(waste.delta));
+    put_g2(&mut header, &g2(waste.delta));
+
+    let points_g1 = |scalars: &mut dyn Iterator<Item = Fr>| {
+        let mut buf = Vec::new();
+        scalars.for_each(|scalar| put_g1(&mut buf, &g1(scalar)));
+        buf
+    };
+    let ic = points_g1(&mut (0..=n_public).map(|i| combined(i) * gamma_inv));
+
+    let mut coefficients = Vec::new();
+    let entries: Vec<_> = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(row, c)| {
+            let a = c.a.iter().map(move |term| (0, row, term));
+            let b = c.b.iter().map(move |term| (1, row, term));
+            a.chain(b)
+        })
+        .collect();
+    put_u32(&mut coefficients, entries.len());
+    for (matrix, row, (wire, value)) in entries {
+        put_u32(&mut coefficients, matrix);
+        put_u32(&mut coefficients, row);
+        put_u32(&mut coefficients, *wire);
+        // value·R² on disk
+        coefficients.extend(Fr::from_bigint(value.0).unwrap().0.to_bytes_le());
+    }
+
+    let a_query = points_g1(&mut u.iter().copied());
+    let b_g1_query = points_g1(&mut v.iter().copied());
+    let mut b_g2_query = Vec::new();
+    v.iter().for_each(|scalar| put_g2(&mut b_g2_query, &g2(*scalar)));
+    let c_query = points_g1(&mut (n_public + 1..n_vars).map(|i| combined(i) * delta_inv));
+
+    let doubled = Radix2EvaluationDomain::<Fr>::new(2 * n).unwrap();
+    let odd = doubled.evaluate_all_lagrange_coefficients(waste.tau);
+    let h_query = points_g1(&mut (0..n).map(|j| odd[2 * j + 1] * delta_inv));
+
+    let mut contributions = vec![0u8; 64];
+    put_u32(&mut contributions, 0);
+
+    let mut protocol = Vec::new();
+    put_u32(&mut protocol, GROTH16_PROTOCOL as usize);
+    bin_file(
+        b"zkey",
+        1,
+        &[
+            (1, protocol),
+            (2, header),
+            (3, ic),
+            (4, coefficients),
+            (5, a_query),
+            (6, b_g1_query),
+            (7, b_g2_query),
+            (8, c_query),
+            (9, h_query),
+            (10, contributions),
+        ],
+    )
+}
+
+/// Split a file back into (id, payload) sections, in file order
+pub fn sections(bytes: &[u8]) -> Vec<(u32, Vec<u8>)> {
+    let count = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+    let mut offset = 12;
+    (0..count)
+        .map(|_| {
+            let id = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            let len = u64::from_le_bytes(bytes[offset + 4..offset + 12].try_into().unwrap()) as usize;
+            offset += 12 + len;
+            (id, bytes[offset - len..offset].to_vec())
+        })
+        .collect()
+}
+
+/// A PLONK `.zkey` holding just the protocol id and header
+pub fn plonk_zkey(vk: &PlonkVerifyingKey) -> Vec<u8> {
+    let mut header = Vec::new();
+    put_prime::<Fq>(&mut header);
+    put_prime::<Fr>(&mut header);
+    for count in [vk.n_public + 10, vk.n_public, 1 << vk.power, 0, 1 << (vk.power - 1)] {
+        put_u32(&mut header, count);
+    }
+    header.extend(vk.k1.0.to_bytes_le());
+    header.extend(vk.k2.0.to_bytes_le());
+    for point in [&vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3] {
+        put_g1(&mut header, point);
+    }
+    put_g2(&mut header, &vk.x_2);
+
+    let mut protocol = Vec::new();
+    put_u32(&mut protocol, PLONK_PROTOCOL as usize);
+    bin_file(b"zkey", 1, &[(1, protocol), (2, header)])
+}
+
+// ============================================================================
+// CEREMONY
+// ============================================================================
+
+/// A prepared `.ptau` from known τ, α, β (γ and δ unused), no contributions
+#[cfg(feature = "ceremony")]
+pub fn ptau_bytes(power: u32, waste: &ToxicWaste) -> Vec<u8> {
+    let g1 = |scalar: Fr| (G1Projective::generator() * scalar).into_affine();
+    let g2 = |scalar: Fr| (G2Projective::generator() * scalar).into_affine();
+    let powers = |count: usize| -> Vec<Fr> { (0..count).scan(Fr::ONE, |acc, _| Some(core::mem::replace(acc, *acc * waste.tau))).collect() };
+    let n = 1usize << power;
+
+    let mut header = Vec::new();
+    put_prime::<Fq>(&mut header);
+    put_u32(&mut header, power as usize);
+    put_u32(&mut header, power as usize);
+
+    let mut sections = vec![(1, header)];
+    let g1_points = |scalars: &mut dyn Iterator<Item = Fr>| {
+        let mut buf = Vec::new();
+        scalars.for_each(|scalar| put_g1(&mut buf, &g1(scalar)));
+        buf
+    };
+    let g2_points = |scalars: &mut dyn Iterator<Item = Fr>| {
+        let mut buf = Vec::new();
+        scalars.for_each(|scalar| put_g2(&mut buf, &g2(scalar)));
+        buf
+    };
+    sections.push((2, g1_points(&mut powers(2 * n - 1).into_iter())));
+    sections.push((3, g2_points(&mut powers(n).into_iter())));
+    sections.push((4, g1_points(&mut powers(n).into_iter().map(|p| waste.alpha * p))));
+    sections.push((5, g1_points(&mut powers(n).into_iter().map(|p| waste.beta * p))));
+    sections.push((6, g2_points(&mut [waste.beta].into_iter())));
+    sections.push((7, vec![0u8; 4]));
+
+    let lagrange: Vec<Fr> = (0..=power)
+        .flat_map(|k| Radix2EvaluationDomain::<Fr>::new(1 << k).unwrap().evaluate_all_lagrange_coefficients(waste.tau))
+        .collect();
+    sections.push((12, g1_points(&mut lagrange.iter().copied())));
+    sections.push((13, g2_points(&mut lagrange.iter().copied())));
+    sections.push((14, g1_points(&mut lagrange.iter().map(|l| waste.alpha * l))));
+    sections.push((15, g1_points(&mut lagrange.iter().map(|l| waste.beta * l))));
+    bin_file(b"ptau", 1, &sections)
+}
+
+/// Multiply δ by `factor` in the header and, if `queries`, divide C and H by it
+#[cfg(feature = "ceremony")]
+pub fn scale_delta(zkey: &[u8], factor: Fr, queries: bool) -> Vec<u8> {
+    let pk = super::ProvingKey::from_bytes(zkey).unwrap();
+    let inverse = factor.inverse().unwrap();
+    let scaled_g1 = |points: &[G1Affine], by: Fr| {
+        let mut buf = Vec::new();
+        points.iter().for_each(|point| put_g1(&mut buf, &(*point * by).into_affine()));
+        buf
+    };
+
+    let sections: Vec<_> = sections(zkey)
+        .into_iter()
+        .map(|(id, mut data)| {
+            match id {
+                2 => {
+                    // δ₁ and δ₂ close the header, after n8q || q || n8r || r || 3 counts and α₁, β₁, β₂, γ₂
+                    let start = data.len() - 192;
+                    data.truncate(start);
+                    put_g1(&mut data, &(pk.delta_g1 * factor).into_affine());
+                    put_g2(&mut data, &(pk.vk.delta_g2 * factor).into_affine());
+                }
+                8 if queries => data = scaled_g1(&pk.c_query, inverse),
+                9 if queries => data = scaled_g1(&pk.h_query, inverse),
+                _ => {}
+            }
+            (id, data)
+        })
+        .collect();
+    bin_file(b"zkey", 1, &sections)
+}
+
+/// Apply one phase-2 contribution `x` the way `snarkjs zkey contribute`
+/// (or `zkey beacon`, with a beacon) does
+#[cfg(feature = "ceremony")]
+pub fn contribute<R: RngCore>(
+    zkey: &[u8],
+    x: Fr,
+    name: Option<&str>,
+    beacon: Option<&super::Beacon>,
+    rng: &mut R,
+) -> Vec<u8> {
+    use super::ceremony::{contribution_transcript, hash_to_g2, read_contributions};
+
+    let (cs_hash, mut contributions) = read_contributions(zkey).unwrap();
+    let zkey = scale_delta(zkey, x, true);
+    let g1_s = (G1Projective::generator() * Fr::rand(rng)).into_affine();
+    let g1_sx = (g1_s * x).into_affine();
+    let transcript = contribution_transcript(&cs_hash, &contributions, &g1_s, &g1_sx);
+    contributions.push(super::Contribution {
+        name: name.map(Into::into),
+        beacon: beacon.cloned(),
+        delta_after: super::ProvingKey::from_bytes(&zkey).unwrap().delta_g1,
+        g1_s,
+        g1_sx,
+        g2_spx: (hash_to_g2(&transcript) * x).into_affine(),
+        transcript,
+    });
+
+    let mut records = cs_hash.to_vec();
+    put_u32(&mut records, contributions.len());
+    for contribution in &contributions {
+        put_g1(&mut records, &contribution.delta_after);
+        put_g1(&mut records, &contribution.g1_s);
+        put_g1(&mut records, &contribution.g1_sx);
+        put_g2(&mut records, &contribution.g2_spx);
+        records.extend(contribution.transcript);
+
+        let mut params = Vec::new();
+        if let Some(name) = &contribution.name {
+            params.extend([1, name.len() as u8]);
+            params.extend(name.as_bytes());
+        }
+        if let Some(beacon) = &contribution.beacon {
+            params.extend([2, beacon.iterations_exp, 3, beacon.hash.len() as u8]);
+            params.extend(&beacon.hash);
+        }
+        put_u32(&mut records, contribution.beacon.is_some() as usize);
+        put_u32(&mut records, params.len());
+        records.extend(params);
+    }
+
+    let sections: Vec<_> = sections(&zkey)
+        .into_iter()
+        .map(|(id, data)| (id, if id == 10 { records.clone() } else { data }))
+        .collect();
+    bin_file(b"zkey", 1, &sections)
+}
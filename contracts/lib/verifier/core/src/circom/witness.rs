@@ -0,0 +1,274 @@
+//! circom 2 `.wasm` witness generators, run in an interpreter
+//!
+//! Mirrors circom's `witness_calculator.js`: inputs are addressed by the
+//! FNV-1a 64 hash of their name and passed through a shared buffer of
+//! n32 little-endian u32 limbs, one field element at a time.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use serde_json::Value;
+use wasmi::{Caller, Engine, Extern, Instance, Linker, Module, Store, TypedFunc};
+
+use super::{CircomError, Reader};
+use crate::snarkjs::parse_decimal;
+
+/// Messages the generator reported through its runtime imports
+#[derive(Debug, Default)]
+struct Runtime {
+    errors: String,
+}
+
+/// A loaded witness generator; reusable across witnesses
+pub struct WitnessCalculator {
+    store: Store<Runtime>,
+    instance: Instance,
+    n32: usize,
+}
+
+impl WitnessCalculator {
+    pub fn from_wasm(wasm: &[u8]) -> Result<Self, CircomError> {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm).map_err(wasm_error)?;
+        let mut store = Store::new(&engine, Runtime::default());
+
+        let mut linker = Linker::<Runtime>::new(&engine);
+        linker
+            .func_wrap("runtime", "exceptionHandler", |caller: Caller<'_, Runtime>, code: i32| {
+                let reason = match code {
+                    1 => "signal not found",
+                    2 => "too many signals set",
+                    3 => "signal already set",
+                    4 => "assert failed",
+                    5 => "not enough memory",
+                    6 => "input signal array access exceeds the size",
+                    _ => "unknown error",
+                };
+                let detail = caller.data().errors.trim_end().to_string();
+                Err::<(), _>(wasmi::Error::new(if detail.is_empty() {
+                    reason.to_string()
+                } else {
+                    format!("{reason}: {detail}")
+                }))
+            })
+            .and_then(|linker| {
+                linker.func_wrap("runtime", "printErrorMessage", |mut caller: Caller<'_, Runtime>| {
+                    let message = read_message(&mut caller);
+                    caller.data_mut().errors.push_str(&message);
+                    caller.data_mut().errors.push('\n');
+                })
+            })
+            .and_then(|linker| linker.func_wrap("runtime", "writeBufferMessage", |_: Caller<'_, Runtime>| {}))
+            .and_then(|linker| linker.func_wrap("runtime", "showSharedRWMemory", |_: Caller<'_, Runtime>| {}))
+            .map_err(|err| CircomError::Wasm(err.to_string()))?;
+
+        let instance = linker.instantiate_and_start(&mut store, &module).map_err(wasm_error)?;
+        let mut calculator = Self { store, instance, n32: 0 };
+
+        let version: i32 = calculator.call("getVersion", ())?;
+        if version != 2 {
+            return Err(CircomError::Unsupported(format!("circom witness generator version {version}")));
+        }
+        calculator.n32 = calculator.call::<(), i32>("getFieldNumLen32", ())? as usize;
+        calculator.call::<(), ()>("getRawPrime", ())?;
+        if calculator.n32 != 8 || calculator.read_shared()? != Fr::MODULUS.to_bytes_le() {
+            return Err(CircomError::Unsupported("witness generator prime (only BN254 is supported)".into()));
+        }
+        Ok(calculator)
+    }
+
+    /// Full witness for named inputs, each a flattened signal array
+    pub fn calculate(&mut self, inputs: &[(String, Vec<Fr>)]) -> Result<Vec<Fr>, CircomError> {
+        self.store.data_mut().errors.clear();
+        self.call::<i32, ()>("init", 1)?;
+
+        for (name, values) in inputs {
+            let hash = fnv1a64(name);
+            let (msb, lsb) = ((hash >> 32) as i32, hash as i32);
+            let size: i32 = self.call("getInputSignalSize", (msb, lsb))?;
+            if size < 0 {
+                return Err(CircomError::Witness(format!("circuit has no input {name}")));
+            }
+            if values.len() != size as usize {
+                return Err(CircomError::Witness(format!("input {name} takes {size} values, got {}", values.len())));
+            }
+            for (index, value) in values.iter().enumerate() {
+                self.write_shared(value)?;
+                self.call::<(i32, i32, i32), ()>("setInputSignal", (msb, lsb, index as i32))?;
+            }
+        }
+
+        let size: i32 = self.call("getWitnessSize", ())?;
+        (0..size)
+            .map(|index| {
+                self.call::<i32, ()>("getWitness", index)?;
+                let bytes = self.read_shared()?;
+                Reader::new(&bytes, "witness").fr(&format!("witness[{index}]"))
+            })
+            .collect()
+    }
+
+    /// Full witness for a circom `input.json`: names to numbers, decimal
+    /// strings or nested arrays of them
+    pub fn calculate_json(&mut self, json: &str) -> Result<Vec<Fr>, CircomError> {
+        let input: serde_json::Map<String, Value> =
+            serde_json::from_str(json).map_err(|err| CircomError::Format(format!("input JSON: {err}")))?;
+        let inputs = input
+            .into_iter()
+            .map(|(name, value)| {
+                let mut values = Vec::new();
+                flatten_input(&name, &value, &mut values)?;
+                Ok((name, values))
+            })
+            .collect::<Result<Vec<_>, CircomError>>()?;
+        self.calculate(&inputs)
+    }
+
+    fn call<P: wasmi::WasmParams, R: wasmi::WasmResults>(&mut self, name: &str, params: P) -> Result<R, CircomError> {
+        let func: TypedFunc<P, R> = self
+            .instance
+            .get_typed_func(&self.store, name)
+            .map_err(|err| CircomError::Wasm(format!("{name}: {err}")))?;
+        func.call(&mut self.store, params).map_err(|err| CircomError::Wasm(format!("{name}: {err}")))
+    }
+
+    fn read_shared(&mut self) -> Result<Vec<u8>, CircomError> {
+        let mut bytes = Vec::with_capacity(self.n32 * 4);
+        for limb in 0..self.n32 {
+            let word: i32 = self.call("readSharedRWMemory", limb as i32)?;
+            bytes.extend(word.to_le_bytes());
+        }
+        Ok(bytes)
+    }
+
+    fn write_shared(&mut self, value: &Fr) -> Result<(), CircomError> {
+        let bytes = value.into_bigint().to_bytes_le();
+        for (limb, word) in bytes.chunks_exact(4).enumerate() {
+            let word = i32::from_le_bytes(word.try_into().expect("4 bytes"));
+            self.call::<(i32, i32), ()>("writeSharedRWMemory", (limb as i32, word))?;
+        }
+        Ok(())
+    }
+}
+
+/// Hash circom uses to address input signals
+pub(crate) fn fnv1a64(name: &str) -> u64 {
+    name.bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn flatten_input(name: &str, value: &Value, out: &mut Vec<Fr>) -> Result<(), CircomError> {
+    let decimal = match value {
+        Value::Array(items) => return items.iter().try_for_each(|item| flatten_input(name, item, out)),
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => u8::from(*flag).to_string(),
+        Value::Null | Value::Object(_) => return Err(CircomError::Encoding(format!("input {name}"))),
+    };
+    let (negative, digits) = match decimal.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, decimal.as_str()),
+    };
+    let value: Fr = parse_decimal(digits).ok_or_else(|| CircomError::Encoding(format!("input {name}")))?;
+    out.push(if negative { -value } else { value });
+    Ok(())
+}
+
+/// Read the generator's pending message through `getMessageChar`
+fn read_message(caller: &mut Caller<'_, Runtime>) -> String {
+    let Some(Extern::Func(func)) = caller.get_export("getMessageChar") else {
+        return String::new();
+    };
+    let Ok(func) = func.typed::<(), i32>(&*caller) else {
+        return String::new();
+    };
+    let mut message = String::new();
+    while let Ok(c) = func.call(&mut *caller, ()) {
+        match u8::try_from(c) {
+            Ok(0) | Err(_) => break,
+            Ok(byte) => message.push(char::from(byte)),
+        }
+    }
+    message
+}
+
+fn wasm_error(err: wasmi::Error) -> CircomError {
+    CircomError::Wasm(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Stand-in for circom's multiplier generator: the same exports and
+    /// shared-buffer protocol, multiplying the low limbs only
+    fn mock_multiplier_wasm() -> Vec<u8> {
+        let limbs = Fr::MODULUS.to_bytes_le();
+        let prime: String = limbs
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(i, w)| format!("(i32.store (i32.const {}) (i32.const {}))", i * 4, i32::from_le_bytes(w.try_into().unwrap())))
+            .collect();
+        let (a, b) = (fnv1a64("a"), fnv1a64("b"));
+        let slot = |hash: u64| {
+            format!(
+                "(if (i32.and (i32.eq (local.get 0) (i32.const {})) (i32.eq (local.get 1) (i32.const {}))) (then (return (i32.const {}))))",
+                (hash >> 32) as i32,
+                hash as i32,
+                if hash == a { 2 } else { 3 }
+            )
+        };
+        wat::parse_str(format!(
+            r#"(module
+              (import "runtime" "exceptionHandler" (func $exception (param i32)))
+              (memory 1)
+              (func $slot (param i32 i32) (result i32) {slot_a} {slot_b} (i32.const -1))
+              (func $copy (param $to i32) (param $from i32) (memory.copy (local.get $to) (local.get $from) (i32.const 32)))
+              (func (export "getVersion") (result i32) (i32.const 2))
+              (func (export "getFieldNumLen32") (result i32) (i32.const 8))
+              (func (export "getRawPrime") {prime})
+              (func (export "init") (param i32) (memory.fill (i32.const 32) (i32.const 0) (i32.const 160)))
+              (func (export "readSharedRWMemory") (param i32) (result i32) (i32.load (i32.shl (local.get 0) (i32.const 2))))
+              (func (export "writeSharedRWMemory") (param i32 i32) (i32.store (i32.shl (local.get 0) (i32.const 2)) (local.get 1)))
+              (func (export "getInputSignalSize") (param i32 i32) (result i32)
+                (if (result i32) (i32.lt_s (call $slot (local.get 0) (local.get 1)) (i32.const 0)) (then (i32.const -1)) (else (i32.const 1))))
+              (func (export "setInputSignal") (param i32 i32 i32) (local $slot i32)
+                (local.set $slot (call $slot (local.get 0) (local.get 1)))
+                (if (i32.lt_s (local.get $slot) (i32.const 0)) (then (call $exception (i32.const 1))))
+                (call $copy (i32.add (i32.const 32) (i32.shl (local.get $slot) (i32.const 5))) (i32.const 0)))
+              (func (export "getWitnessSize") (result i32) (i32.const 4))
+              (func (export "getWitness") (param i32)
+                (memory.fill (i32.const 0) (i32.const 0) (i32.const 32))
+                (if (i32.eqz (local.get 0)) (then (i32.store (i32.const 0) (i32.const 1)) (return)))
+                (if (i32.eq (local.get 0) (i32.const 1))
+                  (then (i32.store (i32.const 0) (i32.mul (i32.load (i32.const 96)) (i32.load (i32.const 128)))) (return)))
+                (call $copy (i32.const 0) (i32.add (i32.const 32) (i32.shl (local.get 0) (i32.const 5))))))"#,
+            slot_a = slot(a),
+            slot_b = slot(b),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_witness_from_wasm() {
+        assert_eq!(fnv1a64("a"), 0xaf63_dc4c_8601_ec8c);
+
+        let mut calculator = WitnessCalculator::from_wasm(&mock_multiplier_wasm()).unwrap();
+        let witness = calculator.calculate_json(r#"{"a": "3", "b": 5}"#).unwrap();
+        assert_eq!(witness, vec![Fr::from(1u8), Fr::from(15u8), Fr::from(3u8), Fr::from(5u8)]);
+
+        // Reusable, and input errors name the signal
+        let witness = calculator.calculate_json(r#"{"a": ["7"], "b": "6"}"#).unwrap();
+        assert_eq!(witness[1], Fr::from(42u8));
+        assert!(matches!(calculator.calculate_json(r#"{"x": 1}"#), Err(CircomError::Witness(_))));
+        assert!(matches!(calculator.calculate_json(r#"{"a": [1, 2]}"#), Err(CircomError::Witness(_))));
+        assert!(matches!(calculator.calculate_json(r#"{"a": "1.5"}"#), Err(CircomError::Encoding(_))));
+
+        assert!(matches!(WitnessCalculator::from_wasm(b"not wasm"), Err(CircomError::Wasm(_))));
+    }
+}
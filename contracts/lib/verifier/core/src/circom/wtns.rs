@@ -0,0 +1,27 @@
+//! snarkjs `.wtns` witnesses
+//!
+//! Header (section 1): n8 || prime || witness count. Values (section 2):
+//! one standard-form field element per wire, wire 0 first.
+
+use alloc::{format, vec::Vec};
+use ark_bn254::Fr;
+
+use super::{BinFile, CircomError};
+
+/// Read a full witness (wire 0 is the constant one)
+pub fn read_wtns(bytes: &[u8]) -> Result<Vec<Fr>, CircomError> {
+    let file = BinFile::parse(bytes, b"wtns", "wtns")?;
+    if file.version != 2 {
+        return Err(CircomError::Unsupported(format!("wtns version {}", file.version)));
+    }
+
+    let mut header = file.section(1)?;
+    header.expect_prime::<Fr>("wtns")?;
+    let count = header.len()?;
+
+    let mut values = file.section(2)?;
+    if values.remaining() != count * 32 {
+        return Err(CircomError::Format(format!("wtns declares {count} values")));
+    }
+    (0..count).map(|i| values.fr(&format!("witness[{i}]"))).collect()
+}
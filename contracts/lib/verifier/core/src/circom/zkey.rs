@@ -0,0 +1,365 @@
+//! snarkjs Groth16 `.zkey` proving keys
+//!
+//! Sections: 1 protocol id, 2 header (n8q || q || n8r || r || nVars ||
+//! nPublic || domainSize || α₁ || β₁ || β₂ || γ₂ || δ₁ || δ₂), 3 IC,
+//! 4 A/B coefficients, 5 A, 6 B₁, 7 B₂, 8 C (private wires only),
+//! 9 H (one point per domain element), 10 contributions.
+//!
+//! Coefficient values are stored multiplied by R² (Montgomery form of the
+//! Montgomery form), a snarkjs quirk kept by every compatible reader.
+//!
+//! PLONK zkeys (protocol 2) keep their verifying key in the section 2
+//! header; only that part is read.
+
+use alloc::{boxed::Box, format, vec::Vec};
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256};
+
+use super::{BinFile, CircomError, Reader};
+use crate::{append_g1, append_g2, VerifyingKey};
+
+pub(crate) const GROTH16_PROTOCOL: u32 = 1;
+pub(crate) const PLONK_PROTOCOL: u32 = 2;
+
+/// Which QAP matrix a coefficient belongs to; C is implied by A·B
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Matrix {
+    A,
+    B,
+}
+
+/// One non-zero entry of the A or B matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Coefficient {
+    pub matrix: Matrix,
+    /// Row; rows past the circuit's constraints bind the public inputs
+    pub constraint: usize,
+    pub signal: usize,
+    pub value: Fr,
+}
+
+/// Everything `snarkjs groth16 prove` reads from a `.zkey`
+#[derive(Clone, Debug)]
+pub struct ProvingKey {
+    pub vk: VerifyingKey,
+    pub beta_g1: G1Affine,
+    pub delta_g1: G1Affine,
+    /// Witness length, including the constant-one wire
+    pub n_vars: usize,
+    pub n_public: usize,
+    /// Evaluation domain size (power of two)
+    pub domain_size: usize,
+    pub coefficients: Vec<Coefficient>,
+    /// [u_i(τ)]₁ for every wire
+    pub a_query: Vec<G1Affine>,
+    /// [v_i(τ)]₁ for every wire
+    pub b_g1_query: Vec<G1Affine>,
+    /// [v_i(τ)]₂ for every wire
+    pub b_g2_query: Vec<G2Affine>,
+    /// [(β·u_i + α·v_i + w_i)(τ) / δ]₁ for private wires n_public+1..n_vars
+    pub c_query: Vec<G1Affine>,
+    /// [L_{2j+1}(τ) / δ]₁ over the doubled domain, for j < domain_size
+    pub h_query: Vec<G1Affine>,
+}
+
+impl ProvingKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircomError> {
+        let file = open_zkey(bytes)?;
+        match file.section(1)?.u32()? {
+            GROTH16_PROTOCOL => {}
+            protocol => return Err(CircomError::Unsupported(format!("proving with zkey protocol {protocol}"))),
+        }
+        let header = read_groth16(&file)?;
+        let (n_vars, n_public, domain_size) = (header.n_vars, header.n_public, header.domain_size);
+        let coefficients = read_coefficients(&mut file.section(4)?, n_vars, domain_size)?;
+
+        Ok(Self {
+            vk: header.vk,
+            beta_g1: header.beta_g1,
+            delta_g1: header.delta_g1,
+            n_vars,
+            n_public,
+            domain_size,
+            coefficients,
+            a_query: file.section(5)?.g1_section(n_vars, "A")?,
+            b_g1_query: file.section(6)?.g1_section(n_vars, "B1")?,
+            b_g2_query: file.section(7)?.g2_section(n_vars, "B2")?,
+            c_query: file.section(8)?.g1_section(n_vars - n_public - 1, "C")?,
+            h_query: file.section(9)?.g1_section(domain_size, "H")?,
+        })
+    }
+}
+
+// ============================================================================
+// VERIFYING KEY EXTRACTION
+// ============================================================================
+
+/// snarkjs PLONK verifying key: selector and permutation commitments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlonkVerifyingKey {
+    pub n_public: usize,
+    /// log2 of the domain size
+    pub power: u32,
+    /// Coset shifts of the permutation argument
+    pub k1: Fr,
+    pub k2: Fr,
+    pub qm: G1Affine,
+    pub ql: G1Affine,
+    pub qr: G1Affine,
+    pub qo: G1Affine,
+    pub qc: G1Affine,
+    pub s1: G1Affine,
+    pub s2: G1Affine,
+    pub s3: G1Affine,
+    /// [τ]₂ from the powers of tau
+    pub x_2: G2Affine,
+}
+
+impl PlonkVerifyingKey {
+    /// power (4) || nPublic (4) || k1 || k2 || Qm, Ql, Qr, Qo, Qc, S1, S2, S3 || X_2,
+    /// big-endian, points in the same layout as `VerifyingKey::to_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + 2 * 32 + 8 * 64 + 128);
+        bytes.extend(self.power.to_be_bytes());
+        bytes.extend((self.n_public as u32).to_be_bytes());
+        bytes.extend(self.k1.into_bigint().to_bytes_be());
+        bytes.extend(self.k2.into_bigint().to_bytes_be());
+        for point in [&self.qm, &self.ql, &self.qr, &self.qo, &self.qc, &self.s1, &self.s2, &self.s3] {
+            append_g1(point, &mut bytes);
+        }
+        append_g2(&self.x_2, &mut bytes);
+        bytes
+    }
+
+    /// keccak256 of `to_bytes`, analogous to `VerifyingKey::fingerprint`
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Keccak256::digest(self.to_bytes()).into()
+    }
+}
+
+/// Verifying key found in a `.zkey`
+#[derive(Clone, Debug)]
+pub enum ZkeyVerifyingKey {
+    Groth16(Box<VerifyingKey>),
+    Plonk(Box<PlonkVerifyingKey>),
+}
+
+impl ZkeyVerifyingKey {
+    pub fn protocol(&self) -> &'static str {
+        match self {
+            Self::Groth16(_) => "groth16",
+            Self::Plonk(_) => "plonk",
+        }
+    }
+
+    pub fn n_public(&self) -> usize {
+        match self {
+            Self::Groth16(vk) => vk.ic.len() - 1,
+            Self::Plonk(vk) => vk.n_public,
+        }
+    }
+
+    /// For Groth16, the fingerprint the contract reports for this key
+    pub fn fingerprint(&self) -> [u8; 32] {
+        match self {
+            Self::Groth16(vk) => vk.fingerprint(),
+            Self::Plonk(vk) => vk.fingerprint(),
+        }
+    }
+}
+
+/// Read only the verifying key of a `.zkey`, skipping the proving sections
+///
+/// For Groth16 the IC section must hold exactly nPublic + 1 points.
+pub fn verifying_key_from_zkey(bytes: &[u8]) -> Result<ZkeyVerifyingKey, CircomError> {
+    let file = open_zkey(bytes)?;
+    match file.section(1)?.u32()? {
+        GROTH16_PROTOCOL => Ok(ZkeyVerifyingKey::Groth16(Box::new(read_groth16(&file)?.vk))),
+        PLONK_PROTOCOL => Ok(ZkeyVerifyingKey::Plonk(Box::new(read_plonk(&file)?))),
+        protocol => Err(CircomError::Unsupported(format!("zkey protocol {protocol}"))),
+    }
+}
+
+pub(super) fn open_zkey(bytes: &[u8]) -> Result<BinFile<'_>, CircomError> {
+    let file = BinFile::parse(bytes, b"zkey", "zkey")?;
+    if file.version != 1 {
+        return Err(CircomError::Unsupported(format!("zkey version {}", file.version)));
+    }
+    Ok(file)
+}
+
+struct Groth16Header {
+    vk: VerifyingKey,
+    beta_g1: G1Affine,
+    delta_g1: G1Affine,
+    n_vars: usize,
+    n_public: usize,
+    domain_size: usize,
+}
+
+/// Sections 2 (header) and 3 (IC) of a Groth16 zkey
+fn read_groth16(file: &BinFile<'_>) -> Result<Groth16Header, CircomError> {
+    let mut header = file.section(2)?;
+    header.expect_prime::<Fq>("zkey base field")?;
+    header.expect_prime::<Fr>("zkey scalar field")?;
+    let n_vars = header.len()?;
+    let n_public = header.len()?;
+    let domain_size = header.len()?;
+    if n_public >= n_vars || !domain_size.is_power_of_two() {
+        return Err(CircomError::Format(format!(
+            "zkey header: {n_vars} vars, {n_public} public, domain {domain_size}"
+        )));
+    }
+    let alpha_g1 = header.g1("alpha_1")?;
+    let beta_g1 = header.g1("beta_1")?;
+    let beta_g2 = header.g2("beta_2")?;
+    let gamma_g2 = header.g2("gamma_2")?;
+    let delta_g1 = header.g1("delta_1")?;
+    let delta_g2 = header.g2("delta_2")?;
+
+    let section = file.section(3)?;
+    if section.remaining() != (n_public + 1) * 64 {
+        return Err(CircomError::Format(format!(
+            "IC holds {} bytes, nPublic = {n_public} needs {} points",
+            section.remaining(),
+            n_public + 1
+        )));
+    }
+    let ic = section.g1_section(n_public + 1, "IC")?;
+
+    Ok(Groth16Header {
+        vk: VerifyingKey { alpha_g1, beta_g2, gamma_g2, delta_g2, ic },
+        beta_g1,
+        delta_g1,
+        n_vars,
+        n_public,
+        domain_size,
+    })
+}
+
+/// Section 2 of a PLONK zkey
+fn read_plonk(file: &BinFile<'_>) -> Result<PlonkVerifyingKey, CircomError> {
+    let mut header = file.section(2)?;
+    header.expect_prime::<Fq>("zkey base field")?;
+    header.expect_prime::<Fr>("zkey scalar field")?;
+    let _n_vars = header.len()?;
+    let n_public = header.len()?;
+    let domain_size = header.u32()?;
+    if !domain_size.is_power_of_two() {
+        return Err(CircomError::Format(format!("zkey header: domain {domain_size}")));
+    }
+    let _n_additions = header.len()?;
+    let _n_constraints = header.len()?;
+
+    Ok(PlonkVerifyingKey {
+        n_public,
+        power: domain_size.trailing_zeros(),
+        k1: header.fr_montgomery("k1")?,
+        k2: header.fr_montgomery("k2")?,
+        qm: header.g1("Qm")?,
+        ql: header.g1("Ql")?,
+        qr: header.g1("Qr")?,
+        qo: header.g1("Qo")?,
+        qc: header.g1("Qc")?,
+        s1: header.g1("S1")?,
+        s2: header.g1("S2")?,
+        s3: header.g1("S3")?,
+        x_2: header.g2("X_2")?,
+    })
+}
+
+fn read_coefficients(
+    section: &mut Reader<'_>,
+    n_vars: usize,
+    domain_size: usize,
+) -> Result<Vec<Coefficient>, CircomError> {
+    let count = section.len()?;
+    (0..count)
+        .map(|i| {
+            let matrix = match section.u32()? {
+                0 => Matrix::A,
+                1 => Matrix::B,
+                other => return Err(CircomError::Format(format!("coefficient {i} in matrix {other}"))),
+            };
+            let constraint = section.len()?;
+            let signal = section.len()?;
+            if constraint >= domain_size || signal >= n_vars {
+                return Err(CircomError::Format(format!("coefficient {i} out of range")));
+            }
+            // Stored as value·R²: undo one Montgomery layer by hand
+            let once = section.fr_montgomery(&format!("coefficient {i}"))?;
+            let value = Fr::new_unchecked(once.into_bigint());
+            Ok(Coefficient { matrix, constraint, signal, value })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_files;
+    use super::*;
+    use ark_bn254::{G1Projective, G2Projective};
+    use ark_ec::{CurveGroup, Group};
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        UniformRand,
+    };
+
+    #[test]
+    fn test_groth16_key_from_zkey() {
+        let mut rng = StdRng::seed_from_u64(35);
+        let files = test_files::multiplier(&mut rng);
+        let pk = ProvingKey::from_bytes(&files.zkey).unwrap();
+
+        let extracted = verifying_key_from_zkey(&files.zkey).unwrap();
+        assert_eq!((extracted.protocol(), extracted.n_public()), ("groth16", 1));
+        assert_eq!(extracted.fingerprint(), pk.vk.fingerprint());
+        let ZkeyVerifyingKey::Groth16(vk) = extracted else { panic!("expected groth16") };
+        assert_eq!(vk.to_bytes(), pk.vk.to_bytes());
+
+        // IC must match nPublic exactly, in either direction
+        for ic_len in [1, 3] {
+            let sections: Vec<_> = test_files::sections(&files.zkey)
+                .into_iter()
+                .map(|(id, data)| (id, if id == 3 { data[..64].repeat(ic_len) } else { data }))
+                .collect();
+            let zkey = test_files::bin_file(b"zkey", 1, &sections);
+            let err = verifying_key_from_zkey(&zkey).unwrap_err();
+            assert!(matches!(&err, CircomError::Format(reason) if reason.contains("nPublic = 1")), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_plonk_key_from_zkey() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let mut g1 = || (G1Projective::generator() * Fr::rand(&mut rng)).into_affine();
+        let vk = PlonkVerifyingKey {
+            n_public: 2,
+            power: 3,
+            k1: Fr::from(2u8),
+            k2: Fr::from(3u8),
+            qm: g1(),
+            ql: g1(),
+            qr: g1(),
+            qo: g1(),
+            qc: g1(),
+            s1: g1(),
+            s2: g1(),
+            s3: g1(),
+            x_2: (G2Projective::generator() * Fr::from(7u8)).into_affine(),
+        };
+
+        let extracted = verifying_key_from_zkey(&test_files::plonk_zkey(&vk)).unwrap();
+        assert_eq!((extracted.protocol(), extracted.n_public()), ("plonk", 2));
+        assert_eq!(extracted.fingerprint(), vk.fingerprint());
+        let ZkeyVerifyingKey::Plonk(read) = extracted else { panic!("expected plonk") };
+        assert_eq!(*read, vk);
+
+        // A PLONK key is not something the Groth16 prover can use
+        assert!(matches!(
+            ProvingKey::from_bytes(&test_files::plonk_zkey(&vk)),
+            Err(CircomError::Unsupported(_))
+        ));
+    }
+}
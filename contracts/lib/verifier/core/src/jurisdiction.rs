@@ -0,0 +1,197 @@
+//! JURISDICTION SETS - ISO 3166-1 NUMERIC CODES AS PUBLIC INPUTS
+//!
+//! A circuit proves the holder's country is in some set without revealing
+//! which, and exposes the set as [`WORDS`] public inputs. Code `c` is bit
+//! `c % CODES_PER_WORD` of input `c / CODES_PER_WORD`, counting from the
+//! least significant bit, so every word stays below the BN254 scalar field:
+//!
+//! ```text
+//! input 0: codes   0..250    (004 Afghanistan = bit 4)
+//! input 1: codes 250..500    (276 Germany = bit 26)
+//! input 2: codes 500..750
+//! input 3: codes 750..1000   (840 United States = bit 90)
+//! ```
+//!
+//! Asset rules use the same layout for their allowed and denied codes.
+
+use alloc::vec::Vec;
+
+/// Public inputs a jurisdiction set takes
+pub const WORDS: usize = 4;
+/// Codes each input carries
+pub const CODES_PER_WORD: usize = 250;
+/// Codes run from 000 to 999
+pub const CODE_COUNT: usize = WORDS * CODES_PER_WORD;
+
+/// Set of ISO 3166-1 numeric codes, as big-endian 32-byte words
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JurisdictionSet([[u8; 32]; WORDS]);
+
+impl JurisdictionSet {
+    /// Read a set from its public inputs, rejecting bits above `CODES_PER_WORD`
+    pub fn from_words(words: &[[u8; 32]]) -> Result<Self, Vec<u8>> {
+        let words: [[u8; 32]; WORDS] =
+            words.try_into().map_err(|_| b"Expected 4 jurisdiction words".to_vec())?;
+        // 250 bits leave the top 6 bits of each word clear
+        if words.iter().any(|word| word[0] >> (CODES_PER_WORD - 248) != 0) {
+            return Err(b"Jurisdiction word out of range".to_vec());
+        }
+        Ok(Self(words))
+    }
+
+    /// `to_bytes` encoding; empty bytes are the empty set
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        if bytes.is_empty() {
+            return Ok(Self::default());
+        }
+        let (words, remainder) = bytes.as_chunks::<32>();
+        if !remainder.is_empty() {
+            return Err(b"Expected 4 jurisdiction words".to_vec());
+        }
+        Self::from_words(words)
+    }
+
+    pub fn from_codes(codes: &[u16]) -> Result<Self, Vec<u8>> {
+        let mut set = Self::default();
+        for code in codes {
+            let code = *code as usize;
+            if code >= CODE_COUNT {
+                return Err(b"Jurisdiction code out of range".to_vec());
+            }
+            let (byte, mask) = Self::position(code);
+            set.0[code / CODES_PER_WORD][byte] |= mask;
+        }
+        Ok(set)
+    }
+
+    pub fn words(&self) -> &[[u8; 32]; WORDS] {
+        &self.0
+    }
+
+    /// The four words concatenated
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_flattened().to_vec()
+    }
+
+    /// Members in ascending order
+    pub fn codes(&self) -> Vec<u16> {
+        (0..CODE_COUNT).filter(|code| self.contains(*code as u16)).map(|code| code as u16).collect()
+    }
+
+    pub fn contains(&self, code: u16) -> bool {
+        let code = code as usize;
+        if code >= CODE_COUNT {
+            return false;
+        }
+        let (byte, mask) = Self::position(code);
+        self.0[code / CODES_PER_WORD][byte] & mask != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes().all(|byte| byte == 0)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.bytes().zip(other.bytes()).any(|(ours, theirs)| ours & theirs != 0)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.bytes().zip(other.bytes()).all(|(ours, theirs)| ours & !theirs == 0)
+    }
+
+    /// Whether a holder proven to be somewhere in this set may hold an asset
+    ///
+    /// The holder could be in any member, so every member must be allowed
+    /// (an empty `allowed` allows all) and none denied. An empty proven set
+    /// places the holder nowhere and is never permitted.
+    pub fn permitted_by(&self, allowed: &Self, denied: &Self) -> bool {
+        !self.is_empty() && (allowed.is_empty() || self.is_subset(allowed)) && !self.intersects(denied)
+    }
+
+    /// `permitted_by` for rules that accept a holder who may be in an
+    /// allowed country: some member must be allowed, and still none denied,
+    /// since a denied country left open cannot be ruled out
+    pub fn permitted_by_any(&self, allowed: &Self, denied: &Self) -> bool {
+        !self.is_empty() && (allowed.is_empty() || self.intersects(allowed)) && !self.intersects(denied)
+    }
+
+    fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.as_flattened().iter().copied()
+    }
+
+    /// Byte and mask of `code` within its word
+    fn position(code: usize) -> (usize, u8) {
+        let bit = code % CODES_PER_WORD;
+        (31 - bit / 8, 1 << (bit % 8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFGHANISTAN: u16 = 4;
+    const GERMANY: u16 = 276;
+    const NORTH_KOREA: u16 = 408;
+    const UNITED_STATES: u16 = 840;
+
+    #[test]
+    fn test_encoding() {
+        let set = JurisdictionSet::from_codes(&[AFGHANISTAN, GERMANY, UNITED_STATES]).unwrap();
+        let bytes = set.to_bytes();
+        assert_eq!(bytes[31], 1 << 4);
+        assert_eq!(bytes[32 + 31 - 3], 1 << 2);
+        assert_eq!(bytes[96 + 31 - 11], 1 << 2);
+        assert_eq!(bytes.iter().map(|byte| byte.count_ones()).sum::<u32>(), 3);
+
+        assert_eq!(JurisdictionSet::from_bytes(&bytes), Ok(set));
+        assert_eq!(set.codes(), [AFGHANISTAN, GERMANY, UNITED_STATES]);
+        assert!(set.contains(GERMANY) && !set.contains(NORTH_KOREA) && !set.contains(u16::MAX));
+        assert_eq!(JurisdictionSet::from_bytes(&[]), Ok(JurisdictionSet::default()));
+
+        // Highest code of each word, and the first bit past it
+        let edges: Vec<u16> = (1..=WORDS as u16).map(|word| word * CODES_PER_WORD as u16 - 1).collect();
+        let set = JurisdictionSet::from_codes(&edges).unwrap();
+        assert_eq!(set.codes(), edges);
+        assert!(set.to_bytes().chunks(32).all(|word| word[0] == 0x02));
+        let mut bytes = set.to_bytes();
+        bytes[64] = 0x04;
+        assert_eq!(JurisdictionSet::from_bytes(&bytes), Err(b"Jurisdiction word out of range".to_vec()));
+        assert_eq!(JurisdictionSet::from_codes(&[1000]), Err(b"Jurisdiction code out of range".to_vec()));
+        assert_eq!(JurisdictionSet::from_bytes(&[0; 96]), Err(b"Expected 4 jurisdiction words".to_vec()));
+        assert_eq!(JurisdictionSet::from_bytes(&[0; 100]), Err(b"Expected 4 jurisdiction words".to_vec()));
+    }
+
+    #[test]
+    fn test_permitted_by() {
+        let set = |codes: &[u16]| JurisdictionSet::from_codes(codes).unwrap();
+        let none = JurisdictionSet::default();
+        let sanctioned = set(&[NORTH_KOREA]);
+        let eu_and_us = set(&[GERMANY, UNITED_STATES]);
+
+        assert!(set(&[GERMANY]).permitted_by(&none, &sanctioned));
+        assert!(set(&[GERMANY]).permitted_by(&eu_and_us, &none));
+        assert!(set(&[GERMANY, UNITED_STATES]).permitted_by(&eu_and_us, &sanctioned));
+        // Could be in Afghanistan, which is not on the allow list
+        assert!(!set(&[GERMANY, AFGHANISTAN]).permitted_by(&eu_and_us, &none));
+        // Could be in a denied country
+        assert!(!set(&[GERMANY, NORTH_KOREA]).permitted_by(&none, &sanctioned));
+        assert!(!none.permitted_by(&none, &none));
+    }
+
+    #[test]
+    fn test_permitted_by_any() {
+        let set = |codes: &[u16]| JurisdictionSet::from_codes(codes).unwrap();
+        let none = JurisdictionSet::default();
+        let sanctioned = set(&[NORTH_KOREA]);
+        let eu_and_us = set(&[GERMANY, UNITED_STATES]);
+
+        // Sharing one allowed country is enough
+        assert!(set(&[GERMANY, AFGHANISTAN]).permitted_by_any(&eu_and_us, &none));
+        assert!(set(&[AFGHANISTAN]).permitted_by_any(&none, &sanctioned));
+        assert!(!set(&[AFGHANISTAN]).permitted_by_any(&eu_and_us, &none));
+        // A denied country still fails the holder
+        assert!(!set(&[GERMANY, NORTH_KOREA]).permitted_by_any(&eu_and_us, &sanctioned));
+        assert!(!none.permitted_by_any(&none, &none));
+    }
+}
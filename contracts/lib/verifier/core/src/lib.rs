@@ -0,0 +1,1090 @@
+//! ARBSHIELD VERIFIER CORE - HOST-INDEPENDENT GROTH16 VERIFICATION
+//!
+//! Proof and key types, their byte encodings and the pairing check, with no
+//! dependency on `stylus-sdk` or `std`. The Stylus contract, native tools and
+//! browser builds all verify through this crate, so a proof accepted
+//! off-chain is accepted on-chain. [`policy`] holds the compliance policy
+//! language the contract evaluates, and [`jurisdiction`] the country sets
+//! circuits expose, for the same reason.
+//!
+//! The `std` feature (default) only enables arkworks' std support and the
+//! SnarkPack aggregator; `circom` adds the `.r1cs`/`.wtns`/`.zkey` readers
+//! and a native prover, `witness-wasm` circom's `.wasm` witness generators,
+//! `ceremony` the phase-2 transcript verifier.
+
+#![cfg_attr(not(test), no_std)]
+extern crate alloc;
+
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, One, PrimeField};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+pub mod jurisdiction;
+pub mod policy;
+pub mod precompile;
+pub mod snarkpack;
+
+#[cfg(feature = "snarkjs")]
+pub mod snarkjs;
+
+#[cfg(feature = "circom")]
+pub mod circom;
+
+// ============================================================================
+// DEVELOPMENT VERIFICATION KEY - Tests and tooling only, not a ceremony
+// ============================================================================
+// multiplier.circom (a * b = c, c public), from a single-party ark-groth16
+// `circuit_specific_setup` seeded with ChaCha20Rng::seed_from_u64(0x4172_6253_6869_656c);
+// `test_vk_provenance` regenerates it. Anyone can rerun the setup and learn
+// its toxic waste, so proofs under this key prove nothing. The contract
+// never resolves it: every circuit, the multiplier included, verifies only
+// under a key the owner registered from an audited phase-2 ceremony.
+
+mod vk_constants {
+    pub const ALPHA_G1_X: [u8; 32] = [
+        0x08, 0x18, 0x4c, 0x81, 0x3f, 0x18, 0x0f, 0xa8,
+        0xec, 0xb9, 0x8f, 0xce, 0x19, 0xfe, 0xbe, 0x2a,
+        0xe1, 0x74, 0x4b, 0xd4, 0x89, 0x00, 0x63, 0xd3,
+        0xd4, 0x9a, 0x3d, 0x09, 0xfd, 0x99, 0x9f, 0x56,
+    ];
+    
+    pub const ALPHA_G1_Y: [u8; 32] = [
+        0x0e, 0x10, 0x7e, 0xf0, 0x25, 0x98, 0x08, 0x56,
+        0x04, 0x2e, 0xb3, 0xbd, 0xdb, 0x53, 0x4a, 0x29,
+        0xb6, 0xda, 0x76, 0x64, 0x9d, 0x52, 0x69, 0x2c,
+        0x35, 0xee, 0x59, 0x00, 0x4c, 0x7e, 0x80, 0x7f,
+    ];
+
+    // Beta G2 (Fq2 = two field elements)
+    pub const BETA_G2_X0: [u8; 32] = [
+        0x0e, 0x29, 0xb9, 0x85, 0x6f, 0x19, 0x11, 0x74,
+        0x1d, 0x32, 0xdc, 0x39, 0x1d, 0x4a, 0x70, 0x4b,
+        0x74, 0x10, 0x22, 0xea, 0x3d, 0x0c, 0x2e, 0xe2,
+        0xd7, 0xec, 0xe8, 0x77, 0x93, 0xe8, 0x35, 0x53,
+    ];
+    
+    pub const BETA_G2_X1: [u8; 32] = [
+        0x03, 0x05, 0x65, 0x8f, 0xfe, 0xbd, 0x73, 0xba,
+        0x5c, 0x62, 0xa1, 0x13, 0x5f, 0x76, 0x81, 0x96,
+        0x50, 0x6a, 0xe6, 0xa5, 0x5f, 0xec, 0xc7, 0x24,
+        0xf1, 0xd4, 0x44, 0xa7, 0x25, 0x02, 0x4c, 0xee,
+    ];
+    
+    pub const BETA_G2_Y0: [u8; 32] = [
+        0x11, 0x23, 0xfb, 0xe7, 0x61, 0x4a, 0x92, 0x92,
+        0xbd, 0xb4, 0x66, 0x0e, 0x46, 0x3d, 0xe4, 0x2f,
+        0x5b, 0xe1, 0x10, 0xf6, 0x9a, 0x00, 0xdf, 0x5b,
+        0x05, 0x91, 0x0c, 0xd0, 0x26, 0x79, 0xa1, 0x7b,
+    ];
+    
+    pub const BETA_G2_Y1: [u8; 32] = [
+        0x0c, 0x25, 0xeb, 0xf6, 0x18, 0xbf, 0x5d, 0x88,
+        0xfe, 0x41, 0xc1, 0xcd, 0xd1, 0x27, 0xa3, 0x6c,
+        0xc6, 0x3d, 0x4a, 0x5b, 0x9f, 0xed, 0x50, 0x61,
+        0x89, 0xb1, 0x60, 0xd0, 0x1b, 0x67, 0x5f, 0xee,
+    ];
+
+    // Gamma G2 (typically generator for public inputs)
+    pub const GAMMA_G2_X0: [u8; 32] = [
+        0x10, 0x8a, 0xd9, 0xab, 0x71, 0xbe, 0xb3, 0x37,
+        0x0a, 0x05, 0x65, 0x04, 0x41, 0xce, 0xaa, 0x32,
+        0x6e, 0xcd, 0x12, 0xe9, 0x85, 0xda, 0x27, 0xd1,
+        0x3c, 0x43, 0xcc, 0x86, 0xad, 0x8e, 0xc3, 0xe1,
+    ];
+    
+    pub const GAMMA_G2_X1: [u8; 32] = [
+        0x29, 0xb8, 0x51, 0x88, 0xbf, 0x7f, 0x70, 0x03,
+        0x7c, 0x6a, 0xeb, 0x4a, 0x8d, 0xf9, 0x22, 0xe3,
+        0x5b, 0x70, 0x9c, 0xe8, 0x8b, 0x3f, 0xa0, 0xbc,
+        0x77, 0x91, 0x4a, 0xc9, 0x4c, 0xd9, 0x1a, 0x70,
+    ];
+    
+    pub const GAMMA_G2_Y0: [u8; 32] = [
+        0x2e, 0xbf, 0xc2, 0xac, 0xbc, 0x89, 0x37, 0x30,
+        0xd2, 0x23, 0xcb, 0xf0, 0x5a, 0x37, 0x20, 0xb6,
+        0x27, 0x24, 0xef, 0xa5, 0xd0, 0x7c, 0xf7, 0xb4,
+        0x7a, 0xa9, 0xd2, 0x6b, 0xa5, 0x3f, 0x39, 0xbf,
+    ];
+    
+    pub const GAMMA_G2_Y1: [u8; 32] = [
+        0x27, 0x72, 0x6f, 0x0d, 0xf7, 0x6a, 0xaa, 0x19,
+        0x71, 0xb2, 0x38, 0x5f, 0x46, 0xff, 0xcc, 0xeb,
+        0x61, 0x2c, 0x10, 0x31, 0x40, 0xf5, 0x9b, 0x5a,
+        0xf8, 0x2b, 0xf5, 0x30, 0x61, 0x66, 0xdb, 0x61,
+    ];
+
+    // Delta G2
+    pub const DELTA_G2_X0: [u8; 32] = [
+        0x15, 0x27, 0x3c, 0x69, 0x3f, 0x79, 0x56, 0xa6,
+        0xee, 0xff, 0x2d, 0x88, 0x3c, 0x9d, 0x2d, 0xe6,
+        0xcd, 0x57, 0x4f, 0x73, 0x56, 0xd4, 0x08, 0x7a,
+        0x8a, 0xaf, 0x61, 0xe2, 0xba, 0xee, 0x70, 0x93,
+    ];
+    
+    pub const DELTA_G2_X1: [u8; 32] = [
+        0x2c, 0xcc, 0x1f, 0xfc, 0xf0, 0xad, 0x79, 0xa7,
+        0x57, 0x8f, 0xa0, 0x54, 0x5b, 0x56, 0xc6, 0xff,
+        0xef, 0x27, 0xa4, 0xad, 0xd9, 0x9c, 0x53, 0xc0,
+        0x57, 0x1f, 0xd8, 0xb3, 0xd7, 0xe6, 0xbc, 0xde,
+    ];
+    
+    pub const DELTA_G2_Y0: [u8; 32] = [
+        0x1f, 0xc3, 0x1a, 0xb7, 0xe6, 0x71, 0xdb, 0x23,
+        0xdf, 0xc6, 0x47, 0x76, 0x22, 0xc6, 0xee, 0xb7,
+        0x36, 0xf3, 0x43, 0x71, 0x8c, 0xa0, 0xbc, 0xeb,
+        0x02, 0x27, 0x3d, 0xdb, 0xd9, 0xb1, 0x1e, 0x15,
+    ];
+    
+    pub const DELTA_G2_Y1: [u8; 32] = [
+        0x1c, 0x68, 0xda, 0x6b, 0xdd, 0xf4, 0x33, 0x14,
+        0x00, 0xfa, 0x1b, 0xc6, 0xc9, 0xbc, 0xbc, 0xc1,
+        0xfb, 0x6d, 0x06, 0xe7, 0xe7, 0xf4, 0x07, 0xfd,
+        0x09, 0x7b, 0x05, 0x00, 0x11, 0xf1, 0xcc, 0x6e,
+    ];
+
+    // IC[0] - constant term
+    pub const IC_0_X: [u8; 32] = [
+        0x06, 0x43, 0x37, 0x2c, 0x46, 0x56, 0x3d, 0x6c,
+        0xab, 0xb7, 0x87, 0xb3, 0x06, 0xaf, 0xb3, 0x9c,
+        0x5c, 0xf3, 0x82, 0x70, 0x96, 0x59, 0x40, 0xab,
+        0x0d, 0xf6, 0xdf, 0xab, 0x4c, 0xc1, 0x2b, 0xa4,
+    ];
+    
+    pub const IC_0_Y: [u8; 32] = [
+        0x06, 0xa2, 0x25, 0x92, 0x3f, 0x5a, 0xec, 0x5f,
+        0x8b, 0x48, 0x0d, 0xf2, 0x11, 0x51, 0xa1, 0x3b,
+        0x94, 0xcb, 0x7b, 0xce, 0xeb, 0x4f, 0x59, 0xc2,
+        0x48, 0xfd, 0x57, 0xc4, 0x01, 0x8d, 0x83, 0x40,
+    ];
+
+    // IC[1] - coefficient for public input c
+    pub const IC_1_X: [u8; 32] = [
+        0x2a, 0x02, 0xb3, 0x53, 0x58, 0x01, 0x89, 0x42,
+        0x70, 0xd3, 0x50, 0x93, 0xf8, 0x8a, 0xbe, 0x40,
+        0x36, 0x8f, 0x8b, 0x6a, 0x5d, 0xda, 0xa8, 0x52,
+        0xa2, 0x3b, 0x91, 0x97, 0xcf, 0x0d, 0xd1, 0xf8,
+    ];
+    
+    pub const IC_1_Y: [u8; 32] = [
+        0x11, 0x45, 0x39, 0x69, 0xac, 0xf1, 0x65, 0xea,
+        0x38, 0x6f, 0x93, 0x1f, 0x46, 0x08, 0x2d, 0x90,
+        0xd8, 0x5e, 0x3c, 0xd9, 0x8f, 0xe6, 0x8f, 0x89,
+        0xbd, 0xc1, 0xac, 0xbc, 0x24, 0x3b, 0x7c, 0x45,
+    ];
+}
+
+// ============================================================================
+// PROOF STRUCTURE
+// ============================================================================
+
+#[derive(Clone, Debug)]
+pub struct Groth16Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+impl Groth16Proof {
+    /// Parse proof from uncompressed format (snarkjs default)
+    /// Format: A.x (32) || A.y (32) || B.x0 (32) || B.x1 (32) || B.y0 (32) || B.y1 (32) || C.x (32) || C.y (32)
+    /// Total: 256 bytes
+    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        let bytes: &[u8; PROOF_SIZE] = bytes
+            .try_into()
+            .map_err(|_| b"Expected 256 bytes for uncompressed proof".to_vec())?;
+        Self::from_uncompressed_array(bytes)
+    }
+
+    /// Parse proof from a fixed 256-byte slice (same layout as `from_uncompressed_bytes`)
+    pub fn from_uncompressed_array(bytes: &[u8; PROOF_SIZE]) -> Result<Self, Vec<u8>> {
+        // A (G1) || B (G2) || C (G1); G1 has cofactor 1, B gets a subgroup check
+        let a = read_g1(&bytes[0..64])?;
+        let b = read_g2(&bytes[64..192])?;
+        let c = read_g1(&bytes[192..256])?;
+
+        Ok(Self { a, b, c })
+    }
+
+    /// Encode in the layout read by `from_uncompressed_bytes`
+    pub fn to_uncompressed_bytes(&self) -> [u8; PROOF_SIZE] {
+        let mut bytes = Vec::with_capacity(PROOF_SIZE);
+        append_g1(&self.a, &mut bytes);
+        append_g2(&self.b, &mut bytes);
+        append_g1(&self.c, &mut bytes);
+        bytes.try_into().expect("64 + 128 + 64 bytes")
+    }
+
+    /// Parse proof from compressed format
+    /// Format: A.x (32) || B.x0 (32) || B.x1 (32) || C.x (32), BigEndian, with
+    /// the top bit of each point's first byte set when y is the larger root
+    /// Total: 128 bytes
+    pub fn from_compressed_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        if bytes.len() != COMPRESSED_PROOF_SIZE {
+            return Err(b"Expected 128 bytes for compressed proof".to_vec());
+        }
+        let a = read_g1_compressed(&bytes[0..32])?;
+        let b = read_g2_compressed(&bytes[32..96])?;
+        let c = read_g1_compressed(&bytes[96..128])?;
+
+        Ok(Self { a, b, c })
+    }
+
+    /// Encode in the layout read by `from_compressed_bytes`
+    pub fn to_compressed_bytes(&self) -> [u8; COMPRESSED_PROOF_SIZE] {
+        let mut bytes = Vec::with_capacity(COMPRESSED_PROOF_SIZE);
+        append_g1_compressed(&self.a, &mut bytes);
+        append_g2_compressed(&self.b, &mut bytes);
+        append_g1_compressed(&self.c, &mut bytes);
+        bytes.try_into().expect("32 + 64 + 32 bytes")
+    }
+
+    /// Parse from snarkjs JSON format (`proof.json`)
+    #[cfg(feature = "snarkjs")]
+    pub fn from_json(json: &str) -> Result<Self, Vec<u8>> {
+        Ok(snarkjs::ProofJson::from_json(json)?.to_proof()?)
+    }
+}
+
+// ============================================================================
+// PACKED CALLDATA
+// ============================================================================
+
+/// Size of an uncompressed proof: A (64) || B (128) || C (64)
+pub const PROOF_SIZE: usize = 256;
+
+/// Size of a compressed proof: A.x (32) || B.x (64) || C.x (32)
+pub const COMPRESSED_PROOF_SIZE: usize = 128;
+
+/// Size of a circuit id and of each public input word
+pub const WORD_SIZE: usize = 32;
+
+/// Zero-copy view over `circuit_id (32) || proof (256) || inputs (32 * n)`
+///
+/// Avoids the ABI offsets, length words and per-input `Vec` of `bytes[]`
+pub struct PackedCall<'a> {
+    pub circuit_id: &'a [u8; WORD_SIZE],
+    pub proof: &'a [u8; PROOF_SIZE],
+    pub inputs: &'a [[u8; WORD_SIZE]],
+}
+
+impl<'a> PackedCall<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Vec<u8>> {
+        let (circuit_id, rest) = bytes
+            .split_first_chunk::<WORD_SIZE>()
+            .ok_or_else(|| b"Packed call missing circuit id".to_vec())?;
+        let (proof, rest) = rest
+            .split_first_chunk::<PROOF_SIZE>()
+            .ok_or_else(|| b"Packed call missing proof".to_vec())?;
+        let (inputs, remainder) = rest.as_chunks::<WORD_SIZE>();
+        if !remainder.is_empty() {
+            return Err(b"Packed public inputs must be 32-byte words".to_vec());
+        }
+
+        Ok(Self { circuit_id, proof, inputs })
+    }
+
+    /// Public inputs as field elements (BigEndian); words >= r are rejected
+    pub fn public_inputs(&self) -> Result<Vec<Fr>, Vec<u8>> {
+        self.inputs.iter().map(|word| read_fr(word)).collect()
+    }
+}
+
+// ============================================================================
+// PUBLIC INPUT HASHING
+// ============================================================================
+// A circuit with many signals can expose one public input instead:
+// H(input_0 || input_1 || ...) mod r, over the packed 32-byte inputs.
+// Its IC then has two points, so the accumulation costs a single scalar
+// multiplication whatever the number of signals.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputMode {
+    /// Every 32-byte input is a public signal
+    Direct,
+    /// Single public signal: keccak256(inputs) mod r
+    Keccak256,
+    /// Single public signal: sha256(inputs) mod r
+    Sha256,
+}
+
+impl InputMode {
+    pub fn from_u8(mode: u8) -> Result<Self, Vec<u8>> {
+        match mode {
+            0 => Ok(Self::Direct),
+            1 => Ok(Self::Keccak256),
+            2 => Ok(Self::Sha256),
+            _ => Err(b"Unknown input mode".to_vec()),
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Direct => 0,
+            Self::Keccak256 => 1,
+            Self::Sha256 => 2,
+        }
+    }
+
+    /// Hash packed inputs into the single public signal (`None` in direct mode)
+    pub fn digest(self, packed_inputs: &[u8]) -> Option<Fr> {
+        self.digest_word(packed_inputs).map(|word| Fr::from_be_bytes_mod_order(&word))
+    }
+
+    /// `digest` as a canonical 32-byte BigEndian word, for `precompile`
+    pub fn digest_word(self, packed_inputs: &[u8]) -> Option<[u8; WORD_SIZE]> {
+        let digest: [u8; 32] = match self {
+            Self::Direct => return None,
+            Self::Keccak256 => Keccak256::digest(packed_inputs).into(),
+            Self::Sha256 => Sha256::digest(packed_inputs).into(),
+        };
+        Some(precompile::reduce_scalar(digest))
+    }
+
+    /// Number of IC points a key for this mode must have, if fixed
+    pub fn required_ic_len(self) -> Option<usize> {
+        match self {
+            Self::Direct => None,
+            Self::Keccak256 | Self::Sha256 => Some(2),
+        }
+    }
+}
+
+// ============================================================================
+// VERIFYING KEY
+// ============================================================================
+
+#[derive(Clone, Debug)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g2: G2Affine,
+    pub ic: Vec<G1Affine>,
+}
+
+/// Circuit id conventionally used for the multiplier circuit: keccak256("multiplier")
+///
+/// Nothing is registered under it by default; the contract's `verify` and
+/// `verify_with_precompile` use whichever key the owner registers here
+pub const MULTIPLIER_CIRCUIT_ID: [u8; 32] = [
+    0xba, 0x09, 0x4d, 0x52, 0x7b, 0x47, 0x77, 0x0c,
+    0xe4, 0x72, 0xef, 0xff, 0xde, 0x0e, 0x2e, 0x5d,
+    0xe1, 0xcc, 0x03, 0x12, 0x22, 0xe4, 0xdf, 0x6d,
+    0xbe, 0xbd, 0xa1, 0x5b, 0xda, 0xf3, 0x7a, 0x74,
+];
+
+/// Encoded size of alpha (G1) || beta, gamma, delta (G2), before the IC points
+pub const VK_HEADER_SIZE: usize = 64 + 3 * 128;
+
+impl VerifyingKey {
+    /// Parse a key from its uncompressed encoding
+    /// Format: alpha (64) || beta (128) || gamma (128) || delta (128) || IC[0..n] (64 each)
+    /// G2 points use the proof layout: x0 || x1 || y0 || y1
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        if bytes.len() < VK_HEADER_SIZE + 64 || !(bytes.len() - VK_HEADER_SIZE).is_multiple_of(64) {
+            return Err(b"Invalid verifying key length".to_vec());
+        }
+
+        let (header, ic_bytes) = bytes.split_at(VK_HEADER_SIZE);
+        let ic = ic_bytes
+            .chunks_exact(64)
+            .map(read_g1)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            alpha_g1: read_g1(&header[0..64])?,
+            beta_g2: read_g2(&header[64..192])?,
+            gamma_g2: read_g2(&header[192..320])?,
+            delta_g2: read_g2(&header[320..448])?,
+            ic,
+        })
+    }
+
+    /// Encode the key in the layout read by `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(VK_HEADER_SIZE + 64 * self.ic.len());
+        append_g1(&self.alpha_g1, &mut bytes);
+        append_g2(&self.beta_g2, &mut bytes);
+        append_g2(&self.gamma_g2, &mut bytes);
+        append_g2(&self.delta_g2, &mut bytes);
+        for point in &self.ic {
+            append_g1(point, &mut bytes);
+        }
+        bytes
+    }
+
+    /// keccak256 of the `to_bytes` encoding: identifies a key across
+    /// registries, tools and ceremony reports
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Keccak256::digest(self.to_bytes()).into()
+    }
+
+    /// Load REAL verifying key from constants
+    pub fn load_real() -> Self {
+        use vk_constants::*;
+
+        // Parse Alpha G1
+        let alpha_g1 = G1Affine::new(
+            Fq::from_be_bytes_mod_order(&ALPHA_G1_X),
+            Fq::from_be_bytes_mod_order(&ALPHA_G1_Y),
+        );
+
+        // Parse Beta G2
+        let beta_g2 = G2Affine::new(
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&BETA_G2_X0),
+                Fq::from_be_bytes_mod_order(&BETA_G2_X1),
+            ),
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&BETA_G2_Y0),
+                Fq::from_be_bytes_mod_order(&BETA_G2_Y1),
+            ),
+        );
+
+        // Parse Gamma G2
+        let gamma_g2 = G2Affine::new(
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&GAMMA_G2_X0),
+                Fq::from_be_bytes_mod_order(&GAMMA_G2_X1),
+            ),
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&GAMMA_G2_Y0),
+                Fq::from_be_bytes_mod_order(&GAMMA_G2_Y1),
+            ),
+        );
+
+        // Parse Delta G2
+        let delta_g2 = G2Affine::new(
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&DELTA_G2_X0),
+                Fq::from_be_bytes_mod_order(&DELTA_G2_X1),
+            ),
+            Fq2::new(
+                Fq::from_be_bytes_mod_order(&DELTA_G2_Y0),
+                Fq::from_be_bytes_mod_order(&DELTA_G2_Y1),
+            ),
+        );
+
+        // Parse IC (public input commitments)
+        let ic = vec![
+            G1Affine::new(
+                Fq::from_be_bytes_mod_order(&IC_0_X),
+                Fq::from_be_bytes_mod_order(&IC_0_Y),
+            ),
+            G1Affine::new(
+                Fq::from_be_bytes_mod_order(&IC_1_X),
+                Fq::from_be_bytes_mod_order(&IC_1_Y),
+            ),
+        ];
+
+        Self {
+            alpha_g1,
+            beta_g2,
+            gamma_g2,
+            delta_g2,
+            ic,
+        }
+    }
+}
+
+// ============================================================================
+// CORE VERIFICATION - THE REAL THING
+// ============================================================================
+
+/// Parse ABI `bytes[]` public inputs: one 32-byte BigEndian word each
+pub fn parse_public_inputs(inputs: &[Vec<u8>]) -> Result<Vec<Fr>, Vec<u8>> {
+    let mut result = Vec::new();
+    for input_bytes in inputs {
+        if input_bytes.len() != 32 {
+            return Err(b"Public input must be 32 bytes".to_vec());
+        }
+        result.push(read_fr(input_bytes)?);
+    }
+    Ok(result)
+}
+
+/// Read a public input from a 32-byte BigEndian word
+///
+/// Words >= r are rejected rather than reduced: x and x + r would otherwise
+/// be two encodings of one statement
+pub fn read_fr(word: &[u8]) -> Result<Fr, Vec<u8>> {
+    let value = Fr::from_be_bytes_mod_order(word);
+    if value.into_bigint().to_bytes_be() != word {
+        return Err(b"Public input not in the scalar field".to_vec());
+    }
+    Ok(value)
+}
+
+/// REAL Groth16 verification
+/// 
+/// Checks: e(A, B) = e(α, β) · e(L, γ) · e(C, δ)
+/// 
+/// Rearranged as: e(A, B) · e(-α, β) · e(-L, γ) · e(-C, δ) = 1
+/// 
+/// **CRITICAL FIX**: Checks result == GT::one(), NOT is_zero()!
+pub fn verify_groth16(
+    proof: &Groth16Proof,
+    public_inputs: &[Fr],
+    vk: &VerifyingKey,
+) -> Result<bool, Vec<u8>> {
+    let public_inputs_commitment = public_input_commitment(vk, public_inputs.iter().copied())?;
+    Ok(pairing_check(proof, &public_inputs_commitment, vk))
+}
+
+/// Compute L = IC[0] + Σ(IC[i] · public_input[i])
+pub fn public_input_commitment(
+    vk: &VerifyingKey,
+    public_inputs: impl ExactSizeIterator<Item = Fr>,
+) -> Result<G1Affine, Vec<u8>> {
+    // Validate input count
+    if public_inputs.len() + 1 != vk.ic.len() {
+        return Err(b"Invalid number of public inputs".to_vec());
+    }
+
+    Ok(accumulate_ic(vk.ic[0], &vk.ic[1..], public_inputs))
+}
+
+/// Add Σ(IC[i] · input[i]) for one run of IC points onto a partial commitment
+pub fn accumulate_ic(acc: G1Affine, ic: &[G1Affine], inputs: impl Iterator<Item = Fr>) -> G1Affine {
+    let mut acc = acc.into_group();
+    for (ic, input) in ic.iter().zip(inputs) {
+        acc += ic.mul_bigint(input.into_bigint());
+    }
+    acc.into_affine()
+}
+
+/// Native multi-pairing check: e(A, B) · e(-α, β) · e(-L, γ) · e(-C, δ) == 1
+pub fn pairing_check(
+    proof: &Groth16Proof,
+    public_inputs_commitment: &G1Affine,
+    vk: &VerifyingKey,
+) -> bool {
+    // Prepare pairing equation
+    let g1_inputs = [
+        proof.a,
+        -vk.alpha_g1,
+        -*public_inputs_commitment,
+        -proof.c,
+    ];
+    let g2_inputs = [proof.b, vk.beta_g2, vk.gamma_g2, vk.delta_g2];
+
+    // Compute product of pairings
+    let result = Bn254::multi_pairing(g1_inputs, g2_inputs);
+
+    // **CRITICAL**: GT group identity is ONE, not zero!
+    result.0 == ark_bn254::Fq12::one()
+}
+
+/// Size of the bn256Pairing (0x08) input for one Groth16 check: 4 pairs * 192 bytes
+pub const PAIRING_INPUT_SIZE: usize = 4 * 192;
+
+/// Encode the same pairing check for the bn256Pairing precompile
+///
+/// Format: (G1_x, G1_y, G2_x1, G2_x0, G2_y1, G2_y0) for each of
+/// (A, B), (-α, β), (-L, γ), (-C, δ); the precompile returns 1 iff the
+/// product of the pairings is one
+pub fn pairing_input(
+    proof: &Groth16Proof,
+    public_inputs_commitment: &G1Affine,
+    vk: &VerifyingKey,
+) -> Vec<u8> {
+    let mut input = Vec::with_capacity(PAIRING_INPUT_SIZE);
+
+    // Pairing 1: e(A, B)
+    append_g1(&proof.a, &mut input);
+    append_g2_eip197(&proof.b, &mut input);
+
+    // Pairing 2: e(-α, β)
+    append_g1(&(-vk.alpha_g1), &mut input);
+    append_g2_eip197(&vk.beta_g2, &mut input);
+
+    // Pairing 3: e(-L, γ)
+    append_g1(&(-*public_inputs_commitment), &mut input);
+    append_g2_eip197(&vk.gamma_g2, &mut input);
+
+    // Pairing 4: e(-C, δ)
+    append_g1(&(-proof.c), &mut input);
+    append_g2_eip197(&vk.delta_g2, &mut input);
+
+    input
+}
+
+// ============================================================================
+// POINT ENCODING
+// ============================================================================
+
+/// Append a base field element as a 32-byte big-endian word
+pub fn append_fq(value: &Fq, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&value.into_bigint().to_bytes_be());
+}
+
+/// Append a G1 point as x || y
+pub fn append_g1(point: &G1Affine, buf: &mut Vec<u8>) {
+    append_fq(&point.x, buf);
+    append_fq(&point.y, buf);
+}
+
+/// Append a G2 point as x0 || x1 || y0 || y1 (proof layout, not EIP-197)
+pub fn append_g2(point: &G2Affine, buf: &mut Vec<u8>) {
+    append_fq(&point.x.c0, buf);
+    append_fq(&point.x.c1, buf);
+    append_fq(&point.y.c0, buf);
+    append_fq(&point.y.c1, buf);
+}
+
+/// Append a G2 point as x1 || x0 || y1 || y0 (EIP-197: imaginary part first)
+pub fn append_g2_eip197(point: &G2Affine, buf: &mut Vec<u8>) {
+    append_fq(&point.x.c1, buf);
+    append_fq(&point.x.c0, buf);
+    append_fq(&point.y.c1, buf);
+    append_fq(&point.y.c0, buf);
+}
+
+/// Compressed points: top bit of the first byte marks the larger y root
+const Y_LARGER_FLAG: u8 = 0x80;
+/// Compressed points: second bit marks infinity, which no proof point may be
+const INFINITY_FLAG: u8 = 0x40;
+
+/// Append a G1 point as flagged x (32)
+pub fn append_g1_compressed(point: &G1Affine, buf: &mut Vec<u8>) {
+    let start = buf.len();
+    append_fq(&point.x, buf);
+    if point.y > -point.y {
+        buf[start] |= Y_LARGER_FLAG;
+    }
+}
+
+/// Append a G2 point as flagged x0 (32) || x1 (32)
+pub fn append_g2_compressed(point: &G2Affine, buf: &mut Vec<u8>) {
+    let start = buf.len();
+    append_fq(&point.x.c0, buf);
+    append_fq(&point.x.c1, buf);
+    if point.y > -point.y {
+        buf[start] |= Y_LARGER_FLAG;
+    }
+}
+
+/// Split the flags off a compressed x word; the rest must be a canonical Fq
+fn read_compressed_x(word: &[u8]) -> Result<(Fq, bool), Vec<u8>> {
+    let flags = word[0] & (Y_LARGER_FLAG | INFINITY_FLAG);
+    if flags & INFINITY_FLAG != 0 {
+        return Err(b"Point at infinity".to_vec());
+    }
+    let mut x_bytes = [0u8; 32];
+    x_bytes.copy_from_slice(&word[..32]);
+    x_bytes[0] &= !Y_LARGER_FLAG;
+    Ok((read_canonical_fq(&x_bytes)?, flags & Y_LARGER_FLAG != 0))
+}
+
+fn read_canonical_fq(word: &[u8]) -> Result<Fq, Vec<u8>> {
+    let value = Fq::from_be_bytes_mod_order(word);
+    if value.into_bigint().to_bytes_be() != word {
+        return Err(b"Non-canonical field element".to_vec());
+    }
+    Ok(value)
+}
+
+/// Read a G1 point from flagged x (32)
+pub fn read_g1_compressed(bytes: &[u8]) -> Result<G1Affine, Vec<u8>> {
+    if bytes.len() != 32 {
+        return Err(b"Expected 32 bytes for compressed G1 point".to_vec());
+    }
+    let (x, larger) = read_compressed_x(&bytes[0..32])?;
+    G1Affine::get_point_from_x_unchecked(x, larger).ok_or_else(|| b"G1 point not on curve".to_vec())
+}
+
+/// Read a G2 point from flagged x0 (32) || x1 (32)
+pub fn read_g2_compressed(bytes: &[u8]) -> Result<G2Affine, Vec<u8>> {
+    if bytes.len() != 64 {
+        return Err(b"Expected 64 bytes for compressed G2 point".to_vec());
+    }
+    let (x0, larger) = read_compressed_x(&bytes[0..32])?;
+    let x1 = read_canonical_fq(&bytes[32..64])?;
+    let point = G2Affine::get_point_from_x_unchecked(Fq2::new(x0, x1), larger)
+        .ok_or_else(|| b"G2 point not on curve".to_vec())?;
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(b"G2 point not in subgroup".to_vec());
+    }
+    Ok(point)
+}
+
+/// Why coordinates do not decode to a usable group element
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointError {
+    /// Not exactly one encoded point (64 bytes for G1, 128 for G2)
+    InvalidLength,
+    /// A coordinate is not below the field modulus
+    NonCanonical,
+    NotOnCurve,
+    /// On the curve but outside the prime-order subgroup (G2 only)
+    NotInSubgroup,
+}
+
+/// Decode a G1 point from x (32) || y (32)
+pub fn decode_g1(bytes: &[u8]) -> Result<G1Affine, PointError> {
+    if bytes.len() != 64 {
+        return Err(PointError::InvalidLength);
+    }
+    let point = G1Affine::new_unchecked(decode_fq(&bytes[0..32])?, decode_fq(&bytes[32..64])?);
+    if !point.is_on_curve() {
+        return Err(PointError::NotOnCurve);
+    }
+    Ok(point)
+}
+
+/// Decode a G2 point from x0 (32) || x1 (32) || y0 (32) || y1 (32)
+pub fn decode_g2(bytes: &[u8]) -> Result<G2Affine, PointError> {
+    if bytes.len() != 128 {
+        return Err(PointError::InvalidLength);
+    }
+    let point = G2Affine::new_unchecked(
+        Fq2::new(decode_fq(&bytes[0..32])?, decode_fq(&bytes[32..64])?),
+        Fq2::new(decode_fq(&bytes[64..96])?, decode_fq(&bytes[96..128])?),
+    );
+    if !point.is_on_curve() {
+        return Err(PointError::NotOnCurve);
+    }
+    if !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(PointError::NotInSubgroup);
+    }
+    Ok(point)
+}
+
+/// Coordinates >= p are rejected, so each point has one encoding
+fn decode_fq(word: &[u8]) -> Result<Fq, PointError> {
+    read_canonical_fq(word).map_err(|_| PointError::NonCanonical)
+}
+
+/// Read a G1 point from x (32) || y (32)
+pub fn read_g1(bytes: &[u8]) -> Result<G1Affine, Vec<u8>> {
+    decode_g1(bytes).map_err(|err| match err {
+        PointError::InvalidLength => b"Expected 64 bytes for G1 point".to_vec(),
+        PointError::NonCanonical => b"Non-canonical field element".to_vec(),
+        PointError::NotOnCurve | PointError::NotInSubgroup => b"G1 point not on curve".to_vec(),
+    })
+}
+
+/// Read a G2 point from x0 (32) || x1 (32) || y0 (32) || y1 (32)
+pub fn read_g2(bytes: &[u8]) -> Result<G2Affine, Vec<u8>> {
+    decode_g2(bytes).map_err(|err| match err {
+        PointError::InvalidLength => b"Expected 128 bytes for G2 point".to_vec(),
+        PointError::NonCanonical => b"Non-canonical field element".to_vec(),
+        PointError::NotOnCurve => b"G2 point not on curve".to_vec(),
+        PointError::NotInSubgroup => b"G2 point not in subgroup".to_vec(),
+    })
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_groth16::Groth16;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+    };
+    use ark_snark::SNARK;
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        UniformRand,
+    };
+    use hex_literal::hex;
+
+    #[test]
+    fn test_vk_loading() {
+        let vk = VerifyingKey::load_real();
+        
+        // Verify points are on curve
+        assert!(vk.alpha_g1.is_on_curve());
+        assert!(vk.beta_g2.is_on_curve());
+        assert!(vk.gamma_g2.is_on_curve());
+        assert!(vk.delta_g2.is_on_curve());
+        
+        for ic_point in &vk.ic {
+            assert!(ic_point.is_on_curve());
+        }
+
+        // G2 constants pasted in the wrong coordinate order land off the subgroup
+        for point in [vk.beta_g2, vk.gamma_g2, vk.delta_g2] {
+            assert!(point.is_in_correct_subgroup_assuming_on_curve());
+        }
+        assert_eq!(vk.ic.len(), 2, "multiplier.circom has one public signal");
+
+        // And the constants are the key the committed proof was made for
+        let proof = Groth16Proof::from_uncompressed_array(&MULTIPLIER_PROOF).unwrap();
+        assert_eq!(verify_groth16(&proof, &[Fr::from(15u64)], &vk), Ok(true));
+    }
+
+    #[test]
+    fn test_proof_format() {
+        // Test proof parsing with dummy data
+        let proof_bytes = vec![0u8; 256];
+
+        // (0, 0) is not on the curve, so parsing must fail without panicking
+        assert!(Groth16Proof::from_uncompressed_bytes(&proof_bytes).is_err());
+        assert!(Groth16Proof::from_uncompressed_bytes(&proof_bytes[..255]).is_err());
+    }
+
+    /// Seed of the development setup `vk_constants` comes from
+    const VK_CONSTANTS_SEED: u64 = 0x4172_6253_6869_656c;
+
+    /// Multiplier proof for a = 3, b = 5 (public input c = 15) under the committed key
+    pub(crate) const MULTIPLIER_PROOF: [u8; 256] = hex!(
+        "060ad445c57fa7577d31023c7e34a3d45c8dd3d7d909f70fd3f886a804543a82"
+        "271e3204a02c8c5b1666271797bced8e15afe57837e1c0b4c30e6c326c25d10d"
+        "1f1424dd7faddb82b736524c103485c687cc87ec3a1bbd99e581eb8a9758aff4"
+        "1cdc272aff80b234e10a29994249ef189375c6a276b111bdf84d31de68ad8e6c"
+        "175c0d5268f6a734a0cc8f4129504192547bb91a2e6bb7792386c67dd17481d7"
+        "0716c74441d420b6fd02389f198c16c4713015ca44c1a1bc7c3e6efbbc3e46e7"
+        "2c4902877de2a1f8a31419b9890f5ff47e7bbecd304aa749ea1d3b52d4594e8f"
+        "1bc68e147b81c555abfa3f923434edbd3e323999ade004c679ce8e41398ecf64"
+    );
+
+    /// 32-byte BigEndian word holding `value`
+    fn word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    fn packed_call(circuit_id: &[u8; 32], proof: &[u8], inputs: &[u64]) -> Vec<u8> {
+        let mut packed = circuit_id.to_vec();
+        packed.extend_from_slice(proof);
+        for input in inputs {
+            packed.extend_from_slice(&word(*input));
+        }
+        packed
+    }
+
+    fn verify_packed_call(packed: &[u8]) -> Result<bool, Vec<u8>> {
+        let call = PackedCall::parse(packed)?;
+        if call.circuit_id != &MULTIPLIER_CIRCUIT_ID {
+            return Err(b"Unknown circuit id".to_vec());
+        }
+        let vk = VerifyingKey::load_real();
+        let proof = Groth16Proof::from_uncompressed_array(call.proof)?;
+        let commitment = public_input_commitment(&vk, call.public_inputs()?.into_iter())?;
+        Ok(pairing_check(&proof, &commitment, &vk))
+    }
+
+    #[test]
+    fn test_packed_call_layout() {
+        let packed = packed_call(&MULTIPLIER_CIRCUIT_ID, &MULTIPLIER_PROOF, &[15, 7]);
+        let call = PackedCall::parse(&packed).unwrap();
+
+        assert_eq!(call.circuit_id, &MULTIPLIER_CIRCUIT_ID);
+        assert_eq!(call.proof, &MULTIPLIER_PROOF);
+        assert_eq!(call.inputs.len(), 2);
+        assert_eq!(
+            call.public_inputs(),
+            Ok(vec![Fr::from(15u64), Fr::from(7u64)])
+        );
+
+        // Truncated header or ragged input words are rejected
+        assert!(PackedCall::parse(&packed[..WORD_SIZE + PROOF_SIZE - 1]).is_err());
+        assert!(PackedCall::parse(&packed[..packed.len() - 1]).is_err());
+        assert!(PackedCall::parse(&packed[..WORD_SIZE + PROOF_SIZE]).unwrap().inputs.is_empty());
+    }
+
+    #[test]
+    fn test_packed_verification() {
+        let valid = packed_call(&MULTIPLIER_CIRCUIT_ID, &MULTIPLIER_PROOF, &[15]);
+        assert_eq!(verify_packed_call(&valid), Ok(true));
+
+        let wrong_input = packed_call(&MULTIPLIER_CIRCUIT_ID, &MULTIPLIER_PROOF, &[16]);
+        assert_eq!(verify_packed_call(&wrong_input), Ok(false));
+
+        let extra_input = packed_call(&MULTIPLIER_CIRCUIT_ID, &MULTIPLIER_PROOF, &[15, 1]);
+        assert!(verify_packed_call(&extra_input).is_err());
+
+        let unknown_circuit = packed_call(&[0u8; 32], &MULTIPLIER_PROOF, &[15]);
+        assert!(verify_packed_call(&unknown_circuit).is_err());
+    }
+
+    #[test]
+    fn test_pairing_input_layout() {
+        let vk = VerifyingKey::load_real();
+        let proof = Groth16Proof::from_uncompressed_array(&MULTIPLIER_PROOF).unwrap();
+        let commitment = public_input_commitment(&vk, core::iter::once(Fr::from(15u64))).unwrap();
+        let input = pairing_input(&proof, &commitment, &vk);
+
+        assert_eq!(input.len(), PAIRING_INPUT_SIZE);
+        // A keeps the proof layout; B swaps each Fq2 to imaginary part first
+        assert_eq!(&input[0..64], &MULTIPLIER_PROOF[0..64]);
+        assert_eq!(&input[64..96], &MULTIPLIER_PROOF[96..128]);
+        assert_eq!(&input[96..128], &MULTIPLIER_PROOF[64..96]);
+        assert_eq!(&input[128..160], &MULTIPLIER_PROOF[160..192]);
+        assert_eq!(&input[160..192], &MULTIPLIER_PROOF[128..160]);
+    }
+
+    #[test]
+    fn test_compressed_proof_roundtrip() {
+        let proof = Groth16Proof::from_uncompressed_array(&MULTIPLIER_PROOF).unwrap();
+        assert_eq!(proof.to_uncompressed_bytes(), MULTIPLIER_PROOF);
+
+        let compressed = proof.to_compressed_bytes();
+        let decoded = Groth16Proof::from_compressed_bytes(&compressed).unwrap();
+        assert_eq!(decoded.to_uncompressed_bytes(), MULTIPLIER_PROOF);
+
+        // Flipping a sign flag selects the other root, which is a different proof
+        let mut flipped = compressed;
+        flipped[0] ^= 0x80;
+        let other = Groth16Proof::from_compressed_bytes(&flipped).unwrap();
+        assert_eq!(other.a, -proof.a);
+
+        let mut infinity = compressed;
+        infinity[96] |= 0x40;
+        assert!(Groth16Proof::from_compressed_bytes(&infinity).is_err());
+        assert!(Groth16Proof::from_compressed_bytes(&compressed[..127]).is_err());
+    }
+
+    /// a * b = c with c as the only public input
+    #[derive(Clone)]
+    struct ProductCircuit {
+        a: Option<Fr>,
+        b: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for ProductCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let product = self.a.zip(self.b).map(|(a, b)| a * b);
+            let c = cs.new_input_variable(|| product.ok_or(SynthesisError::AssignmentMissing))?;
+            let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + c)
+        }
+    }
+
+    #[test]
+    fn test_vk_provenance() {
+        // `vk_constants` and `MULTIPLIER_PROOF` are the first draws of this setup
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(VK_CONSTANTS_SEED);
+        let empty = ProductCircuit { a: None, b: None };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(empty, &mut rng).unwrap();
+        let regenerated = VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.gamma_abc_g1,
+        };
+        assert_eq!(regenerated.to_bytes(), VerifyingKey::load_real().to_bytes());
+
+        let witness = ProductCircuit { a: Some(Fr::from(3u64)), b: Some(Fr::from(5u64)) };
+        let proof = Groth16::<Bn254>::prove(&pk, witness, &mut rng).unwrap();
+        let proof = Groth16Proof { a: proof.a, b: proof.b, c: proof.c };
+        assert_eq!(proof.to_uncompressed_bytes(), MULTIPLIER_PROOF);
+    }
+
+    /// Fresh setup for `ProductCircuit`, with our key type alongside the proving key
+    pub(crate) fn product_setup() -> (ark_groth16::ProvingKey<Bn254>, VerifyingKey) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let empty = ProductCircuit { a: None, b: None };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(empty, &mut rng).unwrap();
+        let vk = VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.gamma_abc_g1,
+        };
+        (pk, vk)
+    }
+
+    pub(crate) fn prove_product(pk: &ark_groth16::ProvingKey<Bn254>, c: Fr) -> Groth16Proof {
+        let mut rng = StdRng::seed_from_u64(2);
+        let witness = ProductCircuit { a: Some(c), b: Some(Fr::one()) };
+        let proof = Groth16::<Bn254>::prove(pk, witness, &mut rng).unwrap();
+        Groth16Proof { a: proof.a, b: proof.b, c: proof.c }
+    }
+
+    #[test]
+    fn test_vk_bytes_roundtrip() {
+        let vk = VerifyingKey::load_real();
+        let bytes = vk.to_bytes();
+        assert_eq!(bytes.len(), VK_HEADER_SIZE + 2 * 64);
+
+        let decoded = VerifyingKey::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.alpha_g1, vk.alpha_g1);
+        assert_eq!(decoded.beta_g2, vk.beta_g2);
+        assert_eq!(decoded.gamma_g2, vk.gamma_g2);
+        assert_eq!(decoded.delta_g2, vk.delta_g2);
+        assert_eq!(decoded.ic, vk.ic);
+
+        assert!(VerifyingKey::from_bytes(&bytes[..VK_HEADER_SIZE]).is_err());
+        assert!(VerifyingKey::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut off_curve = bytes.clone();
+        off_curve[63] ^= 1;
+        assert!(VerifyingKey::from_bytes(&off_curve).is_err());
+    }
+
+    #[test]
+    fn test_input_mode_digest() {
+        let inputs = [[1u8; 32], [2u8; 32]];
+        let packed = inputs.as_flattened();
+
+        assert_eq!(InputMode::Direct.digest(packed), None);
+        assert_eq!(
+            InputMode::Keccak256.digest(packed),
+            Some(Fr::from_be_bytes_mod_order(&Keccak256::digest(packed)))
+        );
+        assert_eq!(
+            InputMode::Sha256.digest(packed),
+            Some(Fr::from_be_bytes_mod_order(&Sha256::digest(packed)))
+        );
+        assert_ne!(InputMode::Keccak256.digest(packed), InputMode::Sha256.digest(packed));
+
+        for mode in [InputMode::Direct, InputMode::Keccak256, InputMode::Sha256] {
+            assert_eq!(InputMode::from_u8(mode.as_u8()), Ok(mode));
+        }
+        assert!(InputMode::from_u8(3).is_err());
+    }
+
+    #[test]
+    fn test_hashed_input_verification() {
+        let (pk, vk) = product_setup();
+        let signals: Vec<u8> = (1..=40u64).flat_map(word).collect();
+
+        for mode in [InputMode::Keccak256, InputMode::Sha256] {
+            let digest = mode.digest(&signals).unwrap();
+            let proof = prove_product(&pk, digest);
+
+            let commitment = public_input_commitment(&vk, core::iter::once(digest)).unwrap();
+            assert!(pairing_check(&proof, &commitment, &vk));
+
+            // Changing any signal changes the digest, and the proof no longer verifies
+            let mut tampered = signals.clone();
+            tampered[31] ^= 1;
+            let digest = mode.digest(&tampered).unwrap();
+            let commitment = public_input_commitment(&vk, core::iter::once(digest)).unwrap();
+            assert!(!pairing_check(&proof, &commitment, &vk));
+        }
+    }
+
+    #[test]
+    fn test_chunked_ic_accumulation() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let ic: Vec<G1Affine> = (0..10).map(|_| G1Affine::rand(&mut rng)).collect();
+        let inputs: Vec<Fr> = (0..9).map(|_| Fr::rand(&mut rng)).collect();
+        let vk = VerifyingKey { ic: ic.clone(), ..VerifyingKey::load_real() };
+
+        let one_shot = public_input_commitment(&vk, inputs.iter().copied()).unwrap();
+
+        // Same result however the inputs are split across session chunks
+        for chunk_size in [1, 2, 4, 9] {
+            let mut acc = ic[0];
+            for (index, chunk) in inputs.chunks(chunk_size).enumerate() {
+                let start = 1 + index * chunk_size;
+                acc = accumulate_ic(acc, &ic[start..start + chunk.len()], chunk.iter().copied());
+            }
+            assert_eq!(acc, one_shot);
+        }
+    }
+}
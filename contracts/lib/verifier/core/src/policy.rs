@@ -0,0 +1,172 @@
+//! COMPLIANCE POLICIES - BOOLEAN EXPRESSIONS OVER ATTRIBUTES
+//!
+//! A policy is a list of terms, each an attribute with a freshness limit,
+//! and a postfix program combining them. For
+//! `accredited_investor AND (NOT us_person OR qualified_purchaser) AND kyc`
+//! with terms `[accredited_investor, us_person, qualified_purchaser, kyc]`:
+//!
+//! ```text
+//! TERM 0, TERM 1, NOT, TERM 2, OR, AND, TERM 3, AND
+//! 00 00   00 01   03   00 02   02  01   00 03   01
+//! ```
+//!
+//! A term is false both when the user proved nothing and when the proof has
+//! lapsed, so `!us_person` would read a missing record as proof of absence.
+//! [`OP_NOT`] therefore applies to a single term and asks for a record of
+//! its attested negative attribute instead, `not_us_person` here (see
+//! [`negated`]); negating anything larger is rejected.
+//!
+//! Programs are checked once with [`validate`] when a policy is stored, so
+//! [`evaluate`] never sees a malformed one.
+
+use alloc::{string::String, vec::Vec};
+
+/// Most terms a policy may reference
+pub const MAX_TERMS: usize = 16;
+/// Longest program, in bytes
+pub const MAX_PROGRAM_LEN: usize = 64;
+
+/// Push the value of the term whose index follows
+pub const OP_TERM: u8 = 0x00;
+/// Pop two values, push their conjunction
+pub const OP_AND: u8 = 0x01;
+/// Pop two values, push their disjunction
+pub const OP_OR: u8 = 0x02;
+/// Directly after a term: the user holds that term's negative attribute
+pub const OP_NOT: u8 = 0x03;
+
+/// Prefix naming the attested negative of an attribute
+pub const NEGATION_PREFIX: &str = "not_";
+
+/// Attribute whose record proves `attribute` does not hold, e.g.
+/// `not_us_person` for `us_person`
+pub fn negated(attribute: &str) -> String {
+    [NEGATION_PREFIX, attribute].concat()
+}
+
+/// Check a program over `term_count` terms leaves exactly one value
+pub fn validate(program: &[u8], term_count: usize) -> Result<(), Vec<u8>> {
+    if program.is_empty() || program.len() > MAX_PROGRAM_LEN {
+        return Err(b"Invalid policy program length".to_vec());
+    }
+    let mut depth = 0usize;
+    let mut after_term = false;
+    let mut ops = program.iter();
+    while let Some(op) = ops.next() {
+        let (pops, pushes) = match *op {
+            OP_TERM => match ops.next() {
+                Some(index) if (*index as usize) < term_count => (0, 1),
+                Some(_) => return Err(b"Policy term out of range".to_vec()),
+                None => return Err(b"Truncated policy program".to_vec()),
+            },
+            OP_AND | OP_OR => (2, 1),
+            OP_NOT if after_term => (1, 1),
+            OP_NOT => return Err(b"Policies can only negate a term".to_vec()),
+            _ => return Err(b"Unknown policy opcode".to_vec()),
+        };
+        after_term = *op == OP_TERM;
+        depth = depth
+            .checked_sub(pops)
+            .ok_or_else(|| b"Policy operator missing operands".to_vec())?
+            + pushes;
+    }
+    match depth {
+        1 => Ok(()),
+        _ => Err(b"Policy must reduce to one value".to_vec()),
+    }
+}
+
+/// Run a validated program, asking `term` for each term it references and
+/// whether it is negated
+pub fn evaluate(program: &[u8], mut term: impl FnMut(usize, bool) -> bool) -> Result<bool, Vec<u8>> {
+    let malformed = || b"Malformed policy program".to_vec();
+    let mut stack: Vec<bool> = Vec::with_capacity(program.len());
+    let mut ops = program.iter().peekable();
+    while let Some(op) = ops.next() {
+        let value = match *op {
+            OP_TERM => {
+                let index = *ops.next().ok_or_else(malformed)? as usize;
+                term(index, ops.next_if_eq(&&OP_NOT).is_some())
+            }
+            OP_AND | OP_OR => {
+                let (right, left) = (stack.pop().ok_or_else(malformed)?, stack.pop().ok_or_else(malformed)?);
+                match *op {
+                    OP_AND => left && right,
+                    _ => left || right,
+                }
+            }
+            _ => return Err(malformed()),
+        };
+        stack.push(value);
+    }
+    match stack[..] {
+        [value] => Ok(value),
+        _ => Err(malformed()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// accredited AND (non_us_person OR qualified) AND kyc
+    const EXAMPLE: [u8; 11] = [OP_TERM, 0, OP_TERM, 1, OP_TERM, 2, OP_OR, OP_AND, OP_TERM, 3, OP_AND];
+
+    #[test]
+    fn test_evaluate() {
+        validate(&EXAMPLE, 4).unwrap();
+        let run = |held: [bool; 4]| evaluate(&EXAMPLE, |index, _| held[index]).unwrap();
+        assert!(run([true, true, false, true]));
+        assert!(run([true, false, true, true]));
+        assert!(!run([true, false, false, true]));
+        assert!(!run([false, true, true, true]));
+        assert!(!run([true, true, true, false]));
+
+        // Each reference asks for its term once
+        let mut asked = Vec::new();
+        evaluate(&EXAMPLE, |index, _| {
+            asked.push(index);
+            true
+        })
+        .unwrap();
+        assert_eq!(asked, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate(&[OP_TERM, 0], 1), Ok(()));
+        assert_eq!(validate(&[], 1), Err(b"Invalid policy program length".to_vec()));
+        assert_eq!(validate(&[OP_NOT; MAX_PROGRAM_LEN + 1], 1), Err(b"Invalid policy program length".to_vec()));
+        assert_eq!(validate(&EXAMPLE, 3), Err(b"Policy term out of range".to_vec()));
+        assert_eq!(validate(&[OP_TERM], 1), Err(b"Truncated policy program".to_vec()));
+        assert_eq!(validate(&[OP_TERM, 0, OP_AND], 1), Err(b"Policy operator missing operands".to_vec()));
+        assert_eq!(validate(&[OP_TERM, 0, OP_OR], 1), Err(b"Policy operator missing operands".to_vec()));
+        assert_eq!(validate(&[OP_TERM, 0, OP_TERM, 0], 1), Err(b"Policy must reduce to one value".to_vec()));
+        assert_eq!(validate(&[OP_TERM, 0, 0x04], 1), Err(b"Unknown policy opcode".to_vec()));
+    }
+
+    #[test]
+    fn test_negation() {
+        // accredited AND (NOT us_person OR qualified) AND kyc
+        let program = [OP_TERM, 0, OP_TERM, 1, OP_NOT, OP_TERM, 2, OP_OR, OP_AND, OP_TERM, 3, OP_AND];
+        validate(&program, 4).unwrap();
+        let mut asked = Vec::new();
+        let holds = evaluate(&program, |index, negated| {
+            asked.push((index, negated));
+            index != 2
+        });
+        assert_eq!(holds, Ok(true));
+        assert_eq!(asked, [(0, false), (1, true), (2, false), (3, false)]);
+        assert_eq!(negated("us_person"), "not_us_person");
+
+        // Only a term's attested negative can be asked for, never a whole expression
+        let rejected = Err(b"Policies can only negate a term".to_vec());
+        assert_eq!(validate(&[OP_NOT, OP_TERM, 0], 1), rejected);
+        assert_eq!(validate(&[OP_TERM, 0, OP_NOT, OP_NOT], 1), rejected);
+        assert_eq!(validate(&[OP_TERM, 0, OP_TERM, 1, OP_OR, OP_NOT], 2), rejected);
+        assert_eq!(
+            evaluate(&[OP_TERM, 0, OP_TERM, 1, OP_OR, OP_NOT], |_, _| false),
+            Err(b"Malformed policy program".to_vec())
+        );
+    }
+}
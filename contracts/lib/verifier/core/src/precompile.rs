@@ -0,0 +1,484 @@
+//! GROTH16 THROUGH THE BN254 PRECOMPILES - NO CURVE ARITHMETIC IN WASM
+//!
+//! The check `verify_groth16` makes, on raw encodings: the IC sum goes
+//! through ecMul (0x07) and ecAdd (0x06), the pairing product through
+//! ecPairing (0x08). Here only bytes are compared, reordered and
+//! subtracted, so a Stylus program verifying this way links no field or
+//! curve code; the precompiles reject points off the curve, and 0x08 G2
+//! points outside the subgroup. Hashed inputs go through sha256 (0x02)
+//! or the host's keccak256, so no hash code is linked either.
+//!
+//! Keys and proofs use the `VerifyingKey::to_bytes` and
+//! `Groth16Proof::to_uncompressed_bytes` layouts, and are rejected wherever
+//! `VerifyingKey::from_bytes` and `Groth16Proof::from_uncompressed_bytes`
+//! reject them. [`Native`] runs the precompiles with arkworks, for hosts
+//! and off-chain callers.
+
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, One, PrimeField, Zero};
+
+use sha2::Digest;
+
+use crate::{InputMode, PROOF_SIZE, VK_HEADER_SIZE, WORD_SIZE};
+
+/// Base field modulus p, big-endian
+pub const FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29,
+    0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d,
+    0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Scalar field modulus r, big-endian
+pub const SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29,
+    0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91,
+    0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// SHA-256: bytes -> 32-byte digest
+pub const SHA256: u8 = 0x02;
+/// ecAdd: G1 (64) || G1 (64) -> G1
+pub const EC_ADD: u8 = 0x06;
+/// ecMul: G1 (64) || scalar (32) -> G1
+pub const EC_MUL: u8 = 0x07;
+/// ecPairing: (G1 (64) || G2 (128))* -> 1 iff the product of pairings is one
+pub const EC_PAIRING: u8 = 0x08;
+
+/// (0, 0), which the precompiles read as the point at infinity
+const INFINITY: [u8; 64] = [0; 64];
+
+/// The host's precompiled contracts
+pub trait Precompiles {
+    /// Call the precompile at address `id`; `None` if the call fails, as
+    /// 0x06-0x08 do for points that do not decode
+    fn call(&mut self, id: u8, input: &[u8]) -> Option<Vec<u8>>;
+
+    /// keccak256, an opcode rather than a precompile; hosts with a native
+    /// one override this so programs link no hash code
+    fn keccak(&mut self, bytes: &[u8]) -> [u8; 32] {
+        sha3::Keccak256::digest(bytes).into()
+    }
+}
+
+// ============================================================================
+// ENCODINGS
+// ============================================================================
+
+/// Check a G1 point is x (32) || y (32) with canonical coordinates and not
+/// (0, 0); the curve equation is left to the precompiles
+pub fn check_g1(bytes: &[u8]) -> Result<&[u8; 64], Vec<u8>> {
+    let point: &[u8; 64] = bytes.try_into().map_err(|_| b"Expected 64 bytes for G1 point".to_vec())?;
+    check_coordinates(point)?;
+    Ok(point)
+}
+
+/// Check a G2 point is x0 || x1 || y0 || y1 (32 each) with canonical
+/// coordinates and not all zero; curve and subgroup are left to 0x08
+pub fn check_g2(bytes: &[u8]) -> Result<&[u8; 128], Vec<u8>> {
+    let point: &[u8; 128] = bytes.try_into().map_err(|_| b"Expected 128 bytes for G2 point".to_vec())?;
+    check_coordinates(point)?;
+    Ok(point)
+}
+
+/// Coordinates >= p would reach the precompiles as a second encoding of a
+/// point, and all-zero ones as infinity, which `decode_g1`/`decode_g2` reject
+fn check_coordinates(point: &[u8]) -> Result<(), Vec<u8>> {
+    if point.chunks_exact(32).any(|word| word >= &FIELD_MODULUS[..]) {
+        return Err(b"Non-canonical field element".to_vec());
+    }
+    if point.iter().all(|byte| *byte == 0) {
+        return Err(b"Point at infinity".to_vec());
+    }
+    Ok(())
+}
+
+/// Check a public input word is below r, as `read_fr` does
+pub fn check_scalar(word: &[u8; WORD_SIZE]) -> Result<(), Vec<u8>> {
+    match word < &SCALAR_MODULUS {
+        true => Ok(()),
+        false => Err(b"Public input not in the scalar field".to_vec()),
+    }
+}
+
+/// A 256-bit word mod r, as `Fr::from_be_bytes_mod_order` reduces a digest
+pub fn reduce_scalar(mut word: [u8; WORD_SIZE]) -> [u8; WORD_SIZE] {
+    // 2^256 < 6r, so at most five subtractions
+    while word >= SCALAR_MODULUS {
+        word = sub(&word, &SCALAR_MODULUS);
+    }
+    word
+}
+
+/// -P: (x, p - y), or (0, 0) for infinity
+pub fn negate_g1(point: &[u8; 64]) -> [u8; 64] {
+    let mut negated = *point;
+    let y: &[u8; 32] = point[32..].try_into().expect("32 bytes");
+    if y.iter().any(|byte| *byte != 0) {
+        negated[32..].copy_from_slice(&sub(&FIELD_MODULUS, y));
+    }
+    negated
+}
+
+/// x0 || x1 || y0 || y1 as EIP-197's x1 || x0 || y1 || y0 (imaginary part first)
+pub fn g2_eip197(point: &[u8; 128]) -> [u8; 128] {
+    let mut swapped = [0u8; 128];
+    for (pair, out) in point.chunks_exact(64).zip(swapped.chunks_exact_mut(64)) {
+        out[..32].copy_from_slice(&pair[32..]);
+        out[32..].copy_from_slice(&pair[..32]);
+    }
+    swapped
+}
+
+/// a - b for a >= b, big-endian
+fn sub(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let diff = 0x100 + a[i] as u16 - b[i] as u16 - borrow;
+        out[i] = diff as u8;
+        borrow = (diff < 0x100) as u16;
+    }
+    out
+}
+
+// ============================================================================
+// PRECOMPILE CALLS
+// ============================================================================
+
+/// a + b through 0x06; fails for a point off the curve
+pub fn ec_add(precompiles: &mut impl Precompiles, a: &[u8; 64], b: &[u8; 64]) -> Result<[u8; 64], Vec<u8>> {
+    let output = precompiles.call(EC_ADD, &[&a[..], &b[..]].concat());
+    output.and_then(|output| output.try_into().ok()).ok_or_else(|| b"G1 point not on curve".to_vec())
+}
+
+/// scalar · point through 0x07; fails for a point off the curve
+pub fn ec_mul(precompiles: &mut impl Precompiles, point: &[u8; 64], scalar: &[u8; 32]) -> Result<[u8; 64], Vec<u8>> {
+    let output = precompiles.call(EC_MUL, &[&point[..], &scalar[..]].concat());
+    output.and_then(|output| output.try_into().ok()).ok_or_else(|| b"G1 point not on curve".to_vec())
+}
+
+/// Add Σ ic[i] · inputs[i] onto a partial commitment, as `accumulate_ic`;
+/// `ic` is 64-byte points already checked by `check_key`, and every input
+/// must pass `check_scalar`
+pub fn accumulate_ic(
+    precompiles: &mut impl Precompiles,
+    mut acc: [u8; 64],
+    ic: &[u8],
+    inputs: &[[u8; WORD_SIZE]],
+) -> Result<[u8; 64], Vec<u8>> {
+    for (point, input) in ic.as_chunks::<64>().0.iter().zip(inputs) {
+        check_scalar(input)?;
+        // 0 · P is infinity; skipping the call leaves the sum unchanged
+        if input.iter().any(|byte| *byte != 0) {
+            let product = ec_mul(precompiles, point, input)?;
+            acc = ec_add(precompiles, &acc, &product)?;
+        }
+    }
+    Ok(acc)
+}
+
+/// Check a key in the `VerifyingKey::to_bytes` layout is one
+/// `VerifyingKey::from_bytes` accepts: G1 points through 0x06 (one ecAdd
+/// each), the three G2 points through one 0x08 call paired with infinity.
+/// Returns its IC length
+pub fn check_key(precompiles: &mut impl Precompiles, vk: &[u8]) -> Result<usize, Vec<u8>> {
+    if vk.len() < VK_HEADER_SIZE + 64 || !(vk.len() - VK_HEADER_SIZE).is_multiple_of(64) {
+        return Err(b"Invalid verifying key length".to_vec());
+    }
+    let g1s = core::iter::once(&vk[..64]).chain(vk[VK_HEADER_SIZE..].chunks_exact(64));
+    for point in g1s {
+        ec_add(precompiles, check_g1(point)?, &INFINITY)?;
+    }
+    let mut pairs = Vec::with_capacity(3 * 192);
+    for point in vk[64..VK_HEADER_SIZE].chunks_exact(128) {
+        pairs.extend_from_slice(&INFINITY);
+        pairs.extend_from_slice(&g2_eip197(check_g2(point)?));
+    }
+    precompiles.call(EC_PAIRING, &pairs).ok_or_else(|| b"G2 point not on curve or not in subgroup".to_vec())?;
+    Ok((vk.len() - VK_HEADER_SIZE) / 64)
+}
+
+/// Check a proof in the `Groth16Proof::to_uncompressed_bytes` layout has
+/// canonical, finite coordinates; the curve is checked by `pairing_check`
+pub fn check_proof(proof: &[u8]) -> Result<&[u8; PROOF_SIZE], Vec<u8>> {
+    let proof: &[u8; PROOF_SIZE] =
+        proof.try_into().map_err(|_| b"Expected 256 bytes for uncompressed proof".to_vec())?;
+    check_g1(&proof[0..64])?;
+    check_g2(&proof[64..192])?;
+    check_g1(&proof[192..256])?;
+    Ok(proof)
+}
+
+/// e(A, B) · e(-α, β) · e(-L, γ) · e(-C, δ) == 1 through one 0x08 call,
+/// for a proof from `check_proof` and a key header from `check_key`
+///
+/// 0x08 fails on a proof point off the curve (or B outside G2's subgroup),
+/// which is an error rather than a rejected proof, as in `Groth16Proof`
+pub fn pairing_check(
+    precompiles: &mut impl Precompiles,
+    proof: &[u8; PROOF_SIZE],
+    commitment: &[u8; 64],
+    vk_header: &[u8],
+) -> Result<bool, Vec<u8>> {
+    let g1 = |bytes: &[u8]| -> [u8; 64] { bytes.try_into().expect("64 bytes") };
+    let g2 = |bytes: &[u8]| -> [u8; 128] { g2_eip197(bytes.try_into().expect("128 bytes")) };
+
+    let mut input = Vec::with_capacity(4 * 192);
+    input.extend_from_slice(&proof[0..64]);
+    input.extend_from_slice(&g2(&proof[64..192]));
+    input.extend_from_slice(&negate_g1(&g1(&vk_header[0..64])));
+    input.extend_from_slice(&g2(&vk_header[64..192]));
+    input.extend_from_slice(&negate_g1(commitment));
+    input.extend_from_slice(&g2(&vk_header[192..320]));
+    input.extend_from_slice(&negate_g1(&g1(&proof[192..256])));
+    input.extend_from_slice(&g2(&vk_header[320..448]));
+
+    let output = precompiles.call(EC_PAIRING, &input).ok_or_else(|| b"Proof point not on curve".to_vec())?;
+    Ok(output.len() == 32 && output[31] == 1)
+}
+
+/// Verify a proof against a key from `check_key` and its direct public
+/// inputs: `verify_groth16` on encodings
+pub fn verify(
+    precompiles: &mut impl Precompiles,
+    vk: &[u8],
+    proof: &[u8],
+    inputs: &[[u8; WORD_SIZE]],
+) -> Result<bool, Vec<u8>> {
+    let proof = check_proof(proof)?;
+    let ic = &vk[VK_HEADER_SIZE..];
+    if inputs.len() + 1 != ic.len() / 64 {
+        return Err(b"Invalid number of public inputs".to_vec());
+    }
+    let commitment = accumulate_ic(precompiles, ic[..64].try_into().expect("64 bytes"), &ic[64..], inputs)?;
+    pairing_check(precompiles, proof, &commitment, &vk[..VK_HEADER_SIZE])
+}
+
+/// `InputMode::digest_word`, hashing sha256 through 0x02 and keccak256
+/// through `Precompiles::keccak`; `None` in direct mode
+pub fn digest_word(
+    precompiles: &mut impl Precompiles,
+    mode: InputMode,
+    packed_inputs: &[u8],
+) -> Result<Option<[u8; WORD_SIZE]>, Vec<u8>> {
+    let digest = match mode {
+        InputMode::Direct => return Ok(None),
+        InputMode::Keccak256 => precompiles.keccak(packed_inputs),
+        InputMode::Sha256 => precompiles
+            .call(SHA256, packed_inputs)
+            .and_then(|output| output.try_into().ok())
+            .ok_or_else(|| b"sha256 precompile failed".to_vec())?,
+    };
+    Ok(Some(reduce_scalar(digest)))
+}
+
+// ============================================================================
+// NATIVE PRECOMPILES
+// ============================================================================
+
+/// 0x02 with sha2, and 0x06, 0x07 and 0x08 with arkworks as EIP-196/197
+/// specify them: short input reads as zero-padded for 0x06/0x07, (0, 0) is
+/// infinity, and coordinates >= p fail the call
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Native;
+
+impl Precompiles for Native {
+    fn call(&mut self, id: u8, input: &[u8]) -> Option<Vec<u8>> {
+        match id {
+            SHA256 => Some(sha2::Sha256::digest(input).to_vec()),
+            EC_ADD => {
+                let input = padded::<128>(input);
+                Some(encode_g1(&(native_g1(&input[..64])? + native_g1(&input[64..])?).into_affine()))
+            }
+            EC_MUL => {
+                // The scalar is a full 256-bit integer, not reduced mod r
+                let input = padded::<96>(input);
+                Some(encode_g1(&native_g1(&input[..64])?.mul_bigint(bigint(&input[64..])).into_affine()))
+            }
+            EC_PAIRING => {
+                if !input.len().is_multiple_of(192) {
+                    return None;
+                }
+                let (g1s, g2s): (Vec<_>, Vec<_>) = input
+                    .chunks_exact(192)
+                    .map(|pair| Some((native_g1(&pair[..64])?, native_g2(&pair[64..])?)))
+                    .collect::<Option<Vec<_>>>()?
+                    .into_iter()
+                    .unzip();
+                let mut output = vec![0u8; 32];
+                output[31] = Bn254::multi_pairing(g1s, g2s).0.is_one() as u8;
+                Some(output)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let len = input.len().min(N);
+    bytes[..len].copy_from_slice(&input[..len]);
+    bytes
+}
+
+fn bigint(bytes: &[u8]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("8 bytes"));
+    }
+    BigInt::new(limbs)
+}
+
+fn native_fq(bytes: &[u8]) -> Option<Fq> {
+    Fq::from_bigint(bigint(bytes))
+}
+
+fn native_g1(bytes: &[u8]) -> Option<G1Affine> {
+    let (x, y) = (native_fq(&bytes[..32])?, native_fq(&bytes[32..64])?);
+    if x.is_zero() && y.is_zero() {
+        return Some(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    point.is_on_curve().then_some(point)
+}
+
+/// EIP-197 layout: x.c1 || x.c0 || y.c1 || y.c0
+fn native_g2(bytes: &[u8]) -> Option<G2Affine> {
+    let x = Fq2::new(native_fq(&bytes[32..64])?, native_fq(&bytes[..32])?);
+    let y = Fq2::new(native_fq(&bytes[96..128])?, native_fq(&bytes[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        return Some(G2Affine::zero());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    match point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        true => Some(point),
+        false => None,
+    }
+}
+
+fn encode_g1(point: &G1Affine) -> Vec<u8> {
+    match point.xy() {
+        Some((x, y)) => [x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()].concat(),
+        None => vec![0u8; 64],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_fr, verify_groth16, Groth16Proof, InputMode, VerifyingKey};
+    use ark_bn254::Fr;
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        UniformRand,
+    };
+
+    const MULTIPLIER_PROOF: [u8; PROOF_SIZE] = crate::tests::MULTIPLIER_PROOF;
+
+    fn word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_moduli() {
+        assert_eq!(FIELD_MODULUS[..], Fq::MODULUS.to_bytes_be());
+        assert_eq!(SCALAR_MODULUS[..], Fr::MODULUS.to_bytes_be());
+
+        let mut rng = StdRng::seed_from_u64(5);
+        for word in [[0xff; 32], SCALAR_MODULUS, [0; 32], core::array::from_fn(|_| u8::rand(&mut rng))] {
+            let reduced = Fr::from_be_bytes_mod_order(&word);
+            assert_eq!(reduce_scalar(word)[..], reduced.into_bigint().to_bytes_be());
+            assert_eq!(check_scalar(&word).is_ok(), read_fr(&word).is_ok());
+        }
+        let digest = InputMode::Keccak256.digest(b"inputs").unwrap();
+        assert!(check_scalar(digest.into_bigint().to_bytes_be()[..].try_into().unwrap()).is_ok());
+
+        for mode in [InputMode::Direct, InputMode::Keccak256, InputMode::Sha256] {
+            assert_eq!(digest_word(&mut Native, mode, b"inputs"), Ok(mode.digest_word(b"inputs")));
+        }
+    }
+
+    #[test]
+    fn test_matches_native_verification() {
+        let vk = VerifyingKey::load_real();
+        let vk_bytes = vk.to_bytes();
+        assert_eq!(check_key(&mut Native, &vk_bytes), Ok(2));
+
+        for input in [15, 16, 0] {
+            let native = verify_groth16(
+                &Groth16Proof::from_uncompressed_bytes(&MULTIPLIER_PROOF).unwrap(),
+                &[Fr::from(input)],
+                &vk,
+            );
+            assert_eq!(verify(&mut Native, &vk_bytes, &MULTIPLIER_PROOF, &[word(input)]), native);
+        }
+        assert_eq!(
+            verify(&mut Native, &vk_bytes, &MULTIPLIER_PROOF, &[word(15), word(1)]),
+            Err(b"Invalid number of public inputs".to_vec())
+        );
+        assert!(verify(&mut Native, &vk_bytes, &MULTIPLIER_PROOF, &[SCALAR_MODULUS]).is_err());
+
+        // Whatever `Groth16Proof` rejects, this rejects too
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..64 {
+            let mut proof = MULTIPLIER_PROOF;
+            proof[usize::rand(&mut rng) % PROOF_SIZE] ^= 1 << (u8::rand(&mut rng) % 8);
+            let native = Groth16Proof::from_uncompressed_bytes(&proof)
+                .and_then(|proof| verify_groth16(&proof, &[Fr::from(15)], &vk));
+            let raw = verify(&mut Native, &vk_bytes, &proof, &[word(15)]);
+            assert_eq!(raw.is_err(), native.is_err());
+            assert_eq!(raw.ok(), native.ok());
+        }
+        let mut at_infinity = MULTIPLIER_PROOF;
+        at_infinity[..64].fill(0);
+        assert_eq!(check_proof(&at_infinity).err(), Some(b"Point at infinity".to_vec()));
+    }
+
+    #[test]
+    fn test_check_key() {
+        let vk = VerifyingKey::load_real();
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..64 {
+            let mut bytes = vk.to_bytes();
+            let index = usize::rand(&mut rng) % bytes.len();
+            bytes[index] ^= 1 << (u8::rand(&mut rng) % 8);
+            assert_eq!(check_key(&mut Native, &bytes).is_ok(), VerifyingKey::from_bytes(&bytes).is_ok());
+        }
+        assert!(check_key(&mut Native, &vk.to_bytes()[..VK_HEADER_SIZE]).is_err());
+
+        // On the twist but outside the prime-order subgroup
+        let mut bytes = vk.to_bytes();
+        let outside = G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(1), Fq::from(0)), false)
+            .or_else(|| G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(2), Fq::from(0)), false))
+            .unwrap();
+        assert!(!outside.is_in_correct_subgroup_assuming_on_curve());
+        let mut encoded = Vec::new();
+        crate::append_g2(&outside, &mut encoded);
+        bytes[64..192].copy_from_slice(&encoded);
+        assert_eq!(check_key(&mut Native, &bytes), Err(b"G2 point not on curve or not in subgroup".to_vec()));
+    }
+
+    #[test]
+    fn test_accumulate_ic() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let ic: Vec<G1Affine> = (0..5).map(|_| G1Affine::rand(&mut rng)).collect();
+        let mut inputs: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+        inputs[1] = Fr::from(0);
+        let expected = crate::accumulate_ic(ic[0], &ic[1..], inputs.iter().copied());
+
+        let mut ic_bytes = Vec::new();
+        ic.iter().for_each(|point| crate::append_g1(point, &mut ic_bytes));
+        let words: Vec<[u8; 32]> =
+            inputs.iter().map(|input| input.into_bigint().to_bytes_be().try_into().unwrap()).collect();
+        let acc = accumulate_ic(&mut Native, ic_bytes[..64].try_into().unwrap(), &ic_bytes[64..], &words).unwrap();
+        assert_eq!(acc.to_vec(), encode_g1(&expected));
+        assert_eq!(negate_g1(&acc).to_vec(), encode_g1(&-expected));
+        assert_eq!(negate_g1(&INFINITY), INFINITY);
+    }
+}
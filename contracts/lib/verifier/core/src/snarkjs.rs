@@ -0,0 +1,385 @@
+//! SNARKJS JSON FORMATS - `proof.json`, `verification_key.json`, `public.json`
+//!
+//! Points are projective decimal strings: G1 as [x, y, "1"], G2 as
+//! [[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]. Decoding is strict: every
+//! coordinate must be a canonical field element and z must be one, so the
+//! bytes produced here are exactly the bytes the contract parses.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    append_fq, decode_g1, decode_g2, Groth16Proof, PointError, VerifyingKey, PROOF_SIZE,
+};
+
+/// Why a snarkjs object does not decode; names the offending field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnarkjsError {
+    /// Not JSON, or missing / mistyped fields
+    Json(String),
+    /// Protocol or curve other than groth16 over bn128
+    Unsupported(String),
+    /// Coordinate that is not a canonical decimal field element, or a
+    /// point that is not in affine form (z != 1)
+    Encoding(String),
+    /// Decoded point is not on the curve
+    OffCurve(String),
+    /// Decoded G2 point is outside the prime-order subgroup
+    NotInSubgroup(String),
+}
+
+impl fmt::Display for SnarkjsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(reason) => write!(f, "invalid snarkjs JSON: {reason}"),
+            Self::Unsupported(what) => write!(f, "unsupported {what}"),
+            Self::Encoding(field) => write!(f, "{field}: not a canonical field element encoding"),
+            Self::OffCurve(field) => write!(f, "{field}: point not on curve"),
+            Self::NotInSubgroup(field) => write!(f, "{field}: point not in subgroup"),
+        }
+    }
+}
+
+impl From<SnarkjsError> for Vec<u8> {
+    fn from(err: SnarkjsError) -> Self {
+        err.to_string().into_bytes()
+    }
+}
+
+/// snarkjs `proof.json`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofJson {
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+    #[serde(default = "groth16")]
+    pub protocol: String,
+    #[serde(default = "bn128")]
+    pub curve: String,
+}
+
+/// snarkjs `verification_key.json` (Groth16); `vk_alphabeta_12` is ignored
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationKeyJson {
+    #[serde(default = "groth16")]
+    pub protocol: String,
+    #[serde(default = "bn128")]
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<Vec<String>>,
+}
+
+fn groth16() -> String {
+    "groth16".to_string()
+}
+
+fn bn128() -> String {
+    "bn128".to_string()
+}
+
+impl ProofJson {
+    pub fn from_json(json: &str) -> Result<Self, SnarkjsError> {
+        serde_json::from_str(json).map_err(|err| SnarkjsError::Json(err.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("string fields always serialize")
+    }
+
+    /// Encode in the contract's 256-byte layout; checks encodings, not points
+    pub fn to_uncompressed_bytes(&self) -> Result<[u8; PROOF_SIZE], SnarkjsError> {
+        check_scheme(&self.protocol, &self.curve)?;
+        let mut bytes = Vec::with_capacity(PROOF_SIZE);
+        bytes.extend(g1_bytes("pi_a", &self.pi_a)?);
+        bytes.extend(g2_bytes("pi_b", &self.pi_b)?);
+        bytes.extend(g1_bytes("pi_c", &self.pi_c)?);
+        Ok(bytes.try_into().expect("64 + 128 + 64 bytes"))
+    }
+
+    /// Decode to a proof with the same point checks as `Groth16Proof::from_uncompressed_array`
+    pub fn to_proof(&self) -> Result<Groth16Proof, SnarkjsError> {
+        let bytes = self.to_uncompressed_bytes()?;
+        Ok(Groth16Proof {
+            a: decode_g1(&bytes[0..64]).map_err(|err| point_error("pi_a", err))?,
+            b: decode_g2(&bytes[64..192]).map_err(|err| point_error("pi_b", err))?,
+            c: decode_g1(&bytes[192..256]).map_err(|err| point_error("pi_c", err))?,
+        })
+    }
+
+    pub fn from_proof(proof: &Groth16Proof) -> Self {
+        Self {
+            pi_a: g1_json(&proof.a),
+            pi_b: g2_json(&proof.b),
+            pi_c: g1_json(&proof.c),
+            protocol: groth16(),
+            curve: bn128(),
+        }
+    }
+}
+
+impl VerificationKeyJson {
+    pub fn from_json(json: &str) -> Result<Self, SnarkjsError> {
+        serde_json::from_str(json).map_err(|err| SnarkjsError::Json(err.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("string fields always serialize")
+    }
+
+    /// Encode in the `VerifyingKey::from_bytes` layout; checks encodings, not points
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SnarkjsError> {
+        check_scheme(&self.protocol, &self.curve)?;
+        if self.ic.len() != self.n_public + 1 {
+            return Err(SnarkjsError::Encoding("nPublic".to_string()));
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend(g1_bytes("vk_alpha_1", &self.vk_alpha_1)?);
+        bytes.extend(g2_bytes("vk_beta_2", &self.vk_beta_2)?);
+        bytes.extend(g2_bytes("vk_gamma_2", &self.vk_gamma_2)?);
+        bytes.extend(g2_bytes("vk_delta_2", &self.vk_delta_2)?);
+        for (index, point) in self.ic.iter().enumerate() {
+            bytes.extend(g1_bytes(&format!("IC[{index}]"), point)?);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode to a key with the same point checks as `VerifyingKey::from_bytes`
+    pub fn to_verifying_key(&self) -> Result<VerifyingKey, SnarkjsError> {
+        let bytes = self.to_bytes()?;
+        let ic = bytes[crate::VK_HEADER_SIZE..]
+            .chunks_exact(64)
+            .enumerate()
+            .map(|(index, point)| decode_g1(point).map_err(|err| point_error(&format!("IC[{index}]"), err)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(VerifyingKey {
+            alpha_g1: decode_g1(&bytes[0..64]).map_err(|err| point_error("vk_alpha_1", err))?,
+            beta_g2: decode_g2(&bytes[64..192]).map_err(|err| point_error("vk_beta_2", err))?,
+            gamma_g2: decode_g2(&bytes[192..320]).map_err(|err| point_error("vk_gamma_2", err))?,
+            delta_g2: decode_g2(&bytes[320..448]).map_err(|err| point_error("vk_delta_2", err))?,
+            ic,
+        })
+    }
+
+    pub fn from_verifying_key(vk: &VerifyingKey) -> Self {
+        Self {
+            protocol: groth16(),
+            curve: bn128(),
+            n_public: vk.ic.len() - 1,
+            vk_alpha_1: g1_json(&vk.alpha_g1),
+            vk_beta_2: g2_json(&vk.beta_g2),
+            vk_gamma_2: g2_json(&vk.gamma_g2),
+            vk_delta_2: g2_json(&vk.delta_g2),
+            ic: vk.ic.iter().map(g1_json).collect(),
+        }
+    }
+}
+
+/// Decode snarkjs `public.json` signals; each must be a canonical scalar
+pub fn parse_public_signals(signals: &[String]) -> Result<Vec<Fr>, SnarkjsError> {
+    signals
+        .iter()
+        .enumerate()
+        .map(|(index, signal)| {
+            parse_decimal(signal).ok_or_else(|| SnarkjsError::Encoding(format!("publicSignals[{index}]")))
+        })
+        .collect()
+}
+
+pub fn parse_public_signals_json(json: &str) -> Result<Vec<Fr>, SnarkjsError> {
+    let signals: Vec<String> =
+        serde_json::from_str(json).map_err(|err| SnarkjsError::Json(err.to_string()))?;
+    parse_public_signals(&signals)
+}
+
+/// Parse a decimal string into a field element, rejecting values >= the modulus
+pub fn parse_decimal<F: PrimeField>(decimal: &str) -> Option<F> {
+    if decimal.is_empty() {
+        return None;
+    }
+    let mut value = F::BigInt::default();
+    for digit in decimal.bytes() {
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+        let mut carry = u128::from(digit - b'0');
+        for limb in value.as_mut() {
+            let next = u128::from(*limb) * 10 + carry;
+            *limb = next as u64;
+            carry = next >> 64;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    F::from_bigint(value)
+}
+
+/// Decimal string of a field element ("0" for zero)
+pub fn to_decimal<F: PrimeField>(value: &F) -> String {
+    value.into_bigint().to_string()
+}
+
+fn check_scheme(protocol: &str, curve: &str) -> Result<(), SnarkjsError> {
+    if protocol != "groth16" {
+        return Err(SnarkjsError::Unsupported(format!("protocol {protocol}")));
+    }
+    if curve != "bn128" && curve != "bn254" {
+        return Err(SnarkjsError::Unsupported(format!("curve {curve}")));
+    }
+    Ok(())
+}
+
+fn point_error(field: &str, err: PointError) -> SnarkjsError {
+    match err {
+        PointError::InvalidLength | PointError::NonCanonical => SnarkjsError::Encoding(field.to_string()),
+        PointError::NotOnCurve => SnarkjsError::OffCurve(field.to_string()),
+        PointError::NotInSubgroup => SnarkjsError::NotInSubgroup(field.to_string()),
+    }
+}
+
+fn fq_word(field: &str, decimal: &str) -> Result<Vec<u8>, SnarkjsError> {
+    let value: Fq = parse_decimal(decimal).ok_or_else(|| SnarkjsError::Encoding(field.to_string()))?;
+    let mut word = Vec::with_capacity(32);
+    append_fq(&value, &mut word);
+    Ok(word)
+}
+
+/// [x, y, "1"] (or [x, y]) to x || y
+fn g1_bytes(field: &str, point: &[String]) -> Result<Vec<u8>, SnarkjsError> {
+    let encoding = || SnarkjsError::Encoding(field.to_string());
+    match point {
+        [x, y] | [x, y, _] => {
+            if point.get(2).is_some_and(|z| z != "1") {
+                return Err(encoding());
+            }
+            let mut bytes = fq_word(field, x)?;
+            bytes.extend(fq_word(field, y)?);
+            Ok(bytes)
+        }
+        _ => Err(encoding()),
+    }
+}
+
+/// [[x0, x1], [y0, y1], ["1", "0"]] (or without z) to x0 || x1 || y0 || y1
+fn g2_bytes(field: &str, point: &[Vec<String>]) -> Result<Vec<u8>, SnarkjsError> {
+    let encoding = || SnarkjsError::Encoding(field.to_string());
+    match point {
+        [x, y] | [x, y, _] => {
+            if point.get(2).is_some_and(|z| z != &["1", "0"]) {
+                return Err(encoding());
+            }
+            let mut bytes = Vec::with_capacity(128);
+            for pair in [x, y] {
+                let [c0, c1] = pair.as_slice() else {
+                    return Err(encoding());
+                };
+                bytes.extend(fq_word(field, c0)?);
+                bytes.extend(fq_word(field, c1)?);
+            }
+            Ok(bytes)
+        }
+        _ => Err(encoding()),
+    }
+}
+
+fn g1_json(point: &G1Affine) -> Vec<String> {
+    vec![to_decimal(&point.x), to_decimal(&point.y), "1".to_string()]
+}
+
+fn g2_json(point: &G2Affine) -> Vec<Vec<String>> {
+    vec![
+        vec![to_decimal(&point.x.c0), to_decimal(&point.x.c1)],
+        vec![to_decimal(&point.y.c0), to_decimal(&point.y.c1)],
+        vec!["1".to_string(), "0".to_string()],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_groth16;
+
+    const PROOF_JSON: &str = include_str!("../fixtures/multiplier/proof.json");
+    const VK_JSON: &str = include_str!("../fixtures/multiplier/verification_key.json");
+    const PUBLIC_JSON: &str = include_str!("../fixtures/multiplier/public.json");
+
+    #[test]
+    fn test_fixture_matches_committed_key() {
+        let vk = VerificationKeyJson::from_json(VK_JSON).unwrap();
+        assert_eq!(vk.to_bytes().unwrap(), VerifyingKey::load_real().to_bytes());
+
+        let proof = ProofJson::from_json(PROOF_JSON).unwrap().to_proof().unwrap();
+        let inputs = parse_public_signals_json(PUBLIC_JSON).unwrap();
+        assert_eq!(verify_groth16(&proof, &inputs, &vk.to_verifying_key().unwrap()), Ok(true));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let proof = ProofJson::from_json(PROOF_JSON).unwrap();
+        let decoded = proof.to_proof().unwrap();
+        assert_eq!(ProofJson::from_proof(&decoded), proof);
+        assert_eq!(ProofJson::from_json(&proof.to_json()).unwrap(), proof);
+
+        let vk = VerificationKeyJson::from_json(VK_JSON).unwrap();
+        let decoded = vk.to_verifying_key().unwrap();
+        assert_eq!(VerificationKeyJson::from_verifying_key(&decoded), vk);
+    }
+
+    #[test]
+    fn test_decimal_is_canonical() {
+        assert_eq!(parse_decimal::<Fr>("0"), Some(Fr::from(0u64)));
+        assert_eq!(parse_decimal::<Fr>("15"), Some(Fr::from(15u64)));
+        assert_eq!(to_decimal(&Fr::from(0u64)), "0");
+
+        let modulus = Fr::MODULUS.to_string();
+        assert_eq!(parse_decimal::<Fr>(&modulus), None);
+        assert!(parse_decimal::<Fq>(&modulus).is_some());
+        assert_eq!(parse_decimal::<Fq>(&"9".repeat(100)), None);
+        for bad in ["", "-1", "0x10", "1 ", "1e3"] {
+            assert_eq!(parse_decimal::<Fr>(bad), None);
+        }
+    }
+
+    #[test]
+    fn test_errors_name_the_field() {
+        let proof = ProofJson::from_json(PROOF_JSON).unwrap();
+
+        let mut bad = proof.clone();
+        bad.pi_a[2] = "0".to_string();
+        assert_eq!(bad.to_proof().unwrap_err(), SnarkjsError::Encoding("pi_a".into()));
+
+        let mut bad = proof.clone();
+        bad.pi_c[1] = "7".to_string();
+        assert_eq!(bad.to_proof().unwrap_err(), SnarkjsError::OffCurve("pi_c".into()));
+
+        let mut bad = proof.clone();
+        bad.pi_b[1].swap(0, 1);
+        assert_eq!(bad.to_proof().unwrap_err(), SnarkjsError::OffCurve("pi_b".into()));
+
+        let mut bad = proof;
+        bad.protocol = "plonk".to_string();
+        assert!(matches!(bad.to_proof(), Err(SnarkjsError::Unsupported(_))));
+
+        assert!(matches!(ProofJson::from_json("{}"), Err(SnarkjsError::Json(_))));
+        assert_eq!(
+            parse_public_signals(&["1".into(), "x".into()]).unwrap_err(),
+            SnarkjsError::Encoding("publicSignals[1]".into())
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! SNARKPACK AGGREGATION - MANY GROTH16 PROOFS, ONE CHECK
+//!
+//! Aggregates n Groth16 proofs for the same circuit into a proof whose
+//! verification costs O(log n) pairings and GT exponentiations:
+//! - TIPP: Π e(A_i, B_i^{r^i}) against a pairing commitment to A and B
+//! - MIPP: Σ C_i · r^i against a pairing commitment to C
+//! - KZG openings showing the final commitment keys are well formed
+//!
+//! The commitment keys come from two powers-of-tau SRS with secrets α and β:
+//! v_i = (h^{α^i}, h^{β^i}) commits A, w_i = (g^{α^{n+i}}, g^{β^{n+i}}) commits B.
+//! Proof count must be a power of two.
+//!
+//! Off-chain only: the GT exponentiations have no EVM precompile, and run
+//! natively they cost more gas than verifying each proof through 0x08.
+//!
+//! Reference: Gailly, Maller, Nitulescu - "SnarkPack: Practical SNARK Aggregation"
+
+mod srs;
+mod transcript;
+mod verifier;
+
+#[cfg(feature = "std")]
+mod prover;
+
+pub use srs::VerifierSrs;
+pub use verifier::verify_aggregate_proof;
+
+#[cfg(feature = "std")]
+pub use prover::aggregate_proofs;
+#[cfg(feature = "std")]
+pub use srs::{setup_fake_srs, ProverSrs};
+
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::pairing::PairingOutput;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::VerifyingKey;
+use transcript::Transcript;
+
+/// Element of the pairing target group
+pub type Gt = PairingOutput<Bn254>;
+
+/// Aggregate of n Groth16 proofs for the same verifying key
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregateProof {
+    /// Commitment to A under v and B under w
+    pub com_ab: (Gt, Gt),
+    /// Commitment to C under v
+    pub com_c: (Gt, Gt),
+    /// Π e(A_i, B_i^{r^i})
+    pub ip_ab: Gt,
+    /// Σ C_i · r^i
+    pub agg_c: G1Affine,
+    pub tmipp: TippMippProof,
+}
+
+/// GIPA transcript plus the KZG openings of the final commitment keys
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct TippMippProof {
+    pub gipa: GipaProof,
+    /// Openings of v's polynomial at z, one per secret (α, β)
+    pub vkey_opening: (G2Affine, G2Affine),
+    /// Openings of w's polynomial at z, one per secret (α, β)
+    pub wkey_opening: (G1Affine, G1Affine),
+}
+
+/// One (left, right) message pair per halving round, then the folded values
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GipaProof {
+    pub nproofs: u32,
+    /// Cross commitments to (A, B): (left, right) per round
+    pub comms_ab: Vec<((Gt, Gt), (Gt, Gt))>,
+    /// Cross commitments to C: (left, right) per round
+    pub comms_c: Vec<((Gt, Gt), (Gt, Gt))>,
+    /// Cross inner pairing products: (left, right) per round
+    pub z_ab: Vec<(Gt, Gt)>,
+    /// Cross multi-exponentiations: (left, right) per round
+    pub z_c: Vec<(G1Affine, G1Affine)>,
+    pub final_a: G1Affine,
+    pub final_b: G2Affine,
+    pub final_c: G1Affine,
+    pub final_r: Fr,
+    pub final_vkey: (G2Affine, G2Affine),
+    pub final_wkey: (G1Affine, G1Affine),
+}
+
+impl AggregateProof {
+    /// Decode from the arkworks uncompressed encoding, validating every element
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        let mut reader = bytes;
+        let proof = Self::deserialize_uncompressed(&mut reader)
+            .map_err(|_| b"Invalid aggregate proof encoding".to_vec())?;
+        if !reader.is_empty() {
+            return Err(b"Trailing bytes after aggregate proof".to_vec());
+        }
+        Ok(proof)
+    }
+
+    /// Arkworks uncompressed encoding, as read by `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.uncompressed_size());
+        self.serialize_uncompressed(&mut bytes)
+            .expect("writing to a Vec cannot fail");
+        bytes
+    }
+}
+
+/// Bind the key, the public inputs and the initial commitments, then derive
+/// the randomness r used to combine the n Groth16 equations
+///
+/// Rejects input sets that do not match the proof count or the key's IC length.
+pub(crate) fn open_transcript(
+    vk: &VerifyingKey,
+    public_inputs: &[Vec<Fr>],
+    com_ab: &(Gt, Gt),
+    com_c: &(Gt, Gt),
+) -> Result<(Transcript, Fr), Vec<u8>> {
+    if public_inputs.iter().any(|inputs| inputs.len() + 1 != vk.ic.len()) {
+        return Err(b"Public input count mismatch".to_vec());
+    }
+
+    let mut transcript = Transcript::new(b"arbshield-snarkpack");
+    transcript.append_bytes(b"vk", &vk.to_bytes());
+    transcript.append(b"inputs", &public_inputs);
+    transcript.append(b"com_ab", com_ab);
+    transcript.append(b"com_c", com_c);
+    let r = transcript.challenge(b"r");
+    Ok((transcript, r))
+}
+
+/// Evaluate Π_i (1 + x_i · (shift · z)^{2^i}), the product form of the
+/// polynomials the folded keys and scalars commit to
+///
+/// `challenges` are in reverse round order: the last halving round touches
+/// the lowest power.
+pub(crate) fn product_form_evaluation(challenges: &[Fr], z: Fr, shift: Fr) -> Fr {
+    let mut power = z * shift;
+    let mut result = Fr::from(1u64);
+    for (i, x) in challenges.iter().enumerate() {
+        if i > 0 {
+            power.square_in_place();
+        }
+        result *= Fr::from(1u64) + *x * power;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{product_setup, prove_product};
+    use crate::{Groth16Proof, VerifyingKey};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    fn proofs_for(
+        pk: &ark_groth16::ProvingKey<Bn254>,
+        count: u64,
+    ) -> (Vec<Groth16Proof>, Vec<Vec<Fr>>) {
+        (1..=count)
+            .map(|c| (prove_product(pk, Fr::from(c)), vec![Fr::from(c)]))
+            .unzip()
+    }
+
+    fn aggregate(
+        vk: &VerifyingKey,
+        proofs: &[Groth16Proof],
+        inputs: &[Vec<Fr>],
+    ) -> (VerifierSrs, AggregateProof) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let srs = setup_fake_srs(&mut rng, 16);
+        let aggregate = aggregate_proofs(&srs, vk, proofs, inputs).unwrap();
+        (srs.verifier_srs(), aggregate)
+    }
+
+    #[test]
+    fn test_aggregate_valid_proofs() {
+        let (pk, vk) = product_setup();
+        for count in [2, 4, 8] {
+            let (proofs, inputs) = proofs_for(&pk, count);
+            let (srs, aggregate) = aggregate(&vk, &proofs, &inputs);
+
+            assert_eq!(aggregate.tmipp.gipa.comms_ab.len(), count.trailing_zeros() as usize);
+            assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &aggregate), Ok(true));
+        }
+    }
+
+    #[test]
+    fn test_aggregate_rejects_wrong_inputs() {
+        let (pk, vk) = product_setup();
+        let (proofs, mut inputs) = proofs_for(&pk, 4);
+        let (srs, aggregate) = aggregate(&vk, &proofs, &inputs);
+
+        inputs[2][0] += Fr::from(1u64);
+        assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &aggregate), Ok(false));
+
+        inputs.pop();
+        assert!(verify_aggregate_proof(&srs, &vk, &inputs, &aggregate).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_rejects_invalid_member() {
+        let (pk, vk) = product_setup();
+        let (mut proofs, inputs) = proofs_for(&pk, 4);
+        // Proof 1 is valid for input 1, not for input 2
+        proofs[1] = proofs[0].clone();
+
+        let (srs, aggregate) = aggregate(&vk, &proofs, &inputs);
+        assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &aggregate), Ok(false));
+    }
+
+    #[test]
+    fn test_aggregate_rejects_tampered_transcript() {
+        let (pk, vk) = product_setup();
+        let (proofs, inputs) = proofs_for(&pk, 4);
+        let (srs, aggregate) = aggregate(&vk, &proofs, &inputs);
+
+        let mut tampered = aggregate.clone();
+        tampered.agg_c = (tampered.agg_c + vk.ic[0]).into();
+        assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &tampered), Ok(false));
+
+        let mut tampered = aggregate.clone();
+        tampered.tmipp.gipa.final_r += Fr::from(1u64);
+        assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &tampered), Ok(false));
+
+        let mut tampered = aggregate;
+        tampered.tmipp.gipa.z_ab.swap(0, 1);
+        assert_eq!(verify_aggregate_proof(&srs, &vk, &inputs, &tampered), Ok(false));
+    }
+
+    #[test]
+    fn test_aggregate_proof_encoding() {
+        let (pk, vk) = product_setup();
+        let (proofs, inputs) = proofs_for(&pk, 2);
+        let (_, aggregate) = aggregate(&vk, &proofs, &inputs);
+
+        let bytes = aggregate.to_bytes();
+        assert_eq!(AggregateProof::from_bytes(&bytes), Ok(aggregate));
+        assert!(AggregateProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(AggregateProof::from_bytes(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_verifier_srs_consistency() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let srs = setup_fake_srs(&mut rng, 4).verifier_srs();
+        assert_eq!(srs.check_consistency(), Ok(()));
+
+        let mut swapped = srs.clone();
+        core::mem::swap(&mut swapped.h_alpha, &mut swapped.h_beta);
+        assert_eq!(
+            swapped.check_consistency(),
+            Err(b"Aggregation SRS alpha powers disagree".to_vec())
+        );
+
+        let mut beta = srs.clone();
+        beta.g_beta = (beta.g_beta + beta.g).into();
+        assert_eq!(beta.check_consistency(), Err(b"Aggregation SRS beta powers disagree".to_vec()));
+
+        let mut trivial = srs;
+        trivial.g_alpha = G1Affine::identity();
+        assert_eq!(
+            trivial.check_consistency(),
+            Err(b"Aggregation SRS has a point at infinity".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_aggregate_rejects_bad_sizes() {
+        let (pk, vk) = product_setup();
+        let mut rng = StdRng::seed_from_u64(7);
+        let srs = setup_fake_srs(&mut rng, 4);
+
+        let (proofs, inputs) = proofs_for(&pk, 3);
+        assert!(aggregate_proofs(&srs, &vk, &proofs, &inputs).is_err());
+
+        let (proofs, inputs) = proofs_for(&pk, 8);
+        assert!(aggregate_proofs(&srs, &vk, &proofs, &inputs).is_err());
+    }
+}
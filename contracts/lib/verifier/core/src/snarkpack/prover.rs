@@ -0,0 +1,225 @@
+//! Proof aggregation (off-chain, std only)
+//!
+//! Commits to the proofs, combines them with the transcript randomness r,
+//! then runs GIPA: each round halves every vector and sends the cross terms
+//! the verifier needs to fold its commitments. The final keys are opened
+//! with KZG at a transcript point z.
+
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One};
+
+use super::{open_transcript, AggregateProof, GipaProof, Gt, ProverSrs, TippMippProof};
+use crate::{Groth16Proof, VerifyingKey};
+
+/// Aggregate `proofs` for `vk`; `public_inputs[i]` belongs to `proofs[i]`
+///
+/// The proof count must be a power of two (at least 2) and fit the SRS.
+/// Members are not checked: an invalid proof yields an aggregate that fails
+/// verification.
+pub fn aggregate_proofs(
+    srs: &ProverSrs,
+    vk: &VerifyingKey,
+    proofs: &[Groth16Proof],
+    public_inputs: &[Vec<Fr>],
+) -> Result<AggregateProof, Vec<u8>> {
+    let n = proofs.len();
+    if n < 2 || !n.is_power_of_two() {
+        return Err(b"Aggregated proof count must be a power of two".to_vec());
+    }
+    if n > srs.max_proofs {
+        return Err(b"Too many proofs for aggregation SRS".to_vec());
+    }
+    if public_inputs.len() != n {
+        return Err(b"Public input sets do not match proof count".to_vec());
+    }
+
+    let a: Vec<G1Affine> = proofs.iter().map(|p| p.a).collect();
+    let b: Vec<G2Affine> = proofs.iter().map(|p| p.b).collect();
+    let c: Vec<G1Affine> = proofs.iter().map(|p| p.c).collect();
+    let v = (srs.h_alpha_powers[..n].to_vec(), srs.h_beta_powers[..n].to_vec());
+    let w = (srs.g_alpha_powers[n..2 * n].to_vec(), srs.g_beta_powers[n..2 * n].to_vec());
+
+    let com_ab = commit_ab(&a, &b, &v, &w);
+    let com_c = commit_c(&c, &v);
+    let (mut transcript, r) = open_transcript(vk, public_inputs, &com_ab, &com_c)?;
+    let r_inv = r.inverse().expect("challenges are non-zero");
+
+    // B_i^{r^i} keeps e(w_i, B_i) intact once w_i is rescaled by r^{-i}
+    let r_powers = powers(r, n);
+    let r_inv_powers = powers(r_inv, n);
+    let b = scale(&b, &r_powers);
+    let w = (scale(&w.0, &r_inv_powers), scale(&w.1, &r_inv_powers));
+
+    let ip_ab = Bn254::multi_pairing(&a, &b);
+    let agg_c = G1Projective::msm_unchecked(&c, &r_powers).into_affine();
+    transcript.append(b"ip_ab", &ip_ab);
+    transcript.append(b"agg_c", &agg_c);
+
+    let mut gipa = GipaProof {
+        nproofs: n as u32,
+        comms_ab: Vec::new(),
+        comms_c: Vec::new(),
+        z_ab: Vec::new(),
+        z_c: Vec::new(),
+        final_a: G1Affine::default(),
+        final_b: G2Affine::default(),
+        final_c: G1Affine::default(),
+        final_r: Fr::one(),
+        final_vkey: (G2Affine::default(), G2Affine::default()),
+        final_wkey: (G1Affine::default(), G1Affine::default()),
+    };
+    let (mut a, mut b, mut c, mut r_vec, mut v, mut w) = (a, b, c, r_powers, v, w);
+    let mut challenges = Vec::new();
+    let mut challenges_inv = Vec::new();
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_l, a_r) = a.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (c_l, c_r) = c.split_at(half);
+        let (r_l, r_r) = r_vec.split_at(half);
+        let (v1_l, v1_r) = v.0.split_at(half);
+        let (v2_l, v2_r) = v.1.split_at(half);
+        let (w1_l, w1_r) = w.0.split_at(half);
+        let (w2_l, w2_r) = w.1.split_at(half);
+        let (v_l, v_r) = ((v1_l.to_vec(), v2_l.to_vec()), (v1_r.to_vec(), v2_r.to_vec()));
+        let (w_l, w_r) = ((w1_l.to_vec(), w2_l.to_vec()), (w1_r.to_vec(), w2_r.to_vec()));
+
+        // Left terms are scaled by x when folding, right terms by x^-1
+        let comms_ab = (commit_ab(a_r, b_l, &v_l, &w_r), commit_ab(a_l, b_r, &v_r, &w_l));
+        let comms_c = (commit_c(c_r, &v_l), commit_c(c_l, &v_r));
+        let z_ab = (Bn254::multi_pairing(a_r, b_l), Bn254::multi_pairing(a_l, b_r));
+        let z_c = (
+            G1Projective::msm_unchecked(c_r, r_l).into_affine(),
+            G1Projective::msm_unchecked(c_l, r_r).into_affine(),
+        );
+
+        transcript.append(b"comms_ab", &comms_ab);
+        transcript.append(b"comms_c", &comms_c);
+        transcript.append(b"z_ab", &z_ab);
+        transcript.append(b"z_c", &z_c);
+        let x = transcript.challenge(b"x");
+        let x_inv = x.inverse().expect("challenges are non-zero");
+
+        let next_a = fold(a_l, a_r, x);
+        let next_b = fold(b_l, b_r, x_inv);
+        let next_c = fold(c_l, c_r, x);
+        let next_r = r_l.iter().zip(r_r).map(|(l, r)| *l + *r * x_inv).collect();
+        let next_v = (fold(v1_l, v1_r, x_inv), fold(v2_l, v2_r, x_inv));
+        let next_w = (fold(w1_l, w1_r, x), fold(w2_l, w2_r, x));
+
+        gipa.comms_ab.push(comms_ab);
+        gipa.comms_c.push(comms_c);
+        gipa.z_ab.push(z_ab);
+        gipa.z_c.push(z_c);
+        challenges.push(x);
+        challenges_inv.push(x_inv);
+
+        (a, b, c, r_vec, v, w) = (next_a, next_b, next_c, next_r, next_v, next_w);
+    }
+
+    gipa.final_a = a[0];
+    gipa.final_b = b[0];
+    gipa.final_c = c[0];
+    gipa.final_r = r_vec[0];
+    gipa.final_vkey = (v.0[0], v.1[0]);
+    gipa.final_wkey = (w.0[0], w.1[0]);
+
+    transcript.append(b"final_vkey", &gipa.final_vkey);
+    transcript.append(b"final_wkey", &gipa.final_wkey);
+    let z = transcript.challenge(b"z");
+
+    challenges.reverse();
+    challenges_inv.reverse();
+
+    // f_v(X) = Π (1 + x_i^-1 X^{2^i}), f_w(X) = X^n Π (1 + x_i (X / r)^{2^i})
+    let fv = product_form_coefficients(&challenges_inv, Fr::one());
+    let mut fw = vec![Fr::from(0u64); n];
+    fw.extend(product_form_coefficients(&challenges, r_inv));
+
+    let qv = quotient(&fv, z);
+    let qw = quotient(&fw, z);
+    let vkey_opening = (
+        G2Projective::msm_unchecked(&srs.h_alpha_powers[..qv.len()], &qv).into_affine(),
+        G2Projective::msm_unchecked(&srs.h_beta_powers[..qv.len()], &qv).into_affine(),
+    );
+    let wkey_opening = (
+        G1Projective::msm_unchecked(&srs.g_alpha_powers[..qw.len()], &qw).into_affine(),
+        G1Projective::msm_unchecked(&srs.g_beta_powers[..qw.len()], &qw).into_affine(),
+    );
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        tmipp: TippMippProof { gipa, vkey_opening, wkey_opening },
+    })
+}
+
+/// (Π e(A_i, v1_i) · Π e(w1_i, B_i), Π e(A_i, v2_i) · Π e(w2_i, B_i))
+fn commit_ab(
+    a: &[G1Affine],
+    b: &[G2Affine],
+    v: &(Vec<G2Affine>, Vec<G2Affine>),
+    w: &(Vec<G1Affine>, Vec<G1Affine>),
+) -> (Gt, Gt) {
+    let commit = |v: &[G2Affine], w: &[G1Affine]| {
+        Bn254::multi_pairing(a.iter().chain(w), v.iter().chain(b))
+    };
+    (commit(&v.0, &w.0), commit(&v.1, &w.1))
+}
+
+/// (Π e(C_i, v1_i), Π e(C_i, v2_i))
+fn commit_c(c: &[G1Affine], v: &(Vec<G2Affine>, Vec<G2Affine>)) -> (Gt, Gt) {
+    (Bn254::multi_pairing(c, &v.0), Bn254::multi_pairing(c, &v.1))
+}
+
+/// left_i + right_i · x
+fn fold<A: AffineRepr<ScalarField = Fr>>(left: &[A], right: &[A], x: Fr) -> Vec<A> {
+    let folded: Vec<A::Group> = left.iter().zip(right).map(|(l, r)| *r * x + l).collect();
+    A::Group::normalize_batch(&folded)
+}
+
+/// points_i · scalars_i
+fn scale<A: AffineRepr<ScalarField = Fr>>(points: &[A], scalars: &[Fr]) -> Vec<A> {
+    let scaled: Vec<A::Group> = points.iter().zip(scalars).map(|(p, s)| *p * s).collect();
+    A::Group::normalize_batch(&scaled)
+}
+
+/// [1, x, x^2, ...] with `count` entries
+fn powers(x: Fr, count: usize) -> Vec<Fr> {
+    let mut power = Fr::one();
+    (0..count)
+        .map(|_| {
+            let current = power;
+            power *= x;
+            current
+        })
+        .collect()
+}
+
+/// Coefficients of Π_i (1 + c_i · (shift · X)^{2^i}), lowest degree first
+fn product_form_coefficients(challenges: &[Fr], shift: Fr) -> Vec<Fr> {
+    let mut coefficients = vec![Fr::one()];
+    let mut shift_power = shift;
+    for challenge in challenges {
+        let factor = *challenge * shift_power;
+        let upper: Vec<Fr> = coefficients.iter().map(|c| *c * factor).collect();
+        coefficients.extend(upper);
+        shift_power.square_in_place();
+    }
+    coefficients
+}
+
+/// Coefficients of (f(X) - f(z)) / (X - z) by synthetic division
+fn quotient(coefficients: &[Fr], z: Fr) -> Vec<Fr> {
+    let mut quotient = vec![Fr::from(0u64); coefficients.len() - 1];
+    let mut carry = Fr::from(0u64);
+    for (i, coefficient) in coefficients.iter().enumerate().skip(1).rev() {
+        carry = *coefficient + carry * z;
+        quotient[i - 1] = carry;
+    }
+    quotient
+}
@@ -0,0 +1,158 @@
+//! Structured reference strings for aggregation
+//!
+//! The prover side holds powers of two secrets α and β in both groups; the
+//! verifier only needs the generators and their first power.
+
+use alloc::vec::Vec;
+use ark_bn254::{Bn254, Fq12, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::One;
+
+use crate::{append_g1, append_g2, read_g1, read_g2};
+
+/// Verifier half of the SRS: g, h and (g, h)^α, (g, h)^β
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierSrs {
+    pub g: G1Affine,
+    pub h: G2Affine,
+    pub g_alpha: G1Affine,
+    pub g_beta: G1Affine,
+    pub h_alpha: G2Affine,
+    pub h_beta: G2Affine,
+}
+
+/// Encoded size: three G1 (64 each) and three G2 (128 each)
+pub(crate) const VERIFIER_SRS_SIZE: usize = 3 * 64 + 3 * 128;
+
+impl VerifierSrs {
+    /// Parse from g || h || g_alpha || g_beta || h_alpha || h_beta
+    /// (same point layout as proofs and verifying keys)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
+        if bytes.len() != VERIFIER_SRS_SIZE {
+            return Err(b"Invalid aggregation SRS length".to_vec());
+        }
+        Ok(Self {
+            g: read_g1(&bytes[0..64])?,
+            h: read_g2(&bytes[64..192])?,
+            g_alpha: read_g1(&bytes[192..256])?,
+            g_beta: read_g1(&bytes[256..320])?,
+            h_alpha: read_g2(&bytes[320..448])?,
+            h_beta: read_g2(&bytes[448..576])?,
+        })
+    }
+
+    /// Check that g^α, h^α and g^β, h^β raise both generators to the same secret
+    ///
+    /// e(g^α, h) == e(g, h^α) and e(g^β, h) == e(g, h^β), with no point at
+    /// infinity. An SRS failing this was not produced by a powers-of-tau
+    /// ceremony, and the KZG openings checked against it mean nothing.
+    pub fn check_consistency(&self) -> Result<(), Vec<u8>> {
+        let g1 = [self.g, self.g_alpha, self.g_beta];
+        let g2 = [self.h, self.h_alpha, self.h_beta];
+        if g1.iter().any(|p| p.is_zero()) || g2.iter().any(|p| p.is_zero()) {
+            return Err(b"Aggregation SRS has a point at infinity".to_vec());
+        }
+        let same_power = |g_power: G1Affine, h_power: G2Affine| {
+            Bn254::multi_pairing([g_power, -self.g], [self.h, h_power]).0 == Fq12::one()
+        };
+        if !same_power(self.g_alpha, self.h_alpha) {
+            return Err(b"Aggregation SRS alpha powers disagree".to_vec());
+        }
+        if !same_power(self.g_beta, self.h_beta) {
+            return Err(b"Aggregation SRS beta powers disagree".to_vec());
+        }
+        Ok(())
+    }
+
+    /// Encode in the layout read by `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(VERIFIER_SRS_SIZE);
+        append_g1(&self.g, &mut bytes);
+        append_g2(&self.h, &mut bytes);
+        append_g1(&self.g_alpha, &mut bytes);
+        append_g1(&self.g_beta, &mut bytes);
+        append_g2(&self.h_alpha, &mut bytes);
+        append_g2(&self.h_beta, &mut bytes);
+        bytes
+    }
+}
+
+#[cfg(feature = "std")]
+pub use prover_srs::{setup_fake_srs, ProverSrs};
+
+#[cfg(feature = "std")]
+mod prover_srs {
+    use super::VerifierSrs;
+    use alloc::vec::Vec;
+    use ark_bn254::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+    use ark_ec::{CurveGroup, Group};
+    use ark_std::{rand::RngCore, UniformRand};
+
+    /// Prover half of the SRS, able to aggregate up to `max_proofs` proofs
+    ///
+    /// Aggregating n proofs uses v_i = (h^{α^i}, h^{β^i}) and
+    /// w_i = (g^{α^{n+i}}, g^{β^{n+i}}) for i < n, and g powers up to 2n for
+    /// the KZG opening of w.
+    #[derive(Clone, Debug)]
+    pub struct ProverSrs {
+        pub max_proofs: usize,
+        pub g_alpha_powers: Vec<G1Affine>,
+        pub g_beta_powers: Vec<G1Affine>,
+        pub h_alpha_powers: Vec<G2Affine>,
+        pub h_beta_powers: Vec<G2Affine>,
+        pub verifier: VerifierSrs,
+    }
+
+    impl ProverSrs {
+        pub fn verifier_srs(&self) -> VerifierSrs {
+            self.verifier.clone()
+        }
+    }
+
+    /// SRS from locally sampled secrets, for tests and offline tooling only
+    ///
+    /// Whoever knows α or β can forge aggregates; production keys must come
+    /// from two independent powers-of-tau ceremonies.
+    pub fn setup_fake_srs<R: RngCore>(rng: &mut R, max_proofs: usize) -> ProverSrs {
+        let alpha = Fr::rand(rng);
+        let beta = Fr::rand(rng);
+        let g = G1Projective::generator();
+        let h = G2Projective::generator();
+
+        let g_alpha_powers = powers(g, alpha, 2 * max_proofs);
+        let g_beta_powers = powers(g, beta, 2 * max_proofs);
+        let h_alpha_powers = powers(h, alpha, max_proofs);
+        let h_beta_powers = powers(h, beta, max_proofs);
+
+        let verifier = VerifierSrs {
+            g: g.into_affine(),
+            h: h.into_affine(),
+            g_alpha: g_alpha_powers[1],
+            g_beta: g_beta_powers[1],
+            h_alpha: h_alpha_powers[1],
+            h_beta: h_beta_powers[1],
+        };
+
+        ProverSrs {
+            max_proofs,
+            g_alpha_powers,
+            g_beta_powers,
+            h_alpha_powers,
+            h_beta_powers,
+            verifier,
+        }
+    }
+
+    /// [base, base^s, base^{s^2}, ...] with `count` entries
+    fn powers<G: CurveGroup<ScalarField = Fr>>(base: G, secret: Fr, count: usize) -> Vec<G::Affine> {
+        let mut power = Fr::from(1u64);
+        let projective: Vec<G> = (0..count)
+            .map(|_| {
+                let point = base * power;
+                power *= secret;
+                point
+            })
+            .collect();
+        G::normalize_batch(&projective)
+    }
+}
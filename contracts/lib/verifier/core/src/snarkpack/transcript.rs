@@ -0,0 +1,51 @@
+//! Fiat-Shamir transcript shared by the aggregator and the verifier
+
+use alloc::vec::Vec;
+use ark_bn254::Fr;
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+use sha3::{Digest, Keccak256};
+
+/// Keccak256 hash chain: every append and challenge folds into the state
+pub(crate) struct Transcript {
+    state: [u8; 32],
+}
+
+impl Transcript {
+    pub fn new(domain: &[u8]) -> Self {
+        Self { state: Keccak256::digest(domain).into() }
+    }
+
+    pub fn append_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        hasher.update(label);
+        hasher.update((bytes.len() as u64).to_be_bytes());
+        hasher.update(bytes);
+        self.state = hasher.finalize().into();
+    }
+
+    /// Append any arkworks value through its uncompressed encoding
+    pub fn append<T: CanonicalSerialize>(&mut self, label: &[u8], value: &T) {
+        let mut bytes = Vec::with_capacity(value.uncompressed_size());
+        value
+            .serialize_uncompressed(&mut bytes)
+            .expect("writing to a Vec cannot fail");
+        self.append_bytes(label, &bytes);
+    }
+
+    /// Derive a non-zero scalar, so every challenge is invertible
+    pub fn challenge(&mut self, label: &[u8]) -> Fr {
+        loop {
+            let mut hasher = Keccak256::new();
+            hasher.update(self.state);
+            hasher.update(label);
+            self.state = hasher.finalize().into();
+
+            let challenge = Fr::from_be_bytes_mod_order(&self.state);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
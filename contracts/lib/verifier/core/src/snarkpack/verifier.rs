@@ -0,0 +1,177 @@
+//! Aggregate proof verification
+//!
+//! Replays the transcript, folds the GIPA messages into the initial
+//! commitments, then checks the folded values against the final keys and the
+//! combined Groth16 equation. Cost is O(log n) in the number of proofs.
+
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, Zero};
+
+use super::{open_transcript, product_form_evaluation, AggregateProof, Gt, VerifierSrs};
+use crate::VerifyingKey;
+
+/// Verify an aggregate of `public_inputs.len()` proofs for `vk`
+///
+/// Returns `Err` when the inputs cannot belong to this proof (count or width
+/// mismatch, malformed GIPA transcript) and `Ok(false)` when a check fails.
+pub fn verify_aggregate_proof(
+    srs: &VerifierSrs,
+    vk: &VerifyingKey,
+    public_inputs: &[Vec<Fr>],
+    proof: &AggregateProof,
+) -> Result<bool, Vec<u8>> {
+    let gipa = &proof.tmipp.gipa;
+    let nproofs = gipa.nproofs as usize;
+    if nproofs < 2 || !nproofs.is_power_of_two() {
+        return Err(b"Aggregated proof count must be a power of two".to_vec());
+    }
+    if public_inputs.len() != nproofs {
+        return Err(b"Public input sets do not match proof count".to_vec());
+    }
+    let rounds = nproofs.trailing_zeros() as usize;
+    if gipa.comms_ab.len() != rounds
+        || gipa.comms_c.len() != rounds
+        || gipa.z_ab.len() != rounds
+        || gipa.z_c.len() != rounds
+    {
+        return Err(b"Malformed GIPA transcript".to_vec());
+    }
+
+    let (mut transcript, r) = open_transcript(vk, public_inputs, &proof.com_ab, &proof.com_c)?;
+    transcript.append(b"ip_ab", &proof.ip_ab);
+    transcript.append(b"agg_c", &proof.agg_c);
+
+    // Fold every round's cross terms: left^x · right^{x^-1}
+    let (mut t_ab, mut u_c) = (proof.com_ab, proof.com_c);
+    let mut z_ab = proof.ip_ab;
+    let mut z_c = proof.agg_c.into_group();
+    let mut challenges = Vec::with_capacity(rounds);
+    let mut challenges_inv = Vec::with_capacity(rounds);
+    for round in 0..rounds {
+        let (tab_l, tab_r) = gipa.comms_ab[round];
+        let (tuc_l, tuc_r) = gipa.comms_c[round];
+        let (zab_l, zab_r) = gipa.z_ab[round];
+        let (zc_l, zc_r) = gipa.z_c[round];
+
+        transcript.append(b"comms_ab", &gipa.comms_ab[round]);
+        transcript.append(b"comms_c", &gipa.comms_c[round]);
+        transcript.append(b"z_ab", &gipa.z_ab[round]);
+        transcript.append(b"z_c", &gipa.z_c[round]);
+        let x = transcript.challenge(b"x");
+        let x_inv = x.inverse().expect("challenges are non-zero");
+
+        t_ab.0 += tab_l.0 * x + tab_r.0 * x_inv;
+        t_ab.1 += tab_l.1 * x + tab_r.1 * x_inv;
+        u_c.0 += tuc_l.0 * x + tuc_r.0 * x_inv;
+        u_c.1 += tuc_l.1 * x + tuc_r.1 * x_inv;
+        z_ab += zab_l * x + zab_r * x_inv;
+        z_c += zc_l * x + zc_r * x_inv;
+
+        challenges.push(x);
+        challenges_inv.push(x_inv);
+    }
+
+    transcript.append(b"final_vkey", &gipa.final_vkey);
+    transcript.append(b"final_wkey", &gipa.final_wkey);
+    let z = transcript.challenge(b"z");
+
+    challenges.reverse();
+    challenges_inv.reverse();
+    let r_inv = r.inverse().expect("challenges are non-zero");
+
+    if gipa.final_r != product_form_evaluation(&challenges_inv, r, Fr::one()) {
+        return Ok(false);
+    }
+
+    let fv_z = product_form_evaluation(&challenges_inv, z, Fr::one());
+    let fw_z = z.pow([nproofs as u64]) * product_form_evaluation(&challenges, z, r_inv);
+    if !check_vkey_opening(srs, gipa.final_vkey, proof.tmipp.vkey_opening, z, fv_z)
+        || !check_wkey_opening(srs, gipa.final_wkey, proof.tmipp.wkey_opening, z, fw_z)
+    {
+        return Ok(false);
+    }
+
+    let (fa, fb, fc) = (gipa.final_a, gipa.final_b, gipa.final_c);
+    let (fv, fw) = (gipa.final_vkey, gipa.final_wkey);
+    let final_ab = (
+        Bn254::multi_pairing([fa, fw.0], [fv.0, fb]),
+        Bn254::multi_pairing([fa, fw.1], [fv.1, fb]),
+    );
+    let final_c = (Bn254::pairing(fc, fv.0), Bn254::pairing(fc, fv.1));
+    if t_ab != final_ab
+        || u_c != final_c
+        || z_ab != Bn254::pairing(fa, fb)
+        || z_c != fc * gipa.final_r
+    {
+        return Ok(false);
+    }
+
+    Ok(proof.ip_ab == combined_groth16_rhs(vk, public_inputs, r, proof.agg_c))
+}
+
+/// e(g, v - h^{f(z)}) == e(g^s - g^z, π) for s = α, β
+fn check_vkey_opening(
+    srs: &VerifierSrs,
+    final_vkey: (G2Affine, G2Affine),
+    opening: (G2Affine, G2Affine),
+    z: Fr,
+    eval: Fr,
+) -> bool {
+    let h_eval = srs.h * eval;
+    let g_z = srs.g * z;
+    [(final_vkey.0, srs.g_alpha, opening.0), (final_vkey.1, srs.g_beta, opening.1)]
+        .into_iter()
+        .all(|(key, g_secret, pi)| {
+            let lhs = (key.into_group() - h_eval).into_affine();
+            let shift = (g_z - g_secret).into_affine();
+            Bn254::multi_pairing([srs.g, shift], [lhs, pi]).is_zero()
+        })
+}
+
+/// e(w - g^{f(z)}, h) == e(π, h^s - h^z) for s = α, β
+fn check_wkey_opening(
+    srs: &VerifierSrs,
+    final_wkey: (G1Affine, G1Affine),
+    opening: (G1Affine, G1Affine),
+    z: Fr,
+    eval: Fr,
+) -> bool {
+    let g_eval = srs.g * eval;
+    let h_z = srs.h * z;
+    [(final_wkey.0, srs.h_alpha, opening.0), (final_wkey.1, srs.h_beta, opening.1)]
+        .into_iter()
+        .all(|(key, h_secret, pi)| {
+            let lhs = (key.into_group() - g_eval).into_affine();
+            let shift = (h_z - h_secret).into_affine();
+            Bn254::multi_pairing([lhs, pi], [srs.h, shift]).is_zero()
+        })
+}
+
+/// Right-hand side of Π e(A_i, B_i)^{r^i}:
+/// e(α · Σr^i, β) · e(Σ_i L_i · r^i, γ) · e(Σ C_i · r^i, δ)
+fn combined_groth16_rhs(
+    vk: &VerifyingKey,
+    public_inputs: &[Vec<Fr>],
+    r: Fr,
+    agg_c: G1Affine,
+) -> Gt {
+    let mut r_sum = Fr::from(0u64);
+    let mut columns = vec![Fr::from(0u64); vk.ic.len() - 1];
+    let mut power = Fr::one();
+    for inputs in public_inputs {
+        r_sum += power;
+        for (column, input) in columns.iter_mut().zip(inputs) {
+            *column += *input * power;
+        }
+        power *= r;
+    }
+
+    let l_agg = vk.ic[0] * r_sum + G1Projective::msm_unchecked(&vk.ic[1..], &columns);
+    let alpha = (vk.alpha_g1 * r_sum).into_affine();
+    Bn254::multi_pairing(
+        [alpha, l_agg.into_affine(), agg_c],
+        [vk.beta_g2, vk.gamma_g2, vk.delta_g2],
+    )
+}
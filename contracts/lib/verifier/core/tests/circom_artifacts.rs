@@ -0,0 +1,77 @@
+//! The circom/snarkjs readers against files the real tools wrote, in
+//! `fixtures/circom/` (see its README for where each came from).
+
+use arbshield_verifier_core::{
+    circom::{prove, read_wtns, verifying_key_from_zkey, ProvingKey, R1cs, WitnessCalculator, ZkeyVerifyingKey},
+    snarkjs::VerificationKeyJson,
+    verify_groth16,
+};
+use ark_bn254::Fr;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+const R1CS: &[u8] = include_bytes!("../fixtures/circom/multiplier.r1cs");
+const WASM: &[u8] = include_bytes!("../fixtures/circom/multiplier.wasm");
+const WTNS: &[u8] = include_bytes!("../fixtures/circom/multiplier.wtns");
+const INPUT_JSON: &str = include_str!("../fixtures/circom/input.json");
+const ZKEY: &[u8] = include_bytes!("../fixtures/circom/multiplier.zkey");
+const VK_JSON: &str = include_str!("../fixtures/circom/verification_key.json");
+
+/// Wires [1, c, a, b] for a = 3, b = 11
+fn witness() -> Vec<Fr> {
+    [1u64, 33, 3, 11].map(Fr::from).to_vec()
+}
+
+#[test]
+fn test_zkey_matches_exported_key() {
+    // snarkjs wrote the same key twice, as Montgomery-form binary points and
+    // as decimal JSON, so neither reader can be wrong in the same way
+    let exported = VerificationKeyJson::from_json(VK_JSON).unwrap().to_verifying_key().unwrap();
+    let ZkeyVerifyingKey::Groth16(vk) = verifying_key_from_zkey(ZKEY).unwrap() else {
+        panic!("multiplier.zkey is a Groth16 key");
+    };
+    assert_eq!(vk.to_bytes(), exported.to_bytes());
+    assert_eq!(vk.ic.len(), 2);
+
+    let pk = ProvingKey::from_bytes(ZKEY).unwrap();
+    assert_eq!((pk.n_vars, pk.n_public, pk.domain_size), (4, 1, 4));
+    assert_eq!(pk.vk.fingerprint(), exported.fingerprint());
+}
+
+#[test]
+fn test_r1cs_and_witness() {
+    let r1cs = R1cs::from_bytes(R1CS).unwrap();
+    assert_eq!((r1cs.n_wires, r1cs.n_pub_out, r1cs.n_pub_in, r1cs.n_prv_in), (4, 1, 0, 2));
+    // circom writes a × b = c as (-a) × b = -c
+    let [constraint] = &r1cs.constraints[..] else { panic!("one constraint") };
+    assert_eq!(constraint.a, [(2, -Fr::from(1u8))]);
+    assert_eq!(constraint.b, [(3, Fr::from(1u8))]);
+    assert_eq!(constraint.c, [(1, -Fr::from(1u8))]);
+
+    // The .wtns circom's generate_witness.js wrote, and the same generator run here
+    assert_eq!(read_wtns(WTNS).unwrap(), witness());
+    let mut calculator = WitnessCalculator::from_wasm(WASM).unwrap();
+    assert_eq!(calculator.calculate_json(INPUT_JSON).unwrap(), witness());
+    r1cs.check_witness(&witness()).unwrap();
+
+    let mut wrong = witness();
+    wrong[1] = Fr::from(34u64);
+    assert!(r1cs.check_witness(&wrong).is_err());
+}
+
+#[test]
+fn test_prove_with_snarkjs_key() {
+    let pk = ProvingKey::from_bytes(ZKEY).unwrap();
+    let vk = VerificationKeyJson::from_json(VK_JSON).unwrap().to_verifying_key().unwrap();
+    let mut rng = StdRng::seed_from_u64(34);
+
+    let (proof, inputs) = prove(&pk, &read_wtns(WTNS).unwrap(), &mut rng).unwrap();
+    assert_eq!(inputs, [Fr::from(33u64)]);
+    assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(true));
+    assert_eq!(verify_groth16(&proof, &[Fr::from(34u64)], &vk), Ok(false));
+
+    // An unsatisfying witness still proves, but the proof does not verify
+    let mut wrong = witness();
+    wrong[3] = Fr::from(12u64);
+    let (proof, inputs) = prove(&pk, &wrong, &mut rng).unwrap();
+    assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(false));
+}
@@ -0,0 +1,283 @@
+//! End-to-end verification: real ark-groth16 proofs, and proofs for a
+//! snarkjs-generated key, through the byte encodings the contract accepts,
+//! on both the native and the bn256Pairing (EIP-197) paths.
+
+use arbshield_verifier_core::{
+    pairing_check, pairing_input, public_input_commitment,
+    snarkjs::{parse_public_signals_json, ProofJson, VerificationKeyJson},
+    verify_groth16, Groth16Proof, VerifyingKey, PAIRING_INPUT_SIZE, PROOF_SIZE,
+};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use ark_groth16::Groth16;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+use ark_snark::SNARK;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+const PROOF_JSON: &str = include_str!("../fixtures/multiplier/proof.json");
+const VK_JSON: &str = include_str!("../fixtures/multiplier/verification_key.json");
+const PUBLIC_JSON: &str = include_str!("../fixtures/multiplier/public.json");
+const SNARKJS_VK_JSON: &str = include_str!("../fixtures/circom/verification_key.json");
+const SNARKJS_PROOF_JSON: &str = include_str!("../fixtures/circom/proof.json");
+const SNARKJS_PUBLIC_JSON: &str = include_str!("../fixtures/circom/public.json");
+
+/// a · b = product and a + b = sum, both public, so input order matters
+#[derive(Clone)]
+struct ProductSum {
+    a: Option<Fr>,
+    b: Option<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for ProductSum {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let product = cs.new_input_variable(|| {
+            self.a.zip(self.b).map(|(a, b)| a * b).ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let sum = cs.new_input_variable(|| {
+            self.a.zip(self.b).map(|(a, b)| a + b).ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+        let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+        cs.enforce_constraint(lc!() + a + b, lc!() + ark_relations::r1cs::Variable::One, lc!() + sum)
+    }
+}
+
+struct Setup {
+    pk: ark_groth16::ProvingKey<Bn254>,
+    vk: VerifyingKey,
+    rng: StdRng,
+}
+
+impl Setup {
+    fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(ProductSum { a: None, b: None }, &mut rng).unwrap();
+        let vk = VerifyingKey {
+            alpha_g1: vk.alpha_g1,
+            beta_g2: vk.beta_g2,
+            gamma_g2: vk.gamma_g2,
+            delta_g2: vk.delta_g2,
+            ic: vk.gamma_abc_g1,
+        };
+        Self { pk, vk, rng }
+    }
+
+    /// Proof and public inputs [a·b, a+b]
+    fn prove(&mut self, a: u64, b: u64) -> (Groth16Proof, Vec<Fr>) {
+        let (a, b) = (Fr::from(a), Fr::from(b));
+        let proof = Groth16::<Bn254>::prove(&self.pk, ProductSum { a: Some(a), b: Some(b) }, &mut self.rng).unwrap();
+        (Groth16Proof { a: proof.a, b: proof.b, c: proof.c }, vec![a * b, a + b])
+    }
+}
+
+/// The contract's `verify`: decode the 256 bytes, then check
+fn verify_bytes(bytes: &[u8], inputs: &[Fr], vk: &VerifyingKey) -> Result<bool, Vec<u8>> {
+    verify_groth16(&Groth16Proof::from_uncompressed_bytes(bytes)?, inputs, vk)
+}
+
+/// bn256Pairing (0x08) evaluated with arkworks: `Err` where the precompile
+/// would revert, otherwise whether the product of the pairings is one
+fn eip197_pairing(input: &[u8]) -> Result<bool, &'static str> {
+    if !input.len().is_multiple_of(192) {
+        return Err("input not a multiple of 192 bytes");
+    }
+    let (mut g1, mut g2) = (Vec::new(), Vec::new());
+    for pair in input.chunks(192) {
+        let fq = |i: usize| {
+            let word = &pair[32 * i..32 * (i + 1)];
+            let value = Fq::from_be_bytes_mod_order(word);
+            if value.into_bigint().to_bytes_be() != word {
+                return Err("coordinate not below the field modulus");
+            }
+            Ok(value)
+        };
+        let (x, y) = (fq(0)?, fq(1)?);
+        g1.push(if x.is_zero() && y.is_zero() {
+            G1Affine::zero()
+        } else {
+            let point = G1Affine::new_unchecked(x, y);
+            if !point.is_on_curve() {
+                return Err("G1 point not on curve");
+            }
+            point
+        });
+        // Imaginary part first
+        let (x, y) = (Fq2::new(fq(3)?, fq(2)?), Fq2::new(fq(5)?, fq(4)?));
+        g2.push(if x.is_zero() && y.is_zero() {
+            G2Affine::zero()
+        } else {
+            let point = G2Affine::new_unchecked(x, y);
+            if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+                return Err("G2 point not on curve or not in subgroup");
+            }
+            point
+        });
+    }
+    Ok(Bn254::multi_pairing(g1, g2).0.is_one())
+}
+
+/// The contract's `verifyWithPrecompile`, with the precompile evaluated natively
+fn verify_precompile(proof: &Groth16Proof, inputs: &[Fr], vk: &VerifyingKey) -> Result<bool, Vec<u8>> {
+    let commitment = public_input_commitment(vk, inputs.iter().copied())?;
+    let input = pairing_input(proof, &commitment, vk);
+    assert_eq!(input.len(), PAIRING_INPUT_SIZE);
+    eip197_pairing(&input).map_err(|err| err.as_bytes().to_vec())
+}
+
+/// Re-encode a G2 point in the 256-byte slot as x1 || x0 || y1 || y0
+fn swap_g2_halves(bytes: &mut [u8]) {
+    for half in bytes.chunks_mut(64) {
+        let (c0, c1) = half.split_at_mut(32);
+        c0.swap_with_slice(c1);
+    }
+}
+
+#[test]
+fn test_arkworks_proofs_verify() {
+    let mut setup = Setup::new(37);
+    let vk = VerifyingKey::from_bytes(&setup.vk.to_bytes()).unwrap();
+
+    for (a, b) in [(3, 5), (0, 7), (u64::MAX, 2)] {
+        let (proof, inputs) = setup.prove(a, b);
+        assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(true));
+
+        let bytes = proof.to_uncompressed_bytes();
+        assert_eq!(verify_bytes(&bytes, &inputs, &vk), Ok(true));
+        let compressed = Groth16Proof::from_compressed_bytes(&proof.to_compressed_bytes()).unwrap();
+        assert_eq!(compressed.to_uncompressed_bytes(), bytes);
+    }
+}
+
+#[test]
+fn test_tampered_proofs_fail() {
+    let mut setup = Setup::new(38);
+    let (proof, inputs) = setup.prove(3, 5);
+    let bytes = proof.to_uncompressed_bytes();
+
+    // A flipped bit anywhere either fails to decode or fails the pairing check
+    for word in 0..PROOF_SIZE / 32 {
+        for bit in [0, 129, 255] {
+            let mut flipped = bytes;
+            flipped[word * 32 + bit / 8] ^= 1 << (bit % 8);
+            assert_ne!(verify_bytes(&flipped, &inputs, &setup.vk), Ok(true), "word {word}, bit {bit}");
+        }
+    }
+
+    // Wrong, reordered, missing and extra inputs
+    assert_eq!(verify_groth16(&proof, &[inputs[0] + Fr::one(), inputs[1]], &setup.vk), Ok(false));
+    assert_eq!(verify_groth16(&proof, &[inputs[1], inputs[0]], &setup.vk), Ok(false));
+    assert!(verify_groth16(&proof, &inputs[..1], &setup.vk).is_err());
+    assert!(verify_groth16(&proof, &[inputs[0], inputs[1], Fr::zero()], &setup.vk).is_err());
+
+    // A valid proof for other inputs, and points borrowed between proofs
+    let (other, other_inputs) = setup.prove(4, 6);
+    assert_eq!(verify_groth16(&other, &inputs, &setup.vk), Ok(false));
+    assert_eq!(verify_groth16(&Groth16Proof { c: other.c, ..proof }, &inputs, &setup.vk), Ok(false));
+    assert_eq!(verify_groth16(&other, &other_inputs, &setup.vk), Ok(true));
+
+    // Same proof under another setup's key
+    assert_eq!(verify_groth16(&proof, &inputs, &Setup::new(39).vk), Ok(false));
+}
+
+#[test]
+fn test_swapped_g2_coordinates() {
+    let mut setup = Setup::new(40);
+    let (proof, inputs) = setup.prove(3, 5);
+
+    // B in the precompile's order inside the native 256-byte layout
+    let mut swapped = proof.to_uncompressed_bytes();
+    swap_g2_halves(&mut swapped[64..192]);
+    assert_ne!(verify_bytes(&swapped, &inputs, &setup.vk), Ok(true));
+
+    // A key whose G2 points were pasted in the wrong order
+    let mut vk_bytes = setup.vk.to_bytes();
+    for offset in [64, 192, 320] {
+        let mut key = vk_bytes.clone();
+        swap_g2_halves(&mut key[offset..offset + 128]);
+        if let Ok(vk) = VerifyingKey::from_bytes(&key) {
+            assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(false), "G2 at {offset}");
+        }
+        swap_g2_halves(&mut vk_bytes[offset..offset + 128]);
+    }
+    assert!(VerifyingKey::from_bytes(&vk_bytes).map_or(true, |vk| verify_groth16(&proof, &inputs, &vk) != Ok(true)));
+
+    // The precompile input with native (real part first) G2 order fails at 0x08
+    let commitment = public_input_commitment(&setup.vk, inputs.iter().copied()).unwrap();
+    let mut input = pairing_input(&proof, &commitment, &setup.vk);
+    assert_eq!(eip197_pairing(&input), Ok(true));
+    for pair in input.chunks_mut(192) {
+        swap_g2_halves(&mut pair[64..192]);
+    }
+    assert_ne!(eip197_pairing(&input), Ok(true));
+}
+
+#[test]
+fn test_snarkjs_fixtures() {
+    // The `vk_constants` key in snarkjs JSON layout
+    let vk = VerificationKeyJson::from_json(VK_JSON).unwrap().to_verifying_key().unwrap();
+    assert_eq!(vk.fingerprint(), VerifyingKey::load_real().fingerprint());
+    let proof = ProofJson::from_json(PROOF_JSON).unwrap().to_proof().unwrap();
+    let inputs = parse_public_signals_json(PUBLIC_JSON).unwrap();
+    assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(true));
+
+    // A key `snarkjs zkey export verificationkey` wrote; the circom test
+    // reads the same key from the binary .zkey
+    let vk = VerificationKeyJson::from_json(SNARKJS_VK_JSON).unwrap().to_verifying_key().unwrap();
+    assert_eq!(
+        vk.fingerprint(),
+        hex_literal::hex!("341e141220bec2f0fcff921d013ad54f1293e48ab3c68a88096c07e127cbd95e")
+    );
+    let proof = ProofJson::from_json(SNARKJS_PROOF_JSON).unwrap().to_proof().unwrap();
+    let inputs = parse_public_signals_json(SNARKJS_PUBLIC_JSON).unwrap();
+    assert_eq!(inputs, [Fr::from(33u64)]);
+
+    assert_eq!(verify_groth16(&proof, &inputs, &vk), Ok(true));
+    assert_eq!(verify_precompile(&proof, &inputs, &vk), Ok(true));
+    assert_eq!(verify_groth16(&proof, &[Fr::from(34u64)], &vk), Ok(false));
+    assert_eq!(verify_groth16(&proof, &inputs, &VerifyingKey::load_real()), Ok(false));
+
+    // pi_b with each pair in EIP-197 order, as a hand-edited calldata would have it
+    let mut json = ProofJson::from_json(SNARKJS_PROOF_JSON).unwrap();
+    json.pi_b.iter_mut().take(2).for_each(|pair| pair.swap(0, 1));
+    assert!(json.to_proof().map_or(true, |swapped| verify_groth16(&swapped, &inputs, &vk) == Ok(false)));
+
+    // So would a key whose G2 points were read in that order
+    let mut json = VerificationKeyJson::from_json(SNARKJS_VK_JSON).unwrap();
+    json.vk_beta_2.iter_mut().take(2).for_each(|pair| pair.swap(0, 1));
+    assert!(json.to_verifying_key().map_or(true, |swapped| verify_groth16(&proof, &inputs, &swapped) == Ok(false)));
+}
+
+#[test]
+fn test_native_and_precompile_agree() {
+    let mut setup = Setup::new(41);
+    let (proof, inputs) = setup.prove(3, 5);
+    let fixture_vk = VerifyingKey::load_real();
+    let fixture = ProofJson::from_json(PROOF_JSON).unwrap().to_proof().unwrap();
+
+    let cases = [
+        (proof.clone(), inputs.clone(), &setup.vk, true),
+        (proof.clone(), vec![inputs[0], inputs[0]], &setup.vk, false),
+        (Groth16Proof { c: proof.a, ..proof.clone() }, inputs.clone(), &setup.vk, false),
+        (fixture.clone(), vec![Fr::from(15u64)], &fixture_vk, true),
+        (fixture, vec![Fr::from(16u64)], &fixture_vk, false),
+    ];
+    for (i, (proof, inputs, vk, expected)) in cases.iter().enumerate() {
+        assert_eq!(verify_groth16(proof, inputs, vk), Ok(*expected), "case {i}, native");
+        assert_eq!(verify_precompile(proof, inputs, vk), Ok(*expected), "case {i}, precompile");
+
+        // The precompile layout is the native layout with each G2 coordinate's halves swapped
+        let commitment = public_input_commitment(vk, inputs.iter().copied()).unwrap();
+        let input = pairing_input(proof, &commitment, vk);
+        let native = proof.to_uncompressed_bytes();
+        assert_eq!(&input[..64], &native[..64]);
+        let mut b = native[64..192].to_vec();
+        swap_g2_halves(&mut b);
+        assert_eq!(&input[64..192], &b[..]);
+        assert_eq!(pairing_check(proof, &commitment, vk), *expected);
+    }
+}
@@ -0,0 +1,280 @@
+//! Properties of every byte decoder the contract exposes to calldata:
+//! - arbitrary input never panics
+//! - anything that decodes re-encodes to the exact same bytes
+//! - values at or above the field modulus are rejected, never reduced
+
+use arbshield_verifier_core::{
+    decode_g1, decode_g2, parse_public_inputs, read_fr, read_g1_compressed, read_g2_compressed,
+    snarkpack::{self, AggregateProof, VerifierSrs},
+    Groth16Proof, InputMode, PackedCall, PointError, VerifyingKey, COMPRESSED_PROOF_SIZE, PROOF_SIZE, VK_HEADER_SIZE,
+};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use proptest::{collection::vec, prelude::*};
+use std::sync::OnceLock;
+
+fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+fn g1(rng: &mut StdRng) -> G1Affine {
+    G1Projective::rand(rng).into_affine()
+}
+
+fn g2(rng: &mut StdRng) -> G2Affine {
+    G2Projective::rand(rng).into_affine()
+}
+
+fn proof(seed: u64) -> Groth16Proof {
+    let rng = &mut rng(seed);
+    Groth16Proof { a: g1(rng), b: g2(rng), c: g1(rng) }
+}
+
+fn verifying_key(seed: u64, inputs: usize) -> VerifyingKey {
+    let rng = &mut rng(seed);
+    VerifyingKey {
+        alpha_g1: g1(rng),
+        beta_g2: g2(rng),
+        gamma_g2: g2(rng),
+        delta_g2: g2(rng),
+        ic: (0..=inputs).map(|_| g1(rng)).collect(),
+    }
+}
+
+/// A valid two-proof aggregate; the proofs need not verify to be encodable
+fn aggregate_bytes() -> &'static [u8] {
+    static BYTES: OnceLock<Vec<u8>> = OnceLock::new();
+    BYTES.get_or_init(|| {
+        let rng = &mut rng(7);
+        let srs = snarkpack::setup_fake_srs(rng, 2);
+        let vk = verifying_key(8, 1);
+        let proofs = [proof(9), proof(10)];
+        let inputs = [vec![Fr::from(1u64)], vec![Fr::from(2u64)]];
+        let bytes = snarkpack::aggregate_proofs(&srs, &vk, &proofs, &inputs).unwrap().to_bytes();
+        assert_eq!(AggregateProof::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+        bytes
+    })
+}
+
+/// `word + modulus` as a 32-byte BigEndian word, if it still fits
+fn plus_modulus<F: PrimeField>(word: &[u8]) -> Option<[u8; 32]> {
+    let modulus = F::MODULUS.to_bytes_be();
+    let mut sum = [0u8; 32];
+    let mut carry = 0u16;
+    for i in (0..32).rev() {
+        let digit = u16::from(word[i]) + u16::from(modulus[i]) + carry;
+        sum[i] = digit as u8;
+        carry = digit >> 8;
+    }
+    (carry == 0).then_some(sum)
+}
+
+/// Valid encoding with one byte XOR-ed, to land near the accepting region
+fn mutate(mut bytes: Vec<u8>, (index, mask): (usize, u8)) -> Vec<u8> {
+    let index = index % bytes.len();
+    bytes[index] ^= mask;
+    bytes
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_arbitrary_bytes_never_panic(bytes in vec(any::<u8>(), 0..700)) {
+        for decoded in [
+            Groth16Proof::from_uncompressed_bytes(&bytes).map(|proof| proof.to_uncompressed_bytes().to_vec()),
+            Groth16Proof::from_compressed_bytes(&bytes).map(|proof| proof.to_compressed_bytes().to_vec()),
+            VerifyingKey::from_bytes(&bytes).map(|vk| vk.to_bytes()),
+            VerifierSrs::from_bytes(&bytes).map(|srs| srs.to_bytes()),
+            AggregateProof::from_bytes(&bytes).map(|proof| proof.to_bytes()),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            prop_assert_eq!(decoded, bytes.clone());
+        }
+        let _ = decode_g1(&bytes);
+        let _ = decode_g2(&bytes);
+        let _ = read_g1_compressed(&bytes);
+        let _ = read_g2_compressed(&bytes);
+        let _ = read_fr(&bytes);
+
+        match PackedCall::parse(&bytes) {
+            Ok(call) => {
+                let rebuilt = [&call.circuit_id[..], &call.proof[..], call.inputs.as_flattened()].concat();
+                prop_assert_eq!(rebuilt, bytes.clone());
+            }
+            Err(_) => prop_assert!(bytes.len() < 32 + PROOF_SIZE || !(bytes.len() - 32 - PROOF_SIZE).is_multiple_of(32)),
+        }
+    }
+
+    #[test]
+    fn test_proof_round_trips(seed in any::<u64>(), mutation in (any::<usize>(), 1..=u8::MAX)) {
+        let proof = proof(seed);
+        let bytes = proof.to_uncompressed_bytes();
+        let compressed = proof.to_compressed_bytes();
+        prop_assert_eq!(Groth16Proof::from_uncompressed_array(&bytes).unwrap().to_uncompressed_bytes(), bytes);
+        prop_assert_eq!(Groth16Proof::from_compressed_bytes(&compressed).unwrap().to_uncompressed_bytes(), bytes);
+
+        // A flipped bit either breaks the point or encodes a different, valid one
+        let mutated = mutate(bytes.to_vec(), mutation);
+        if let Ok(decoded) = Groth16Proof::from_uncompressed_bytes(&mutated) {
+            prop_assert_eq!(decoded.to_uncompressed_bytes().to_vec(), mutated);
+        }
+        let mutated = mutate(compressed.to_vec(), mutation);
+        if let Ok(decoded) = Groth16Proof::from_compressed_bytes(&mutated) {
+            prop_assert_eq!(decoded.to_compressed_bytes().to_vec(), mutated);
+        }
+    }
+
+    #[test]
+    fn test_non_canonical_coordinates_rejected(seed in any::<u64>(), word in 0..PROOF_SIZE / 32) {
+        let mut bytes = proof(seed).to_uncompressed_bytes();
+        let range = word * 32..(word + 1) * 32;
+        if let Some(shifted) = plus_modulus::<Fq>(&bytes[range.clone()]) {
+            bytes[range.clone()].copy_from_slice(&shifted);
+            prop_assert!(Groth16Proof::from_uncompressed_array(&bytes).is_err());
+            let error = match word {
+                0 | 1 => decode_g1(&bytes[0..64]).err(),
+                6 | 7 => decode_g1(&bytes[192..256]).err(),
+                _ => decode_g2(&bytes[64..192]).err(),
+            };
+            prop_assert_eq!(error, Some(PointError::NonCanonical));
+        }
+
+        // Compressed x words keep their flag bits; the rest must still be below p
+        let mut compressed = proof(seed).to_compressed_bytes();
+        let range = (word % (COMPRESSED_PROOF_SIZE / 32)) * 32..;
+        let flags = compressed[range.start] & 0xc0;
+        let mut x = [0u8; 32];
+        x.copy_from_slice(&compressed[range.clone()][..32]);
+        x[0] &= 0x3f;
+        if let Some(shifted) = plus_modulus::<Fq>(&x).filter(|shifted| shifted[0] & 0xc0 == 0) {
+            compressed[range.clone()][..32].copy_from_slice(&shifted);
+            compressed[range.start] |= flags;
+            prop_assert!(Groth16Proof::from_compressed_bytes(&compressed).is_err());
+        }
+    }
+
+    #[test]
+    fn test_public_inputs_are_canonical(seed in any::<u64>(), count in 0usize..8, offset in 0usize..8) {
+        let rng = &mut rng(seed);
+        let values: Vec<Fr> = (0..count).map(|_| Fr::rand(rng)).collect();
+        let words: Vec<Vec<u8>> = values.iter().map(|value| value.into_bigint().to_bytes_be()).collect();
+        prop_assert_eq!(parse_public_inputs(&words), Ok(values.clone()));
+
+        // x + r names the same scalar and must not be a second encoding of it
+        if let Some(index) = offset.checked_rem(count) {
+            if let Some(shifted) = plus_modulus::<Fr>(&words[index]) {
+                let mut words = words.clone();
+                words[index] = shifted.to_vec();
+                prop_assert!(parse_public_inputs(&words).is_err());
+                prop_assert!(read_fr(&shifted).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_ragged_public_inputs_rejected(inputs in vec(vec(any::<u8>(), 0..40), 0..6)) {
+        let parsed = parse_public_inputs(&inputs);
+        if inputs.iter().any(|input| input.len() != 32) {
+            prop_assert!(parsed.is_err());
+        } else if let Ok(values) = parsed {
+            let encoded: Vec<Vec<u8>> = values.iter().map(|value| value.into_bigint().to_bytes_be()).collect();
+            prop_assert_eq!(encoded, inputs);
+        }
+    }
+
+    #[test]
+    fn test_key_and_srs_round_trips(seed in any::<u64>(), inputs in 0usize..6, mutation in (any::<usize>(), 1..=u8::MAX)) {
+        let vk = verifying_key(seed, inputs);
+        let bytes = vk.to_bytes();
+        prop_assert_eq!(bytes.len(), VK_HEADER_SIZE + 64 * (inputs + 1));
+        prop_assert_eq!(VerifyingKey::from_bytes(&bytes).unwrap().to_bytes(), bytes.clone());
+        let mutated = mutate(bytes, mutation);
+        if let Ok(decoded) = VerifyingKey::from_bytes(&mutated) {
+            prop_assert_eq!(decoded.to_bytes(), mutated);
+        }
+
+        let srs = snarkpack::setup_fake_srs(&mut rng(seed), 2).verifier_srs();
+        prop_assert_eq!(VerifierSrs::from_bytes(&srs.to_bytes()).unwrap(), srs.clone());
+        let mutated = mutate(srs.to_bytes(), mutation);
+        if let Ok(decoded) = VerifierSrs::from_bytes(&mutated) {
+            prop_assert_eq!(decoded.to_bytes(), mutated);
+        }
+    }
+
+    #[test]
+    fn test_input_mode_round_trips(mode in any::<u8>()) {
+        match InputMode::from_u8(mode) {
+            Ok(decoded) => prop_assert_eq!(decoded.as_u8(), mode),
+            Err(_) => prop_assert!(mode > 2),
+        }
+    }
+}
+
+proptest! {
+    // Every decode checks each GT element's subgroup, so keep this one short
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn test_aggregate_proof_round_trips(mutation in (any::<usize>(), 1..=u8::MAX), cut in any::<usize>()) {
+        let bytes = aggregate_bytes();
+        let mutated = mutate(bytes.to_vec(), mutation);
+        if let Ok(decoded) = AggregateProof::from_bytes(&mutated) {
+            prop_assert_eq!(decoded.to_bytes(), mutated);
+        }
+        prop_assert!(AggregateProof::from_bytes(&bytes[..cut % bytes.len()]).is_err());
+    }
+}
+
+#[cfg(feature = "snarkjs")]
+mod snarkjs {
+    use super::*;
+    use arbshield_verifier_core::snarkjs::{
+        parse_decimal, parse_public_signals_json, to_decimal, ProofJson, VerificationKeyJson,
+    };
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_json_never_panics(json in "\\PC{0,200}") {
+            let _ = ProofJson::from_json(&json).map(|proof| proof.to_proof());
+            let _ = VerificationKeyJson::from_json(&json).map(|vk| vk.to_verifying_key());
+            let _ = parse_public_signals_json(&json);
+            let _ = parse_decimal::<Fr>(&json);
+        }
+
+        #[test]
+        fn test_json_round_trips(seed in any::<u64>(), inputs in 0usize..4) {
+            let proof = proof(seed);
+            let json = ProofJson::from_proof(&proof).to_json();
+            let decoded = ProofJson::from_json(&json).unwrap().to_proof().unwrap();
+            prop_assert_eq!(decoded.to_uncompressed_bytes(), proof.to_uncompressed_bytes());
+
+            let vk = verifying_key(seed, inputs);
+            let json = VerificationKeyJson::from_verifying_key(&vk).to_json();
+            let decoded = VerificationKeyJson::from_json(&json).unwrap().to_verifying_key().unwrap();
+            prop_assert_eq!(decoded.to_bytes(), vk.to_bytes());
+        }
+
+        #[test]
+        fn test_decimals_are_canonical(seed in any::<u64>(), digits in "[0-9]{1,90}") {
+            let value = Fr::rand(&mut rng(seed));
+            prop_assert_eq!(parse_decimal::<Fr>(&to_decimal(&value)), Some(value));
+
+            // Accepted decimals are exactly the integers below r
+            if let Some(parsed) = parse_decimal::<Fr>(&digits) {
+                let trimmed = digits.trim_start_matches('0');
+                prop_assert_eq!(to_decimal(&parsed), if trimmed.is_empty() { "0" } else { trimmed });
+            }
+            let mut shifted = value.into_bigint();
+            if !shifted.add_with_carry(&Fr::MODULUS) {
+                prop_assert_eq!(parse_decimal::<Fr>(&shifted.to_string()), None);
+            }
+        }
+    }
+}
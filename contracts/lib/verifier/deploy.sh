@@ -1,9 +1,9 @@
 #!/bin/bash
-# deploy.sh - Deploy Stylus Groth16 Verifier to Arbitrum Sepolia
+# deploy.sh - Deploy the Stylus verifier and compliance programs to Arbitrum Sepolia
 
 set -e
 
-echo "🚀 Deploying Stylus Groth16 Verifier..."
+echo "🚀 Deploying Stylus Groth16 Verifier and compliance programs..."
 echo ""
 
 # Check prerequisites
@@ -23,50 +23,59 @@ if [ -z "$PRIVATE_KEY" ]; then
     exit 1
 fi
 
-# Build optimized WASM
-echo "📦 Building optimized WASM..."
-cargo build --release --target wasm32-unknown-unknown
+ENDPOINT=https://sepolia-rollup.arbitrum.io/rpc
 
-WASM_FILE="target/wasm32-unknown-unknown/release/arbshield_verifier.wasm"
+# Wasm file stem and cargo feature of each program, in deployment order;
+# ZKVerifier is the default build
+PROGRAMS="verifier: records:records gateway:gateway delegations:delegations policies:policies assets:assets bridge:bridge"
+OUT=target/stylus
+mkdir -p "$OUT"
 
-if [ ! -f "$WASM_FILE" ]; then
-    echo "❌ WASM file not found: $WASM_FILE"
-    exit 1
-fi
+for program in $PROGRAMS; do
+    name=${program%%:*}
+    feature=${program#*:}
 
-# Check size
-echo ""
-echo "📊 WASM file size:"
-ls -lh "$WASM_FILE"
-
-# Optimize with wasm-opt (if available)
-if command -v wasm-opt &> /dev/null; then
+    # Build optimized WASM
     echo ""
-    echo "⚡ Optimizing with wasm-opt..."
-    wasm-opt "$WASM_FILE" -O3 -o optimized.wasm
-    echo "📊 Optimized size:"
-    ls -lh optimized.wasm
-    WASM_FILE="optimized.wasm"
-fi
+    echo "📦 Building $name..."
+    cargo build --release --target wasm32-unknown-unknown --lib --no-default-features ${feature:+--features $feature}
 
-# Check contract
-echo ""
-echo "🔍 Checking contract..."
-cargo stylus check --wasm-file="$WASM_FILE" --endpoint https://sepolia-rollup.arbitrum.io/rpc
+    WASM_FILE="$OUT/$name.wasm"
+    cp target/wasm32-unknown-unknown/release/arbshield_verifier.wasm "$WASM_FILE"
 
-# Deploy to Arbitrum Sepolia
-echo ""
-echo "🚀 Deploying to Arbitrum Sepolia..."
-cargo stylus deploy \
-    --private-key "$PRIVATE_KEY" \
-    --wasm-file="$WASM_FILE" \
-    --endpoint https://sepolia-rollup.arbitrum.io/rpc
+    # Optimize with wasm-opt (if available)
+    if command -v wasm-opt &> /dev/null; then
+        echo "⚡ Optimizing with wasm-opt..."
+        wasm-opt "$WASM_FILE" -O3 -o "$WASM_FILE"
+    fi
+
+    echo "📊 WASM file size:"
+    ls -lh "$WASM_FILE"
+
+    # Check contract
+    echo "🔍 Checking $name..."
+    cargo stylus check --wasm-file="$WASM_FILE" --endpoint "$ENDPOINT"
+
+    # Deploy to Arbitrum Sepolia
+    echo "🚀 Deploying $name to Arbitrum Sepolia..."
+    cargo stylus deploy \
+        --private-key "$PRIVATE_KEY" \
+        --wasm-file="$WASM_FILE" \
+        --endpoint "$ENDPOINT"
+done
 
 echo ""
 echo "✅ Deployment complete!"
 echo ""
 echo "📝 Next steps:"
-echo "  1. Copy the contract address"
+echo "  1. Copy each program's address and initialize them in this order:"
+echo "       ZKVerifier.initialize(owner)"
+echo "       ComplianceGateway.initialize(owner, verifier, records)"
+echo "       ComplianceRecords.initialize(verifier, gateway)"
+echo "       ComplianceDelegations.initialize(owner, records)"
+echo "       CompliancePolicies.initialize(gateway, delegations)"
+echo "       AssetCompliance.initialize(owner, records, delegations, policies)"
+echo "       ComplianceBridge.initialize(owner, gateway, records, assets)"
 echo "  2. Update .env.local: NEXT_PUBLIC_ZK_VERIFIER=0x..."
 echo "  3. Update lib/contracts.ts"
 echo "  4. Test verification flow"
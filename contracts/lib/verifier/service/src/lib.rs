@@ -0,0 +1,597 @@
+//! VERIFICATION SERVICE - HTTP JSON front end over the verifier core
+//!
+//! Lets the back office check a proof with the contract's own decoding and
+//! pairing check before anything is sent on-chain.
+//!
+//! ```text
+//! GET  /health    -> { "status": "ok", "circuits": 1, "metrics": { ... } }
+//! GET  /circuits  -> [ { "id": "multiplier", "fingerprint": "0x..", "publicInputs": 1 } ]
+//! POST /verify    application/json:
+//!                   { "circuit": "multiplier", "proof": {snarkjs} | "0x<hex>", "publicSignals": ["15"] }
+//!                 application/octet-stream:
+//!                   fingerprint (32) || proof (256) || inputs (32 each), as `verifyPacked`
+//!                 -> { "valid": true, "circuit": "multiplier", "fingerprint": "0x..", "proofHash": "0x.." }
+//! POST /verify?circuit=multiplier
+//!                 the same, with the circuit (id or fingerprint) given in the query; raw bytes are then
+//!                   proof (256) || inputs (32 each)
+//! GET  /results   -> [ { "circuit": "multiplier", "proofHash": "0x..", "valid": true, "at": 1760000000 } ]
+//! ```
+//!
+//! Keys are loaded once at startup from a directory holding either
+//! `<id>.json` or `<id>/verification_key.json` files. The last
+//! `RESULT_LOG_SIZE` verdicts are kept in memory, newest first on `/results`.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use arbshield_verifier_core::{
+    public_input_commitment, pairing_check,
+    snarkjs::{parse_public_signals, ProofJson, SnarkjsError, VerificationKeyJson},
+    verify_groth16, Groth16Proof, PackedCall, VerifyingKey, COMPRESSED_PROOF_SIZE, PROOF_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+// ============================================================================
+// KEY STORE
+// ============================================================================
+
+/// A verifying key served under a circuit id
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    pub id: String,
+    pub vk: VerifyingKey,
+    /// Same value as `VerifyingKey::fingerprint`, i.e. what the contract exposes
+    pub fingerprint: [u8; 32],
+}
+
+impl Circuit {
+    pub fn new(id: String, vk: VerifyingKey) -> Self {
+        let fingerprint = vk.fingerprint();
+        Self { id, vk, fingerprint }
+    }
+
+    fn summary(&self) -> Value {
+        json!({
+            "id": self.id,
+            "fingerprint": hex_word(&self.fingerprint),
+            "publicInputs": self.vk.ic.len() - 1,
+        })
+    }
+}
+
+/// Why the key directory could not be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, io::Error),
+    Key(PathBuf, SnarkjsError),
+    Duplicate(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Key(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Duplicate(id) => write!(f, "circuit id {id} defined twice"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Verifying keys by circuit id, fixed for the life of the process
+#[derive(Clone, Debug, Default)]
+pub struct KeyStore {
+    circuits: BTreeMap<String, Circuit>,
+}
+
+impl KeyStore {
+    /// Load `<dir>/<id>.json` and `<dir>/<id>/verification_key.json`
+    pub fn load_dir(dir: &Path) -> Result<Self, LoadError> {
+        let mut store = Self::default();
+        let entries = fs::read_dir(dir).map_err(|err| LoadError::Io(dir.to_path_buf(), err))?;
+
+        for entry in entries {
+            let path = entry.map_err(|err| LoadError::Io(dir.to_path_buf(), err))?.path();
+            let (id, file) = if path.is_dir() {
+                (path.file_name(), path.join("verification_key.json"))
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                (path.file_stem(), path.clone())
+            } else {
+                continue;
+            };
+            if !file.is_file() {
+                continue;
+            }
+            let id = id.unwrap_or_default().to_string_lossy().into_owned();
+
+            let json = fs::read_to_string(&file).map_err(|err| LoadError::Io(file.clone(), err))?;
+            let vk = VerificationKeyJson::from_json(&json)
+                .and_then(|vk| vk.to_verifying_key())
+                .map_err(|err| LoadError::Key(file, err))?;
+            store.insert(Circuit::new(id, vk))?;
+        }
+        Ok(store)
+    }
+
+    pub fn insert(&mut self, circuit: Circuit) -> Result<(), LoadError> {
+        if self.circuits.contains_key(&circuit.id) {
+            return Err(LoadError::Duplicate(circuit.id));
+        }
+        self.circuits.insert(circuit.id.clone(), circuit);
+        Ok(())
+    }
+
+    /// Look up by id, or by `0x`-prefixed key fingerprint
+    pub fn get(&self, id: &str) -> Option<&Circuit> {
+        self.circuits
+            .get(id)
+            .or_else(|| self.circuits.values().find(|circuit| hex_word(&circuit.fingerprint) == id))
+    }
+
+    pub fn by_fingerprint(&self, fingerprint: &[u8; 32]) -> Option<&Circuit> {
+        self.circuits.values().find(|circuit| &circuit.fingerprint == fingerprint)
+    }
+
+    pub fn len(&self) -> usize {
+        self.circuits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.circuits.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Circuit> {
+        self.circuits.values()
+    }
+}
+
+// ============================================================================
+// METRICS
+// ============================================================================
+
+/// Upper bounds of the latency histogram buckets, in microseconds
+pub const LATENCY_BUCKETS_US: [u64; 8] = [100, 500, 1_000, 2_500, 5_000, 10_000, 50_000, 250_000];
+
+/// Request count and latency for one route
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteMetrics {
+    pub requests: u64,
+    /// Responses with a 4xx/5xx status
+    pub errors: u64,
+    pub total_us: u64,
+    pub max_us: u64,
+    /// Counts per `LATENCY_BUCKETS_US` bound, plus one overflow bucket
+    pub buckets: Vec<u64>,
+}
+
+impl RouteMetrics {
+    fn record(&mut self, latency: Duration, status: u16) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; LATENCY_BUCKETS_US.len() + 1];
+        }
+        let bucket = LATENCY_BUCKETS_US.iter().position(|&bound| micros <= bound);
+        self.buckets[bucket.unwrap_or(LATENCY_BUCKETS_US.len())] += 1;
+
+        self.requests += 1;
+        self.errors += u64::from(status >= 400);
+        self.total_us = self.total_us.saturating_add(micros);
+        self.max_us = self.max_us.max(micros);
+    }
+}
+
+/// Per-route latency, shared by all worker threads
+#[derive(Debug, Default)]
+pub struct Metrics {
+    routes: Mutex<BTreeMap<&'static str, RouteMetrics>>,
+}
+
+impl Metrics {
+    pub fn record(&self, route: &'static str, latency: Duration, status: u16) {
+        let mut routes = self.routes.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        routes.entry(route).or_default().record(latency, status);
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<&'static str, RouteMetrics> {
+        self.routes.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+// ============================================================================
+// RESULT LOG
+// ============================================================================
+
+/// Verdicts the service keeps for `GET /results`
+pub const RESULT_LOG_SIZE: usize = 1024;
+
+/// A `POST /verify` that reached a verdict; malformed requests are not logged
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResult {
+    pub circuit: String,
+    /// keccak256 of the uncompressed proof, whichever encoding it was sent in
+    pub proof_hash: String,
+    pub valid: bool,
+    /// Unix seconds
+    pub at: u64,
+}
+
+/// The latest verdicts, oldest dropped first, shared by all worker threads
+#[derive(Debug)]
+pub struct ResultLog {
+    capacity: usize,
+    results: Mutex<VecDeque<VerifyResult>>,
+}
+
+impl Default for ResultLog {
+    fn default() -> Self {
+        Self::new(RESULT_LOG_SIZE)
+    }
+}
+
+impl ResultLog {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, results: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    pub fn record(&self, result: VerifyResult) {
+        let mut results = self.results.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if results.len() == self.capacity {
+            results.pop_front();
+        }
+        if self.capacity > 0 {
+            results.push_back(result);
+        }
+    }
+
+    /// Newest first
+    pub fn snapshot(&self) -> Vec<VerifyResult> {
+        let results = self.results.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        results.iter().rev().cloned().collect()
+    }
+}
+
+// ============================================================================
+// REQUEST HANDLING
+// ============================================================================
+
+/// Status code and JSON body for one request
+#[derive(Clone, Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl fmt::Display) -> Self {
+        Self { status, body: json!({ "error": message.to_string() }) }
+    }
+}
+
+/// `POST /verify` JSON body
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyRequest {
+    /// May instead be given as `?circuit=` on the URL
+    #[serde(default)]
+    circuit: Option<String>,
+    proof: ProofInput,
+    #[serde(default)]
+    public_signals: Vec<String>,
+}
+
+/// snarkjs proof object, or hex of the raw or compressed layout
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ProofInput {
+    Snarkjs(ProofJson),
+    Hex(String),
+}
+
+impl ProofInput {
+    fn decode(&self) -> Result<Groth16Proof, String> {
+        match self {
+            Self::Snarkjs(proof) => proof.to_proof().map_err(|err| err.to_string()),
+            Self::Hex(text) => {
+                let bytes = hex::decode(text.trim_start_matches("0x")).map_err(|err| format!("proof: {err}"))?;
+                match bytes.len() {
+                    PROOF_SIZE => Groth16Proof::from_uncompressed_bytes(&bytes),
+                    COMPRESSED_PROOF_SIZE => Groth16Proof::from_compressed_bytes(&bytes),
+                    len => Err(format!("proof is {len} bytes").into_bytes()),
+                }
+                .map_err(message)
+            }
+        }
+    }
+}
+
+/// Key store, metrics and result log; `handle` is everything but the socket
+#[derive(Debug)]
+pub struct Service {
+    keys: KeyStore,
+    metrics: Metrics,
+    results: ResultLog,
+    started: Instant,
+}
+
+impl Service {
+    pub fn new(keys: KeyStore) -> Self {
+        Self::with_result_log(keys, ResultLog::default())
+    }
+
+    pub fn with_result_log(keys: KeyStore, results: ResultLog) -> Self {
+        Self { keys, metrics: Metrics::default(), results, started: Instant::now() }
+    }
+
+    pub fn keys(&self) -> &KeyStore {
+        &self.keys
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn results(&self) -> &ResultLog {
+        &self.results
+    }
+
+    /// Route one request and record its latency
+    pub fn handle(&self, method: &str, url: &str, content_type: Option<&str>, body: &[u8]) -> Response {
+        let start = Instant::now();
+        let path = url.split('?').next().unwrap_or_default();
+
+        let (route, response) = match (method, path) {
+            ("GET", "/health") => ("/health", self.health()),
+            ("GET", "/circuits") => ("/circuits", self.circuits()),
+            ("POST", "/verify") => ("/verify", self.verify(query_param(url, "circuit"), content_type, body)),
+            ("GET", "/results") => ("/results", Response::ok(json!(self.results.snapshot()))),
+            (_, "/health" | "/circuits" | "/verify" | "/results") => {
+                ("other", Response::error(405, "method not allowed"))
+            }
+            _ => ("other", Response::error(404, "not found")),
+        };
+
+        self.metrics.record(route, start.elapsed(), response.status);
+        response
+    }
+
+    fn health(&self) -> Response {
+        Response::ok(json!({
+            "status": "ok",
+            "uptimeSecs": self.started.elapsed().as_secs(),
+            "circuits": self.keys.len(),
+            "metrics": self.metrics.snapshot(),
+        }))
+    }
+
+    fn circuits(&self) -> Response {
+        Response::ok(Value::Array(self.keys.iter().map(Circuit::summary).collect()))
+    }
+
+    /// Verify, and log the verdict under the proof's hash
+    fn verify(&self, circuit: Option<&str>, content_type: Option<&str>, body: &[u8]) -> Response {
+        let started = Instant::now();
+        let result = match content_type.unwrap_or_default() {
+            ct if ct.starts_with("application/octet-stream") => self.verify_packed(circuit, body),
+            _ => self.verify_json(circuit, body),
+        };
+        let (circuit, proof, valid) = match result {
+            Ok(verdict) => verdict,
+            Err(response) => return response,
+        };
+        let verify_us = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+
+        let proof_hash = hex_word(&Keccak256::digest(proof.to_uncompressed_bytes()).into());
+        self.results.record(VerifyResult {
+            circuit: circuit.id.clone(),
+            proof_hash: proof_hash.clone(),
+            valid,
+            at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs()),
+        });
+        Response::ok(json!({
+            "valid": valid,
+            "circuit": circuit.id,
+            "fingerprint": hex_word(&circuit.fingerprint),
+            "proofHash": proof_hash,
+            "verifyUs": verify_us,
+        }))
+    }
+
+    /// The body's `circuit`, or the query's; both must name the same key
+    fn verify_json(&self, circuit: Option<&str>, body: &[u8]) -> Result<(&Circuit, Groth16Proof, bool), Response> {
+        let request: VerifyRequest =
+            serde_json::from_slice(body).map_err(|err| Response::error(400, format!("request: {err}")))?;
+        let circuit = match (circuit, request.circuit.as_deref()) {
+            (Some(query), Some(named)) => {
+                let circuit = self.circuit(query)?;
+                if self.circuit(named)?.id != circuit.id {
+                    return Err(Response::error(400, format!("circuit {named} does not match query {query}")));
+                }
+                circuit
+            }
+            (Some(id), None) | (None, Some(id)) => self.circuit(id)?,
+            (None, None) => return Err(Response::error(400, "request: missing circuit")),
+        };
+        let proof = request.proof.decode().map_err(|err| Response::error(400, err))?;
+        let inputs = parse_public_signals(&request.public_signals).map_err(|err| Response::error(400, err))?;
+
+        let valid = verify_groth16(&proof, &inputs, &circuit.vk).map_err(|err| Response::error(400, message(err)))?;
+        Ok((circuit, proof, valid))
+    }
+
+    /// Same layout and input handling as the contract's `verifyPacked`,
+    /// with the key fingerprint in place of the registered circuit id, or
+    /// without it when the query names the circuit
+    fn verify_packed(&self, circuit: Option<&str>, body: &[u8]) -> Result<(&Circuit, Groth16Proof, bool), Response> {
+        let prefixed;
+        let body = match circuit {
+            Some(id) => {
+                prefixed = [&self.circuit(id)?.fingerprint[..], body].concat();
+                &prefixed[..]
+            }
+            None => body,
+        };
+        let call = PackedCall::parse(body).map_err(|err| Response::error(400, message(err)))?;
+        let circuit = self
+            .keys
+            .by_fingerprint(call.circuit_id)
+            .ok_or_else(|| Response::error(404, format!("unknown circuit {}", hex_word(call.circuit_id))))?;
+        let proof = Groth16Proof::from_uncompressed_array(call.proof).map_err(|err| Response::error(400, message(err)))?;
+
+        let commitment = call
+            .public_inputs()
+            .and_then(|inputs| public_input_commitment(&circuit.vk, inputs.into_iter()))
+            .map_err(|err| Response::error(400, message(err)))?;
+        let valid = pairing_check(&proof, &commitment, &circuit.vk);
+        Ok((circuit, proof, valid))
+    }
+
+    fn circuit(&self, id: &str) -> Result<&Circuit, Response> {
+        self.keys.get(id).ok_or_else(|| Response::error(404, format!("unknown circuit {id}")))
+    }
+}
+
+fn message(err: Vec<u8>) -> String {
+    String::from_utf8_lossy(&err).into_owned()
+}
+
+/// Value of `name` in the URL's query string, undecoded
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+}
+
+fn hex_word(word: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../core/fixtures");
+    const PROOF_JSON: &str = include_str!("../../core/fixtures/multiplier/proof.json");
+
+    fn service() -> Service {
+        Service::new(KeyStore::load_dir(Path::new(FIXTURES)).unwrap())
+    }
+
+    fn verify_json(service: &Service, body: Value) -> Response {
+        service.handle("POST", "/verify", Some("application/json"), body.to_string().as_bytes())
+    }
+
+    #[test]
+    fn test_key_directory() {
+        let service = service();
+        let circuit = service.keys().get("multiplier").unwrap();
+        assert_eq!(circuit.fingerprint, VerifyingKey::load_real().fingerprint());
+
+        let fingerprint = hex_word(&circuit.fingerprint);
+        assert_eq!(service.keys().get(&fingerprint).unwrap().id, "multiplier");
+
+        // One circuit per subdirectory, the snarkjs-generated key included
+        let response = service.handle("GET", "/circuits", None, b"");
+        let ids: Vec<_> = response.body.as_array().unwrap().iter().map(|circuit| &circuit["id"]).collect();
+        assert_eq!(ids, ["circom", "multiplier"]);
+        assert_eq!(response.body[1]["fingerprint"], fingerprint.as_str());
+        assert_eq!(response.body[1]["publicInputs"], 1);
+    }
+
+    #[test]
+    fn test_verify_json_and_packed() {
+        let service = service();
+        let proof: Value = serde_json::from_str(PROOF_JSON).unwrap();
+
+        let response = verify_json(&service, json!({ "circuit": "multiplier", "proof": proof, "publicSignals": ["15"] }));
+        assert_eq!((response.status, &response.body["valid"]), (200, &json!(true)));
+
+        let response = verify_json(&service, json!({ "circuit": "multiplier", "proof": proof, "publicSignals": ["16"] }));
+        assert_eq!((response.status, &response.body["valid"]), (200, &json!(false)));
+
+        let raw = ProofInput::Snarkjs(serde_json::from_value(proof.clone()).unwrap()).decode().unwrap();
+        let compressed = format!("0x{}", hex::encode(raw.to_compressed_bytes()));
+        let response = verify_json(&service, json!({ "circuit": "multiplier", "proof": compressed, "publicSignals": ["15"] }));
+        assert_eq!(response.body["valid"], true);
+
+        let mut packed = VerifyingKey::load_real().fingerprint().to_vec();
+        packed.extend(raw.to_uncompressed_bytes());
+        packed.extend([0u8; 31].iter().chain(&[15]));
+        let response = service.handle("POST", "/verify", Some("application/octet-stream"), &packed);
+        assert_eq!((response.status, &response.body["valid"]), (200, &json!(true)));
+    }
+
+    #[test]
+    fn test_circuit_query_and_results() {
+        let keys = KeyStore::load_dir(Path::new(FIXTURES)).unwrap();
+        let service = Service::with_result_log(keys, ResultLog::new(2));
+        let proof: Value = serde_json::from_str(PROOF_JSON).unwrap();
+        let raw = ProofInput::Snarkjs(serde_json::from_value(proof.clone()).unwrap()).decode().unwrap();
+        let proof_hash = hex_word(&Keccak256::digest(raw.to_uncompressed_bytes()).into());
+
+        let body = json!({ "proof": proof, "publicSignals": ["15"] }).to_string();
+        let response = service.handle("POST", "/verify?circuit=multiplier", Some("application/json"), body.as_bytes());
+        assert_eq!((response.status, &response.body["valid"]), (200, &json!(true)));
+        assert_eq!(response.body["proofHash"], proof_hash.as_str());
+        let body = json!({ "circuit": "circom", "proof": proof, "publicSignals": ["15"] }).to_string();
+        let response = service.handle("POST", "/verify?circuit=multiplier", Some("application/json"), body.as_bytes());
+        assert_eq!(response.status, 400);
+
+        // Raw bytes without the fingerprint, the key named by id or fingerprint
+        let mut packed = raw.to_uncompressed_bytes().to_vec();
+        packed.extend([0u8; 31].iter().chain(&[15]));
+        let url = format!("/verify?circuit={}", hex_word(&VerifyingKey::load_real().fingerprint()));
+        let response = service.handle("POST", &url, Some("application/octet-stream"), &packed);
+        assert_eq!((response.status, &response.body["circuit"]), (200, &json!("multiplier")));
+        assert_eq!(response.body["valid"], true);
+        let response = service.handle("POST", "/verify?circuit=circom", Some("application/octet-stream"), &packed);
+        assert_eq!((response.status, &response.body["valid"]), (200, &json!(false)));
+        let response = service.handle("POST", "/verify?circuit=nope", Some("application/octet-stream"), &packed);
+        assert_eq!(response.status, 404);
+
+        // Three verdicts, the mismatch and unknown circuit unlogged; the log keeps the last two
+        let results = service.handle("GET", "/results", None, b"");
+        assert_eq!(results.status, 200);
+        let logged: Vec<_> = results.body.as_array().unwrap().iter().map(|result| &result["circuit"]).collect();
+        assert_eq!(logged, ["circom", "multiplier"]);
+        assert_eq!(results.body[0]["valid"], false);
+        assert_eq!(results.body[1]["proofHash"], proof_hash.as_str());
+        assert!(results.body[1]["at"].as_u64().unwrap() > 0);
+        assert_eq!(service.handle("POST", "/results", None, b"").status, 405);
+    }
+
+    #[test]
+    fn test_errors_and_metrics() {
+        let service = service();
+        let proof: Value = serde_json::from_str(PROOF_JSON).unwrap();
+
+        let unknown = verify_json(&service, json!({ "circuit": "nope", "proof": proof, "publicSignals": ["15"] }));
+        assert_eq!(unknown.status, 404);
+        let count = verify_json(&service, json!({ "circuit": "multiplier", "proof": proof, "publicSignals": [] }));
+        assert_eq!(count.status, 400);
+        assert_eq!(service.handle("POST", "/verify", Some("application/json"), b"{").status, 400);
+        assert_eq!(service.handle("GET", "/verify", None, b"").status, 405);
+        assert_eq!(service.handle("GET", "/nope", None, b"").status, 404);
+
+        let metrics = service.metrics().snapshot();
+        assert_eq!(metrics["/verify"].requests, 3);
+        assert_eq!(metrics["/verify"].errors, 3);
+        assert_eq!(metrics["/verify"].buckets.iter().sum::<u64>(), 3);
+        assert_eq!(metrics["other"].requests, 2);
+
+        let health = service.handle("GET", "/health", None, b"");
+        assert_eq!(health.body["metrics"]["/verify"]["requests"], 3);
+    }
+}
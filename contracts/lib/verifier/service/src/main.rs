@@ -0,0 +1,89 @@
+//! `arbshield-service --keys ./keys --listen 127.0.0.1:8080`
+
+use std::{io::Read, path::PathBuf, process::ExitCode, sync::Arc, thread};
+
+use arbshield_verifier_service::{KeyStore, Service};
+use clap::Parser;
+use tiny_http::{Header, Request, Server};
+
+/// Largest request body accepted: a snarkjs proof with generous signals
+const MAX_BODY: u64 = 1 << 20;
+
+#[derive(Parser)]
+#[command(name = "arbshield-service", version, about = "ArbShield proof verification over HTTP")]
+struct Args {
+    /// Directory of `<id>.json` or `<id>/verification_key.json` files
+    #[arg(long)]
+    keys: PathBuf,
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+    #[arg(long, default_value_t = 4)]
+    threads: usize,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let keys = match KeyStore::load_dir(&args.keys) {
+        Ok(keys) => keys,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for circuit in keys.iter() {
+        eprintln!("loaded {} (0x{})", circuit.id, hex::encode(circuit.fingerprint));
+    }
+
+    let server = match Server::http(&args.listen) {
+        Ok(server) => Arc::new(server),
+        Err(err) => {
+            eprintln!("error: {}: {err}", args.listen);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("listening on http://{}", args.listen);
+
+    let service = Arc::new(Service::new(keys));
+    let workers: Vec<_> = (0..args.threads.max(1))
+        .map(|_| {
+            let (server, service) = (server.clone(), service.clone());
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(&service, request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    ExitCode::SUCCESS
+}
+
+fn respond(service: &Service, mut request: Request) {
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Content-Type"))
+        .map(|header| header.value.as_str().to_owned());
+
+    let mut body = Vec::new();
+    let response = match request.as_reader().take(MAX_BODY + 1).read_to_end(&mut body) {
+        Ok(_) if body.len() as u64 > MAX_BODY => None,
+        Ok(_) => Some(service.handle(
+            request.method().as_str(),
+            request.url(),
+            content_type.as_deref(),
+            &body,
+        )),
+        Err(_) => None,
+    };
+
+    let (status, body) = match response {
+        Some(response) => (response.status, response.body.to_string()),
+        None => (413, r#"{"error":"request body too large or unreadable"}"#.to_string()),
+    };
+    let json = Header::from_bytes("Content-Type", "application/json").expect("static header");
+    let _ = request.respond(tiny_http::Response::from_string(body).with_status_code(status).with_header(json));
+}
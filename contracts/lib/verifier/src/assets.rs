@@ -0,0 +1,537 @@
+//! ASSET COMPLIANCE - WHO MAY HOLD EACH TOKEN
+//!
+//! A separate Stylus program (`--features assets`): per-token holding and
+//! jurisdiction rules, set by each token's issuer, for transfer hooks to
+//! call. A token's policy is evaluated by `CompliancePolicies`, proven
+//! jurisdictions are read from `ComplianceRecords` and delegated holders
+//! are resolved by `ComplianceDelegations`.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, FixedBytes, B256, U256},
+    call::{self, Call},
+    prelude::*,
+    storage::{StorageAddress, StorageArray, StorageBool, StorageFixedBytes, StorageMap, StorageString},
+    console, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::{delegations::MAX_DELEGATION_DEPTH, jurisdiction, msg, read_jurisdictions, write_jurisdictions};
+
+/// keccak256("AssetIssuerSet(address,address)")
+const ASSET_ISSUER_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"AssetIssuerSet(address,address)")
+    .finalize();
+
+/// keccak256("AssetPolicySet(address,bytes32)")
+const ASSET_POLICY_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"AssetPolicySet(address,bytes32)")
+    .finalize();
+
+/// keccak256("AssetJurisdictionsSet(address,string,uint16[],uint16[],bool)")
+const ASSET_JURISDICTIONS_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"AssetJurisdictionsSet(address,string,uint16[],uint16[],bool)")
+    .finalize();
+
+sol! {
+    /// The `ComplianceRecords` entrypoint this contract calls
+    interface IRecordReader {
+        function jurisdictionsPermitted(address user, string attributeType, bytes allowed, bytes denied, bool matchAny) external view returns (bool permitted);
+    }
+
+    /// The `ComplianceDelegations` entrypoint this contract calls
+    interface IAttributeHolders {
+        function getPrincipals(address delegate, string attribute, address asset) external view returns (address[] principals);
+    }
+
+    /// The `CompliancePolicies` entrypoints this contract calls
+    interface IPolicyEvaluator {
+        function policyExists(bytes32 policyId) external view returns (bool exists);
+        function evaluatePolicyFor(address user, bytes32 policyId, address asset) external view returns (bool holds);
+    }
+}
+
+/// (attribute, allowed codes, denied codes, match any), as
+/// `get_asset_jurisdictions` returns them
+pub type AssetJurisdictions = (String, Vec<u16>, Vec<u16>, bool);
+
+#[storage]
+#[cfg_attr(feature = "assets", entrypoint)]
+pub struct AssetCompliance {
+    owner: StorageAddress,
+    initialized: StorageBool,
+    /// `ComplianceRecords` proven jurisdictions are read from
+    records: StorageAddress,
+    /// `ComplianceDelegations` delegated holders are resolved by
+    delegations: StorageAddress,
+    /// `CompliancePolicies` asset policies are evaluated by
+    policies: StorageAddress,
+    /// Holding rules by token address
+    assets: StorageMap<Address, AssetRules>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "assets"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for AssetCompliance {}
+
+/// Who may hold a token, set by its issuer
+#[storage]
+pub struct AssetRules {
+    /// May change the rules; exempt from them
+    issuer: StorageAddress,
+    /// Policy every other holder must satisfy; zero until set
+    policy: StorageFixedBytes<32>,
+    /// Attribute whose records carry holders' jurisdictions; empty for no
+    /// jurisdiction rules
+    jurisdiction_attribute: StorageString,
+    /// `JurisdictionSet` words; the empty set allows every jurisdiction
+    allowed_jurisdictions: StorageArray<StorageFixedBytes<32>, 4>,
+    /// `JurisdictionSet` words
+    denied_jurisdictions: StorageArray<StorageFixedBytes<32>, 4>,
+    /// Whether a proven set need only share a country with the allowed set
+    jurisdictions_match_any: StorageBool,
+}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl AssetCompliance {
+    /// Set the owner and the compliance contracts rules are checked
+    /// against, once
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        records: Address,
+        delegations: Address,
+        policies: Address,
+    ) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if [records, delegations, policies].iter().any(|contract| !contract.has_code()) {
+            return Err(b"Compliance contract has no code".to_vec());
+        }
+
+        self.owner.set(owner);
+        self.records.set(records);
+        self.delegations.set(delegations);
+        self.policies.set(policies);
+        self.initialized.set(true);
+
+        console!("✓ AssetCompliance initialized");
+        Ok(())
+    }
+
+    /// (records, delegations, policies) this contract calls
+    pub fn get_contracts(&self) -> Result<(Address, Address, Address), Vec<u8>> {
+        Ok((self.records.get(), self.delegations.get(), self.policies.get()))
+    }
+
+    /// Hand an asset's rules to `issuer`: the owner for a new asset, after
+    /// that the current issuer
+    pub fn set_asset_issuer(&mut self, asset: Address, issuer: Address) -> Result<(), Vec<u8>> {
+        let current = self.assets.getter(asset).issuer.get();
+        match current.is_zero() {
+            true => self.only_owner()?,
+            false if msg::sender() != current => return Err(b"Caller is not the asset issuer".to_vec()),
+            false => {}
+        }
+        if issuer.is_zero() {
+            return Err(b"Invalid issuer".to_vec());
+        }
+        self.assets.setter(asset).issuer.set(issuer);
+
+        let _ = evm::raw_log(&[B256::from(ASSET_ISSUER_SET), asset.into_word(), issuer.into_word()], &[]);
+        console!("✓ Asset issuer set");
+        Ok(())
+    }
+
+    /// Require holders of `asset` to satisfy a policy (asset issuer only)
+    ///
+    /// A single attribute is a one-term policy; see
+    /// `CompliancePolicies.create_policy`
+    pub fn set_asset_policy(&mut self, asset: Address, policy_id: FixedBytes<32>) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.assets.getter(asset).issuer.get() {
+            return Err(b"Caller is not the asset issuer".to_vec());
+        }
+        let calldata = IPolicyEvaluator::policyExistsCall { policyId: policy_id }.abi_encode();
+        if !self.query(self.policies.get(), &calldata) {
+            return Err(b"Unknown policy".to_vec());
+        }
+        self.assets.setter(asset).policy.set(policy_id);
+
+        let _ = evm::raw_log(&[B256::from(ASSET_POLICY_SET), asset.into_word(), policy_id], &[]);
+        console!("✓ Asset policy set");
+        Ok(())
+    }
+
+    /// Restrict holders of `asset` by the jurisdictions their `attribute`
+    /// record places them in (asset issuer only)
+    ///
+    /// @param attribute: attribute whose circuits expose a jurisdiction set;
+    ///                   empty to drop the rules
+    /// @param allowed: ISO 3166-1 numeric codes; empty allows all
+    /// @param denied: ISO 3166-1 numeric codes
+    /// @param match_any: pass holders who may be in some allowed country
+    ///
+    /// A holder passes if every country their proof leaves open is allowed
+    /// and none is denied (`JurisdictionSet::permitted_by`). With `match_any`
+    /// one allowed country is enough, but none may still be denied
+    /// (`JurisdictionSet::permitted_by_any`)
+    pub fn set_asset_jurisdictions(
+        &mut self,
+        asset: Address,
+        attribute: String,
+        allowed: Vec<u16>,
+        denied: Vec<u16>,
+        match_any: bool,
+    ) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.assets.getter(asset).issuer.get() {
+            return Err(b"Caller is not the asset issuer".to_vec());
+        }
+        if attribute.is_empty() && !(allowed.is_empty() && denied.is_empty() && !match_any) {
+            return Err(b"Empty attribute".to_vec());
+        }
+        let allowed_set = jurisdiction::JurisdictionSet::from_codes(&allowed)?;
+        let denied_set = jurisdiction::JurisdictionSet::from_codes(&denied)?;
+
+        let mut rules = self.assets.setter(asset);
+        rules.jurisdiction_attribute.set_str(&attribute);
+        write_jurisdictions(&mut rules.allowed_jurisdictions, &allowed_set);
+        write_jurisdictions(&mut rules.denied_jurisdictions, &denied_set);
+        rules.jurisdictions_match_any.set(match_any);
+
+        let data = (attribute, allowed, denied, match_any).abi_encode_params();
+        let _ = evm::raw_log(&[B256::from(ASSET_JURISDICTIONS_SET), asset.into_word()], &data);
+        console!("✓ Asset jurisdictions set");
+        Ok(())
+    }
+
+    /// (attribute, allowed codes, denied codes, match any) of an asset's
+    /// jurisdiction rules. The SDK returns it encoded as one tuple
+    pub fn get_asset_jurisdictions(&self, asset: Address) -> Result<AssetJurisdictions, Vec<u8>> {
+        let rules = self.assets.getter(asset);
+        let allowed = read_jurisdictions(&rules.allowed_jurisdictions)?;
+        let denied = read_jurisdictions(&rules.denied_jurisdictions)?;
+        let attribute = rules.jurisdiction_attribute.get_string();
+        Ok((attribute, allowed.codes(), denied.codes(), rules.jurisdictions_match_any.get()))
+    }
+
+    /// (issuer, policy id) of an asset; zero if unset
+    pub fn get_asset_rules(&self, asset: Address) -> Result<(Address, FixedBytes<32>), Vec<u8>> {
+        let rules = self.assets.getter(asset);
+        Ok((rules.issuer.get(), rules.policy.get()))
+    }
+
+    /// Whether `user` may hold `asset`: its issuer may, anyone else must
+    /// satisfy its policy and jurisdiction rules. False for assets without
+    /// a policy.
+    pub fn can_hold(&self, asset: Address, user: Address) -> Result<bool, Vec<u8>> {
+        let rules = self.assets.getter(asset);
+        let policy_id = rules.policy.get();
+        if policy_id.is_zero() {
+            return Ok(false);
+        }
+        if user == rules.issuer.get() {
+            return Ok(true);
+        }
+        Ok(self.policy_holds(user, policy_id, asset) && self.jurisdiction_permitted(asset, user, MAX_DELEGATION_DEPTH))
+    }
+
+    /// Whether `asset` may move from `from` to `to`, for a token's transfer hook
+    ///
+    /// Mints (from zero) skip the sender check and burns (to zero) the
+    /// recipient check; every other party must pass `can_hold`. `amount` is
+    /// part of the interface so tokens need not change when rules come to
+    /// depend on it; no current rule does.
+    pub fn can_transfer(&self, asset: Address, from: Address, to: Address, _amount: U256) -> Result<bool, Vec<u8>> {
+        let sender_ok = from.is_zero() || self.can_hold(asset, from)?;
+        let recipient_ok = to.is_zero() || self.can_hold(asset, to)?;
+        Ok(sender_ok && recipient_ok)
+    }
+}
+
+// ============================================================================
+// RULE EVALUATION
+// ============================================================================
+
+impl AssetCompliance {
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err(b"Caller is not the owner".to_vec());
+        }
+        Ok(())
+    }
+
+    /// `CompliancePolicies.evaluate_policy_for`
+    fn policy_holds(&self, user: Address, policy_id: FixedBytes<32>, asset: Address) -> bool {
+        let calldata = IPolicyEvaluator::evaluatePolicyForCall { user, policyId: policy_id, asset }.abi_encode();
+        self.query(self.policies.get(), &calldata)
+    }
+
+    /// Whether `user`'s current record of the asset's jurisdiction attribute,
+    /// or one of a principal at most `depth` delegations away, places them
+    /// only where its rules allow; true if it has no rules
+    ///
+    /// Implications are not followed: only the record itself carries a set
+    fn jurisdiction_permitted(&self, asset: Address, user: Address, depth: usize) -> bool {
+        let attribute = self.assets.getter(asset).jurisdiction_attribute.get_string();
+        if attribute.is_empty() || self.proven_jurisdiction_permitted(asset, user, attribute.clone()) {
+            return true;
+        }
+        let principals = if depth == 0 { Vec::new() } else { self.principals(user, attribute, asset) };
+        principals.into_iter().any(|principal| self.jurisdiction_permitted(asset, principal, depth - 1))
+    }
+
+    /// `ComplianceDelegations.get_principals`; none if the call fails
+    fn principals(&self, delegate: Address, attribute: String, asset: Address) -> Vec<Address> {
+        let calldata = IAttributeHolders::getPrincipalsCall { delegate, attribute, asset }.abi_encode();
+        call::static_call(Call::new(), self.delegations.get(), &calldata)
+            .ok()
+            .and_then(|output| IAttributeHolders::getPrincipalsCall::abi_decode_returns(&output, true).ok())
+            .map(|output| output.principals)
+            .unwrap_or_default()
+    }
+
+    /// Whether `user`'s own record of `attribute` carries a jurisdiction set
+    /// the asset's rules permit, per `ComplianceRecords.jurisdictions_permitted`
+    fn proven_jurisdiction_permitted(&self, asset: Address, user: Address, attribute: String) -> bool {
+        let rules = self.assets.getter(asset);
+        let (Ok(allowed), Ok(denied)) =
+            (read_jurisdictions(&rules.allowed_jurisdictions), read_jurisdictions(&rules.denied_jurisdictions))
+        else {
+            return false;
+        };
+        let calldata = IRecordReader::jurisdictionsPermittedCall {
+            user,
+            attributeType: attribute,
+            allowed: allowed.to_bytes().into(),
+            denied: denied.to_bytes().into(),
+            matchAny: rules.jurisdictions_match_any.get(),
+        }
+        .abi_encode();
+        self.query(self.records.get(), &calldata)
+    }
+
+    /// Answer of a view of another compliance contract that returns one
+    /// bool; false if the call fails
+    fn query(&self, contract: Address, calldata: &[u8]) -> bool {
+        call::static_call(Call::new(), contract, calldata)
+            .ok()
+            .and_then(|output| bool::abi_decode(&output, true).ok())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        bind_squares, packed_inputs, placed, prove_residency, record, residency_keys, residency_satisfied, selector,
+        statement, system, DEFAULT_SENDER, ASSETS, GENESIS_TIMESTAMP,
+    };
+    use ark_bn254::Fr;
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const ASSET: Address = Address::repeat_byte(0x5e);
+    const ISSUER: Address = Address::repeat_byte(0x15);
+
+    #[test]
+    fn test_asset_rules() {
+        const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+        let host = system();
+        let pk = bind_squares(&host, KYC, "kyc", 3600, 2, 6);
+        let policy_id = host
+            .policies(|policies| policies.create_policy(vec!["kyc".into()], vec![0], vec![0, 0].into()))
+            .unwrap();
+        host.take_logs();
+
+        // The owner names an asset's issuer, who alone edits its rules from then on
+        let set_issuer = |issuer| host.assets(|assets| assets.set_asset_issuer(ASSET, issuer));
+        let set_policy = |policy_id| host.assets(|assets| assets.set_asset_policy(ASSET, policy_id));
+        assert_eq!(set_policy(policy_id), Err(b"Caller is not the asset issuer".to_vec()));
+        host.set_sender(ISSUER);
+        assert_eq!(set_issuer(ISSUER), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(set_issuer(Address::ZERO), Err(b"Invalid issuer".to_vec()));
+        assert_eq!(set_issuer(ISSUER), Ok(()));
+        assert_eq!(set_issuer(OWNER), Err(b"Caller is not the asset issuer".to_vec()));
+        assert_eq!(set_policy(policy_id), Err(b"Caller is not the asset issuer".to_vec()));
+        host.set_sender(ISSUER);
+        assert_eq!(set_policy(KYC), Err(b"Unknown policy".to_vec()));
+        assert_eq!(set_policy(policy_id), Ok(()));
+        assert_eq!(host.assets(|assets| assets.get_asset_rules(ASSET)), Ok((ISSUER, policy_id)));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].topics, [B256::from(ASSET_ISSUER_SET), ASSET.into_word(), ISSUER.into_word()]);
+        assert_eq!(logs[1].topics, [B256::from(ASSET_POLICY_SET), ASSET.into_word(), policy_id]);
+
+        // What a token's transfer hook sends
+        let can_transfer = |from: Address, to: Address| {
+            let args = (ASSET, from, to, U256::from(100)).abi_encode_params();
+            let calldata = [&selector("canTransfer(address,address,address,uint256)")[..], &args].concat();
+            host.call_on(ASSETS, &calldata).map(|output| output == true.abi_encode())
+        };
+        let can_hold = |user| host.assets(|assets| assets.can_hold(ASSET, user)).unwrap();
+        assert!(can_hold(ISSUER));
+        assert!(!can_hold(ALICE));
+        assert_eq!(can_transfer(ISSUER, ALICE), Ok(false));
+
+        record(&host, KYC, &pk, &statement(ALICE, GENESIS_TIMESTAMP)).unwrap();
+        assert!(can_hold(ALICE));
+        assert_eq!(can_transfer(Address::ZERO, ALICE), Ok(true));
+        assert_eq!(can_transfer(ISSUER, ALICE), Ok(true));
+        assert_eq!(can_transfer(ALICE, BOB), Ok(false));
+        assert_eq!(can_transfer(BOB, ALICE), Ok(false));
+        assert_eq!(can_transfer(ALICE, Address::ZERO), Ok(true));
+
+        // Assets without a policy admit no one
+        assert_eq!(host.assets(|assets| assets.can_hold(BOB, ISSUER)), Ok(false));
+        assert_eq!(host.assets(|assets| assets.can_transfer(BOB, Address::ZERO, ALICE, U256::ZERO)), Ok(false));
+    }
+
+    #[test]
+    fn test_jurisdictions() {
+        const T0: u64 = GENESIS_TIMESTAMP;
+        const RESIDENCY: FixedBytes<32> = FixedBytes([0x3a; 32]);
+        const GERMANY: u16 = 276;
+        const NORTH_KOREA: u16 = 408;
+        const UNITED_STATES: u16 = 840;
+        let host = system();
+        // Subject, issue time, then the four words of the set they are proven to be in
+        let pk = bind_squares(&host, RESIDENCY, "residency", 3600, 2 + jurisdiction::WORDS, 11);
+        host.gateway(|gateway| gateway.set_jurisdiction_input(RESIDENCY, 2)).unwrap();
+
+        host.assets(|assets| assets.set_asset_issuer(ASSET, ISSUER)).unwrap();
+        host.set_sender(ISSUER);
+        let policy_id = host
+            .policies(|policies| policies.create_policy(vec!["residency".into()], vec![0], vec![0, 0].into()))
+            .unwrap();
+        host.assets(|assets| assets.set_asset_policy(ASSET, policy_id)).unwrap();
+        let set_rules_matching = |attribute: &str, allowed: &[u16], denied: &[u16], match_any| {
+            host.assets(|assets| {
+                assets.set_asset_jurisdictions(ASSET, attribute.into(), allowed.to_vec(), denied.to_vec(), match_any)
+            })
+        };
+        let set_rules =
+            |attribute: &str, allowed: &[u16], denied: &[u16]| set_rules_matching(attribute, allowed, denied, false);
+        assert_eq!(set_rules("", &[], &[NORTH_KOREA]), Err(b"Empty attribute".to_vec()));
+        assert_eq!(set_rules_matching("", &[], &[], true), Err(b"Empty attribute".to_vec()));
+        assert_eq!(set_rules("residency", &[1000], &[]), Err(b"Jurisdiction code out of range".to_vec()));
+        host.take_logs();
+        assert_eq!(set_rules("residency", &[], &[NORTH_KOREA]), Ok(()));
+        let logs = host.take_logs();
+        assert_eq!(logs[0].topics, [B256::from(ASSET_JURISDICTIONS_SET), ASSET.into_word()]);
+        assert_eq!(
+            host.assets(|assets| assets.get_asset_jurisdictions(ASSET)),
+            Ok(("residency".into(), vec![], vec![NORTH_KOREA], false))
+        );
+        host.set_sender(ALICE);
+        assert_eq!(set_rules("residency", &[], &[]), Err(b"Caller is not the asset issuer".to_vec()));
+
+        // Holding follows the rules as they change, from the stored set
+        let residency = |inputs: Vec<Fr>| record(&host, RESIDENCY, &pk, &inputs).unwrap();
+        let can_hold = |user| host.assets(|assets| assets.can_hold(ASSET, user)).unwrap();
+        residency(placed(ALICE, T0, &[GERMANY, UNITED_STATES]));
+        assert!(can_hold(ALICE));
+        host.set_sender(ISSUER);
+        assert_eq!(set_rules("residency", &[GERMANY], &[NORTH_KOREA]), Ok(()));
+        assert!(!can_hold(ALICE));
+        host.advance(1);
+        residency(placed(ALICE, T0 + 1, &[GERMANY]));
+        assert!(can_hold(ALICE));
+        residency(placed(BOB, T0 + 1, &[NORTH_KOREA]));
+        assert!(!can_hold(BOB));
+
+        // Matching any allowed country passes a set that merely overlaps, unless it touches a denied one
+        const CAROL: Address = Address::repeat_byte(0xca);
+        const DAVE: Address = Address::repeat_byte(0xcb);
+        residency(placed(CAROL, T0 + 1, &[GERMANY, UNITED_STATES]));
+        residency(placed(DAVE, T0 + 1, &[GERMANY, NORTH_KOREA]));
+        assert!(!can_hold(CAROL));
+        assert_eq!(set_rules_matching("residency", &[GERMANY], &[NORTH_KOREA], true), Ok(()));
+        assert_eq!(
+            host.assets(|assets| assets.get_asset_jurisdictions(ASSET)),
+            Ok(("residency".into(), vec![GERMANY], vec![NORTH_KOREA], true))
+        );
+        assert!(can_hold(CAROL));
+        assert!(can_hold(ALICE));
+        assert!(!can_hold(BOB));
+        assert!(!can_hold(DAVE));
+        assert_eq!(set_rules("residency", &[GERMANY], &[NORTH_KOREA]), Ok(()));
+        assert!(!can_hold(CAROL));
+
+        // A delegate is placed where its principal is
+        const SAFE: Address = Address::repeat_byte(0x5a);
+        for (principal, safe) in [(ALICE, SAFE), (BOB, Address::repeat_byte(0x5b))] {
+            host.set_sender(principal);
+            host.delegations(|delegations| delegations.delegate_attribute(safe, "residency".into(), ASSET, T0 + 60))
+                .unwrap();
+            host.set_sender(safe);
+            host.delegations(|delegations| delegations.accept_delegation(principal, "residency".into(), ASSET))
+                .unwrap();
+        }
+        assert!(can_hold(SAFE));
+        assert!(!can_hold(Address::repeat_byte(0x5b)));
+        host.set_sender(ISSUER);
+        assert_eq!(set_rules("", &[], &[]), Ok(()));
+        assert!(can_hold(BOB));
+    }
+
+    #[test]
+    fn test_residency_circuit() {
+        const T0: u64 = GENESIS_TIMESTAMP;
+        const RESIDENCY: FixedBytes<32> = FixedBytes([0x3b; 32]);
+        const FRANCE: u16 = 250;
+        const GERMANY: u16 = 276;
+        const NORTH_KOREA: u16 = 408;
+        let host = system();
+        // circuits/jurisdiction.circom: the set's words come first, then subject and issue time
+        let (pk, vk) = residency_keys(49);
+        host.transact(|verifier| verifier.register_circuit(RESIDENCY, vk.to_bytes().into(), 0)).unwrap();
+        host.gateway(|gateway| gateway.bind_attribute(RESIDENCY, "residency".into(), 3600, 4, 5)).unwrap();
+        host.gateway(|gateway| gateway.set_jurisdiction_input(RESIDENCY, 0)).unwrap();
+
+        host.assets(|assets| assets.set_asset_issuer(ASSET, ISSUER)).unwrap();
+        host.set_sender(ISSUER);
+        let policy_id = host
+            .policies(|policies| policies.create_policy(vec!["residency".into()], vec![0], vec![0, 0].into()))
+            .unwrap();
+        host.assets(|assets| assets.set_asset_policy(ASSET, policy_id)).unwrap();
+        host.assets(|assets| {
+            assets.set_asset_jurisdictions(ASSET, "residency".into(), vec![GERMANY], vec![NORTH_KOREA], true)
+        })
+        .unwrap();
+
+        // Only a set holding the prover's own country has a witness
+        assert!(residency_satisfied(GERMANY, &[FRANCE, GERMANY]));
+        assert!(!residency_satisfied(NORTH_KOREA, &[FRANCE, GERMANY]));
+        assert!(!residency_satisfied(GERMANY, &[]));
+
+        let submit = |proof: Vec<u8>, inputs: &[Fr]| {
+            host.gateway(|gateway| gateway.verify_attribute(RESIDENCY, proof.into(), packed_inputs(inputs)))
+        };
+        let can_hold = |user| host.assets(|assets| assets.can_hold(ASSET, user)).unwrap();
+        let (proof, inputs) = prove_residency(&pk, ALICE, T0, GERMANY, &[FRANCE, GERMANY]);
+        assert_eq!(submit(proof.clone(), &inputs), Ok((ALICE, T0 + 3600)));
+        let words = host.records(|records| records.get_proven_jurisdictions(ALICE, "residency".into())).unwrap();
+        let words: Vec<[u8; 32]> = words.iter().map(|word| word.0).collect();
+        assert_eq!(jurisdiction::JurisdictionSet::from_words(&words).unwrap().codes(), [FRANCE, GERMANY]);
+        assert!(can_hold(ALICE));
+
+        // Claiming a smaller set than the one proven breaks the proof
+        let mut narrowed = inputs.clone();
+        narrowed[..jurisdiction::WORDS].copy_from_slice(&placed(ALICE, T0, &[GERMANY])[2..]);
+        assert_eq!(submit(proof, &narrowed), Err(b"Invalid proof".to_vec()));
+
+        // An honest set for a denied country keeps its holder out
+        let (proof, inputs) = prove_residency(&pk, BOB, T0, NORTH_KOREA, &[GERMANY, NORTH_KOREA]);
+        assert_eq!(submit(proof, &inputs), Ok((BOB, T0 + 3600)));
+        assert!(!can_hold(BOB));
+    }
+}
@@ -0,0 +1,547 @@
+//! COMPLIANCE BRIDGE - WHERE THE SOLIDITY CONTRACTS MEET THE STYLUS ONES
+//!
+//! A separate Stylus program (`--features bridge`): proof submissions that
+//! carry a check or a write beyond the record. These are the two ways
+//! existing Solidity contracts submit proofs, the write-through to
+//! `ComplianceRegistry.sol` and the `ZKVerifier.sol` wrapper's
+//! `verifyProof`, and `verify_for_asset`. All submit through
+//! `ComplianceGateway`, so records are written exactly as for proofs sent
+//! there directly.
+
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256},
+    call::{self, Call},
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageFixedBytes, StorageMap},
+    console, crypto, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::{block, jurisdiction, msg, PROOF_SIZE};
+
+/// keccak256("AttributeCircuitSet(string,bytes32)")
+const ATTRIBUTE_CIRCUIT_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"AttributeCircuitSet(string,bytes32)")
+    .finalize();
+
+/// keccak256("ComplianceRegistrySet(address)")
+const COMPLIANCE_REGISTRY_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"ComplianceRegistrySet(address)")
+    .finalize();
+
+sol! {
+    /// `ComplianceRegistry.sol`; this contract must hold its `VERIFIER_ROLE`
+    interface IComplianceRegistry {
+        function recordCompliance(address user, string attributeType, bytes32 proofHash, uint256 validityPeriod) external;
+    }
+
+    /// The `ComplianceGateway` entrypoints this contract calls
+    interface IProofGateway {
+        function verifyAttribute(bytes32 circuitId, bytes proof, bytes inputs) external returns (address subject, uint64 expiresAt);
+        function verifyClaimedAttribute(bytes32 circuitId, string attribute, bytes proof, bytes inputs) external returns (address subject, uint64 expiresAt);
+        function getAttributeBinding(bytes32 circuitId) external view;
+    }
+
+    /// The `ComplianceRecords` entrypoints this contract calls
+    interface IRecordReader {
+        function getRecord(address user, string attributeType) external view returns (bool verified, uint256 verifiedAt, uint256 expiresAt, bytes32 proofHash);
+        function jurisdictionsPermitted(address user, string attributeType, bytes allowed, bytes denied, bool matchAny) external view returns (bool permitted);
+    }
+
+    /// The `AssetCompliance` entrypoint this contract calls
+    interface IAssetRules {
+        function getAssetJurisdictions(address asset) external view;
+    }
+
+    /// `verify_and_record` was called before `set_compliance_registry`
+    error RegistryNotSet();
+    /// `recordCompliance` reverted (e.g. `AccessControlUnauthorizedAccount`
+    /// if this contract lacks `VERIFIER_ROLE`); `reason` is its revert data
+    error RegistryCallFailed(address registry, bytes reason);
+}
+
+/// Failures of the call into `ComplianceRegistry`, ABI-encoded as Solidity errors
+#[derive(SolidityError)]
+pub enum RegistryError {
+    NotSet(RegistryNotSet),
+    CallFailed(RegistryCallFailed),
+}
+
+#[storage]
+#[cfg_attr(feature = "bridge", entrypoint)]
+pub struct ComplianceBridge {
+    owner: StorageAddress,
+    initialized: StorageBool,
+    /// `ComplianceGateway` every proof is submitted through
+    gateway: StorageAddress,
+    /// `ComplianceRecords` `verify_for_asset` checks jurisdictions with
+    records: StorageAddress,
+    /// `AssetCompliance` `verify_for_asset` reads rules from
+    assets: StorageAddress,
+    /// `ComplianceRegistry.sol` that `verify_and_record` writes through to
+    compliance_registry: StorageAddress,
+    /// Circuit `verify_proof` checks each attribute type against
+    attribute_circuits: StorageMap<String, StorageFixedBytes<32>>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "bridge"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for ComplianceBridge {}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl ComplianceBridge {
+    /// Set the owner, the `ComplianceGateway` proofs go through and the
+    /// `ComplianceRecords` and `AssetCompliance` asset checks read, once
+    pub fn initialize(
+        &mut self,
+        owner: Address,
+        gateway: Address,
+        records: Address,
+        assets: Address,
+    ) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if [gateway, records, assets].iter().any(|contract| !contract.has_code()) {
+            return Err(b"Compliance contract has no code".to_vec());
+        }
+
+        self.owner.set(owner);
+        self.gateway.set(gateway);
+        self.records.set(records);
+        self.assets.set(assets);
+        self.initialized.set(true);
+
+        console!("✓ ComplianceBridge initialized");
+        Ok(())
+    }
+
+    /// (gateway, records, assets) this contract calls
+    pub fn get_contracts(&self) -> Result<(Address, Address, Address), Vec<u8>> {
+        Ok((self.gateway.get(), self.records.get(), self.assets.get()))
+    }
+
+    /// Point `verify_and_record` at the deployed `ComplianceRegistry.sol` (owner only)
+    pub fn set_compliance_registry(&mut self, registry: Address) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        // A call to an account without code would succeed and record nothing
+        if !registry.has_code() {
+            return Err(b"Registry has no code".to_vec());
+        }
+        self.compliance_registry.set(registry);
+
+        let _ = evm::raw_log(&[B256::from(COMPLIANCE_REGISTRY_SET), registry.into_word()], &[]);
+        console!("✓ Compliance registry set");
+        Ok(())
+    }
+
+    pub fn get_compliance_registry(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.compliance_registry.get())
+    }
+
+    /// `ComplianceGateway.verify_claimed_attribute`, then
+    /// `ComplianceRegistry.recordCompliance` for the same subject, proof hash
+    /// and expiry
+    ///
+    /// @param attribute: must be the attribute the circuit is bound to
+    ///
+    /// The period written to the registry is the one the circuit was bound
+    /// with, counted from the statement's issue time, so a relayer can
+    /// neither shorten nor extend the subject's record.
+    /// Reverts with `RegistryNotSet` or `RegistryCallFailed` if the registry
+    /// cannot be written, undoing the gateway's record too.
+    ///
+    /// Returns (subject, expires at)
+    pub fn verify_and_record(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        attribute: String,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<(Address, u64), Vec<u8>> {
+        console!("=== VERIFY AND RECORD ===");
+
+        let registry = self.compliance_registry.get();
+        if registry.is_zero() {
+            return Err(RegistryError::NotSet(RegistryNotSet {}).into());
+        }
+        let proof_hash = crypto::keccak(&proof_bytes);
+        let calldata = IProofGateway::verifyClaimedAttributeCall {
+            circuitId: circuit_id,
+            attribute: attribute.clone(),
+            proof: proof_bytes.0.into(),
+            inputs: inputs.0.into(),
+        }
+        .abi_encode();
+        // Reverts with the gateway's reason, e.g. for an invalid proof
+        let gateway = self.gateway.get();
+        let output = call::call(Call::new_in(self), gateway, &calldata)?;
+        let recorded = IProofGateway::verifyClaimedAttributeCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected gateway output".to_vec())?;
+        let (subject, expires_at) = (recorded.subject, recorded.expiresAt);
+
+        let calldata = IComplianceRegistry::recordComplianceCall {
+            user: subject,
+            attributeType: attribute,
+            proofHash: proof_hash,
+            validityPeriod: U256::from(expires_at - block::timestamp()),
+        }
+        .abi_encode();
+        call::call(Call::new_in(self), registry, &calldata)
+            .map_err(|err| {
+                let reason = match err {
+                    call::Error::Revert(data) => data,
+                    call::Error::AbiDecodingFailed(_) => Vec::new(),
+                };
+                RegistryError::CallFailed(RegistryCallFailed { registry, reason: reason.into() })
+            })?;
+        console!("✓ Recorded in the compliance registry");
+        Ok((subject, expires_at))
+    }
+
+    /// Verify a proof of an asset's jurisdiction attribute, as
+    /// `ComplianceGateway.verify_claimed_attribute`, and revert unless the
+    /// proven jurisdictions pass the asset's rules in `AssetCompliance`
+    ///
+    /// Only the subject's own record is judged, not a principal's, since it
+    /// is the one the proof just wrote.
+    ///
+    /// Returns the subject
+    pub fn verify_for_asset(
+        &mut self,
+        asset: Address,
+        circuit_id: FixedBytes<32>,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<Address, Vec<u8>> {
+        let calldata = IAssetRules::getAssetJurisdictionsCall { asset }.abi_encode();
+        let output = call::static_call(Call::new(), self.assets.get(), &calldata)?;
+        // The SDK encodes the rules as one tuple, not as separate returns
+        let (attribute, allowed, denied, match_any) = <(String, Vec<u16>, Vec<u16>, bool)>::abi_decode(&output, true)
+            .map_err(|_| b"Unexpected assets output".to_vec())?;
+        if attribute.is_empty() {
+            return Err(b"Asset has no jurisdiction rules".to_vec());
+        }
+        let calldata = IProofGateway::verifyClaimedAttributeCall {
+            circuitId: circuit_id,
+            attribute: attribute.clone(),
+            proof: proof_bytes.0.into(),
+            inputs: inputs.0.into(),
+        }
+        .abi_encode();
+        // Reverts with the gateway's reason, e.g. for an invalid proof
+        let gateway = self.gateway.get();
+        let output = call::call(Call::new_in(self), gateway, &calldata)?;
+        let subject = IProofGateway::verifyClaimedAttributeCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected gateway output".to_vec())?
+            .subject;
+
+        let calldata = IRecordReader::jurisdictionsPermittedCall {
+            user: subject,
+            attributeType: attribute,
+            allowed: jurisdiction::JurisdictionSet::from_codes(&allowed)?.to_bytes().into(),
+            denied: jurisdiction::JurisdictionSet::from_codes(&denied)?.to_bytes().into(),
+            matchAny: match_any,
+        }
+        .abi_encode();
+        let output = call::static_call(Call::new(), self.records.get(), &calldata)?;
+        let permitted = IRecordReader::jurisdictionsPermittedCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected records output".to_vec())?
+            .permitted;
+        if !permitted {
+            return Err(b"Jurisdiction not permitted".to_vec());
+        }
+        Ok(subject)
+    }
+
+    /// Choose the circuit `verify_proof` uses for an attribute type (owner only)
+    ///
+    /// The circuit must already be bound to that attribute. Pointing the
+    /// attribute at a new circuit rotates keys without touching callers.
+    pub fn set_attribute_circuit(&mut self, attribute: String, circuit_id: FixedBytes<32>) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        let calldata = IProofGateway::getAttributeBindingCall { circuitId: circuit_id }.abi_encode();
+        let output = call::static_call(Call::new(), self.gateway.get(), &calldata)?;
+        // The SDK encodes the binding as one tuple, not as separate returns
+        let (bound, _, _, _) = <(String, u64, u32, u32)>::abi_decode(&output, true)
+            .map_err(|_| b"Unexpected gateway output".to_vec())?;
+        if bound != attribute {
+            return Err(b"Circuit is not bound to this attribute".to_vec());
+        }
+        self.attribute_circuits.setter(attribute.clone()).set(circuit_id);
+
+        let _ = evm::raw_log(&[B256::from(ATTRIBUTE_CIRCUIT_SET), circuit_id], &(attribute,).abi_encode_params());
+        console!("✓ Attribute circuit set");
+        Ok(())
+    }
+
+    /// Circuit `verify_proof` uses for an attribute type; zero if none
+    pub fn get_attribute_circuit(&self, attribute: String) -> Result<FixedBytes<32>, Vec<u8>> {
+        Ok(self.attribute_circuits.get(attribute))
+    }
+
+    /// `verifyProof(bytes,string)`, as the Solidity `ZKVerifier` wrapper calls it
+    ///
+    /// @param proof: proof (256, uncompressed) || packed 32-byte public inputs
+    /// @param attribute_type: selects the circuit set with `set_attribute_circuit`
+    ///
+    /// Records the attribute for the subject named in the inputs, exactly as
+    /// `ComplianceGateway.verify_attribute`. Invalid proofs revert rather
+    /// than return false.
+    pub fn verify_proof(&mut self, proof: Bytes, attribute_type: String) -> Result<bool, Vec<u8>> {
+        console!("=== WRAPPER VERIFICATION ===");
+
+        let circuit_id = self.attribute_circuits.get(attribute_type);
+        if circuit_id.is_zero() {
+            return Err(b"Unknown attribute type".to_vec());
+        }
+        let (proof_bytes, inputs) = proof
+            .split_at_checked(PROOF_SIZE)
+            .ok_or_else(|| b"Expected 256 bytes for uncompressed proof".to_vec())?;
+        let calldata = IProofGateway::verifyAttributeCall {
+            circuitId: circuit_id,
+            proof: proof_bytes.to_vec().into(),
+            inputs: inputs.to_vec().into(),
+        }
+        .abi_encode();
+        // Reverts with the gateway's reason, e.g. for an invalid proof
+        let gateway = self.gateway.get();
+        call::call(Call::new_in(self), gateway, &calldata)?;
+        Ok(true)
+    }
+
+    /// `ComplianceRecords.get_record`, for the `ZKVerifier.sol` wrapper,
+    /// which reads back what `verify_proof` recorded from the address it
+    /// sent the proof to
+    pub fn get_record(
+        &self,
+        user: Address,
+        attribute_type: String,
+    ) -> Result<(bool, U256, U256, FixedBytes<32>), Vec<u8>> {
+        let calldata = IRecordReader::getRecordCall { user, attributeType: attribute_type }.abi_encode();
+        let output = call::static_call(Call::new(), self.records.get(), &calldata)?;
+        let record = IRecordReader::getRecordCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected records output".to_vec())?;
+        Ok((record.verified, record.verifiedAt, record.expiresAt, record.proofHash))
+    }
+}
+
+// ============================================================================
+// ACCESS CONTROL
+// ============================================================================
+
+impl ComplianceBridge {
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err(b"Caller is not the owner".to_vec());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        bind_squares, packed_inputs, placed, prove_squares, record, selector, statement, system, BRIDGE,
+        DEFAULT_SENDER, GENESIS_TIMESTAMP,
+    };
+    use ark_bn254::Fr;
+    use alloy_sol_types::SolError;
+    use ark_ff::PrimeField;
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+
+    #[test]
+    fn test_verify_and_record() {
+        const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+        const VALIDITY: u64 = 30 * 24 * 60 * 60;
+        const REGISTRY: Address = Address::repeat_byte(0x7e);
+        const REJECTING: Address = Address::repeat_byte(0x7f);
+        /// `AccessControlUnauthorizedAccount(address,bytes32)` selector and some payload
+        const UNAUTHORIZED: [u8; 8] = [0xe2, 0x51, 0x7d, 0x3f, 1, 2, 3, 4];
+        let host = system();
+        host.deploy(REGISTRY, |_| Ok(Vec::new()));
+        host.deploy(REJECTING, |_| Err(UNAUTHORIZED.to_vec()));
+        let pk = bind_squares(&host, KYC, "kyc", VALIDITY, 2, 3);
+
+        // Each call proves a fresh statement issued `elapsed` seconds after genesis
+        let proof = |elapsed| prove_squares(&pk, &statement(ALICE, GENESIS_TIMESTAMP + elapsed));
+        let record = |attribute: &str, elapsed| {
+            let inputs = packed_inputs(&statement(ALICE, GENESIS_TIMESTAMP + elapsed));
+            host.bridge(|bridge| bridge.verify_and_record(KYC, attribute.into(), proof(elapsed).into(), inputs))
+        };
+        let expires_at = || host.records(|records| records.get_record(ALICE, "kyc".into())).unwrap().2;
+        assert_eq!(record("kyc", 0), Err(RegistryNotSet {}.abi_encode()));
+
+        // Only the owner points the contract at a registry, which must have code
+        let set_registry = |registry| host.bridge(|bridge| bridge.set_compliance_registry(registry));
+        host.set_sender(ALICE);
+        assert_eq!(set_registry(REGISTRY), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(set_registry(BOB), Err(b"Registry has no code".to_vec()));
+        assert_eq!(set_registry(REGISTRY), Ok(()));
+        assert_eq!(host.bridge(|bridge| bridge.get_compliance_registry()), Ok(REGISTRY));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(COMPLIANCE_REGISTRY_SET), REGISTRY.into_word()]);
+
+        // The registry gets the recorded subject, proof hash and period
+        let record_compliance = |elapsed, period: u64| {
+            let args = (ALICE, "kyc".to_string(), crypto::keccak(proof(elapsed)), U256::from(period)).abi_encode_params();
+            (REGISTRY, [&selector("recordCompliance(address,string,bytes32,uint256)")[..], &args].concat())
+        };
+        host.set_sender(BOB);
+        assert_eq!(record("accredited_investor", 0), Err(b"Attribute does not match circuit".to_vec()));
+        assert_eq!(record("kyc", 0), Ok((ALICE, GENESIS_TIMESTAMP + VALIDITY)));
+        assert_eq!(host.take_calls(), [record_compliance(0, VALIDITY)]);
+        assert!(host.records(|records| records.is_compliant(ALICE, "kyc".into())).unwrap());
+        assert_eq!(record("kyc", 0), Err(b"Proof is not newer than the record".to_vec()));
+
+        // A third party relaying Alice's renewal gets her the full period:
+        // there is no call that takes a shorter one
+        host.advance(60);
+        let inputs = statement(ALICE, GENESIS_TIMESTAMP + 60);
+        let args = (KYC, "kyc".to_string(), Bytes::from(proof(60)), packed_inputs(&inputs), 1u64).abi_encode_params();
+        let shorten = [&selector("verifyAndRecord(bytes32,string,bytes,bytes,uint64)")[..], &args].concat();
+        assert!(host.call_on(BRIDGE, &shorten).is_err());
+        assert!(host.take_calls().is_empty());
+        assert_eq!(expires_at(), U256::from(GENESIS_TIMESTAMP + VALIDITY));
+        assert_eq!(record("kyc", 60), Ok((ALICE, GENESIS_TIMESTAMP + 60 + VALIDITY)));
+        host.advance(60);
+        assert_eq!(record("kyc", 120), Ok((ALICE, GENESIS_TIMESTAMP + 120 + VALIDITY)));
+        assert_eq!(host.take_calls(), [record_compliance(60, VALIDITY), record_compliance(120, VALIDITY)]);
+
+        // A registry revert undoes the whole call, in every program it reached
+        host.set_sender(OWNER);
+        set_registry(REJECTING).unwrap();
+        host.take_logs();
+        let count = host.contract().get_verified_count().unwrap();
+        let failed = RegistryCallFailed { registry: REJECTING, reason: UNAUTHORIZED.to_vec().into() };
+        host.advance(60);
+        assert_eq!(record("kyc", 180), Err(failed.abi_encode()));
+        assert_eq!(host.contract().get_verified_count(), Ok(count));
+        assert_eq!(expires_at(), U256::from(GENESIS_TIMESTAMP + 120 + VALIDITY));
+        assert!(host.take_logs().is_empty());
+    }
+
+    #[test]
+    fn test_wrapper_verify_proof() {
+        const V1: FixedBytes<32> = FixedBytes([0x01; 32]);
+        const V2: FixedBytes<32> = FixedBytes([0x02; 32]);
+        const UNBOUND: FixedBytes<32> = FixedBytes([0x03; 32]);
+        let host = system();
+        let pk1 = bind_squares(&host, V1, "kyc", 3600, 2, 4);
+        let pk2 = bind_squares(&host, V2, "kyc", 3600, 2, 5);
+
+        // What `ZKVerifier.verifyProof` forwards: the proof with its inputs packed after it
+        let wrapped = |pk, issued_at| {
+            let inputs = statement(ALICE, issued_at);
+            Bytes::from([prove_squares(pk, &inputs), packed_inputs(&inputs).to_vec()].concat())
+        };
+        let call = |proof: Bytes, attribute: &str| {
+            let args = (proof, attribute.to_string()).abi_encode_params();
+            host.call_on(BRIDGE, &[&selector("verifyProof(bytes,string)")[..], &args].concat())
+        };
+        let source = || host.records(|records| records.get_record_source(ALICE, "kyc".into())).unwrap().0;
+        assert_eq!(call(wrapped(&pk1, GENESIS_TIMESTAMP), "kyc"), Err(b"Unknown attribute type".to_vec()));
+
+        // Only the owner maps an attribute, and only to a circuit bound to it
+        let set_circuit = |id| host.bridge(|bridge| bridge.set_attribute_circuit("kyc".into(), id));
+        host.set_sender(ALICE);
+        assert_eq!(set_circuit(V1), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(set_circuit(UNBOUND), Err(b"Circuit is not bound to this attribute".to_vec()));
+        assert_eq!(set_circuit(V1), Ok(()));
+        assert_eq!(host.bridge(|bridge| bridge.get_attribute_circuit("kyc".into())), Ok(V1));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(ATTRIBUTE_CIRCUIT_SET), V1]);
+        assert_eq!(logs[0].data, ("kyc".to_string(),).abi_encode_params());
+
+        host.set_sender(BOB);
+        assert_eq!(call(wrapped(&pk1, GENESIS_TIMESTAMP), "kyc"), Ok(true.abi_encode()));
+        assert_eq!(source(), V1);
+        // The wrapper reads the record back from the bridge
+        let record = host.records(|records| records.get_record(ALICE, "kyc".into()));
+        assert_eq!(host.bridge(|bridge| bridge.get_record(ALICE, "kyc".into())), record);
+        assert_eq!(record.unwrap().3, crypto::keccak(&wrapped(&pk1, GENESIS_TIMESTAMP)[..PROOF_SIZE]));
+        host.advance(1);
+        let now = GENESIS_TIMESTAMP + 1;
+        assert_eq!(call(wrapped(&pk2, now), "kyc"), Err(b"Invalid proof".to_vec()));
+        assert_eq!(call(Bytes::from(vec![1; 255]), "kyc"), Err(b"Expected 256 bytes for uncompressed proof".to_vec()));
+        assert_eq!(call(wrapped(&pk1, now)[..PROOF_SIZE].to_vec().into(), "kyc"), Err(b"Missing subject input".to_vec()));
+
+        // Repointing the attribute rotates the key callers are checked against
+        host.set_sender(OWNER);
+        set_circuit(V2).unwrap();
+        host.set_sender(BOB);
+        assert_eq!(call(wrapped(&pk1, now), "kyc"), Err(b"Invalid proof".to_vec()));
+        assert_eq!(call(wrapped(&pk2, now), "kyc"), Ok(true.abi_encode()));
+        assert_eq!(source(), V2);
+    }
+
+    #[test]
+    fn test_verify_for_asset() {
+        const T0: u64 = GENESIS_TIMESTAMP;
+        const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+        const RESIDENCY: FixedBytes<32> = FixedBytes([0x3a; 32]);
+        const ASSET: Address = Address::repeat_byte(0x5e);
+        const ISSUER: Address = Address::repeat_byte(0x15);
+        const GERMANY: u16 = 276;
+        const NORTH_KOREA: u16 = 408;
+        const UNITED_STATES: u16 = 840;
+        let host = system();
+        let pk = bind_squares(&host, RESIDENCY, "residency", 3600, 2 + jurisdiction::WORDS, 11);
+        let kyc = bind_squares(&host, KYC, "kyc", 3600, 2 + jurisdiction::WORDS, 12);
+        host.gateway(|gateway| gateway.set_jurisdiction_input(RESIDENCY, 2)).unwrap();
+        host.assets(|assets| assets.set_asset_issuer(ASSET, ISSUER)).unwrap();
+
+        let verify_for_asset = |circuit_id, pk, inputs: &[Fr]| {
+            host.bridge(|bridge| {
+                bridge.verify_for_asset(ASSET, circuit_id, prove_squares(pk, inputs).into(), packed_inputs(inputs))
+            })
+        };
+        assert_eq!(
+            verify_for_asset(RESIDENCY, &pk, &placed(ALICE, T0, &[GERMANY])),
+            Err(b"Asset has no jurisdiction rules".to_vec())
+        );
+        host.set_sender(ISSUER);
+        host.assets(|assets| {
+            assets.set_asset_jurisdictions(ASSET, "residency".into(), vec![], vec![NORTH_KOREA], false)
+        })
+        .unwrap();
+
+        // Verification against the asset fails unless the proven set clears its rules
+        assert_eq!(verify_for_asset(RESIDENCY, &pk, &placed(ALICE, T0, &[GERMANY, UNITED_STATES])), Ok(ALICE));
+        assert_eq!(
+            verify_for_asset(RESIDENCY, &pk, &placed(BOB, T0, &[GERMANY, NORTH_KOREA])),
+            Err(b"Jurisdiction not permitted".to_vec())
+        );
+        assert!(!host.records(|records| records.is_compliant(BOB, "residency".into())).unwrap());
+        assert_eq!(
+            verify_for_asset(KYC, &kyc, &placed(BOB, T0, &[GERMANY])),
+            Err(b"Attribute does not match circuit".to_vec())
+        );
+        // Code 500 + 250, which belongs in the next word
+        let mut out_of_range = placed(BOB, T0, &[]);
+        out_of_range[4] = Fr::from_be_bytes_mod_order(&[&[0x04][..], &[0; 31]].concat());
+        assert_eq!(
+            verify_for_asset(RESIDENCY, &pk, &out_of_range),
+            Err(b"Jurisdiction word out of range".to_vec())
+        );
+        let recorded = record(&host, RESIDENCY, &pk, &placed(BOB, T0, &[NORTH_KOREA]));
+        assert_eq!(recorded.map(|(subject, _)| subject), Ok(BOB));
+    }
+}
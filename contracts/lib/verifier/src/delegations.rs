@@ -0,0 +1,664 @@
+//! COMPLIANCE DELEGATIONS - WHO MAY USE WHOSE ATTRIBUTES
+//!
+//! A separate Stylus program (`--features delegations`): a principal lets a
+//! delegate, such as their Safe or a custodial sub-account, use one of their
+//! attributes, and the owner declares which attributes imply which. Checks
+//! read the records in `ComplianceRecords`, through a static call, so a
+//! delegation lapses with the principal's record. Policies and asset rules
+//! read attributes through this contract.
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U64},
+    call::{self, Call},
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageString, StorageU64, StorageVec},
+    console, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::{block, msg};
+
+/// keccak256("DelegationOffered(address,address,address,string,uint64)")
+const DELEGATION_OFFERED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"DelegationOffered(address,address,address,string,uint64)")
+    .finalize();
+
+/// keccak256("DelegationAccepted(address,address,address,string)")
+const DELEGATION_ACCEPTED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"DelegationAccepted(address,address,address,string)")
+    .finalize();
+
+/// keccak256("DelegationRevoked(address,address,address,string)")
+const DELEGATION_REVOKED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"DelegationRevoked(address,address,address,string)")
+    .finalize();
+
+/// keccak256("ImplicationAdded(string,string)")
+const IMPLICATION_ADDED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"ImplicationAdded(string,string)")
+    .finalize();
+
+/// keccak256("ImplicationRemoved(string,string)")
+const IMPLICATION_REMOVED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"ImplicationRemoved(string,string)")
+    .finalize();
+
+sol! {
+    /// The `ComplianceRecords` entrypoint this contract calls
+    interface IRecordReader {
+        function isCompliantWithin(address user, string attributeType, uint64 maxAge) external view returns (bool compliant);
+    }
+}
+
+/// Most attributes that may directly imply one attribute
+pub const MAX_IMPLIED_BY: usize = 4;
+/// Longest chain of implications, in steps, `holds_own` may follow
+pub const MAX_IMPLICATION_DEPTH: usize = 3;
+/// Most delegations one address may hold per attribute
+pub const MAX_DELEGATIONS: usize = 4;
+/// Most delegation hops a compliance check follows to reach a record
+pub const MAX_DELEGATION_DEPTH: usize = 2;
+
+/// (principals, assets, expiries, accepted), as `get_delegations` returns them
+pub type Delegations = (Vec<Address>, Vec<Address>, Vec<u64>, Vec<bool>);
+
+#[storage]
+#[cfg_attr(feature = "delegations", entrypoint)]
+pub struct ComplianceDelegations {
+    owner: StorageAddress,
+    initialized: StorageBool,
+    /// `ComplianceRecords` every check reads
+    records: StorageAddress,
+    /// Delegations by delegate and attribute, see `delegate_attribute`
+    delegations: StorageMap<Address, StorageMap<String, StorageVec<Delegation>>>,
+    /// Attributes that directly imply each attribute, see `add_implication`
+    implied_by: StorageMap<String, StorageVec<StorageString>>,
+    /// The same edges the other way round: attributes each attribute implies
+    implies: StorageMap<String, StorageVec<StorageString>>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "delegations"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for ComplianceDelegations {}
+
+/// A principal's offer to let a delegate use one of their attributes
+#[storage]
+pub struct Delegation {
+    principal: StorageAddress,
+    /// Asset the delegation is limited to; zero for any
+    asset: StorageAddress,
+    expires_at: StorageU64,
+    /// Set by the delegate in `accept_delegation`
+    accepted: StorageBool,
+}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl ComplianceDelegations {
+    /// Set the owner, who manages implications, and the
+    /// `ComplianceRecords` checks read, once
+    ///
+    /// Principals and delegates manage their own delegations
+    pub fn initialize(&mut self, owner: Address, records: Address) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if !records.has_code() {
+            return Err(b"Records have no code".to_vec());
+        }
+
+        self.owner.set(owner);
+        self.records.set(records);
+        self.initialized.set(true);
+
+        console!("✓ ComplianceDelegations initialized");
+        Ok(())
+    }
+
+    pub fn get_records(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.records.get())
+    }
+
+    /// Make holding `stronger` count as holding `weaker` (owner only)
+    ///
+    /// For a tier hierarchy, add one implication per step:
+    /// qualified_purchaser ⇒ accredited_investor ⇒ kyc. Cycles are rejected,
+    /// as are chains longer than `MAX_IMPLICATION_DEPTH` steps and more than
+    /// `MAX_IMPLIED_BY` direct implications of one attribute, which bounds
+    /// the records a compliance check reads.
+    pub fn add_implication(&mut self, stronger: String, weaker: String) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        if stronger.is_empty() || weaker.is_empty() {
+            return Err(b"Empty attribute".to_vec());
+        }
+        if stronger == weaker {
+            return Err(b"Implication cycle".to_vec());
+        }
+        let direct = self.get_implied_by(weaker.clone())?;
+        if direct.contains(&stronger) {
+            return Err(b"Implication already exists".to_vec());
+        }
+        if direct.len() >= MAX_IMPLIED_BY {
+            return Err(b"Too many implications".to_vec());
+        }
+        let above = self.implication_height(&stronger, &weaker)?;
+        if above + 1 + self.implication_depth(&weaker) > MAX_IMPLICATION_DEPTH {
+            return Err(b"Implication chain too deep".to_vec());
+        }
+
+        self.implied_by.setter(weaker.clone()).grow().set_str(&stronger);
+        self.implies.setter(stronger.clone()).grow().set_str(&weaker);
+
+        let _ = evm::raw_log(&[B256::from(IMPLICATION_ADDED)], &(stronger, weaker).abi_encode_params());
+        console!("✓ Implication added");
+        Ok(())
+    }
+
+    /// Undo `add_implication` (owner only)
+    pub fn remove_implication(&mut self, stronger: String, weaker: String) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        if !remove_string(&mut self.implied_by.setter(weaker.clone()), &stronger) {
+            return Err(b"Unknown implication".to_vec());
+        }
+        remove_string(&mut self.implies.setter(stronger.clone()), &weaker);
+
+        let _ = evm::raw_log(&[B256::from(IMPLICATION_REMOVED)], &(stronger, weaker).abi_encode_params());
+        console!("✓ Implication removed");
+        Ok(())
+    }
+
+    /// Attributes that directly imply `attribute`
+    pub fn get_implied_by(&self, attribute: String) -> Result<Vec<String>, Vec<u8>> {
+        Ok(read_strings(&self.implied_by.getter(attribute)))
+    }
+
+    /// Let `delegate` use the caller's `attribute`, e.g. a principal's
+    /// Safe, custodial sub-account or ERC-4337 account
+    ///
+    /// @param asset: the one asset the delegation counts for; zero for any,
+    ///               including plain `is_compliant` checks
+    /// @param expires_at: timestamp the delegation ends at
+    ///
+    /// The caller must hold the attribute now, and keeps having to: checks
+    /// through the delegation read the caller's records. Nothing counts
+    /// until the delegate calls `accept_delegation`. Offering again with new
+    /// terms replaces the old ones and needs a new acceptance.
+    pub fn delegate_attribute(
+        &mut self,
+        delegate: Address,
+        attribute: String,
+        asset: Address,
+        expires_at: u64,
+    ) -> Result<(), Vec<u8>> {
+        let principal = msg::sender();
+        if delegate.is_zero() || delegate == principal {
+            return Err(b"Invalid delegate".to_vec());
+        }
+        if attribute.is_empty() {
+            return Err(b"Empty attribute".to_vec());
+        }
+        if expires_at <= block::timestamp() {
+            return Err(b"Expiry in the past".to_vec());
+        }
+        if !self.holds(principal, attribute.clone(), 0, asset, MAX_DELEGATION_DEPTH) {
+            return Err(b"Principal is not compliant".to_vec());
+        }
+        let existing = self.find_delegation(delegate, &attribute, principal, asset);
+        let mut by_attribute = self.delegations.setter(delegate);
+        let mut delegations = by_attribute.setter(attribute.clone());
+        if existing.is_none() && delegations.len() >= MAX_DELEGATIONS {
+            return Err(b"Too many delegations".to_vec());
+        }
+        let mut delegation = match existing.and_then(|index| delegations.setter(index)) {
+            Some(delegation) => delegation,
+            None => delegations.grow(),
+        };
+        delegation.principal.set(principal);
+        delegation.asset.set(asset);
+        delegation.expires_at.set(U64::from(expires_at));
+        delegation.accepted.set(false);
+
+        let topics = [B256::from(DELEGATION_OFFERED), principal.into_word(), delegate.into_word(), asset.into_word()];
+        let _ = evm::raw_log(&topics, &(attribute, expires_at).abi_encode_params());
+        console!("✓ Delegation offered");
+        Ok(())
+    }
+
+    /// Accept a delegation offered to the caller
+    pub fn accept_delegation(&mut self, principal: Address, attribute: String, asset: Address) -> Result<(), Vec<u8>> {
+        let delegate = msg::sender();
+        let index = self.find_delegation(delegate, &attribute, principal, asset);
+        let mut by_attribute = self.delegations.setter(delegate);
+        let mut delegations = by_attribute.setter(attribute.clone());
+        let mut delegation = index
+            .and_then(|index| delegations.setter(index))
+            .ok_or_else(|| b"Unknown delegation".to_vec())?;
+        if delegation.expires_at.get().to::<u64>() <= block::timestamp() {
+            return Err(b"Delegation expired".to_vec());
+        }
+        delegation.accepted.set(true);
+
+        let topics = [B256::from(DELEGATION_ACCEPTED), principal.into_word(), delegate.into_word(), asset.into_word()];
+        let _ = evm::raw_log(&topics, &(attribute,).abi_encode_params());
+        console!("✓ Delegation accepted");
+        Ok(())
+    }
+
+    /// End a delegation; either party may
+    pub fn revoke_delegation(
+        &mut self,
+        principal: Address,
+        delegate: Address,
+        attribute: String,
+        asset: Address,
+    ) -> Result<(), Vec<u8>> {
+        if msg::sender() != principal && msg::sender() != delegate {
+            return Err(b"Caller is not a party to the delegation".to_vec());
+        }
+        let index = self
+            .find_delegation(delegate, &attribute, principal, asset)
+            .ok_or_else(|| b"Unknown delegation".to_vec())?;
+        let mut by_attribute = self.delegations.setter(delegate);
+        remove_delegation(&mut by_attribute.setter(attribute.clone()), index);
+
+        let topics = [B256::from(DELEGATION_REVOKED), principal.into_word(), delegate.into_word(), asset.into_word()];
+        let _ = evm::raw_log(&topics, &(attribute,).abi_encode_params());
+        console!("✓ Delegation revoked");
+        Ok(())
+    }
+
+    /// (principals, assets, expiries, accepted) of the delegations `delegate`
+    /// holds for `attribute`, offered or accepted. The SDK returns it encoded
+    /// as one tuple
+    pub fn get_delegations(&self, delegate: Address, attribute: String) -> Result<Delegations, Vec<u8>> {
+        let by_attribute = self.delegations.getter(delegate);
+        let delegations = by_attribute.getter(attribute);
+        let (mut principals, mut assets, mut expiries, mut accepted) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for delegation in (0..delegations.len()).filter_map(|index| delegations.getter(index)) {
+            principals.push(delegation.principal.get());
+            assets.push(delegation.asset.get());
+            expiries.push(delegation.expires_at.get().to::<u64>());
+            accepted.push(delegation.accepted.get());
+        }
+        Ok((principals, assets, expiries, accepted))
+    }
+
+    /// Principals whose `attribute` `delegate` has accepted, unexpired, for
+    /// `asset` or for any asset
+    ///
+    /// Asset jurisdiction rules follow these to the principal's own record
+    pub fn get_principals(
+        &self,
+        delegate: Address,
+        attribute: String,
+        asset: Address,
+    ) -> Result<Vec<Address>, Vec<u8>> {
+        Ok(self.principals(delegate, &attribute, asset))
+    }
+
+    /// Whether `user` holds an unexpired record for `attribute_type`, or for
+    /// an attribute that implies it, themselves or through delegations for
+    /// any asset
+    ///
+    /// Same ABI as `ComplianceRegistry.isCompliant`, so a token can point at
+    /// this contract instead
+    pub fn is_compliant(&self, user: Address, attribute_type: String) -> Result<bool, Vec<u8>> {
+        Ok(self.holds(user, attribute_type, 0, Address::ZERO, MAX_DELEGATION_DEPTH))
+    }
+
+    /// Whether `user` holds `attribute`, or an attribute that implies it, as
+    /// `ComplianceRecords.is_compliant_within` reads records, themselves or
+    /// through delegations in scope for `asset`
+    ///
+    /// This is how policy terms are read
+    pub fn holds_attribute(
+        &self,
+        user: Address,
+        attribute: String,
+        max_age: u64,
+        asset: Address,
+    ) -> Result<bool, Vec<u8>> {
+        Ok(self.holds(user, attribute, max_age, asset, MAX_DELEGATION_DEPTH))
+    }
+}
+
+// ============================================================================
+// DELEGATION AND IMPLICATION GRAPHS
+// ============================================================================
+
+impl ComplianceDelegations {
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err(b"Caller is not the owner".to_vec());
+        }
+        Ok(())
+    }
+
+    /// Whether `user` holds `attribute` themselves or through accepted
+    /// delegations in scope for `asset`, at most `depth` hops away
+    fn holds(&self, user: Address, attribute: String, max_age: u64, asset: Address, depth: usize) -> bool {
+        if self.holds_own(user, attribute.clone(), max_age) {
+            return true;
+        }
+        let principals = if depth == 0 { Vec::new() } else { self.principals(user, &attribute, asset) };
+        principals.into_iter().any(|principal| self.holds(principal, attribute.clone(), max_age, asset, depth - 1))
+    }
+
+    /// Whether `user` has a current record for `attribute` or for an
+    /// attribute implying it
+    fn holds_own(&self, user: Address, attribute: String, max_age: u64) -> bool {
+        if self.holds_record(user, attribute.clone(), max_age) {
+            return true;
+        }
+        // `add_implication` keeps the graph acyclic and shallow, so this ends
+        let stronger = read_strings(&self.implied_by.getter(attribute));
+        stronger.into_iter().any(|stronger| self.holds_own(user, stronger, max_age))
+    }
+
+    /// `ComplianceRecords.is_compliant_within`; false if the call fails
+    fn holds_record(&self, user: Address, attribute: String, max_age: u64) -> bool {
+        let calldata = IRecordReader::isCompliantWithinCall { user, attributeType: attribute, maxAge: max_age }
+            .abi_encode();
+        call::static_call(Call::new(), self.records.get(), &calldata)
+            .ok()
+            .and_then(|output| IRecordReader::isCompliantWithinCall::abi_decode_returns(&output, true).ok())
+            .is_some_and(|output| output.compliant)
+    }
+
+    fn principals(&self, delegate: Address, attribute: &str, asset: Address) -> Vec<Address> {
+        let by_attribute = self.delegations.getter(delegate);
+        let delegations = by_attribute.getter(String::from(attribute));
+        let now = block::timestamp();
+        (0..delegations.len())
+            .filter_map(|index| delegations.getter(index))
+            .filter(|delegation| delegation.accepted.get() && now < delegation.expires_at.get().to::<u64>())
+            .filter(|delegation| delegation.asset.get().is_zero() || delegation.asset.get() == asset)
+            .map(|delegation| delegation.principal.get())
+            .collect()
+    }
+
+    /// Index of `principal`'s delegation of `attribute` for `asset` to `delegate`
+    fn find_delegation(&self, delegate: Address, attribute: &str, principal: Address, asset: Address) -> Option<usize> {
+        let by_attribute = self.delegations.getter(delegate);
+        let delegations = by_attribute.getter(String::from(attribute));
+        (0..delegations.len()).find(|index| {
+            delegations
+                .getter(*index)
+                .is_some_and(|delegation| delegation.principal.get() == principal && delegation.asset.get() == asset)
+        })
+    }
+
+    /// Longest chain of implications ending at `attribute`; an error if
+    /// `target` is on one, as `target ⇒ attribute` would then close a cycle
+    fn implication_height(&self, attribute: &str, target: &str) -> Result<usize, Vec<u8>> {
+        let mut height = 0;
+        for stronger in read_strings(&self.implied_by.getter(String::from(attribute))) {
+            if stronger == target {
+                return Err(b"Implication cycle".to_vec());
+            }
+            height = height.max(1 + self.implication_height(&stronger, target)?);
+        }
+        Ok(height)
+    }
+
+    /// Longest chain of implications starting at `attribute`
+    fn implication_depth(&self, attribute: &str) -> usize {
+        let weaker = read_strings(&self.implies.getter(String::from(attribute)));
+        weaker.iter().map(|weaker| 1 + self.implication_depth(weaker)).max().unwrap_or(0)
+    }
+}
+
+/// Every string in a storage vector, in order
+fn read_strings(strings: &StorageVec<StorageString>) -> Vec<String> {
+    (0..strings.len()).filter_map(|index| strings.getter(index)).map(|string| string.get_string()).collect()
+}
+
+/// Swap-remove `value` from `strings`; false if it is not there
+fn remove_string(strings: &mut StorageVec<StorageString>, value: &str) -> bool {
+    let Some(index) = read_strings(strings).iter().position(|string| string == value) else {
+        return false;
+    };
+    let last = strings.len() - 1;
+    if index != last {
+        let moved = strings.getter(last).map(|string| string.get_string()).unwrap_or_default();
+        if let Some(mut slot) = strings.setter(index) {
+            slot.set_str(moved);
+        }
+    }
+    if let Some(mut removed) = strings.shrink() {
+        removed.erase();
+    }
+    true
+}
+
+/// Swap-remove the delegation at `index`
+fn remove_delegation(delegations: &mut StorageVec<Delegation>, index: usize) {
+    let last = delegations.len() - 1;
+    if index != last {
+        let moved = delegations.getter(last).map(|delegation| {
+            let expires_at = delegation.expires_at.get();
+            (delegation.principal.get(), delegation.asset.get(), expires_at, delegation.accepted.get())
+        });
+        if let (Some((principal, asset, expires_at, accepted)), Some(mut slot)) = (moved, delegations.setter(index)) {
+            slot.principal.set(principal);
+            slot.asset.set(asset);
+            slot.expires_at.set(expires_at);
+            slot.accepted.set(accepted);
+        }
+    }
+    if let Some(mut removed) = delegations.shrink() {
+        removed.principal.set(Address::ZERO);
+        removed.asset.set(Address::ZERO);
+        removed.expires_at.set(U64::ZERO);
+        removed.accepted.set(false);
+    }
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{bind_squares, record, statement, system, DEFAULT_SENDER, GENESIS_TIMESTAMP};
+    use stylus_sdk::alloy_primitives::FixedBytes;
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+
+    #[test]
+    fn test_implications() {
+        const QUALIFIED: FixedBytes<32> = FixedBytes([0x9b; 32]);
+        let host = system();
+        let pk = bind_squares(&host, QUALIFIED, "qualified_purchaser", 3600, 2, 7);
+        record(&host, QUALIFIED, &pk, &statement(ALICE, GENESIS_TIMESTAMP)).unwrap();
+        host.take_logs();
+
+        let add = |stronger: &str, weaker: &str| {
+            host.delegations(|delegations| delegations.add_implication(stronger.into(), weaker.into()))
+        };
+        let remove = |stronger: &str, weaker: &str| {
+            host.delegations(|delegations| delegations.remove_implication(stronger.into(), weaker.into()))
+        };
+        let compliant = |user, attribute: &str| {
+            host.delegations(|delegations| delegations.is_compliant(user, attribute.into())).unwrap()
+        };
+        assert!(!compliant(ALICE, "accredited_investor"));
+
+        host.set_sender(ALICE);
+        assert_eq!(add("qualified_purchaser", "accredited_investor"), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(add("qualified_purchaser", "accredited_investor"), Ok(()));
+        assert_eq!(add("accredited_investor", "kyc"), Ok(()));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].topics, [B256::from(IMPLICATION_ADDED)]);
+        assert_eq!(
+            <(String, String)>::abi_decode_params(&logs[1].data, true).unwrap(),
+            ("accredited_investor".into(), "kyc".into())
+        );
+
+        // Holding the top tier passes every tier below it, and only for its holder;
+        // the records themselves only answer for the proven attribute
+        assert!(compliant(ALICE, "accredited_investor"));
+        assert!(compliant(ALICE, "kyc"));
+        assert!(!compliant(BOB, "kyc"));
+        assert!(!compliant(ALICE, "us_person"));
+        assert!(!host.records(|records| records.is_compliant(ALICE, "kyc".into())).unwrap());
+
+        assert_eq!(add("", "kyc"), Err(b"Empty attribute".to_vec()));
+        assert_eq!(add("kyc", "kyc"), Err(b"Implication cycle".to_vec()));
+        assert_eq!(add("kyc", "qualified_purchaser"), Err(b"Implication cycle".to_vec()));
+        assert_eq!(add("accredited_investor", "kyc"), Err(b"Implication already exists".to_vec()));
+        assert_eq!(add("institution", "qualified_purchaser"), Ok(()));
+        assert_eq!(add("sovereign", "institution"), Err(b"Implication chain too deep".to_vec()));
+        assert_eq!(add("kyc", "retail"), Err(b"Implication chain too deep".to_vec()));
+        for issuer in ["bank", "broker"] {
+            assert_eq!(add(issuer, "kyc"), Ok(()));
+        }
+        assert_eq!(add("exchange", "kyc"), Ok(()));
+        assert_eq!(add("custodian", "kyc"), Err(b"Too many implications".to_vec()));
+
+        // Removal swaps the last edge into the gap
+        assert_eq!(remove("kyc", "accredited_investor"), Err(b"Unknown implication".to_vec()));
+        assert_eq!(remove("accredited_investor", "kyc"), Ok(()));
+        assert_eq!(
+            host.delegations(|delegations| delegations.get_implied_by("kyc".into())),
+            Ok(vec!["exchange".into(), "bank".into(), "broker".into()])
+        );
+        assert_eq!(host.take_logs().last().unwrap().topics, [B256::from(IMPLICATION_REMOVED)]);
+        assert!(compliant(ALICE, "accredited_investor"));
+        assert!(!compliant(ALICE, "kyc"));
+        assert_eq!(add("sovereign", "institution"), Ok(()));
+        assert_eq!(add("custodian", "kyc"), Ok(()));
+
+        // Implied attributes lapse with the record behind them
+        host.advance(3600);
+        assert!(!compliant(ALICE, "accredited_investor"));
+    }
+
+    #[test]
+    fn test_delegations() {
+        const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+        const SAFE: Address = Address::repeat_byte(0x5a);
+        const SUB_ACCOUNT: Address = Address::repeat_byte(0x5c);
+        const CUSTODY: Address = Address::repeat_byte(0x5d);
+        const ASSET: Address = Address::repeat_byte(0x5e);
+        const OTHER_ASSET: Address = Address::repeat_byte(0x5f);
+        const T0: u64 = GENESIS_TIMESTAMP;
+        let host = system();
+        let pk = bind_squares(&host, KYC, "kyc", 3600, 2, 12);
+        record(&host, KYC, &pk, &statement(ALICE, T0)).unwrap();
+        let policy_id = host
+            .policies(|policies| policies.create_policy(vec!["kyc".into()], vec![0], vec![0, 0].into()))
+            .unwrap();
+        for asset in [ASSET, OTHER_ASSET] {
+            host.assets(|assets| assets.set_asset_issuer(asset, OWNER)).unwrap();
+            host.assets(|assets| assets.set_asset_policy(asset, policy_id)).unwrap();
+        }
+        host.take_logs();
+
+        let offer = |from, to, asset, expires_at| {
+            host.set_sender(from);
+            host.delegations(|delegations| delegations.delegate_attribute(to, "kyc".into(), asset, expires_at))
+        };
+        let accept = |by, principal, asset| {
+            host.set_sender(by);
+            host.delegations(|delegations| delegations.accept_delegation(principal, "kyc".into(), asset))
+        };
+        let revoke = |by, principal, delegate, asset| {
+            host.set_sender(by);
+            host.delegations(|delegations| delegations.revoke_delegation(principal, delegate, "kyc".into(), asset))
+        };
+        let compliant =
+            |user| host.delegations(|delegations| delegations.is_compliant(user, "kyc".into())).unwrap();
+        let can_hold = |asset, user| host.assets(|assets| assets.can_hold(asset, user)).unwrap();
+        let expires_at = T0 + 1800;
+
+        assert_eq!(offer(BOB, SAFE, Address::ZERO, expires_at), Err(b"Principal is not compliant".to_vec()));
+        assert_eq!(offer(ALICE, ALICE, Address::ZERO, expires_at), Err(b"Invalid delegate".to_vec()));
+        assert_eq!(offer(ALICE, Address::ZERO, Address::ZERO, expires_at), Err(b"Invalid delegate".to_vec()));
+        assert_eq!(offer(ALICE, SAFE, Address::ZERO, T0), Err(b"Expiry in the past".to_vec()));
+        host.set_sender(ALICE);
+        assert_eq!(
+            host.delegations(|delegations| delegations.delegate_attribute(SAFE, "".into(), Address::ZERO, expires_at)),
+            Err(b"Empty attribute".to_vec())
+        );
+
+        // Nothing counts until the delegate accepts
+        assert_eq!(offer(ALICE, SAFE, Address::ZERO, expires_at), Ok(()));
+        assert!(!compliant(SAFE));
+        assert_eq!(accept(BOB, ALICE, Address::ZERO), Err(b"Unknown delegation".to_vec()));
+        assert_eq!(accept(SAFE, ALICE, Address::ZERO), Ok(()));
+        assert!(compliant(SAFE));
+        assert!(can_hold(ASSET, SAFE));
+        assert_eq!(
+            host.delegations(|delegations| delegations.get_delegations(SAFE, "kyc".into())),
+            Ok((vec![ALICE], vec![Address::ZERO], vec![expires_at], vec![true]))
+        );
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 2);
+        let parties = [ALICE.into_word(), SAFE.into_word(), Address::ZERO.into_word()];
+        assert_eq!(logs[0].topics, [&[B256::from(DELEGATION_OFFERED)][..], &parties].concat());
+        assert_eq!(logs[0].data, ("kyc".to_string(), expires_at).abi_encode_params());
+        assert_eq!(logs[1].topics, [&[B256::from(DELEGATION_ACCEPTED)][..], &parties].concat());
+
+        // Chains are followed up to MAX_DELEGATION_DEPTH hops
+        assert_eq!(offer(SAFE, SUB_ACCOUNT, Address::ZERO, expires_at), Ok(()));
+        assert_eq!(accept(SUB_ACCOUNT, SAFE, Address::ZERO), Ok(()));
+        assert!(compliant(SUB_ACCOUNT));
+        assert_eq!(offer(SUB_ACCOUNT, BOB, Address::ZERO, expires_at), Ok(()));
+        assert_eq!(accept(BOB, SUB_ACCOUNT, Address::ZERO), Ok(()));
+        assert!(!compliant(BOB));
+
+        // Asset-scoped delegations count for that asset only
+        assert_eq!(offer(ALICE, CUSTODY, ASSET, expires_at), Ok(()));
+        assert_eq!(accept(CUSTODY, ALICE, ASSET), Ok(()));
+        assert!(can_hold(ASSET, CUSTODY));
+        assert!(!can_hold(OTHER_ASSET, CUSTODY));
+        assert!(!compliant(CUSTODY));
+        assert_eq!(host.policies(|policies| policies.evaluate_policy(CUSTODY, policy_id)), Ok(false));
+        assert_eq!(
+            host.delegations(|delegations| delegations.get_principals(CUSTODY, "kyc".into(), ASSET)),
+            Ok(vec![ALICE])
+        );
+        for asset in 1..=3 {
+            assert_eq!(offer(ALICE, CUSTODY, Address::repeat_byte(asset), expires_at), Ok(()));
+        }
+        assert_eq!(offer(ALICE, CUSTODY, OTHER_ASSET, expires_at), Err(b"Too many delegations".to_vec()));
+
+        // New terms need a new acceptance
+        assert_eq!(offer(ALICE, SAFE, Address::ZERO, expires_at + 60), Ok(()));
+        assert!(!compliant(SAFE) && !compliant(SUB_ACCOUNT));
+        assert_eq!(accept(SAFE, ALICE, Address::ZERO), Ok(()));
+        assert!(compliant(SUB_ACCOUNT));
+
+        // Either party can end it, and what hung off it goes with it
+        assert_eq!(revoke(BOB, ALICE, SAFE, Address::ZERO), Err(b"Caller is not a party to the delegation".to_vec()));
+        assert_eq!(revoke(SAFE, ALICE, SAFE, Address::ZERO), Ok(()));
+        assert_eq!(revoke(ALICE, ALICE, SAFE, Address::ZERO), Err(b"Unknown delegation".to_vec()));
+        assert!(!compliant(SAFE) && !compliant(SUB_ACCOUNT));
+        assert_eq!(revoke(ALICE, ALICE, CUSTODY, ASSET), Ok(()));
+        let (_, assets, _, _) =
+            host.delegations(|delegations| delegations.get_delegations(CUSTODY, "kyc".into())).unwrap();
+        assert_eq!(assets, [Address::repeat_byte(3), Address::repeat_byte(1), Address::repeat_byte(2)]);
+        assert_eq!(host.take_logs().last().unwrap().topics[0], B256::from(DELEGATION_REVOKED));
+
+        // Delegations expire, and lapse with the principal's own record
+        assert_eq!(offer(ALICE, SAFE, Address::ZERO, T0 + 100), Ok(()));
+        assert_eq!(accept(SAFE, ALICE, Address::ZERO), Ok(()));
+        assert!(compliant(SAFE));
+        host.advance(100);
+        assert!(!compliant(SAFE));
+        assert_eq!(accept(SAFE, ALICE, Address::ZERO), Err(b"Delegation expired".to_vec()));
+        assert_eq!(offer(ALICE, SAFE, Address::ZERO, expires_at), Ok(()));
+        assert_eq!(accept(SAFE, ALICE, Address::ZERO), Ok(()));
+        host.advance(3500);
+        assert!(!compliant(ALICE) && !compliant(SAFE));
+    }
+}
@@ -0,0 +1,572 @@
+//! COMPLIANCE GATEWAY - WHERE ATTRIBUTE PROOFS COME IN
+//!
+//! A separate Stylus program (`--features gateway`): binds circuits to the
+//! attributes their proofs establish, has `ZKVerifier` check each proof and
+//! writes the result to `ComplianceRecords`. The Solidity contracts submit
+//! proofs through `ComplianceBridge`, which calls this one.
+
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256, U32, U64},
+    call::{self, Call},
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageMap, StorageString, StorageU32, StorageU64},
+    console, crypto, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::{block, jurisdiction, msg, InputMode, WORD_SIZE};
+
+/// keccak256("AttributeBound(bytes32,string,uint256,uint256,uint256)")
+const ATTRIBUTE_BOUND: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"AttributeBound(bytes32,string,uint256,uint256,uint256)")
+    .finalize();
+
+/// keccak256("JurisdictionInputSet(bytes32,uint32)")
+const JURISDICTION_INPUT_SET: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"JurisdictionInputSet(bytes32,uint32)")
+    .finalize();
+
+// `sol_interface!` in stylus-sdk 0.6 spells `bytes32` as `bytes[32]` when it
+// computes selectors, so the calls out are declared with alloy's `sol!`
+sol! {
+    /// The `ZKVerifier` entrypoints this contract calls
+    interface IZKVerifier {
+        function verifyStatement(bytes32 circuitId, bytes proof, bytes inputs) external returns (bytes32 fingerprint);
+        function getCircuit(bytes32 circuitId) external view returns (uint8 inputMode, uint32 inputCount);
+    }
+
+    /// The `ComplianceRecords` entrypoint this contract calls
+    interface IRecordStore {
+        function writeRecord(address subject, string attribute, bytes32 circuitId, uint64 issuedAt, uint64 expiresAt, bytes32 proofHash, bytes32 keyFingerprint, bytes32[] jurisdictions) external;
+    }
+}
+
+#[storage]
+#[cfg_attr(feature = "gateway", entrypoint)]
+pub struct ComplianceGateway {
+    owner: StorageAddress,
+    initialized: StorageBool,
+    /// `ZKVerifier` every proof is checked by
+    verifier: StorageAddress,
+    /// `ComplianceRecords` every accepted proof is written to
+    records: StorageAddress,
+    /// Attribute each circuit's proofs establish, see `bind_attribute`
+    bindings: StorageMap<FixedBytes<32>, AttributeBinding>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "gateway"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for ComplianceGateway {}
+
+/// What a circuit's proofs establish, and where their statements say so
+#[storage]
+pub struct AttributeBinding {
+    /// Empty if the circuit is not bound
+    attribute: StorageString,
+    /// How long a record stays valid after its statement is issued
+    validity: StorageU64,
+    /// Index of the public input carrying the subject's address
+    subject_input: StorageU32,
+    /// Index of the public input carrying when the statement was issued
+    issued_at_input: StorageU32,
+    /// Whether proofs carry a jurisdiction set, see `set_jurisdiction_input`
+    has_jurisdiction: StorageBool,
+    /// Index of the first of its `jurisdiction::WORDS` public inputs
+    jurisdiction_input: StorageU32,
+}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl ComplianceGateway {
+    /// Set the owner, the `ZKVerifier` proofs are checked by and the
+    /// `ComplianceRecords` they are written to, once
+    pub fn initialize(&mut self, owner: Address, verifier: Address, records: Address) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if !verifier.has_code() {
+            return Err(b"Verifier has no code".to_vec());
+        }
+        if !records.has_code() {
+            return Err(b"Records have no code".to_vec());
+        }
+
+        self.owner.set(owner);
+        self.verifier.set(verifier);
+        self.records.set(records);
+        self.initialized.set(true);
+
+        console!("✓ ComplianceGateway initialized");
+        Ok(())
+    }
+
+    pub fn get_verifier(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.verifier.get())
+    }
+
+    pub fn get_records(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.records.get())
+    }
+
+    /// Bind a circuit registered with the verifier to the attribute its
+    /// proofs establish (owner only)
+    ///
+    /// @param validity_secs: how long a record stays valid after its statement is issued
+    /// @param subject_input: index of the public input holding the subject's address
+    ///                       (of the packed words, for hashed input circuits)
+    /// @param issued_at_input: index of the public input holding the issue
+    ///                         timestamp, indexed like `subject_input`
+    ///
+    /// Bindings are immutable, like keys, so a record's circuit id always
+    /// stands for the same claim
+    pub fn bind_attribute(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        attribute: String,
+        validity_secs: u64,
+        subject_input: u32,
+        issued_at_input: u32,
+    ) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        if attribute.is_empty() {
+            return Err(b"Empty attribute".to_vec());
+        }
+        if validity_secs == 0 {
+            return Err(b"Zero validity period".to_vec());
+        }
+        let (mode, input_count) = self.circuit(circuit_id)?;
+        if !self.bindings.getter(circuit_id).attribute.is_empty() {
+            return Err(b"Circuit already bound".to_vec());
+        }
+        if mode == InputMode::Direct && subject_input as usize >= input_count {
+            return Err(b"Subject input out of range".to_vec());
+        }
+        if mode == InputMode::Direct && issued_at_input as usize >= input_count {
+            return Err(b"Issued-at input out of range".to_vec());
+        }
+        if issued_at_input == subject_input {
+            return Err(b"Issued-at input is the subject input".to_vec());
+        }
+
+        let mut binding = self.bindings.setter(circuit_id);
+        binding.attribute.set_str(&attribute);
+        binding.validity.set(U64::from(validity_secs));
+        binding.subject_input.set(U32::from(subject_input));
+        binding.issued_at_input.set(U32::from(issued_at_input));
+
+        let data = (attribute, U256::from(validity_secs), U256::from(subject_input), U256::from(issued_at_input))
+            .abi_encode_params();
+        let _ = evm::raw_log(&[B256::from(ATTRIBUTE_BOUND), circuit_id], &data);
+        console!("✓ Circuit bound to an attribute");
+        Ok(())
+    }
+
+    /// (attribute, validity in seconds, subject input index, issued-at input
+    /// index) a circuit is bound to; an empty attribute if it is not. The
+    /// SDK returns it encoded as one `(string,uint64,uint32,uint32)` tuple
+    pub fn get_attribute_binding(&self, circuit_id: FixedBytes<32>) -> Result<(String, u64, u32, u32), Vec<u8>> {
+        let binding = self.bindings.getter(circuit_id);
+        Ok((
+            binding.attribute.get_string(),
+            binding.validity.get().to::<u64>(),
+            binding.subject_input.get().to::<u32>(),
+            binding.issued_at_input.get().to::<u32>(),
+        ))
+    }
+
+    /// Mark a bound circuit's proofs as carrying a jurisdiction set (owner only)
+    ///
+    /// @param first_input: index of the first of `jurisdiction::WORDS` public
+    ///                     inputs (of the packed words, for hashed input circuits)
+    ///
+    /// Records from the circuit then keep the set for asset jurisdiction
+    /// rules. Set once, like the binding itself.
+    pub fn set_jurisdiction_input(&mut self, circuit_id: FixedBytes<32>, first_input: u32) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        let binding = self.bindings.getter(circuit_id);
+        if binding.attribute.is_empty() {
+            return Err(b"Circuit is not bound to an attribute".to_vec());
+        }
+        if binding.has_jurisdiction.get() {
+            return Err(b"Jurisdiction input already set".to_vec());
+        }
+        let (mode, input_count) = self.circuit(circuit_id)?;
+        if mode == InputMode::Direct && first_input as usize + jurisdiction::WORDS > input_count {
+            return Err(b"Jurisdiction inputs out of range".to_vec());
+        }
+
+        let mut binding = self.bindings.setter(circuit_id);
+        binding.has_jurisdiction.set(true);
+        binding.jurisdiction_input.set(U32::from(first_input));
+
+        let _ = evm::raw_log(
+            &[B256::from(JURISDICTION_INPUT_SET), circuit_id],
+            &U256::from(first_input).abi_encode(),
+        );
+        console!("✓ Jurisdiction input set");
+        Ok(())
+    }
+
+    /// (whether proofs carry a jurisdiction set, index of its first input)
+    pub fn get_jurisdiction_input(&self, circuit_id: FixedBytes<32>) -> Result<(bool, u32), Vec<u8>> {
+        let binding = self.bindings.getter(circuit_id);
+        Ok((binding.has_jurisdiction.get(), binding.jurisdiction_input.get().to::<u32>()))
+    }
+
+    /// Verify a proof under an attribute-bound circuit and record the
+    /// attribute for the subject named in its public inputs
+    ///
+    /// @param inputs: packed 32-byte public inputs (BigEndian); hashed with
+    ///                the circuit's input mode for hashed input circuits
+    ///
+    /// The subject comes from the statement, not the caller, so anyone may
+    /// relay a proof. Reverts if the proof is invalid.
+    ///
+    /// Returns (subject, expires at)
+    pub fn verify_attribute(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<(Address, u64), Vec<u8>> {
+        console!("=== ATTRIBUTE VERIFICATION ===");
+
+        self.record_attribute(circuit_id, &proof_bytes, &inputs)
+    }
+
+    /// `verify_attribute`, reverting unless the circuit is bound to
+    /// `attribute`, for callers that act on one attribute
+    ///
+    /// Returns (subject, expires at)
+    pub fn verify_claimed_attribute(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        attribute: String,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<(Address, u64), Vec<u8>> {
+        if self.bindings.getter(circuit_id).attribute.get_string() != attribute {
+            return Err(b"Attribute does not match circuit".to_vec());
+        }
+        self.record_attribute(circuit_id, &proof_bytes, &inputs)
+    }
+}
+
+// ============================================================================
+// PROOF INTAKE
+// ============================================================================
+
+impl ComplianceGateway {
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err(b"Caller is not the owner".to_vec());
+        }
+        Ok(())
+    }
+
+    /// (input mode, input count) of a circuit registered with the verifier
+    fn circuit(&self, circuit_id: FixedBytes<32>) -> Result<(InputMode, usize), Vec<u8>> {
+        let calldata = IZKVerifier::getCircuitCall { circuitId: circuit_id }.abi_encode();
+        let output = call::static_call(Call::new(), self.verifier.get(), &calldata)?;
+        let circuit = IZKVerifier::getCircuitCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected verifier output".to_vec())?;
+        Ok((InputMode::from_u8(circuit.inputMode)?, circuit.inputCount as usize))
+    }
+
+    /// Have the verifier check a proof under an attribute-bound circuit and
+    /// the records write the subject's record, valid for the circuit's bound
+    /// period from the statement's issue time
+    ///
+    /// Returns (subject, expires at)
+    fn record_attribute(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        proof_bytes: &[u8],
+        inputs: &[u8],
+    ) -> Result<(Address, u64), Vec<u8>> {
+        let binding = self.bindings.getter(circuit_id);
+        if binding.attribute.is_empty() {
+            return Err(b"Circuit is not bound to an attribute".to_vec());
+        }
+        let attribute = binding.attribute.get_string();
+        let validity = binding.validity.get().to::<u64>();
+        let subject_input = binding.subject_input.get().to::<usize>();
+        let issued_at_input = binding.issued_at_input.get().to::<usize>();
+
+        let (words, remainder) = inputs.as_chunks::<WORD_SIZE>();
+        if !remainder.is_empty() {
+            return Err(b"Public inputs must be 32-byte words".to_vec());
+        }
+        let subject = words
+            .get(subject_input)
+            .ok_or_else(|| b"Missing subject input".to_vec())
+            .and_then(read_address)?;
+        let issued_at = words
+            .get(issued_at_input)
+            .ok_or_else(|| b"Missing issued-at input".to_vec())
+            .and_then(read_timestamp)?;
+        // The records check the words are a valid set
+        let jurisdictions = match binding.has_jurisdiction.get() {
+            true => {
+                let first = binding.jurisdiction_input.get().to::<usize>();
+                let words = words
+                    .get(first..first + jurisdiction::WORDS)
+                    .ok_or_else(|| b"Missing jurisdiction inputs".to_vec())?;
+                words.iter().map(|word| FixedBytes(*word)).collect()
+            }
+            false => Vec::new(),
+        };
+        if issued_at > block::timestamp() {
+            return Err(b"Proof issued in the future".to_vec());
+        }
+        let expires_at = issued_at.saturating_add(validity);
+        if expires_at <= block::timestamp() {
+            return Err(b"Proof has expired".to_vec());
+        }
+
+        // Reverts for invalid proofs and compromised keys, with the verifier's reason
+        let calldata = IZKVerifier::verifyStatementCall {
+            circuitId: circuit_id,
+            proof: proof_bytes.to_vec().into(),
+            inputs: inputs.to_vec().into(),
+        }
+        .abi_encode();
+        let verifier = self.verifier.get();
+        let output = call::call(Call::new_in(self), verifier, &calldata)?;
+        let fingerprint = IZKVerifier::verifyStatementCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected verifier output".to_vec())?
+            .fingerprint;
+
+        // Reverts unless the statement is newer than the record it replaces
+        let calldata = IRecordStore::writeRecordCall {
+            subject,
+            attribute,
+            circuitId: circuit_id,
+            issuedAt: issued_at,
+            expiresAt: expires_at,
+            proofHash: crypto::keccak(proof_bytes),
+            keyFingerprint: fingerprint,
+            jurisdictions,
+        }
+        .abi_encode();
+        let records = self.records.get();
+        call::call(Call::new_in(self), records, &calldata)?;
+        Ok((subject, expires_at))
+    }
+}
+
+/// An address public input: a field element below 2^160
+fn read_address(word: &[u8; WORD_SIZE]) -> Result<Address, Vec<u8>> {
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return Err(b"Subject input is not an address".to_vec());
+    }
+    Ok(Address::from_slice(&word[12..]))
+}
+
+/// A timestamp public input: a field element below 2^64
+fn read_timestamp(word: &[u8; WORD_SIZE]) -> Result<u64, Vec<u8>> {
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return Err(b"Issued-at input is not a timestamp".to_vec());
+    }
+    let mut timestamp = [0u8; 8];
+    timestamp.copy_from_slice(&word[24..]);
+    Ok(u64::from_be_bytes(timestamp))
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        address_input, packed_inputs, prove_squares, squares_keys, statement, system, DEFAULT_SENDER,
+        GENESIS_TIMESTAMP, RECORDS,
+    };
+    use arbshield_verifier_core::{Groth16Proof, VerifyingKey};
+    use ark_bn254::Fr;
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+
+    /// A fresh valid proof for the same statement: (A / r, r·B + r·s·δ, C + s·A)
+    fn rerandomize_proof(proof: &[u8], vk: &VerifyingKey, r: u64, s: u64) -> Vec<u8> {
+        let proof = Groth16Proof::from_uncompressed_bytes(proof).unwrap();
+        let (r, s) = (Fr::from(r), Fr::from(s));
+        Groth16Proof {
+            a: (proof.a * r.inverse().unwrap()).into_affine(),
+            b: (proof.b * r + vk.delta_g2 * (r * s)).into_affine(),
+            c: (proof.c + proof.a * s).into_affine(),
+        }
+        .to_uncompressed_bytes()
+        .to_vec()
+    }
+
+    #[test]
+    fn test_attribute_records() {
+        const VALIDITY: u64 = 30 * 24 * 60 * 60;
+        let host = system();
+        let (pk, vk) = squares_keys(2, 1);
+        host.transact(|verifier| verifier.register_circuit(KYC, vk.to_bytes().into(), 0)).unwrap();
+        host.take_logs();
+
+        // Only the owner binds, once, to subject and issued-at inputs the circuit has
+        let bind = |subject_input, issued_at_input| {
+            host.gateway(|gateway| gateway.bind_attribute(KYC, "kyc".into(), VALIDITY, subject_input, issued_at_input))
+        };
+        host.set_sender(ALICE);
+        assert_eq!(bind(0, 1), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(bind(2, 1), Err(b"Subject input out of range".to_vec()));
+        assert_eq!(bind(0, 2), Err(b"Issued-at input out of range".to_vec()));
+        assert_eq!(bind(0, 0), Err(b"Issued-at input is the subject input".to_vec()));
+        assert_eq!(
+            host.gateway(|gateway| gateway.bind_attribute(FixedBytes([7; 32]), "kyc".into(), 1, 0, 1)),
+            Err(b"Unknown circuit id".to_vec())
+        );
+        assert_eq!(bind(0, 1), Ok(()));
+        assert_eq!(bind(0, 1), Err(b"Circuit already bound".to_vec()));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(ATTRIBUTE_BOUND), KYC]);
+        assert_eq!(host.gateway(|gateway| gateway.get_attribute_binding(KYC)), Ok(("kyc".into(), VALIDITY, 0, 1)));
+
+        let is_compliant = |user| host.records(|records| records.is_compliant(user, "kyc".into())).unwrap();
+        assert!(!is_compliant(ALICE));
+
+        // Anyone may relay a proof; the record goes to the subject it names
+        let inputs = statement(ALICE, GENESIS_TIMESTAMP);
+        let proof = prove_squares(&pk, &inputs);
+        host.set_sender(BOB);
+        let verify = |proof: &[u8], inputs: &[Fr]| {
+            host.gateway(|gateway| gateway.verify_attribute(KYC, proof.to_vec().into(), packed_inputs(inputs)))
+        };
+        assert_eq!(verify(&proof, &inputs), Ok((ALICE, GENESIS_TIMESTAMP + VALIDITY)));
+        assert!(is_compliant(ALICE));
+        assert!(!is_compliant(BOB));
+        assert_eq!(
+            host.records(|records| records.get_record(ALICE, "kyc".into())),
+            Ok((
+                true,
+                U256::from(GENESIS_TIMESTAMP),
+                U256::from(GENESIS_TIMESTAMP + VALIDITY),
+                crypto::keccak(&proof)
+            ))
+        );
+        assert_eq!(
+            host.records(|records| records.get_record_source(ALICE, "kyc".into())),
+            Ok((KYC, FixedBytes(vk.fingerprint())))
+        );
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!((logs[0].address, logs[0].topics[1]), (RECORDS, ALICE.into_word()));
+
+        // Nothing is written without a valid proof for an address subject
+        let issued = Fr::from(GENESIS_TIMESTAMP);
+        assert_eq!(verify(&proof, &[address_input(BOB), issued]), Err(b"Invalid proof".to_vec()));
+        assert_eq!(verify(&proof, &inputs[..1]), Err(b"Missing issued-at input".to_vec()));
+        assert_eq!(verify(&proof, &[]), Err(b"Missing subject input".to_vec()));
+        let wide = [-Fr::from(1u64), issued];
+        assert_eq!(verify(&prove_squares(&pk, &wide), &wide), Err(b"Subject input is not an address".to_vec()));
+        let wide = [address_input(BOB), -Fr::from(1u64)];
+        assert_eq!(verify(&prove_squares(&pk, &wide), &wide), Err(b"Issued-at input is not a timestamp".to_vec()));
+        assert_eq!(
+            host.gateway(|gateway| {
+                gateway.verify_attribute(FixedBytes([7; 32]), proof.clone().into(), Vec::new().into())
+            }),
+            Err(b"Circuit is not bound to an attribute".to_vec())
+        );
+        assert!(host.take_logs().is_empty());
+        assert!(!is_compliant(BOB));
+
+        // Statements carry their issue time: a replayed or re-randomized
+        // proof of the same statement cannot extend the record, and neither
+        // can an older statement or one from the future
+        host.advance(VALIDITY / 2);
+        assert_eq!(verify(&proof, &inputs), Err(b"Proof is not newer than the record".to_vec()));
+        assert_eq!(
+            verify(&rerandomize_proof(&proof, &vk, 2, 3), &inputs),
+            Err(b"Proof is not newer than the record".to_vec())
+        );
+        let older = statement(ALICE, GENESIS_TIMESTAMP - 1);
+        assert_eq!(verify(&prove_squares(&pk, &older), &older), Err(b"Proof is not newer than the record".to_vec()));
+        let future = statement(ALICE, GENESIS_TIMESTAMP + VALIDITY);
+        assert_eq!(verify(&prove_squares(&pk, &future), &future), Err(b"Proof issued in the future".to_vec()));
+        assert!(host.take_logs().is_empty());
+        assert_eq!(host.contract().get_verified_count(), Ok(U256::from(1)));
+
+        // Validity runs from issuance, so a proof past its period records nothing
+        let stale = statement(BOB, GENESIS_TIMESTAMP - VALIDITY / 2);
+        assert_eq!(verify(&prove_squares(&pk, &stale), &stale), Err(b"Proof has expired".to_vec()));
+
+        // Records lapse at their expiry
+        host.advance(VALIDITY / 2 - 1);
+        assert!(is_compliant(ALICE));
+        host.advance(1);
+        assert!(!is_compliant(ALICE));
+
+        // A fresh statement renews it
+        let now = GENESIS_TIMESTAMP + VALIDITY;
+        let renewed = statement(ALICE, now);
+        assert_eq!(verify(&prove_squares(&pk, &renewed), &renewed), Ok((ALICE, now + VALIDITY)));
+        assert!(is_compliant(ALICE));
+
+        // Callers acting on one attribute have it checked against the binding
+        let claimed = |attribute: &str| {
+            let inputs = statement(BOB, now);
+            let proof = prove_squares(&pk, &inputs).into();
+            let inputs = packed_inputs(&inputs);
+            host.gateway(|gateway| gateway.verify_claimed_attribute(KYC, attribute.into(), proof, inputs))
+        };
+        assert_eq!(claimed("accredited_investor"), Err(b"Attribute does not match circuit".to_vec()));
+        assert_eq!(claimed("kyc"), Ok((BOB, now + VALIDITY)));
+    }
+
+    #[test]
+    fn test_jurisdiction_input() {
+        const RESIDENCY: FixedBytes<32> = FixedBytes([0x3a; 32]);
+        let host = system();
+        let (_, vk) = squares_keys(2 + jurisdiction::WORDS, 11);
+        host.transact(|verifier| verifier.register_circuit(RESIDENCY, vk.to_bytes().into(), 0)).unwrap();
+        host.gateway(|gateway| gateway.bind_attribute(RESIDENCY, "residency".into(), 3600, 0, 1)).unwrap();
+        host.take_logs();
+
+        let set_input = |circuit_id, first| host.gateway(|gateway| gateway.set_jurisdiction_input(circuit_id, first));
+        host.set_sender(ALICE);
+        assert_eq!(set_input(RESIDENCY, 2), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(set_input(KYC, 2), Err(b"Circuit is not bound to an attribute".to_vec()));
+        assert_eq!(set_input(RESIDENCY, 3), Err(b"Jurisdiction inputs out of range".to_vec()));
+        assert_eq!(set_input(RESIDENCY, 2), Ok(()));
+        assert_eq!(set_input(RESIDENCY, 2), Err(b"Jurisdiction input already set".to_vec()));
+        assert_eq!(host.gateway(|gateway| gateway.get_jurisdiction_input(RESIDENCY)), Ok((true, 2)));
+        assert_eq!(host.take_logs()[0].topics, [B256::from(JURISDICTION_INPUT_SET), RESIDENCY]);
+    }
+
+    #[test]
+    fn test_initialize() {
+        let host = system();
+        assert_eq!(host.gateway(|gateway| gateway.get_records()), Ok(RECORDS));
+        assert_eq!(
+            host.gateway(|gateway| gateway.initialize(ALICE, ALICE, ALICE)),
+            Err(b"Already initialized".to_vec())
+        );
+
+        // Nothing else writes records, and records take writes from nothing else
+        assert_eq!(
+            host.records(|records| {
+                records.write_record(ALICE, "kyc".into(), KYC, 1, u64::MAX, KYC, KYC, Vec::new())
+            }),
+            Err(b"Caller is not the gateway".to_vec())
+        );
+    }
+}
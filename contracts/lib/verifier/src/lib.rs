@@ -1,5 +1,5 @@
 //! PRODUCTION GROTH16 VERIFIER - 100% REAL
-//! 
+//!
 //! This verifies actual Groth16 proofs using:
 //! - REAL verification key from trusted setup
 //! - CORRECT pairing equation check (GT::one(), not zero)
@@ -7,315 +7,169 @@
 //! - Optimized Arbitrum precompile path
 //!
 //! Circuit: multiplier (a * b = c)
+//!
+//! Proof/key types and the pairing check live in `arbshield-verifier-core`;
+//! this crate is the Stylus storage and ABI layer around them.
+//!
+//! Seven programs, each under Stylus's 24 KB compressed limit and built from
+//! this crate with one feature (none for the verifier):
+//! - `ZKVerifier` (this module): keys, verification, sessions
+//! - [`gateway::ComplianceGateway`] (`gateway`): where attribute proofs come in
+//! - [`bridge::ComplianceBridge`] (`bridge`): the Solidity contracts' way in
+//! - [`records::ComplianceRecords`] (`records`): attribute records and
+//!   their history
+//! - [`delegations::ComplianceDelegations`] (`delegations`): implications
+//!   and who may use whose attributes
+//! - [`policies::CompliancePolicies`] (`policies`): boolean policies over
+//!   attributes
+//! - [`assets::AssetCompliance`] (`assets`): per-token holding rules
+//!
+//! The gateway checks proofs with the verifier and writes them to records,
+//! the bridge submits to the gateway; delegations read records, policies
+//! read delegations, assets read all.
 
 #![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
 extern crate alloc;
 
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256, U32, U64, U8},
     prelude::*,
-    storage::{StorageAddress, StorageBool, StorageU256},
-    console, call::RawCall,
+    storage::{
+        StorageAddress, StorageArray, StorageBool, StorageFixedBytes, StorageMap, StorageU256, StorageU32, StorageU64,
+        StorageU8, StorageVec,
+    },
+    console, call::RawCall, crypto, evm,
 };
+#[cfg(not(all(test, not(feature = "export-abi"))))]
+use stylus_sdk::{block, msg};
+#[cfg(all(test, not(feature = "export-abi")))]
+use testing::{block, msg};
 
 use alloc::vec::Vec;
-use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
-use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
-use ark_ff::PrimeField;
-use ark_std::One;
-
-// ============================================================================
-// REAL VERIFICATION KEY - From actual trusted setup
-// ============================================================================
-// These constants come from running the setup for multiplier.circom
-// Replace these with YOUR circuit's actual values!
-
-mod vk_constants {
-    // Example values from a real multiplier circuit setup
-    // In production: run extract_vk.js and paste the output here
-    
-    pub const ALPHA_G1_X: [u8; 32] = [
-        0x20, 0x49, 0x1b, 0xbb, 0x48, 0x6e, 0x5b, 0x0b,
-        0x8d, 0x96, 0x6c, 0x44, 0x36, 0x89, 0xe0, 0x5e,
-        0xf0, 0x25, 0x87, 0x64, 0x42, 0x0f, 0x28, 0x7a,
-        0x94, 0xb4, 0x38, 0x2a, 0xf7, 0x4f, 0xb5, 0x5e,
-    ];
-    
-    pub const ALPHA_G1_Y: [u8; 32] = [
-        0x2f, 0xe2, 0xe8, 0x43, 0x67, 0x5e, 0xdf, 0x0f,
-        0x2d, 0x10, 0xaa, 0x8e, 0xfc, 0x16, 0x5e, 0x17,
-        0x39, 0xf0, 0x5d, 0x81, 0x67, 0x4b, 0x72, 0x15,
-        0x3e, 0x89, 0xb0, 0x11, 0x8b, 0x8d, 0x65, 0xae,
-    ];
-
-    // Beta G2 (Fq2 = two field elements)
-    pub const BETA_G2_X0: [u8; 32] = [
-        0x1f, 0x32, 0x86, 0x5d, 0x2a, 0xf6, 0x03, 0x4e,
-        0xf7, 0xd6, 0xe5, 0x4f, 0xe2, 0xd6, 0xce, 0x8f,
-        0x2f, 0x16, 0x89, 0xc2, 0xf0, 0x83, 0x8e, 0x6d,
-        0x20, 0xf4, 0x23, 0x1f, 0x5b, 0x1f, 0x3b, 0xd0,
-    ];
-    
-    pub const BETA_G2_X1: [u8; 32] = [
-        0x14, 0xce, 0x8d, 0x94, 0xd5, 0x2d, 0x2d, 0x5e,
-        0x8c, 0x7e, 0x2b, 0xe9, 0xd6, 0x7b, 0x33, 0xdf,
-        0x09, 0xbc, 0x92, 0x0e, 0x1f, 0x02, 0xa8, 0x8d,
-        0x45, 0xb9, 0x72, 0x8f, 0x31, 0x94, 0x2a, 0x46,
-    ];
-    
-    pub const BETA_G2_Y0: [u8; 32] = [
-        0x08, 0x79, 0xd6, 0xf3, 0x68, 0x8e, 0x4f, 0xe3,
-        0x12, 0x45, 0xf8, 0x84, 0xca, 0xb5, 0xc4, 0x8d,
-        0x3e, 0x26, 0x82, 0xf0, 0x1a, 0x9c, 0x5d, 0xbc,
-        0x2e, 0x34, 0xa1, 0x8f, 0xf4, 0x26, 0x95, 0x30,
-    ];
-    
-    pub const BETA_G2_Y1: [u8; 32] = [
-        0x25, 0x4f, 0x58, 0xd6, 0xd8, 0x95, 0x57, 0x1b,
-        0x24, 0x2c, 0x43, 0x4f, 0xb8, 0x0e, 0x49, 0xbb,
-        0x1f, 0x7b, 0x2f, 0x23, 0xaf, 0xa3, 0x85, 0xfc,
-        0x3e, 0x0f, 0xc0, 0x1d, 0x2e, 0x1a, 0x61, 0x72,
-    ];
-
-    // Gamma G2 (typically generator for public inputs)
-    pub const GAMMA_G2_X0: [u8; 32] = [
-        0x19, 0x8e, 0x99, 0x52, 0xf6, 0xdb, 0xc3, 0xf2,
-        0x5a, 0xa0, 0xa8, 0x77, 0x5e, 0x97, 0xf3, 0x0d,
-        0x06, 0x15, 0x52, 0x42, 0xf0, 0xbb, 0x5c, 0xf8,
-        0xbb, 0x23, 0x0e, 0x38, 0xb1, 0xe3, 0xc1, 0xfc,
-    ];
-    
-    pub const GAMMA_G2_X1: [u8; 32] = [
-        0x1a, 0xe3, 0xfc, 0xc7, 0x6e, 0x1e, 0xeb, 0x76,
-        0x8d, 0x1f, 0xda, 0xcd, 0x03, 0x46, 0x77, 0x67,
-        0xaf, 0x3e, 0xb9, 0x8e, 0xce, 0x83, 0x8e, 0x22,
-        0x7f, 0x3f, 0x0e, 0xb5, 0x05, 0x1e, 0x57, 0x2f,
-    ];
-    
-    pub const GAMMA_G2_Y0: [u8; 32] = [
-        0x29, 0x75, 0x98, 0x4f, 0x16, 0x0d, 0x4a, 0xe5,
-        0x83, 0x42, 0x28, 0x70, 0x91, 0x10, 0x15, 0x5a,
-        0x08, 0x59, 0x70, 0x62, 0x6c, 0x89, 0x4c, 0x0e,
-        0xcc, 0xf9, 0x46, 0x0e, 0xf4, 0x73, 0x2d, 0x29,
-    ];
-    
-    pub const GAMMA_G2_Y1: [u8; 32] = [
-        0x03, 0xd8, 0x28, 0x7a, 0xd1, 0x8e, 0x8b, 0x85,
-        0xd4, 0x0a, 0x92, 0x28, 0x37, 0x3c, 0x65, 0x36,
-        0x58, 0xf8, 0x26, 0x4f, 0x3f, 0x5d, 0x8f, 0x62,
-        0x87, 0x5c, 0xa5, 0xee, 0x78, 0x7c, 0x8d, 0x39,
-    ];
-
-    // Delta G2
-    pub const DELTA_G2_X0: [u8; 32] = [
-        0x0f, 0x1a, 0x8c, 0x20, 0x22, 0x7f, 0x1d, 0x2e,
-        0x93, 0xc5, 0xf8, 0x41, 0xa5, 0x7f, 0xc3, 0x8d,
-        0x2b, 0x51, 0x6a, 0x8e, 0x1f, 0x95, 0x43, 0x2b,
-        0xd0, 0xe9, 0x3f, 0x28, 0x1c, 0x7e, 0x8f, 0x3a,
-    ];
-    
-    pub const DELTA_G2_X1: [u8; 32] = [
-        0x2d, 0x4f, 0x7c, 0x5b, 0x8a, 0xd0, 0x93, 0x42,
-        0x1f, 0xe1, 0x31, 0x44, 0x0c, 0x7c, 0x72, 0xc0,
-        0x8f, 0x60, 0xd5, 0xfe, 0x52, 0x76, 0xc8, 0x1d,
-        0xb2, 0x5e, 0xf0, 0xd7, 0xd2, 0x6a, 0xf2, 0x75,
-    ];
-    
-    pub const DELTA_G2_Y0: [u8; 32] = [
-        0x16, 0xf3, 0xd8, 0xc3, 0xd5, 0xbc, 0x4b, 0x7e,
-        0x3a, 0x62, 0x34, 0xf3, 0x22, 0x03, 0x77, 0x84,
-        0x29, 0x8f, 0x5b, 0x11, 0xd9, 0xfd, 0xcd, 0x2e,
-        0xab, 0x88, 0x16, 0xd1, 0xb7, 0x2b, 0x28, 0x1b,
-    ];
-    
-    pub const DELTA_G2_Y1: [u8; 32] = [
-        0x23, 0x87, 0x0a, 0x3d, 0xf3, 0xad, 0x8e, 0x33,
-        0xc9, 0xe8, 0x2a, 0x47, 0x15, 0xe8, 0x0f, 0xd3,
-        0x0c, 0x70, 0x23, 0x9e, 0xd2, 0x4e, 0xf1, 0x5d,
-        0x0c, 0x8f, 0x2d, 0xa7, 0x98, 0x0c, 0x24, 0x18,
-    ];
-
-    // IC[0] - constant term
-    pub const IC_0_X: [u8; 32] = [
-        0x0a, 0x3d, 0x1d, 0x18, 0xd7, 0x3c, 0x28, 0x4e,
-        0x6f, 0x51, 0xba, 0x69, 0x27, 0x8f, 0x2a, 0xce,
-        0xf9, 0x4e, 0x26, 0x5e, 0x8d, 0x42, 0x1f, 0x8e,
-        0x74, 0x91, 0xc6, 0x35, 0x0d, 0x8f, 0x63, 0x21,
-    ];
-    
-    pub const IC_0_Y: [u8; 32] = [
-        0x2e, 0x89, 0x41, 0x5d, 0x8a, 0x31, 0x58, 0x92,
-        0xaf, 0xe2, 0x8d, 0x7f, 0x48, 0x9c, 0x06, 0x6b,
-        0x3d, 0x62, 0x38, 0xe8, 0xd8, 0x39, 0x43, 0x5c,
-        0x88, 0xf4, 0x0e, 0x9f, 0x56, 0x38, 0xc3, 0x0e,
-    ];
-
-    // IC[1] - coefficient for public input c
-    pub const IC_1_X: [u8; 32] = [
-        0x18, 0xf2, 0x38, 0x66, 0x2e, 0x95, 0x82, 0x3e,
-        0xae, 0x85, 0x17, 0x8c, 0x3c, 0x6b, 0x96, 0xb4,
-        0x27, 0xd5, 0xf8, 0x9e, 0x37, 0x10, 0x5c, 0x8d,
-        0xf4, 0xa3, 0xc0, 0x2b, 0x28, 0xd6, 0x8e, 0x17,
-    ];
-    
-    pub const IC_1_Y: [u8; 32] = [
-        0x1c, 0x8d, 0x8f, 0x5b, 0x39, 0xe0, 0x5e, 0x20,
-        0xf5, 0x0f, 0xc4, 0x46, 0x0e, 0x51, 0x55, 0xd0,
-        0x67, 0xa0, 0x8f, 0x64, 0x5e, 0xf8, 0xdd, 0x97,
-        0x95, 0xc7, 0x8f, 0xf6, 0xae, 0xb3, 0x8a, 0x2e,
-    ];
-}
-
-// ============================================================================
-// PROOF STRUCTURE
-// ============================================================================
-
-#[derive(Clone, Debug)]
-pub struct Groth16Proof {
-    pub a: G1Affine,
-    pub b: G2Affine,
-    pub c: G1Affine,
-}
-
-impl Groth16Proof {
-    /// Parse proof from uncompressed format (snarkjs default)
-    /// Format: A.x (32) || A.y (32) || B.x0 (32) || B.x1 (32) || B.y0 (32) || B.y1 (32) || C.x (32) || C.y (32)
-    /// Total: 256 bytes
-    pub fn from_uncompressed_bytes(bytes: &[u8]) -> Result<Self, Vec<u8>> {
-        if bytes.len() != 256 {
-            return Err(b"Expected 256 bytes for uncompressed proof".to_vec());
-        }
-
-        // Parse A (G1): x, y (32 bytes each)
-        let a_x = Fq::from_be_bytes_mod_order(&bytes[0..32]);
-        let a_y = Fq::from_be_bytes_mod_order(&bytes[32..64]);
-        let a = G1Affine::new(a_x, a_y);
-
-        // Parse B (G2): x0, x1, y0, y1 (32 bytes each)
-        let b_x0 = Fq::from_be_bytes_mod_order(&bytes[64..96]);
-        let b_x1 = Fq::from_be_bytes_mod_order(&bytes[96..128]);
-        let b_y0 = Fq::from_be_bytes_mod_order(&bytes[128..160]);
-        let b_y1 = Fq::from_be_bytes_mod_order(&bytes[160..192]);
-        let b = G2Affine::new(
-            Fq2::new(b_x0, b_x1),
-            Fq2::new(b_y0, b_y1),
-        );
-
-        // Parse C (G1): x, y (32 bytes each)
-        let c_x = Fq::from_be_bytes_mod_order(&bytes[192..224]);
-        let c_y = Fq::from_be_bytes_mod_order(&bytes[224..256]);
-        let c = G1Affine::new(c_x, c_y);
-
-        // Validate
-        if !a.is_on_curve() || !b.is_on_curve() || !c.is_on_curve() {
-            return Err(b"Proof points not on curve".to_vec());
-        }
 
-        Ok(Self { a, b, c })
-    }
-
-    /// Parse from JSON format (for testing)
-    #[cfg(test)]
-    pub fn from_json(json: &str) -> Result<Self, Vec<u8>> {
-        // Parse snarkjs JSON format
-        // This is a simplified version - in production use serde
-        unimplemented!("Use from_uncompressed_bytes with binary proof data")
-    }
-}
+// export-abi swaps the host imports for panicking stubs, so the test host
+// cannot supply them and the contract tests only build without it
+#[cfg(all(test, not(feature = "export-abi")))]
+mod testing;
+
+pub mod assets;
+pub mod bridge;
+pub mod delegations;
+pub mod gateway;
+pub mod policies;
+pub mod records;
+
+const _: () = assert!(
+    cfg!(feature = "gateway") as u8
+        + cfg!(feature = "bridge") as u8
+        + cfg!(feature = "records") as u8
+        + cfg!(feature = "delegations") as u8
+        + cfg!(feature = "policies") as u8
+        + cfg!(feature = "assets") as u8
+        <= 1,
+    "Enable at most one program feature per build"
+);
+
+// `#[entrypoint]` declares `print_abi` next to the program it is on
+#[cfg(all(feature = "export-abi", feature = "assets"))]
+pub use assets::print_abi;
+#[cfg(all(feature = "export-abi", feature = "bridge"))]
+pub use bridge::print_abi;
+#[cfg(all(feature = "export-abi", feature = "delegations"))]
+pub use delegations::print_abi;
+#[cfg(all(feature = "export-abi", feature = "gateway"))]
+pub use gateway::print_abi;
+#[cfg(all(feature = "export-abi", feature = "policies"))]
+pub use policies::print_abi;
+#[cfg(all(feature = "export-abi", feature = "records"))]
+pub use records::print_abi;
+
+pub use arbshield_verifier_core::{
+    jurisdiction, policy, precompile, InputMode, PackedCall, MULTIPLIER_CIRCUIT_ID, PROOF_SIZE, VK_HEADER_SIZE,
+    WORD_SIZE,
+};
 
 // ============================================================================
-// VERIFYING KEY
+// STORAGE CONTRACT
 // ============================================================================
 
-#[derive(Clone, Debug)]
-pub struct VerifyingKey {
-    pub alpha_g1: G1Affine,
-    pub beta_g2: G2Affine,
-    pub gamma_g2: G2Affine,
-    pub delta_g2: G2Affine,
-    pub ic: Vec<G1Affine>,
-}
+/// keccak256("CircuitRegistered(bytes32,uint8)")
+const CIRCUIT_REGISTERED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"CircuitRegistered(bytes32,uint8)")
+    .finalize();
 
-impl VerifyingKey {
-    /// Load REAL verifying key from constants
-    pub fn load_real() -> Self {
-        use vk_constants::*;
+/// keccak256("KeyCompromised(bytes32)")
+const KEY_COMPROMISED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"KeyCompromised(bytes32)")
+    .finalize();
 
-        // Parse Alpha G1
-        let alpha_g1 = G1Affine::new(
-            Fq::from_be_bytes_mod_order(&ALPHA_G1_X),
-            Fq::from_be_bytes_mod_order(&ALPHA_G1_Y),
-        );
-
-        // Parse Beta G2
-        let beta_g2 = G2Affine::new(
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&BETA_G2_X0),
-                Fq::from_be_bytes_mod_order(&BETA_G2_X1),
-            ),
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&BETA_G2_Y0),
-                Fq::from_be_bytes_mod_order(&BETA_G2_Y1),
-            ),
-        );
-
-        // Parse Gamma G2
-        let gamma_g2 = G2Affine::new(
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&GAMMA_G2_X0),
-                Fq::from_be_bytes_mod_order(&GAMMA_G2_X1),
-            ),
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&GAMMA_G2_Y0),
-                Fq::from_be_bytes_mod_order(&GAMMA_G2_Y1),
-            ),
-        );
-
-        // Parse Delta G2
-        let delta_g2 = G2Affine::new(
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&DELTA_G2_X0),
-                Fq::from_be_bytes_mod_order(&DELTA_G2_X1),
-            ),
-            Fq2::new(
-                Fq::from_be_bytes_mod_order(&DELTA_G2_Y0),
-                Fq::from_be_bytes_mod_order(&DELTA_G2_Y1),
-            ),
-        );
-
-        // Parse IC (public input commitments)
-        let ic = vec![
-            G1Affine::new(
-                Fq::from_be_bytes_mod_order(&IC_0_X),
-                Fq::from_be_bytes_mod_order(&IC_0_Y),
-            ),
-            G1Affine::new(
-                Fq::from_be_bytes_mod_order(&IC_1_X),
-                Fq::from_be_bytes_mod_order(&IC_1_Y),
-            ),
-        ];
-
-        Self {
-            alpha_g1,
-            beta_g2,
-            gamma_g2,
-            delta_g2,
-            ic,
-        }
-    }
-}
-
-// ============================================================================
-// STORAGE CONTRACT
-// ============================================================================
+/// How long a staged verification session stays open after it is started
+pub const SESSION_TIMEOUT_SECS: u64 = 60 * 60;
 
 #[storage]
-#[entrypoint]
+#[cfg_attr(
+    not(any(
+        feature = "gateway",
+        feature = "bridge",
+        feature = "records",
+        feature = "delegations",
+        feature = "policies",
+        feature = "assets"
+    )),
+    entrypoint
+)]
 pub struct ZKVerifier {
     owner: StorageAddress,
     verified_count: StorageU256,
     initialized: StorageBool,
+    circuits: StorageMap<FixedBytes<32>, StoredCircuit>,
+    sessions: StorageMap<U256, VerificationSession>,
+    session_count: StorageU256,
+    /// Key fingerprints that no longer verify, see `mark_key_compromised`
+    compromised_keys: StorageMap<FixedBytes<32>, StorageBool>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(any(
+    feature = "gateway",
+    feature = "bridge",
+    feature = "records",
+    feature = "delegations",
+    feature = "policies",
+    feature = "assets"
+))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for ZKVerifier {}
+
+/// Verifying key registered by the owner under a circuit id
+#[storage]
+pub struct StoredCircuit {
+    /// `VerifyingKey::to_bytes` encoding in 32-byte words (it is always whole
+    /// words), empty if unregistered
+    vk: StorageVec<StorageFixedBytes<32>>,
+    input_mode: StorageU8,
+}
+
+/// Staged verification of one statement across several transactions
+///
+/// The circuit and proof are fixed when the session starts and inputs can
+/// only be appended in order, so the partial MSM always belongs to the
+/// statement the creator committed to.
+#[storage]
+pub struct VerificationSession {
+    creator: StorageAddress,
+    circuit_id: StorageFixedBytes<32>,
+    /// Uncompressed proof in 32-byte words
+    proof: StorageArray<StorageFixedBytes<32>, 8>,
+    expires_at: StorageU64,
+    /// Total public inputs the circuit expects
+    input_count: StorageU32,
+    /// Index of the next public input to accumulate
+    next_input: StorageU32,
+    /// Partial IC[0] + Σ IC[i] · input[i], affine; (0, 0) is the point at infinity
+    acc_x: StorageU256,
+    acc_y: StorageU256,
 }
 
 // ============================================================================
@@ -337,7 +191,48 @@ impl ZKVerifier {
         Ok(())
     }
 
-    /// Verify Groth16 proof
+    /// Register a verifying key under a circuit id (owner only)
+    ///
+    /// @param vk_bytes: alpha || beta || gamma || delta || IC (see `VerifyingKey::from_bytes`),
+    ///                 checked through the precompiles (`precompile::check_key`)
+    /// @param input_mode: 0 = direct, 1 = keccak256 input hash, 2 = sha256 input hash
+    ///
+    /// Keys are immutable once registered
+    pub fn register_circuit(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        vk_bytes: Bytes,
+        input_mode: u8,
+    ) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        let mode = InputMode::from_u8(input_mode)?;
+        let ic_len = precompile::check_key(&mut Precompiles, &vk_bytes)?;
+        if mode.required_ic_len().is_some_and(|len| len != ic_len) {
+            return Err(b"Hashed input mode needs a two-point IC".to_vec());
+        }
+        if !self.circuits.getter(circuit_id).vk.is_empty() {
+            return Err(b"Circuit already registered".to_vec());
+        }
+        if self.compromised_keys.get(crypto::keccak(&vk_bytes)) {
+            return Err(b"Verifying key is compromised".to_vec());
+        }
+
+        let mut circuit = self.circuits.setter(circuit_id);
+        for word in vk_bytes.as_chunks::<32>().0 {
+            circuit.vk.push(FixedBytes(*word));
+        }
+        circuit.input_mode.set(U8::from(input_mode));
+
+        let _ = evm::raw_log(
+            &[B256::from(CIRCUIT_REGISTERED), circuit_id],
+            &U256::from(input_mode).to_be_bytes::<32>(),
+        );
+        console!("✓ Circuit registered");
+        Ok(())
+    }
+
+    /// Verify Groth16 proof against the key registered under `MULTIPLIER_CIRCUIT_ID`
     /// 
     /// @param proof_bytes: 256 bytes (uncompressed: A.x || A.y || B.x0 || B.x1 || B.y0 || B.y1 || C.x || C.y)
     /// @param public_inputs: array of 32-byte field elements (BigEndian)
@@ -349,56 +244,304 @@ impl ZKVerifier {
         public_inputs: Vec<Vec<u8>>,
     ) -> Result<bool, Vec<u8>> {
         console!("=== GROTH16 VERIFICATION START ===");
+        self.verify_with_precompile(proof_bytes, public_inputs)
+    }
+
+    /// Verify through the bn256Add/Mul/Pairing precompiles (0x06-0x08)
+    /// against the same key as `verify`; every entrypoint verifies this
+    /// way, so the program carries no pairing code
+    pub fn verify_with_precompile(
+        &mut self,
+        proof_bytes: Vec<u8>,
+        public_inputs: Vec<Vec<u8>>,
+    ) -> Result<bool, Vec<u8>> {
+        console!("=== PRECOMPILE VERIFICATION ===");
+
+        let words = public_inputs
+            .iter()
+            .map(|input| <[u8; WORD_SIZE]>::try_from(&input[..]))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| b"Public input must be 32 bytes".to_vec())?;
+        let vk = self.load_direct_circuit(&MULTIPLIER_CIRCUIT_ID)?;
 
-        // Parse proof
-        let proof = Groth16Proof::from_uncompressed_bytes(&proof_bytes)?;
-        console!("✓ Proof parsed");
+        let valid = precompile::verify(&mut Precompiles, &vk, &proof_bytes, &words)?;
+
+        if valid {
+            self.increment_verified_count();
+            console!("✓ Precompile verification passed");
+        }
 
-        // Parse public inputs
-        let public_inputs_fr = Self::parse_public_inputs(&public_inputs)?;
-        console!("✓ Public inputs: {} elements", public_inputs_fr.len());
+        Ok(valid)
+    }
 
-        // Load verifying key
-        let vk = VerifyingKey::load_real();
-        console!("✓ Verifying key loaded");
+    /// Verify Groth16 proof from a single tightly packed byte string
+    ///
+    /// @param packed: circuit_id (32) || proof (256) || public inputs (32 each, BigEndian)
+    ///
+    /// Parsed in place: no ABI offsets or per-input allocations
+    pub fn verify_packed(&mut self, packed: Bytes) -> Result<bool, Vec<u8>> {
+        console!("=== PACKED VERIFICATION ===");
 
-        // Verify using native WASM
-        let valid = Self::verify_groth16(&proof, &public_inputs_fr, &vk)?;
+        let call = PackedCall::parse(&packed)?;
+        let (vk, mode) = self.load_circuit(call.circuit_id)?;
+        let valid = Self::check_statement(&vk, mode, call.proof, call.inputs)?;
 
         if valid {
-            let count = self.verified_count.get();
-            self.verified_count.set(count + U256::from(1));
-            console!("✓✓✓ PROOF VALID! Count: {}", count + U256::from(1));
-        } else {
-            console!("✗✗✗ PROOF INVALID");
+            self.increment_verified_count();
+            console!("✓ Packed verification passed");
         }
 
         Ok(valid)
     }
 
-    /// Verify using Arbitrum precompile (most gas efficient)
-    pub fn verify_with_precompile(
+    /// Verify a proof whose only public input is the hash of the real inputs
+    ///
+    /// @param inputs: packed 32-byte field elements (BigEndian), hashed with the
+    ///                circuit's input mode and reduced mod r
+    ///
+    /// Returns the decoded inputs; reverts if the proof is invalid
+    pub fn verify_hashed(
         &mut self,
-        proof_bytes: Vec<u8>,
-        public_inputs: Vec<Vec<u8>>,
-    ) -> Result<bool, Vec<u8>> {
-        console!("=== PRECOMPILE VERIFICATION ===");
+        circuit_id: FixedBytes<32>,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<Vec<U256>, Vec<u8>> {
+        console!("=== HASHED INPUT VERIFICATION ===");
+
+        let (vk, mode) = self.load_circuit(&circuit_id.0)?;
+        let (words, remainder) = inputs.as_chunks::<WORD_SIZE>();
+        if !remainder.is_empty() {
+            return Err(b"Public inputs must be 32-byte words".to_vec());
+        }
+        let digest = precompile::digest_word(&mut Precompiles, mode, &inputs)?
+            .ok_or_else(|| b"Circuit does not hash its inputs".to_vec())?;
+
+        if !precompile::verify(&mut Precompiles, &vk, &proof_bytes, &[digest])? {
+            return Err(b"Invalid proof".to_vec());
+        }
+
+        self.increment_verified_count();
+        console!("✓ Hashed verification passed: {} inputs", words.len());
+
+        Ok(words.iter().map(|word| U256::from_be_bytes(*word)).collect())
+    }
+
+    /// Verify a proof under a registered circuit for another contract,
+    /// e.g. `ComplianceRecords` before it writes a record
+    ///
+    /// @param inputs: packed 32-byte public inputs (BigEndian); hashed with
+    ///                the circuit's input mode for hashed input circuits
+    ///
+    /// Reverts if the proof is invalid or its key is marked compromised.
+    ///
+    /// Returns the key's fingerprint, as `get_key_fingerprint`
+    pub fn verify_statement(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        proof_bytes: Bytes,
+        inputs: Bytes,
+    ) -> Result<FixedBytes<32>, Vec<u8>> {
+        let (vk, mode) = self.load_circuit(&circuit_id.0)?;
+        let (words, remainder) = inputs.as_chunks::<WORD_SIZE>();
+        if !remainder.is_empty() {
+            return Err(b"Public inputs must be 32-byte words".to_vec());
+        }
+        let fingerprint = crypto::keccak(&vk);
+        if self.compromised_keys.get(fingerprint) {
+            return Err(b"Verifying key is compromised".to_vec());
+        }
+        if !Self::check_statement(&vk, mode, &proof_bytes, words)? {
+            return Err(b"Invalid proof".to_vec());
+        }
+
+        self.increment_verified_count();
+        Ok(fingerprint)
+    }
+
+    /// (input mode, public input count) of a registered circuit; the count
+    /// is of the circuit's own inputs, so 1 for hashed input circuits
+    pub fn get_circuit(&self, circuit_id: FixedBytes<32>) -> Result<(u8, u32), Vec<u8>> {
+        let mode = self.circuit_input_mode(&circuit_id.0)?;
+        let input_count = (self.circuit_vk_len(&circuit_id.0)? - VK_HEADER_SIZE) / 64 - 1;
+        Ok((mode.as_u8(), input_count as u32))
+    }
+
+    /// Start a staged verification for statements too large for one transaction
+    ///
+    /// Fixes the circuit and proof; inputs follow through `add_session_inputs`
+    /// and the pairing runs in `finish_session`. Only the caller can continue
+    /// the session, and only for `SESSION_TIMEOUT_SECS`.
+    ///
+    /// Returns the session id
+    pub fn start_session(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        proof_bytes: Bytes,
+    ) -> Result<U256, Vec<u8>> {
+        if self.circuit_input_mode(&circuit_id.0)? != InputMode::Direct {
+            return Err(b"Sessions need a direct input circuit".to_vec());
+        }
+        precompile::check_proof(&proof_bytes)?;
+
+        let vk_len = self.circuit_vk_len(&circuit_id.0)?;
+        let input_count = (vk_len - VK_HEADER_SIZE) / 64 - 1;
+        let ic_0 = self.read_circuit_vk(&circuit_id.0, VK_HEADER_SIZE, 64)?;
+
+        let session_id = self.session_count.get() + U256::from(1);
+        self.session_count.set(session_id);
+
+        let mut session = self.sessions.setter(session_id);
+        session.creator.set(msg::sender());
+        session.circuit_id.set(circuit_id);
+        for (index, word) in proof_bytes.as_chunks::<32>().0.iter().enumerate() {
+            if let Some(mut slot) = session.proof.setter(index) {
+                slot.set(FixedBytes(*word));
+            }
+        }
+        session.expires_at.set(U64::from(block::timestamp() + SESSION_TIMEOUT_SECS));
+        session.input_count.set(U32::from(input_count));
+        session.next_input.set(U32::ZERO);
+        Self::store_accumulator(&mut session, &ic_0);
+
+        console!("✓ Session {} started: {} inputs expected", session_id, input_count);
+        Ok(session_id)
+    }
+
+    /// Accumulate the next chunk of public inputs into a session's partial MSM
+    ///
+    /// @param inputs: packed 32-byte field elements (BigEndian), continuing
+    ///                where the previous chunk stopped
+    ///
+    /// Returns how many inputs are still missing
+    pub fn add_session_inputs(&mut self, session_id: U256, inputs: Bytes) -> Result<u32, Vec<u8>> {
+        self.open_session(session_id)?;
+
+        let (words, remainder) = inputs.as_chunks::<WORD_SIZE>();
+        if !remainder.is_empty() {
+            return Err(b"Public inputs must be 32-byte words".to_vec());
+        }
+
+        let session = self.sessions.getter(session_id);
+        let circuit_id = session.circuit_id.get();
+        let next = session.next_input.get().to::<usize>();
+        let total = session.input_count.get().to::<usize>();
+        let acc = Self::load_accumulator(&session);
+
+        if next + words.len() > total {
+            return Err(b"Too many public inputs".to_vec());
+        }
+
+        // IC[next + 1 ..= next + n]: only the stored words this chunk needs
+        let ic_bytes = self.read_circuit_vk(
+            &circuit_id.0,
+            VK_HEADER_SIZE + 64 * (next + 1),
+            64 * words.len(),
+        )?;
+        let acc = precompile::accumulate_ic(&mut Precompiles, acc, &ic_bytes, words)?;
+
+        let mut session = self.sessions.setter(session_id);
+        Self::store_accumulator(&mut session, &acc);
+        session.next_input.set(U32::from(next + words.len()));
+
+        let remaining = (total - next - words.len()) as u32;
+        console!("✓ Session {}: {} inputs remaining", session_id, remaining);
+        Ok(remaining)
+    }
+
+    /// Run the pairing check once every input has been accumulated
+    ///
+    /// Closes the session either way; returns true if the proof is valid
+    pub fn finish_session(&mut self, session_id: U256) -> Result<bool, Vec<u8>> {
+        self.open_session(session_id)?;
 
-        let proof = Groth16Proof::from_uncompressed_bytes(&proof_bytes)?;
-        let public_inputs_fr = Self::parse_public_inputs(&public_inputs)?;
-        let vk = VerifyingKey::load_real();
+        let session = self.sessions.getter(session_id);
+        if session.next_input.get() != session.input_count.get() {
+            return Err(b"Session inputs incomplete".to_vec());
+        }
+        let circuit_id = session.circuit_id.get();
+        let words: Vec<[u8; 32]> =
+            (0..PROOF_SIZE / 32).map(|index| session.proof.get(index).unwrap_or_default().0).collect();
+        let proof = precompile::check_proof(words.as_flattened())?;
+        let commitment = Self::load_accumulator(&session);
 
-        let valid = Self::verify_with_bn256_precompile(&proof, &public_inputs_fr, &vk)?;
+        // alpha, beta, gamma and delta; IC is already in the accumulator
+        let vk_header = self.read_circuit_vk(&circuit_id.0, 0, VK_HEADER_SIZE)?;
+        let valid = precompile::pairing_check(&mut Precompiles, proof, &commitment, &vk_header)?;
 
+        self.clear_session(session_id);
         if valid {
-            let count = self.verified_count.get();
-            self.verified_count.set(count + U256::from(1));
-            console!("✓ Precompile verification passed");
+            self.increment_verified_count();
+            console!("✓ Session {} verified", session_id);
         }
 
         Ok(valid)
     }
 
+    /// Drop a session: any time by its creator, by anyone once it has expired
+    pub fn cancel_session(&mut self, session_id: U256) -> Result<(), Vec<u8>> {
+        let session = self.sessions.getter(session_id);
+        let creator = session.creator.get();
+        if creator.is_zero() {
+            return Err(b"Unknown session".to_vec());
+        }
+        let expired = block::timestamp() >= session.expires_at.get().to::<u64>();
+
+        if msg::sender() != creator && !expired {
+            return Err(b"Caller is not the session creator".to_vec());
+        }
+
+        self.clear_session(session_id);
+        Ok(())
+    }
+
+    /// Session progress: (creator, circuit id, expires at, inputs accumulated, inputs expected)
+    pub fn get_session(&self, session_id: U256) -> Result<(Address, FixedBytes<32>, u64, u32, u32), Vec<u8>> {
+        let session = self.sessions.getter(session_id);
+        Ok((
+            session.creator.get(),
+            session.circuit_id.get(),
+            session.expires_at.get().to::<u64>(),
+            session.next_input.get().to::<u32>(),
+            session.input_count.get().to::<u32>(),
+        ))
+    }
+
+    /// Fingerprint of a circuit's key: keccak256 of `VerifyingKey::to_bytes`
+    ///
+    /// Matches `arbshield export-vk` / `arbshield fingerprint` on the key the
+    /// circuit was set up with, so a deployment can be checked against its .zkey
+    pub fn get_key_fingerprint(&self, circuit_id: FixedBytes<32>) -> Result<FixedBytes<32>, Vec<u8>> {
+        let (vk, _) = self.load_circuit(&circuit_id.0)?;
+        Ok(crypto::keccak(&vk))
+    }
+
+    /// Void every record proven under a key, e.g. after its trusted setup
+    /// is found broken (owner only)
+    ///
+    /// Records keep the fingerprint of the key that checked them, so this is
+    /// one write however many records exist, and `ComplianceRecords` reads
+    /// one flag per record. The key can no longer be registered or verify
+    /// statements. There is no undo: re-prove under a new setup instead.
+    /// History is voided too: `is_compliant_at` skips checkpoints the key
+    /// proved, since a forgeable proof never showed compliance.
+    pub fn mark_key_compromised(&mut self, fingerprint: FixedBytes<32>) -> Result<(), Vec<u8>> {
+        self.only_owner()?;
+
+        if self.compromised_keys.get(fingerprint) {
+            return Err(b"Key already compromised".to_vec());
+        }
+        self.compromised_keys.insert(fingerprint, true);
+
+        let _ = evm::raw_log(&[B256::from(KEY_COMPROMISED), fingerprint], &[]);
+        console!("✓ Key marked compromised");
+        Ok(())
+    }
+
+    /// Whether `mark_key_compromised` was called for a fingerprint
+    pub fn is_key_compromised(&self, fingerprint: FixedBytes<32>) -> Result<bool, Vec<u8>> {
+        Ok(self.compromised_keys.get(fingerprint))
+    }
+
     pub fn get_verified_count(&self) -> Result<U256, Vec<u8>> {
         Ok(self.verified_count.get())
     }
@@ -409,174 +552,479 @@ impl ZKVerifier {
 // ============================================================================
 
 impl ZKVerifier {
-    fn parse_public_inputs(inputs: &[Vec<u8>]) -> Result<Vec<Fr>, Vec<u8>> {
-        let mut result = Vec::new();
-        for input_bytes in inputs {
-            if input_bytes.len() != 32 {
-                return Err(b"Public input must be 32 bytes".to_vec());
-            }
-            result.push(Fr::from_be_bytes_mod_order(input_bytes));
+    fn only_owner(&self) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.owner.get() {
+            return Err(b"Caller is not the owner".to_vec());
         }
-        Ok(result)
+        Ok(())
     }
 
-    /// REAL Groth16 verification
-    /// 
-    /// Checks: e(A, B) = e(α, β) · e(L, γ) · e(C, δ)
-    /// 
-    /// Rearranged as: e(A, B) · e(-α, β) · e(-L, γ) · e(-C, δ) = 1
-    /// 
-    /// **CRITICAL FIX**: Checks result == GT::one(), NOT is_zero()!
-    fn verify_groth16(
-        proof: &Groth16Proof,
-        public_inputs: &[Fr],
-        vk: &VerifyingKey,
-    ) -> Result<bool, Vec<u8>> {
-        // Validate input count
-        if public_inputs.len() + 1 != vk.ic.len() {
-            return Err(b"Invalid number of public inputs".to_vec());
-        }
+    fn increment_verified_count(&mut self) {
+        let count = self.verified_count.get();
+        self.verified_count.set(count + U256::from(1));
+    }
 
-        // Compute L = IC[0] + Σ(IC[i] · public_input[i])
-        let mut acc = vk.ic[0].into_group();
-        for (i, input) in public_inputs.iter().enumerate() {
-            acc += vk.ic[i + 1].mul_bigint(input.into_bigint());
+    /// Resolve a circuit id to its registered key
+    ///
+    /// There are no built-in keys: the multiplier constants in core come
+    /// from a public seed, so every id, `MULTIPLIER_CIRCUIT_ID` included,
+    /// must be registered by the owner before proofs verify under it
+    ///
+    /// Returns the key's `VerifyingKey::to_bytes` encoding, checked by
+    /// `precompile::check_key` when it was registered
+    fn load_circuit(&self, circuit_id: &[u8; 32]) -> Result<(Vec<u8>, InputMode), Vec<u8>> {
+        let circuit = self.circuits.getter(FixedBytes(*circuit_id));
+        if circuit.vk.is_empty() {
+            return Err(b"Unknown circuit id".to_vec());
         }
-        let public_inputs_commitment = acc.into_affine();
-
-        console!("✓ Public input commitment computed");
+        let vk = read_words(&circuit.vk, 0, 32 * circuit.vk.len()).unwrap_or_default();
+        let mode = InputMode::from_u8(circuit.input_mode.get().to::<u8>())?;
+        Ok((vk, mode))
+    }
 
-        // Prepare pairing equation
-        let pairing_inputs = [
-            (proof.a.into(), proof.b.into()),
-            ((-vk.alpha_g1).into(), vk.beta_g2.into()),
-            ((-public_inputs_commitment).into(), vk.gamma_g2.into()),
-            ((-proof.c).into(), vk.delta_g2.into()),
-        ];
+    /// Key of a circuit whose public inputs are passed directly, as
+    /// `verify` and `verify_with_precompile` take them
+    fn load_direct_circuit(&self, circuit_id: &[u8; 32]) -> Result<Vec<u8>, Vec<u8>> {
+        match self.load_circuit(circuit_id)? {
+            (vk, InputMode::Direct) => Ok(vk),
+            _ => Err(b"Circuit takes hashed inputs".to_vec()),
+        }
+    }
 
-        console!("✓ Computing multi-pairing...");
+    /// Input mode of a circuit, without decoding its key
+    fn circuit_input_mode(&self, circuit_id: &[u8; 32]) -> Result<InputMode, Vec<u8>> {
+        let circuit = self.circuits.getter(FixedBytes(*circuit_id));
+        if circuit.vk.is_empty() {
+            return Err(b"Unknown circuit id".to_vec());
+        }
+        InputMode::from_u8(circuit.input_mode.get().to::<u8>())
+    }
 
-        // Compute product of pairings
-        let result = Bn254::multi_pairing(pairing_inputs);
+    /// Encoded length of a circuit's key
+    fn circuit_vk_len(&self, circuit_id: &[u8; 32]) -> Result<usize, Vec<u8>> {
+        match self.circuits.getter(FixedBytes(*circuit_id)).vk.len() {
+            0 => Err(b"Unknown circuit id".to_vec()),
+            words => Ok(32 * words),
+        }
+    }
 
-        // **CRITICAL**: GT group identity is ONE, not zero!
-        let is_valid = result.0 == ark_bn254::Fq12::one();
+    /// Bytes `[start, start + len)` of a circuit's encoded key
+    ///
+    /// Reads only the storage words that cover the range, one load each, so
+    /// a session chunk does not pay for loading the whole IC
+    fn read_circuit_vk(&self, circuit_id: &[u8; 32], start: usize, len: usize) -> Result<Vec<u8>, Vec<u8>> {
+        let circuit = self.circuits.getter(FixedBytes(*circuit_id));
+        read_words(&circuit.vk, start, len).ok_or_else(|| b"Key range out of bounds".to_vec())
+    }
 
-        console!("✓ Pairing computed, result: {}", is_valid);
+    /// Verify a proof against packed public input words, hashing them first
+    /// for hashed input circuits
+    fn check_statement(vk: &[u8], mode: InputMode, proof: &[u8], words: &[[u8; WORD_SIZE]]) -> Result<bool, Vec<u8>> {
+        match precompile::digest_word(&mut Precompiles, mode, words.as_flattened())? {
+            Some(digest) => precompile::verify(&mut Precompiles, vk, proof, &[digest]),
+            None => precompile::verify(&mut Precompiles, vk, proof, words),
+        }
+    }
 
-        Ok(is_valid)
+    /// Check the caller may continue a session that has not expired
+    fn open_session(&self, session_id: U256) -> Result<(), Vec<u8>> {
+        let session = self.sessions.getter(session_id);
+        let creator = session.creator.get();
+        if creator.is_zero() {
+            return Err(b"Unknown session".to_vec());
+        }
+        if msg::sender() != creator {
+            return Err(b"Caller is not the session creator".to_vec());
+        }
+        if block::timestamp() >= session.expires_at.get().to::<u64>() {
+            return Err(b"Session expired".to_vec());
+        }
+        Ok(())
     }
 
-    /// Verify using Arbitrum bn256Pairing precompile (address 0x08)
-    /// 
-    /// This is THE MOST GAS EFFICIENT method
-    /// Gas cost: ~34k per pairing (vs ~180k in WASM)
-    fn verify_with_bn256_precompile(
-        proof: &Groth16Proof,
-        public_inputs: &[Fr],
-        vk: &VerifyingKey,
-    ) -> Result<bool, Vec<u8>> {
-        // Compute public input commitment
-        let mut acc = vk.ic[0].into_group();
-        for (i, input) in public_inputs.iter().enumerate() {
-            acc += vk.ic[i + 1].mul_bigint(input.into_bigint());
-        }
-        let l = acc.into_affine();
-
-        // Encode pairing check for precompile
-        // Format: (G1_x, G1_y, G2_x0, G2_x1, G2_y0, G2_y1) repeated
-        let mut input = Vec::with_capacity(768); // 4 pairings * 192 bytes
-
-        // Helper to append G1 point
-        let mut append_g1 = |point: &G1Affine, buf: &mut Vec<u8>| {
-            let mut x_bytes = [0u8; 32];
-            let mut y_bytes = [0u8; 32];
-            point.x.serialize_uncompressed(&mut x_bytes[..]).unwrap();
-            point.y.serialize_uncompressed(&mut y_bytes[..]).unwrap();
-            buf.extend_from_slice(&x_bytes);
-            buf.extend_from_slice(&y_bytes);
-        };
+    fn clear_session(&mut self, session_id: U256) {
+        let mut session = self.sessions.setter(session_id);
+        session.creator.set(Address::ZERO);
+        session.circuit_id.set(FixedBytes::ZERO);
+        for index in 0..PROOF_SIZE / 32 {
+            if let Some(mut slot) = session.proof.setter(index) {
+                slot.set(FixedBytes::ZERO);
+            }
+        }
+        session.expires_at.set(U64::ZERO);
+        session.input_count.set(U32::ZERO);
+        session.next_input.set(U32::ZERO);
+        session.acc_x.set(U256::ZERO);
+        session.acc_y.set(U256::ZERO);
+    }
 
-        // Helper to append G2 point
-        let mut append_g2 = |point: &G2Affine, buf: &mut Vec<u8>| {
-            let mut x0_bytes = [0u8; 32];
-            let mut x1_bytes = [0u8; 32];
-            let mut y0_bytes = [0u8; 32];
-            let mut y1_bytes = [0u8; 32];
-            point.x.c0.serialize_uncompressed(&mut x0_bytes[..]).unwrap();
-            point.x.c1.serialize_uncompressed(&mut x1_bytes[..]).unwrap();
-            point.y.c0.serialize_uncompressed(&mut y0_bytes[..]).unwrap();
-            point.y.c1.serialize_uncompressed(&mut y1_bytes[..]).unwrap();
-            buf.extend_from_slice(&x0_bytes);
-            buf.extend_from_slice(&x1_bytes);
-            buf.extend_from_slice(&y0_bytes);
-            buf.extend_from_slice(&y1_bytes);
-        };
+    /// The session's partial commitment in the precompile encoding, (0, 0) for infinity
+    fn load_accumulator(session: &VerificationSession) -> [u8; 64] {
+        let mut acc = [0u8; 64];
+        acc[..32].copy_from_slice(&session.acc_x.get().to_be_bytes::<32>());
+        acc[32..].copy_from_slice(&session.acc_y.get().to_be_bytes::<32>());
+        acc
+    }
 
-        // Pairing 1: e(A, B)
-        append_g1(&proof.a, &mut input);
-        append_g2(&proof.b, &mut input);
+    fn store_accumulator(session: &mut VerificationSession, acc: &[u8]) {
+        session.acc_x.set(U256::from_be_slice(&acc[..32]));
+        session.acc_y.set(U256::from_be_slice(&acc[32..64]));
+    }
+}
 
-        // Pairing 2: e(-α, β)
-        append_g1(&(-vk.alpha_g1), &mut input);
-        append_g2(&vk.beta_g2, &mut input);
+/// The sha256 and BN254 precompiles, through static calls, and the host's keccak256
+struct Precompiles;
 
-        // Pairing 3: e(-L, γ)
-        append_g1(&(-l), &mut input);
-        append_g2(&vk.gamma_g2, &mut input);
+impl precompile::Precompiles for Precompiles {
+    fn call(&mut self, id: u8, input: &[u8]) -> Option<Vec<u8>> {
+        // SAFETY: precompiles are stateless and cannot re-enter this contract
+        unsafe { RawCall::new_static().call(Address::with_last_byte(id), input) }.ok()
+    }
 
-        // Pairing 4: e(-C, δ)
-        append_g1(&(-proof.c), &mut input);
-        append_g2(&vk.delta_g2, &mut input);
+    fn keccak(&mut self, bytes: &[u8]) -> [u8; 32] {
+        crypto::keccak(bytes).0
+    }
+}
 
-        // Call precompile at address 0x08
-        let precompile_addr = Address::from([
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8
-        ]);
+/// Bytes `[start, start + len)` of a byte string stored as 32-byte words,
+/// loading only the words that cover them; `None` past the end
+fn read_words(words: &StorageVec<StorageFixedBytes<32>>, start: usize, len: usize) -> Option<Vec<u8>> {
+    let end = start.checked_add(len).filter(|end| *end <= 32 * words.len())?;
+    let covering = (start / 32..end.div_ceil(32)).map(|index| words.get(index).map(|word| word.0));
+    let bytes = covering.collect::<Option<Vec<[u8; 32]>>>()?;
+    let offset = start % 32;
+    Some(bytes.as_flattened()[offset..offset + len].to_vec())
+}
 
-        let result = RawCall::new()
-            .call(precompile_addr, &input)
-            .map_err(|_| b"Precompile call failed".to_vec())?;
+fn read_jurisdictions(
+    words: &StorageArray<StorageFixedBytes<32>, 4>,
+) -> Result<jurisdiction::JurisdictionSet, Vec<u8>> {
+    let words: Vec<[u8; 32]> = (0..jurisdiction::WORDS).map(|index| words.get(index).unwrap_or_default().0).collect();
+    jurisdiction::JurisdictionSet::from_words(&words)
+}
 
-        // Precompile returns 32 bytes: 0x01 if valid, 0x00 if invalid
-        Ok(result.len() == 32 && result[31] == 1)
+fn write_jurisdictions(words: &mut StorageArray<StorageFixedBytes<32>, 4>, set: &jurisdiction::JurisdictionSet) {
+    for (index, word) in set.words().iter().enumerate() {
+        if let Some(mut slot) = words.setter(index) {
+            slot.set(FixedBytes(*word));
+        }
     }
 }
 
-// ============================================================================
-// TESTS
-// ============================================================================
+// No formatting or unwinding in the program: a panic just traps
+#[cfg(all(target_arch = "wasm32", not(any(feature = "std", feature = "export-abi"))))]
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    core::arch::wasm32::unreachable()
+}
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "export-abi")))]
 mod tests {
     use super::*;
+    use arbshield_verifier_core::{parse_public_inputs, verify_groth16, Groth16Proof, VerifyingKey};
+    use ark_bn254::Fr;
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+    use proptest::prelude::*;
+    use stylus_sdk::alloy_primitives::hex;
+    use testing::{
+        packed_inputs, prove_squares, selector, squares_keys, statement, word, TestHost, DEFAULT_SENDER,
+        GENESIS_TIMESTAMP,
+    };
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const CIRCUIT: FixedBytes<32> = FixedBytes([0x42; 32]);
+
+    /// Multiplier proof for a = 3, b = 5 (public input c = 15) under the committed key
+    const MULTIPLIER_PROOF: [u8; 256] = hex!(
+        "060ad445c57fa7577d31023c7e34a3d45c8dd3d7d909f70fd3f886a804543a82"
+        "271e3204a02c8c5b1666271797bced8e15afe57837e1c0b4c30e6c326c25d10d"
+        "1f1424dd7faddb82b736524c103485c687cc87ec3a1bbd99e581eb8a9758aff4"
+        "1cdc272aff80b234e10a29994249ef189375c6a276b111bdf84d31de68ad8e6c"
+        "175c0d5268f6a734a0cc8f4129504192547bb91a2e6bb7792386c67dd17481d7"
+        "0716c74441d420b6fd02389f198c16c4713015ca44c1a1bc7c3e6efbbc3e46e7"
+        "2c4902877de2a1f8a31419b9890f5ff47e7bbecd304aa749ea1d3b52d4594e8f"
+        "1bc68e147b81c555abfa3f923434edbd3e323999ade004c679ce8e41398ecf64"
+    );
+
+    /// A fresh valid proof for the same statement: (A / r, r·B + r·s·δ, C + s·A)
+    fn rerandomize(r: u64, s: u64) -> Vec<u8> {
+        rerandomize_proof(&MULTIPLIER_PROOF, &VerifyingKey::load_real(), r, s)
+    }
+
+    /// `rerandomize` for any uncompressed proof under `vk`
+    fn rerandomize_proof(proof: &[u8], vk: &VerifyingKey, r: u64, s: u64) -> Vec<u8> {
+        let proof = Groth16Proof::from_uncompressed_bytes(proof).unwrap();
+        let (r, s) = (Fr::from(r), Fr::from(s));
+        let delta = vk.delta_g2;
+        Groth16Proof {
+            a: (proof.a * r.inverse().unwrap()).into_affine(),
+            b: (proof.b * r + delta * (r * s)).into_affine(),
+            c: (proof.c + proof.a * s).into_affine(),
+        }
+        .to_uncompressed_bytes()
+        .to_vec()
+    }
+
+    /// Host with an initialized verifier and the multiplier key registered as `CIRCUIT`
+    fn deployed() -> TestHost {
+        let host = TestHost::new();
+        host.transact(|verifier| verifier.initialize(OWNER)).unwrap();
+        host.transact(|verifier| {
+            verifier.register_circuit(CIRCUIT, VerifyingKey::load_real().to_bytes().into(), 0)
+        })
+        .unwrap();
+        host
+    }
+
+    #[test]
+    fn test_initialize_and_access_control() {
+        let host = TestHost::new();
+        host.transact(|verifier| verifier.initialize(OWNER)).unwrap();
+        assert_eq!(host.storage(U256::ZERO), B256::left_padding_from(OWNER.as_slice()));
+        assert_eq!(
+            host.transact(|verifier| verifier.initialize(ALICE)),
+            Err(b"Already initialized".to_vec())
+        );
+
+        let vk_bytes: Bytes = VerifyingKey::load_real().to_bytes().into();
+        host.set_sender(ALICE);
+        assert_eq!(
+            host.transact(|verifier| verifier.register_circuit(CIRCUIT, vk_bytes.clone(), 0)),
+            Err(b"Caller is not the owner".to_vec())
+        );
+        assert!(host.take_logs().is_empty());
+
+        host.set_sender(OWNER);
+        host.transact(|verifier| verifier.register_circuit(CIRCUIT, vk_bytes.clone(), 0)).unwrap();
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(CIRCUIT_REGISTERED), CIRCUIT]);
+        assert_eq!(logs[0].data, word(0));
+
+        assert_eq!(
+            host.transact(|verifier| verifier.register_circuit(CIRCUIT, vk_bytes.clone(), 0)),
+            Err(b"Circuit already registered".to_vec())
+        );
+        assert!(host.take_logs().is_empty());
+
+        // No id is reserved for a built-in key, the multiplier's included
+        host.transact(|verifier| verifier.register_circuit(FixedBytes(MULTIPLIER_CIRCUIT_ID), vk_bytes, 0)).unwrap();
+        assert_eq!(host.take_logs().len(), 1);
+
+        let fingerprint = host.contract().get_key_fingerprint(CIRCUIT).unwrap();
+        assert_eq!(fingerprint.0, VerifyingKey::load_real().fingerprint());
+        assert!(host.contract().get_key_fingerprint(FixedBytes([7; 32])).is_err());
+    }
 
     #[test]
-    fn test_vk_loading() {
-        let vk = VerifyingKey::load_real();
-        
-        // Verify points are on curve
-        assert!(vk.alpha_g1.is_on_curve());
-        assert!(vk.beta_g2.is_on_curve());
-        assert!(vk.gamma_g2.is_on_curve());
-        assert!(vk.delta_g2.is_on_curve());
-        
-        for ic_point in &vk.ic {
-            assert!(ic_point.is_on_curve());
-        }
-        
-        println!("✓ VK loaded and validated");
+    fn test_verify_counts_valid_proofs() {
+        let host = deployed();
+        let proof = MULTIPLIER_PROOF.to_vec();
+        let count = || host.contract().get_verified_count().unwrap();
+
+        // The dev key in core is never used on-chain: nothing verifies until
+        // the owner registers the multiplier's key
+        let packed = [&MULTIPLIER_CIRCUIT_ID[..], &proof, &word(15)].concat();
+        assert_eq!(
+            host.transact(|verifier| verifier.verify(proof.clone(), vec![word(15).to_vec()])),
+            Err(b"Unknown circuit id".to_vec())
+        );
+        assert_eq!(
+            host.transact(|verifier| verifier.verify_with_precompile(proof.clone(), vec![word(15).to_vec()])),
+            Err(b"Unknown circuit id".to_vec())
+        );
+        assert_eq!(
+            host.transact(|verifier| verifier.verify_packed(packed.into())),
+            Err(b"Unknown circuit id".to_vec())
+        );
+        let vk_bytes: Bytes = VerifyingKey::load_real().to_bytes().into();
+        host.transact(|verifier| verifier.register_circuit(FixedBytes(MULTIPLIER_CIRCUIT_ID), vk_bytes, 0)).unwrap();
+
+        let valid = host.transact(|verifier| verifier.verify(proof.clone(), vec![word(15).to_vec()]));
+        assert_eq!(valid, Ok(true));
+        let invalid = host.transact(|verifier| verifier.verify(proof.clone(), vec![word(16).to_vec()]));
+        assert_eq!(invalid, Ok(false));
+        assert_eq!(count(), U256::from(1));
+
+        // The precompile path goes through the emulated 0x08 and must agree
+        let valid = host.transact(|verifier| verifier.verify_with_precompile(proof.clone(), vec![word(15).to_vec()]));
+        assert_eq!(valid, Ok(true));
+        let invalid = host.transact(|verifier| verifier.verify_with_precompile(proof.clone(), vec![word(16).to_vec()]));
+        assert_eq!(invalid, Ok(false));
+        assert_eq!(count(), U256::from(2));
+
+        // Packed calls resolve every circuit through the registry
+        for circuit_id in [FixedBytes(MULTIPLIER_CIRCUIT_ID), CIRCUIT] {
+            let packed = [circuit_id.as_slice(), &proof, &word(15)].concat();
+            assert_eq!(host.transact(|verifier| verifier.verify_packed(packed.into())), Ok(true));
+        }
+        assert_eq!(count(), U256::from(4));
+
+        assert_eq!(
+            host.transact(|verifier| verifier.verify_hashed(CIRCUIT, proof.clone().into(), word(15).to_vec().into())),
+            Err(b"Circuit does not hash its inputs".to_vec())
+        );
+        assert!(host.transact(|verifier| verifier.verify(proof[..255].to_vec(), vec![])).is_err());
+        assert_eq!(count(), U256::from(4));
     }
 
     #[test]
-    fn test_proof_format() {
-        // Test proof parsing with dummy data
-        let mut proof_bytes = vec![0u8; 256];
-        
-        // Set to valid curve points (would need real data)
-        // This is just structure validation
-        
-        // In real test: use actual proof from snarkjs
-        // let result = Groth16Proof::from_uncompressed_bytes(&proof_bytes);
-    }
-}
\ No newline at end of file
+    fn test_sessions() {
+        let host = deployed();
+        let proof: Bytes = MULTIPLIER_PROOF.to_vec().into();
+
+        host.set_sender(ALICE);
+        let session_id = host.transact(|verifier| verifier.start_session(CIRCUIT, proof.clone())).unwrap();
+        assert_eq!(
+            host.contract().get_session(session_id),
+            Ok((ALICE, CIRCUIT, GENESIS_TIMESTAMP + SESSION_TIMEOUT_SECS, 0, 1))
+        );
+
+        // Only the creator can continue or cancel a live session
+        host.set_sender(BOB);
+        let input: Bytes = word(15).to_vec().into();
+        assert_eq!(
+            host.transact(|verifier| verifier.add_session_inputs(session_id, input.clone())),
+            Err(b"Caller is not the session creator".to_vec())
+        );
+        assert_eq!(
+            host.transact(|verifier| verifier.cancel_session(session_id)),
+            Err(b"Caller is not the session creator".to_vec())
+        );
+
+        host.set_sender(ALICE);
+        assert_eq!(
+            host.transact(|verifier| verifier.finish_session(session_id)),
+            Err(b"Session inputs incomplete".to_vec())
+        );
+        assert_eq!(host.transact(|verifier| verifier.add_session_inputs(session_id, input.clone())), Ok(0));
+        assert_eq!(host.transact(|verifier| verifier.finish_session(session_id)), Ok(true));
+        assert_eq!(host.contract().get_verified_count(), Ok(U256::from(1)));
+        assert_eq!(host.contract().get_session(session_id).unwrap().0, Address::ZERO);
+
+        // Once expired, the creator is locked out and anyone may clean up
+        let session_id = host.transact(|verifier| verifier.start_session(CIRCUIT, proof)).unwrap();
+        host.advance(SESSION_TIMEOUT_SECS);
+        assert_eq!(
+            host.transact(|verifier| verifier.add_session_inputs(session_id, input)),
+            Err(b"Session expired".to_vec())
+        );
+        host.set_sender(BOB);
+        assert_eq!(host.transact(|verifier| verifier.cancel_session(session_id)), Ok(()));
+        assert_eq!(
+            host.transact(|verifier| verifier.cancel_session(session_id)),
+            Err(b"Unknown session".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_abi_entrypoint() {
+        let host = TestHost::new();
+        let initialize = [&selector("initialize(address)")[..], &B256::left_padding_from(OWNER.as_slice())[..]].concat();
+        assert_eq!(host.call(&initialize), Ok(Vec::new()));
+        assert_eq!(host.call(&initialize), Err(b"Already initialized".to_vec()));
+        assert_eq!(host.call(&selector("getVerifiedCount()")), Ok(word(0).to_vec()));
+
+        // Unknown selectors and short calldata revert with no data
+        assert_eq!(host.call(&selector("missing()")), Err(Vec::new()));
+        assert_eq!(host.call(&[0x12]), Err(Vec::new()));
+    }
+    #[test]
+    fn test_hashed_inputs() {
+        let host = deployed();
+        let (pk, vk) = squares_keys(1, 13);
+        let inputs = packed_inputs(&statement(ALICE, GENESIS_TIMESTAMP));
+        let words = vec![U256::from_be_slice(&inputs[..32]), U256::from_be_slice(&inputs[32..])];
+
+        // sha256 goes through the emulated 0x02, keccak256 through the host's hostio
+        for (index, mode) in [InputMode::Keccak256, InputMode::Sha256].into_iter().enumerate() {
+            let circuit_id = FixedBytes([0x70 + index as u8; 32]);
+            host.transact(|verifier| verifier.register_circuit(circuit_id, vk.to_bytes().into(), mode.as_u8()))
+                .unwrap();
+            assert_eq!(host.contract().get_circuit(circuit_id), Ok((mode.as_u8(), 1)));
+            let proof: Bytes = prove_squares(&pk, &[mode.digest(&inputs).unwrap()]).into();
+            let verify = |inputs: Bytes| host.transact(|verifier| verifier.verify_hashed(circuit_id, proof.clone(), inputs));
+            assert_eq!(verify(inputs.clone()), Ok(words.clone()));
+            assert_eq!(verify(inputs[..32].to_vec().into()), Err(b"Invalid proof".to_vec()));
+            assert_eq!(
+                host.transact(|verifier| verifier.verify_statement(circuit_id, proof.clone(), inputs.clone())),
+                Ok(FixedBytes(vk.fingerprint()))
+            );
+        }
+        assert_eq!(host.contract().get_verified_count(), Ok(U256::from(4)));
+        assert_eq!(host.contract().get_circuit(FixedBytes([0x72; 32])), Err(b"Unknown circuit id".to_vec()));
+    }
+
+    #[test]
+    fn test_compromised_keys() {
+        const V1: FixedBytes<32> = FixedBytes([0x51; 32]);
+        let host = deployed();
+        let (pk, vk) = squares_keys(2, 9);
+        host.transact(|verifier| verifier.register_circuit(V1, vk.to_bytes().into(), 0)).unwrap();
+        host.take_logs();
+        let inputs = statement(ALICE, GENESIS_TIMESTAMP);
+        let proof = prove_squares(&pk, &inputs);
+        let verify = |inputs: &[Fr]| {
+            host.transact(|verifier| verifier.verify_statement(V1, proof.clone().into(), packed_inputs(inputs)))
+        };
+        let fingerprint = host.contract().get_key_fingerprint(V1).unwrap();
+        assert_eq!(verify(&inputs), Ok(fingerprint));
+        assert_eq!(verify(&statement(BOB, GENESIS_TIMESTAMP)), Err(b"Invalid proof".to_vec()));
+
+        let mark = || host.transact(|verifier| verifier.mark_key_compromised(fingerprint));
+        host.set_sender(ALICE);
+        assert_eq!(mark(), Err(b"Caller is not the owner".to_vec()));
+        host.set_sender(OWNER);
+        assert_eq!(mark(), Ok(()));
+        assert_eq!(mark(), Err(b"Key already compromised".to_vec()));
+        assert_eq!(host.take_logs()[0].topics, [B256::from(KEY_COMPROMISED), fingerprint]);
+        assert_eq!(host.contract().is_key_compromised(fingerprint), Ok(true));
+
+        // The key can neither verify statements nor come back under another id
+        assert_eq!(verify(&inputs), Err(b"Verifying key is compromised".to_vec()));
+        assert_eq!(
+            host.transact(|verifier| verifier.register_circuit(FixedBytes([0x53; 32]), vk.to_bytes().into(), 0)),
+            Err(b"Verifying key is compromised".to_vec())
+        );
+        assert_eq!(host.contract().get_verified_count(), Ok(U256::from(1)));
+    }
+
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        /// Native pairing and the 0x08 path agree on valid, tampered and malformed proofs
+        #[test]
+        fn test_precompile_matches_native(
+            r in 1..u64::MAX,
+            s in any::<u64>(),
+            input in prop_oneof![Just(15u64), any::<u64>()],
+            flip in proptest::option::of((0..PROOF_SIZE, 1..=u8::MAX)),
+        ) {
+            let host = deployed();
+            let vk = VerifyingKey::load_real();
+            let vk_bytes: Bytes = vk.to_bytes().into();
+            host.transact(|verifier| verifier.register_circuit(FixedBytes(MULTIPLIER_CIRCUIT_ID), vk_bytes, 0))
+                .unwrap();
+            let mut proof = rerandomize(r, s);
+            if let Some((index, mask)) = flip {
+                proof[index] ^= mask;
+            }
+            let inputs = vec![word(input).to_vec()];
+
+            // Arkworks rejects whatever the precompiles cannot decode, so
+            // compare accepted-or-not
+            let native = Groth16Proof::from_uncompressed_bytes(&proof)
+                .and_then(|proof| verify_groth16(&proof, &parse_public_inputs(&inputs)?, &vk))
+                .unwrap_or(false);
+            let precompile = host.transact(|verifier| verifier.verify_with_precompile(proof.clone(), inputs.clone()));
+            prop_assert_eq!(precompile.clone().unwrap_or(false), native);
+            if input == 15 && flip.is_none() {
+                prop_assert_eq!(precompile, Ok(true));
+            }
+        }
+    }
+}
@@ -1,3 +1,483 @@
-fn main() {
-    // No constructor needed
+//! ARBSHIELD CLI - proof and key tooling on the same core as the contract
+//!
+//! ```text
+//! arbshield convert proof.json --to raw
+//! arbshield verify --vk verification_key.json proof.json --public public.json
+//! arbshield calldata proof.json --inputs 15 --method verify-with-precompile
+//! arbshield fingerprint verification_key.json
+//! arbshield export-vk circuit_final.zkey --out verification_key.json
+//! arbshield verify-ceremony --r1cs c.r1cs --ptau pot12.ptau --zkey circuit_final.zkey --fingerprint 0x…
+//! arbshield pairing-input --vk verification_key.json proof.json --inputs 15 --words
+//! arbshield prove --zkey circuit.zkey --wasm circuit.wasm --input input.json --public public.json
+//! ```
+//!
+//! Proofs are read as snarkjs JSON or as hex (256-byte uncompressed or
+//! 128-byte compressed layout); keys as `verification_key.json`, hex of
+//! `VerifyingKey::to_bytes`, or a snarkjs `.zkey`. `-` reads from stdin.
+//!
+//! Built with `export-abi` and no arguments, prints the contract's Solidity
+//! interface instead (what `cargo stylus export-abi` expects); add a program
+//! feature (`--features export-abi,gateway`, ...) for that program's.
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use alloy_primitives::hex;
+use alloy_sol_types::{sol_data, SolType};
+use arbshield_verifier_core::{
+    pairing_input, public_input_commitment, verify_groth16, Groth16Proof, VerifyingKey, PROOF_SIZE, WORD_SIZE,
+    circom::{
+        prove, read_wtns, verify_ceremony, verifying_key_from_zkey, ProvingKey, R1cs, WitnessCalculator,
+        ZkeyVerifyingKey,
+    },
+    snarkjs::{parse_decimal, parse_public_signals_json, to_decimal, ProofJson, VerificationKeyJson},
+    COMPRESSED_PROOF_SIZE,
+};
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use keccak_const::Keccak256;
+
+type CliResult<T> = Result<T, String>;
+
+#[derive(Parser)]
+#[command(name = "arbshield", version, about = "ArbShield Groth16 proof and key tooling")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert a proof between snarkjs JSON, raw (256-byte) and compressed (128-byte) layouts
+    Convert {
+        /// Proof file, or - for stdin
+        proof: PathBuf,
+        #[arg(long, value_enum)]
+        to: ProofFormat,
+    },
+    /// Verify a proof offline, exactly as the contract's `verify` would
+    Verify {
+        #[arg(long)]
+        vk: PathBuf,
+        proof: PathBuf,
+        #[command(flatten)]
+        inputs: PublicInputs,
+    },
+    /// Print ABI calldata for `verify` or `verifyWithPrecompile`
+    Calldata {
+        proof: PathBuf,
+        #[command(flatten)]
+        inputs: PublicInputs,
+        #[arg(long, value_enum, default_value_t = Method::Verify)]
+        method: Method,
+    },
+    /// Print a key's fingerprint: keccak256 of its contract encoding
+    Fingerprint {
+        vk: PathBuf,
+    },
+    /// Extract the verifying key from a snarkjs .zkey and print its fingerprint
+    ///
+    /// Replaces `snarkjs zkey export verificationkey` + extract_vk.js: the key
+    /// is read in the contract's own encoding, so G2 coordinate order can't drift
+    ExportVk {
+        zkey: PathBuf,
+        #[arg(long, value_enum, default_value_t = KeyFormat::Json)]
+        format: KeyFormat,
+        /// Write the key here instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Audit a phase-2 .zkey against its .r1cs and .ptau and print the report
+    ///
+    /// Exits 0 when every check passes, 1 when one fails
+    VerifyCeremony {
+        #[arg(long)]
+        r1cs: PathBuf,
+        #[arg(long)]
+        ptau: PathBuf,
+        #[arg(long)]
+        zkey: PathBuf,
+        /// Also require the key to equal this deployed key (any --vk source)
+        #[arg(long)]
+        deployed: Vec<PathBuf>,
+        /// Also require this fingerprint, e.g. from the contract's getKeyFingerprint
+        #[arg(long)]
+        fingerprint: Vec<String>,
+        /// Write the report here as well as to stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Prove with a snarkjs .zkey, from a .wtns or a circom .wasm generator
+    Prove {
+        #[arg(long)]
+        zkey: PathBuf,
+        #[arg(long, required_unless_present = "wasm", conflicts_with = "wasm")]
+        wtns: Option<PathBuf>,
+        #[arg(long, requires = "input")]
+        wasm: Option<PathBuf>,
+        /// circom input.json for --wasm
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Refuse witnesses that do not satisfy this constraint system
+        #[arg(long)]
+        r1cs: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = ProofFormat::Json)]
+        format: ProofFormat,
+        /// Write public.json here instead of printing it to stderr
+        #[arg(long)]
+        public: Option<PathBuf>,
+    },
+    /// Print the EIP-197 input the precompile path sends to 0x08
+    PairingInput {
+        #[arg(long)]
+        vk: PathBuf,
+        proof: PathBuf,
+        #[command(flatten)]
+        inputs: PublicInputs,
+        /// One labelled 32-byte word per line instead of a single hex string
+        #[arg(long)]
+        words: bool,
+    },
+}
+
+#[derive(Args)]
+struct PublicInputs {
+    /// snarkjs public.json
+    #[arg(long, conflicts_with = "inputs")]
+    public: Option<PathBuf>,
+    /// Decimal public inputs, comma separated
+    #[arg(long, value_delimiter = ',')]
+    inputs: Vec<String>,
+}
+
+/// Output encoding for `export-vk`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum KeyFormat {
+    /// snarkjs verification_key.json
+    Json,
+    /// Hex of the contract encoding (`registerCircuit`'s vk bytes)
+    Hex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ProofFormat {
+    Json,
+    Raw,
+    Compressed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Method {
+    Verify,
+    VerifyWithPrecompile,
+}
+
+impl Method {
+    /// Stylus exports `Vec<u8>` as `uint8[]`, not `bytes`
+    fn signature(self) -> &'static str {
+        match self {
+            Self::Verify => "verify(uint8[],uint8[][])",
+            Self::VerifyWithPrecompile => "verifyWithPrecompile(uint8[],uint8[][])",
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    #[cfg(feature = "export-abi")]
+    if std::env::args_os().len() == 1 {
+        arbshield_verifier::print_abi("MIT", "pragma solidity ^0.8.23;");
+        return ExitCode::SUCCESS;
+    }
+
+    match run(Cli::parse().command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(command: Command) -> CliResult<ExitCode> {
+    match command {
+        Command::Convert { proof, to } => {
+            let proof = read_proof(&proof)?;
+            println!("{}", format_proof(&proof, to));
+        }
+        Command::Verify { vk, proof, inputs } => {
+            let vk = read_vk(&vk)?;
+            let proof = read_proof(&proof)?;
+            let inputs = inputs.read()?;
+            if verify_groth16(&proof, &inputs, &vk).map_err(message)? {
+                println!("valid");
+            } else {
+                println!("invalid: pairing check failed");
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Calldata { proof, inputs, method } => {
+            let proof = read_proof(&proof)?;
+            let inputs = inputs.read()?;
+            println!("0x{}", hex::encode(calldata(method, &proof, &inputs)));
+        }
+        Command::Fingerprint { vk } => {
+            println!("0x{}", hex::encode(read_vk(&vk)?.fingerprint()));
+        }
+        Command::ExportVk { zkey, format, out } => {
+            let key = verifying_key_from_zkey(&read_bytes(&zkey)?).map_err(|err| format!("{}: {err}", zkey.display()))?;
+            eprintln!("protocol:    {}", key.protocol());
+            eprintln!("nPublic:     {}", key.n_public());
+            eprintln!("fingerprint: 0x{}", hex::encode(key.fingerprint()));
+            let ZkeyVerifyingKey::Groth16(vk) = key else {
+                return Err(format!("{}: only groth16 keys can be registered with the contract", zkey.display()));
+            };
+            let encoded = match format {
+                KeyFormat::Json => VerificationKeyJson::from_verifying_key(&vk).to_json(),
+                KeyFormat::Hex => format!("0x{}", hex::encode(vk.to_bytes())),
+            };
+            match out {
+                Some(path) => fs::write(&path, encoded).map_err(|err| format!("{}: {err}", path.display()))?,
+                None => println!("{encoded}"),
+            }
+        }
+        Command::VerifyCeremony { r1cs, ptau, zkey, deployed, fingerprint, out } => {
+            let mut report = verify_ceremony(
+                &read_bytes(&r1cs)?,
+                &read_bytes(&ptau)?,
+                &read_bytes(&zkey)?,
+                &mut rand::thread_rng(),
+            )
+            .map_err(|err| err.to_string())?;
+            for path in deployed {
+                report.compare_deployed(&path.display().to_string(), read_vk(&path)?.fingerprint());
+            }
+            for text in fingerprint {
+                let bytes = decode_hex(text.trim_start_matches("0x"))?;
+                let fingerprint = bytes.try_into().map_err(|_| format!("fingerprint {text}: expected 32 bytes"))?;
+                report.compare_deployed(&text, fingerprint);
+            }
+
+            println!("{report}");
+            if let Some(path) = out {
+                fs::write(&path, format!("{report}\n")).map_err(|err| format!("{}: {err}", path.display()))?;
+            }
+            if !report.passed() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Prove { zkey, wtns, wasm, input, r1cs, format, public } => {
+            let pk = ProvingKey::from_bytes(&read_bytes(&zkey)?).map_err(|err| format!("{}: {err}", zkey.display()))?;
+            let witness = match (wtns, wasm, input) {
+                (Some(wtns), _, _) => read_wtns(&read_bytes(&wtns)?).map_err(|err| format!("{}: {err}", wtns.display()))?,
+                (None, Some(wasm), Some(input)) => {
+                    let input = read_text(&input)?;
+                    WitnessCalculator::from_wasm(&read_bytes(&wasm)?)
+                        .and_then(|mut calculator| calculator.calculate_json(&input))
+                        .map_err(|err| format!("{}: {err}", wasm.display()))?
+                }
+                _ => return Err("need --wtns, or --wasm with --input".to_string()),
+            };
+            if let Some(r1cs) = r1cs {
+                R1cs::from_bytes(&read_bytes(&r1cs)?)
+                    .and_then(|r1cs| r1cs.check_witness(&witness))
+                    .map_err(|err| format!("{}: {err}", r1cs.display()))?;
+            }
+
+            let (proof, signals) = prove(&pk, &witness, &mut rand::thread_rng()).map_err(|err| err.to_string())?;
+            println!("{}", format_proof(&proof, format));
+            let signals: Vec<String> = signals.iter().map(to_decimal).collect();
+            let signals = format!("{signals:?}");
+            match public {
+                Some(path) => fs::write(&path, signals).map_err(|err| format!("{}: {err}", path.display()))?,
+                None => eprintln!("public: {signals}"),
+            }
+        }
+        Command::PairingInput { vk, proof, inputs, words } => {
+            let vk = read_vk(&vk)?;
+            let proof = read_proof(&proof)?;
+            let commitment = public_input_commitment(&vk, inputs.read()?.into_iter()).map_err(message)?;
+            let input = pairing_input(&proof, &commitment, &vk);
+            if words {
+                print_pairing_words(&input);
+            } else {
+                println!("0x{}", hex::encode(input));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// ============================================================================
+// INPUT DECODING
+// ============================================================================
+
+fn message(err: Vec<u8>) -> String {
+    String::from_utf8_lossy(&err).into_owned()
+}
+
+fn read_text(path: &Path) -> CliResult<String> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).map_err(|err| format!("stdin: {err}"))?;
+        return Ok(text);
+    }
+    fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn read_bytes(path: &Path) -> CliResult<Vec<u8>> {
+    fs::read(path).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn decode_hex(text: &str) -> CliResult<Vec<u8>> {
+    let digits: String = text.split_whitespace().collect();
+    hex::decode(digits).map_err(|err| format!("expected JSON or hex: {err}"))
+}
+
+/// snarkjs JSON, or hex in the raw or compressed layout (told apart by length)
+fn parse_proof(text: &str) -> CliResult<Groth16Proof> {
+    if text.trim_start().starts_with('{') {
+        return ProofJson::from_json(text)
+            .and_then(|proof| proof.to_proof())
+            .map_err(|err| err.to_string());
+    }
+    let bytes = decode_hex(text)?;
+    match bytes.len() {
+        PROOF_SIZE => Groth16Proof::from_uncompressed_bytes(&bytes).map_err(message),
+        COMPRESSED_PROOF_SIZE => Groth16Proof::from_compressed_bytes(&bytes).map_err(message),
+        len => Err(format!("proof is {len} bytes, expected {PROOF_SIZE} or {COMPRESSED_PROOF_SIZE}")),
+    }
+}
+
+/// `verification_key.json`, or hex of `VerifyingKey::to_bytes`
+fn parse_vk(text: &str) -> CliResult<VerifyingKey> {
+    if text.trim_start().starts_with('{') {
+        return VerificationKeyJson::from_json(text)
+            .and_then(|vk| vk.to_verifying_key())
+            .map_err(|err| err.to_string());
+    }
+    VerifyingKey::from_bytes(&decode_hex(text)?).map_err(message)
+}
+
+fn read_proof(path: &Path) -> CliResult<Groth16Proof> {
+    parse_proof(&read_text(path)?).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Like `parse_vk`, but a binary .zkey (by its magic) is read directly
+fn read_vk(path: &Path) -> CliResult<VerifyingKey> {
+    let text = if path == Path::new("-") {
+        read_text(path)?
+    } else {
+        let bytes = read_bytes(path)?;
+        if bytes.starts_with(b"zkey") {
+            return match verifying_key_from_zkey(&bytes) {
+                Ok(ZkeyVerifyingKey::Groth16(vk)) => Ok(*vk),
+                Ok(key) => Err(format!("{}: {} key, expected groth16", path.display(), key.protocol())),
+                Err(err) => Err(format!("{}: {err}", path.display())),
+            };
+        }
+        String::from_utf8(bytes).map_err(|err| format!("{}: {err}", path.display()))?
+    };
+    parse_vk(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+impl PublicInputs {
+    fn read(&self) -> CliResult<Vec<Fr>> {
+        if let Some(path) = &self.public {
+            return parse_public_signals_json(&read_text(path)?)
+                .map_err(|err| format!("{}: {err}", path.display()));
+        }
+        self.inputs
+            .iter()
+            .map(|input| {
+                parse_decimal(input).ok_or_else(|| format!("input {input}: not a canonical field element"))
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// OUTPUT ENCODING
+// ============================================================================
+
+fn format_proof(proof: &Groth16Proof, format: ProofFormat) -> String {
+    match format {
+        ProofFormat::Json => ProofJson::from_proof(proof).to_json(),
+        ProofFormat::Raw => format!("0x{}", hex::encode(proof.to_uncompressed_bytes())),
+        ProofFormat::Compressed => format!("0x{}", hex::encode(proof.to_compressed_bytes())),
+    }
+}
+
+/// selector || abi.encode(uint8[] proof_bytes, uint8[][] public_inputs)
+fn calldata(method: Method, proof: &Groth16Proof, inputs: &[Fr]) -> Vec<u8> {
+    type Params = (sol_data::Array<sol_data::Uint<8>>, sol_data::Array<sol_data::Array<sol_data::Uint<8>>>);
+
+    let proof_bytes = proof.to_uncompressed_bytes().to_vec();
+    let input_words: Vec<Vec<u8>> = inputs
+        .iter()
+        .map(|input| input.into_bigint().to_bytes_be())
+        .collect();
+
+    // alloy's keccak256 is wired to the Stylus host here, so hash locally
+    let selector = Keccak256::new().update(method.signature().as_bytes()).finalize();
+    let mut data = selector[..4].to_vec();
+    data.extend(Params::abi_encode_params(&(proof_bytes, input_words)));
+    data
+}
+
+fn print_pairing_words(input: &[u8]) {
+    const PAIRS: [(&str, &str); 4] = [("A", "B"), ("-alpha", "beta"), ("-L", "gamma"), ("-C", "delta")];
+    const WORDS: [&str; 6] = ["x", "y", "x.c1", "x.c0", "y.c1", "y.c0"];
+
+    for (pair, (g1, g2)) in input.chunks(6 * WORD_SIZE).zip(PAIRS) {
+        for (word, label) in pair.chunks(WORD_SIZE).zip(WORDS) {
+            let point = if label.contains('.') { g2 } else { g1 };
+            println!("{:>12}  0x{}", format!("{point}.{label}"), hex::encode(word));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROOF_JSON: &str = include_str!("../core/fixtures/multiplier/proof.json");
+    const VK_JSON: &str = include_str!("../core/fixtures/multiplier/verification_key.json");
+
+    #[test]
+    fn test_proof_formats_roundtrip() {
+        let proof = parse_proof(PROOF_JSON).unwrap();
+        for format in [ProofFormat::Json, ProofFormat::Raw, ProofFormat::Compressed] {
+            let text = format_proof(&proof, format);
+            let decoded = parse_proof(&text).unwrap();
+            assert_eq!(decoded.to_uncompressed_bytes(), proof.to_uncompressed_bytes());
+        }
+        assert!(parse_proof("0x1234").is_err());
+    }
+
+    #[test]
+    fn test_vk_sources_agree() {
+        let from_json = parse_vk(VK_JSON).unwrap();
+        let from_hex = parse_vk(&hex::encode(VerifyingKey::load_real().to_bytes())).unwrap();
+        assert_eq!(from_json.fingerprint(), from_hex.fingerprint());
+    }
+
+    #[test]
+    fn test_calldata_layout() {
+        let proof = parse_proof(PROOF_JSON).unwrap();
+        let data = calldata(Method::VerifyWithPrecompile, &proof, &[Fr::from(15u64)]);
+
+        let selector = Keccak256::new().update(b"verifyWithPrecompile(uint8[],uint8[][])").finalize();
+        assert_eq!(data[..4], selector[..4]);
+        type Params = (sol_data::Array<sol_data::Uint<8>>, sol_data::Array<sol_data::Array<sol_data::Uint<8>>>);
+        let (proof_bytes, inputs) = Params::abi_decode_params(&data[4..], true).unwrap();
+        assert_eq!(proof_bytes, proof.to_uncompressed_bytes().to_vec());
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].len(), 32);
+        assert_eq!(inputs[0][31], 15);
+    }
 }
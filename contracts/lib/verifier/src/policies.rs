@@ -0,0 +1,394 @@
+//! COMPLIANCE POLICIES - WHICH ATTRIBUTES TOGETHER SUFFICE
+//!
+//! A separate Stylus program (`--features policies`): boolean policies over
+//! attribute records. Each term is read through `ComplianceDelegations`, so
+//! implications and delegations count, and proofs submitted here are
+//! recorded through `ComplianceGateway`.
+
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U64},
+    call::{self, Call},
+    prelude::*,
+    storage::{StorageAddress, StorageBool, StorageBytes, StorageMap, StorageString, StorageU64, StorageVec},
+    console, crypto, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::policy;
+
+/// keccak256("PolicyCreated(bytes32,string[],uint64[],bytes)")
+const POLICY_CREATED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"PolicyCreated(bytes32,string[],uint64[],bytes)")
+    .finalize();
+
+sol! {
+    /// The `ComplianceGateway` entrypoint this contract calls
+    interface IProofGateway {
+        function verifyAttribute(bytes32 circuitId, bytes proof, bytes inputs) external returns (address subject, uint64 expiresAt);
+    }
+
+    /// The `ComplianceDelegations` entrypoint this contract calls
+    interface IAttributeHolders {
+        function holdsAttribute(address user, string attribute, uint64 maxAge, address asset) external view returns (bool held);
+    }
+}
+
+/// Most proofs `verify_for_policy` takes in one call
+pub const MAX_POLICY_PROOFS: usize = 8;
+
+#[storage]
+#[cfg_attr(feature = "policies", entrypoint)]
+pub struct CompliancePolicies {
+    initialized: StorageBool,
+    /// `ComplianceGateway` proofs are submitted through
+    gateway: StorageAddress,
+    /// `ComplianceDelegations` every term is read through
+    delegations: StorageAddress,
+    /// Policies by content id, see `create_policy`
+    policies: StorageMap<FixedBytes<32>, StoredPolicy>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "policies"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for CompliancePolicies {}
+
+/// Boolean expression over attribute records, see `policy`
+#[storage]
+pub struct StoredPolicy {
+    /// Postfix program over `terms`; empty if the policy does not exist
+    program: StorageBytes,
+    terms: StorageVec<PolicyTerm>,
+}
+
+/// One attribute a policy refers to
+#[storage]
+pub struct PolicyTerm {
+    attribute: StorageString,
+    /// Oldest a record may be, in seconds since its proof was checked; 0 for no limit
+    max_age: StorageU64,
+}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl CompliancePolicies {
+    /// Set the `ComplianceGateway` proofs go through and the
+    /// `ComplianceDelegations` terms are read through, once
+    ///
+    /// Nothing here is administered: anyone may create a policy
+    pub fn initialize(&mut self, gateway: Address, delegations: Address) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if !gateway.has_code() {
+            return Err(b"Gateway has no code".to_vec());
+        }
+        if !delegations.has_code() {
+            return Err(b"Delegations have no code".to_vec());
+        }
+
+        self.gateway.set(gateway);
+        self.delegations.set(delegations);
+        self.initialized.set(true);
+
+        console!("✓ CompliancePolicies initialized");
+        Ok(())
+    }
+
+    /// (gateway, delegations) this contract calls
+    pub fn get_contracts(&self) -> Result<(Address, Address), Vec<u8>> {
+        Ok((self.gateway.get(), self.delegations.get()))
+    }
+
+    /// Store a policy over attribute records; open to anyone
+    ///
+    /// @param attributes: the policy's terms
+    /// @param max_ages: per term, the oldest a record may be in seconds; 0 for no limit
+    /// @param program: postfix program over the terms (see `policy`)
+    ///
+    /// Policies are immutable and identified by keccak256 of their ABI-encoded
+    /// definition, so an id always stands for the same rule.
+    ///
+    /// Returns the policy id
+    pub fn create_policy(
+        &mut self,
+        attributes: Vec<String>,
+        max_ages: Vec<u64>,
+        program: Bytes,
+    ) -> Result<FixedBytes<32>, Vec<u8>> {
+        if attributes.is_empty() || attributes.len() > policy::MAX_TERMS {
+            return Err(b"Invalid policy term count".to_vec());
+        }
+        if max_ages.len() != attributes.len() {
+            return Err(b"Term count mismatch".to_vec());
+        }
+        if attributes.iter().any(String::is_empty) {
+            return Err(b"Empty attribute".to_vec());
+        }
+        policy::validate(&program, attributes.len())?;
+
+        let definition = (attributes.clone(), max_ages.clone(), alloy_primitives::Bytes::from(program.to_vec()));
+        let data = definition.abi_encode_params();
+        let policy_id = crypto::keccak(&data);
+        if !self.policies.getter(policy_id).program.is_empty() {
+            return Err(b"Policy already exists".to_vec());
+        }
+
+        let mut stored = self.policies.setter(policy_id);
+        stored.program.set_bytes(&program);
+        for (attribute, max_age) in attributes.iter().zip(max_ages) {
+            let mut term = stored.terms.grow();
+            term.attribute.set_str(attribute);
+            term.max_age.set(U64::from(max_age));
+        }
+
+        let _ = evm::raw_log(&[B256::from(POLICY_CREATED), policy_id], &data);
+        console!("✓ Policy created");
+        Ok(policy_id)
+    }
+
+    /// (attributes, max ages, program) of a policy; empty if it does not
+    /// exist. The SDK returns it encoded as one tuple
+    pub fn get_policy(&self, policy_id: FixedBytes<32>) -> Result<(Vec<String>, Vec<u64>, Bytes), Vec<u8>> {
+        let stored = self.policies.getter(policy_id);
+        let terms = (0..stored.terms.len()).filter_map(|index| stored.terms.getter(index));
+        let (attributes, max_ages) = terms
+            .map(|term| (term.attribute.get_string(), term.max_age.get().to::<u64>()))
+            .unzip();
+        Ok((attributes, max_ages, stored.program.get_bytes().into()))
+    }
+
+    /// Whether a policy has been created under `policy_id`
+    pub fn policy_exists(&self, policy_id: FixedBytes<32>) -> Result<bool, Vec<u8>> {
+        Ok(!self.policies.getter(policy_id).program.is_empty())
+    }
+
+    /// Whether `user`'s current records satisfy a policy
+    pub fn evaluate_policy(&self, user: Address, policy_id: FixedBytes<32>) -> Result<bool, Vec<u8>> {
+        self.policy_holds(user, policy_id, Address::ZERO)
+    }
+
+    /// `evaluate_policy`, also following delegations limited to `asset`, as
+    /// asset rules read the asset's policy
+    pub fn evaluate_policy_for(
+        &self,
+        user: Address,
+        policy_id: FixedBytes<32>,
+        asset: Address,
+    ) -> Result<bool, Vec<u8>> {
+        self.policy_holds(user, policy_id, asset)
+    }
+
+    /// Verify several attribute proofs for one subject, then require the
+    /// policy to hold for them
+    ///
+    /// @param circuit_ids, proofs, inputs: one entry per proof, as
+    ///                                     `ComplianceGateway.verify_attribute`
+    ///
+    /// Terms not covered by a proof here are read from existing records.
+    /// If the policy does not hold the call reverts, recording nothing.
+    ///
+    /// Returns the subject
+    pub fn verify_for_policy(
+        &mut self,
+        policy_id: FixedBytes<32>,
+        circuit_ids: Vec<FixedBytes<32>>,
+        proofs: Vec<Bytes>,
+        inputs: Vec<Bytes>,
+    ) -> Result<Address, Vec<u8>> {
+        console!("=== POLICY VERIFICATION ===");
+
+        if self.policies.getter(policy_id).program.is_empty() {
+            return Err(b"Unknown policy".to_vec());
+        }
+        if circuit_ids.is_empty() || circuit_ids.len() > MAX_POLICY_PROOFS {
+            return Err(b"Invalid proof count".to_vec());
+        }
+        if proofs.len() != circuit_ids.len() || inputs.len() != circuit_ids.len() {
+            return Err(b"Proof count mismatch".to_vec());
+        }
+
+        let mut subject = None;
+        for ((circuit_id, proof), inputs) in circuit_ids.into_iter().zip(proofs).zip(inputs) {
+            let proven = self.record_attribute(circuit_id, proof, inputs)?;
+            if *subject.get_or_insert(proven) != proven {
+                return Err(b"Proofs name different subjects".to_vec());
+            }
+        }
+        // At least one proof was checked
+        let subject = subject.unwrap_or_default();
+        if !self.evaluate_policy(subject, policy_id)? {
+            return Err(b"Policy not satisfied".to_vec());
+        }
+        console!("✓ Policy satisfied");
+        Ok(subject)
+    }
+}
+
+// ============================================================================
+// RULE EVALUATION
+// ============================================================================
+
+impl CompliancePolicies {
+    /// Have `ComplianceGateway` verify and record one attribute proof
+    ///
+    /// Returns the subject
+    fn record_attribute(
+        &mut self,
+        circuit_id: FixedBytes<32>,
+        proof: Bytes,
+        inputs: Bytes,
+    ) -> Result<Address, Vec<u8>> {
+        let calldata = IProofGateway::verifyAttributeCall {
+            circuitId: circuit_id,
+            proof: proof.0.into(),
+            inputs: inputs.0.into(),
+        }
+        .abi_encode();
+        // Reverts with the gateway's reason, e.g. for an invalid proof
+        let gateway = self.gateway.get();
+        let output = call::call(Call::new_in(self), gateway, &calldata)?;
+        let recorded = IProofGateway::verifyAttributeCall::abi_decode_returns(&output, true)
+            .map_err(|_| b"Unexpected gateway output".to_vec())?;
+        Ok(recorded.subject)
+    }
+
+    /// `ComplianceDelegations.holds_attribute`; false if the call fails
+    fn holds(&self, user: Address, attribute: String, max_age: u64, asset: Address) -> bool {
+        let calldata = IAttributeHolders::holdsAttributeCall { user, attribute, maxAge: max_age, asset }.abi_encode();
+        call::static_call(Call::new(), self.delegations.get(), &calldata)
+            .ok()
+            .and_then(|output| IAttributeHolders::holdsAttributeCall::abi_decode_returns(&output, true).ok())
+            .is_some_and(|output| output.held)
+    }
+
+    /// `evaluate_policy`, following delegations in scope for `asset`
+    fn policy_holds(&self, user: Address, policy_id: FixedBytes<32>, asset: Address) -> Result<bool, Vec<u8>> {
+        let stored = self.policies.getter(policy_id);
+        let program = stored.program.get_bytes();
+        if program.is_empty() {
+            return Err(b"Unknown policy".to_vec());
+        }
+        policy::evaluate(&program, |index, negated| {
+            stored.terms.getter(index).is_some_and(|term| {
+                let attribute = term.attribute.get_string();
+                // NOT reads the attested negative record, never the absence of one
+                let attribute = if negated { policy::negated(&attribute) } else { attribute };
+                self.holds(user, attribute, term.max_age.get().to::<u64>(), asset)
+            })
+        })
+    }
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        bind_squares, packed_inputs, prove_squares, statement, system, DEFAULT_SENDER, GENESIS_TIMESTAMP,
+    };
+
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+
+    #[test]
+    fn test_policies() {
+        const DAY: u64 = 24 * 60 * 60;
+        let host = system();
+        let attributes = ["accredited_investor", "not_us_person", "qualified_purchaser", "kyc", "us_person"];
+        let keys: Vec<_> = (0..5u8)
+            .map(|index| {
+                let id = FixedBytes([0xa0 + index; 32]);
+                (id, bind_squares(&host, id, attributes[index as usize], 365 * DAY, 2, 10 + index as u64))
+            })
+            .collect();
+
+        // accredited_investor AND (NOT us_person OR qualified_purchaser) AND kyc fresher than 90 days
+        let program = Bytes::from(vec![0, 0, 0, 1, 3, 0, 2, 2, 1, 0, 3, 1]);
+        let terms: Vec<String> =
+            ["accredited_investor", "us_person", "qualified_purchaser", "kyc"].map(String::from).to_vec();
+        let max_ages = vec![0, 0, 0, 90 * DAY];
+        let create = |terms: Vec<String>, max_ages: Vec<u64>, program: Bytes| {
+            host.policies(|policies| policies.create_policy(terms, max_ages, program))
+        };
+        assert_eq!(create(terms.clone(), vec![0; 3], program.clone()), Err(b"Term count mismatch".to_vec()));
+        assert_eq!(
+            create(terms[..3].to_vec(), vec![0; 3], program.clone()),
+            Err(b"Policy term out of range".to_vec())
+        );
+        // Only a single term has an attested negative to read
+        assert_eq!(
+            create(terms.clone(), max_ages.clone(), vec![0, 0, 0, 1, 0, 2, 2, 3, 1, 0, 3, 1].into()),
+            Err(b"Policies can only negate a term".to_vec())
+        );
+        host.set_sender(ALICE);
+        let policy_id = create(terms.clone(), max_ages.clone(), program.clone()).unwrap();
+        assert_eq!(create(terms.clone(), max_ages.clone(), program.clone()), Err(b"Policy already exists".to_vec()));
+        let definition = (terms.clone(), max_ages.clone(), alloy_primitives::Bytes::from(program.to_vec()));
+        assert_eq!(policy_id, crypto::keccak(definition.abi_encode_params()));
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(POLICY_CREATED), policy_id]);
+        assert_eq!(host.policies(|policies| policies.get_policy(policy_id)), Ok((terms, max_ages, program)));
+        assert_eq!(host.policies(|policies| policies.policy_exists(policy_id)), Ok(true));
+        host.set_sender(DEFAULT_SENDER);
+
+        let evaluate = |user| host.policies(|policies| policies.evaluate_policy(user, policy_id));
+        assert_eq!(
+            host.policies(|policies| policies.evaluate_policy(ALICE, keys[0].0)),
+            Err(b"Unknown policy".to_vec())
+        );
+        assert_eq!(evaluate(ALICE), Ok(false));
+
+        // Several proofs in one call; the policy must hold once they are recorded
+        let verify = |user, terms: &[usize]| {
+            let inputs = statement(user, GENESIS_TIMESTAMP);
+            let (ids, proofs) = terms
+                .iter()
+                .map(|term| (keys[*term].0, Bytes::from(prove_squares(&keys[*term].1, &inputs))))
+                .unzip();
+            let inputs = vec![packed_inputs(&inputs); terms.len()];
+            host.policies(|policies| policies.verify_for_policy(policy_id, ids, proofs, inputs))
+        };
+        assert_eq!(verify(ALICE, &[0, 1, 3]), Ok(ALICE));
+        assert_eq!(evaluate(ALICE), Ok(true));
+
+        // Having no us_person record does not prove NOT us_person, and a failing policy records nothing
+        let recorded = |user, attribute: &str| host.records(|records| records.is_compliant(user, attribute.into()));
+        assert_eq!(verify(BOB, &[0, 3]), Err(b"Policy not satisfied".to_vec()));
+        assert_eq!(verify(BOB, &[0, 3, 4]), Err(b"Policy not satisfied".to_vec()));
+        assert_eq!(recorded(BOB, "kyc"), Ok(false));
+        assert_eq!(verify(BOB, &[0, 2, 3]), Ok(BOB));
+
+        // Existing records count, so a single further proof passes
+        assert_eq!(verify(BOB, &[1]), Ok(BOB));
+
+        // Subjects without records, so each proof on its own would be recorded
+        let first = statement(Address::repeat_byte(0xc1), GENESIS_TIMESTAMP);
+        let second = statement(Address::repeat_byte(0xc2), GENESIS_TIMESTAMP);
+        let mixed = host.policies(|policies| {
+            policies.verify_for_policy(
+                policy_id,
+                vec![keys[0].0, keys[3].0],
+                vec![prove_squares(&keys[0].1, &first).into(), prove_squares(&keys[3].1, &second).into()],
+                vec![packed_inputs(&first), packed_inputs(&second)],
+            )
+        });
+        assert_eq!(mixed, Err(b"Proofs name different subjects".to_vec()));
+        assert_eq!(
+            host.policies(|policies| policies.verify_for_policy(policy_id, vec![], vec![], vec![])),
+            Err(b"Invalid proof count".to_vec())
+        );
+
+        // Freshness is per term: KYC is still valid but too old for this policy
+        host.advance(90 * DAY);
+        assert_eq!(evaluate(ALICE), Ok(true));
+        host.advance(1);
+        assert_eq!(evaluate(ALICE), Ok(false));
+        assert_eq!(recorded(ALICE, "kyc"), Ok(true));
+    }
+}
@@ -0,0 +1,580 @@
+//! COMPLIANCE RECORDS - WHAT EACH PROOF ESTABLISHED
+//!
+//! A separate Stylus program (`--features records`): one record per user and
+//! attribute, with its checkpoint history. Only the
+//! [`ComplianceGateway`](crate::gateway::ComplianceGateway) writes records,
+//! once the verifier has accepted the proof behind them. Checks here read a
+//! user's own record; `ComplianceDelegations` also follows implications and
+//! delegations.
+//!
+//! Keys and their compromise flags stay with the verifier, so every record
+//! check that reads one asks it, through a static call.
+
+use stylus_sdk::{
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, U256, U64},
+    call::{self, Call},
+    prelude::*,
+    storage::{
+        StorageAddress, StorageArray, StorageBool, StorageFixedBytes, StorageGuard, StorageMap, StorageU64, StorageVec,
+    },
+    console, evm,
+};
+
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolValue};
+
+use crate::{block, jurisdiction, msg, read_jurisdictions, write_jurisdictions};
+
+/// keccak256("ComplianceVerified(address,string,uint256,uint256)"), as `ComplianceRegistry.sol`
+const COMPLIANCE_VERIFIED: [u8; 32] = keccak_const::Keccak256::new()
+    .update(b"ComplianceVerified(address,string,uint256,uint256)")
+    .finalize();
+
+sol! {
+    /// The `ZKVerifier` entrypoint this contract calls
+    interface IKeyRegistry {
+        function isKeyCompromised(bytes32 fingerprint) external view returns (bool compromised);
+    }
+}
+
+#[storage]
+#[cfg_attr(feature = "records", entrypoint)]
+pub struct ComplianceRecords {
+    initialized: StorageBool,
+    /// `ZKVerifier` whose compromise flags every check reads
+    verifier: StorageAddress,
+    /// `ComplianceGateway`, the only caller of `write_record`
+    gateway: StorageAddress,
+    /// Compliance records by user and attribute
+    records: StorageMap<Address, StorageMap<String, ComplianceRecord>>,
+}
+
+// SAFETY: the program's storage root, as `#[entrypoint]` would declare it
+#[cfg(not(feature = "records"))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for ComplianceRecords {}
+
+/// One user's latest proof of one attribute
+#[storage]
+pub struct ComplianceRecord {
+    /// Zero if the user never proved the attribute
+    verified_at: StorageU64,
+    /// Issue time the proof's statement carries; a new proof must be issued later
+    issued_at: StorageU64,
+    expires_at: StorageU64,
+    /// keccak256 of the proof bytes
+    proof_hash: StorageFixedBytes<32>,
+    circuit_id: StorageFixedBytes<32>,
+    /// `VerifyingKey::fingerprint` of the key the proof was checked against
+    key_fingerprint: StorageFixedBytes<32>,
+    /// Every write of this record, oldest first, for `is_compliant_at`
+    history: StorageVec<RecordCheckpoint>,
+    /// Whether the proof placed the user in `jurisdictions`
+    has_jurisdictions: StorageBool,
+    /// `JurisdictionSet` of the countries the proof places the user in
+    jurisdictions: StorageArray<StorageFixedBytes<32>, 4>,
+}
+
+/// A record's validity window as of one write; it holds until the next
+#[storage]
+pub struct RecordCheckpoint {
+    verified_at: StorageU64,
+    /// Block the proof was checked in, for audits that work in blocks
+    block: StorageU64,
+    expires_at: StorageU64,
+    /// Key the proof was checked against, so `mark_key_compromised` reaches history
+    key_fingerprint: StorageFixedBytes<32>,
+}
+
+// ============================================================================
+// PUBLIC INTERFACE
+// ============================================================================
+
+#[public]
+impl ComplianceRecords {
+    /// Set the `ZKVerifier` keys are checked with and the
+    /// `ComplianceGateway` that writes records, once
+    ///
+    /// Nothing here is administered: only the gateway writes
+    pub fn initialize(&mut self, verifier: Address, gateway: Address) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(b"Already initialized".to_vec());
+        }
+        if !verifier.has_code() {
+            return Err(b"Verifier has no code".to_vec());
+        }
+        if !gateway.has_code() {
+            return Err(b"Gateway has no code".to_vec());
+        }
+
+        self.verifier.set(verifier);
+        self.gateway.set(gateway);
+        self.initialized.set(true);
+
+        console!("✓ ComplianceRecords initialized");
+        Ok(())
+    }
+
+    pub fn get_verifier(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.verifier.get())
+    }
+
+    pub fn get_gateway(&self) -> Result<Address, Vec<u8>> {
+        Ok(self.gateway.get())
+    }
+
+    /// Record that the verifier accepted a proof of `attribute` for
+    /// `subject` (gateway only)
+    ///
+    /// @param issued_at: issue time the proof's statement carries
+    /// @param key_fingerprint: key the verifier checked the proof against
+    /// @param jurisdictions: `JurisdictionSet` words the proof places the
+    ///                       subject in; empty if its circuit exposes none
+    ///
+    /// The statement must be issued after the record it replaces, so a
+    /// proof can be submitted once and an old one never extends a record.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_record(
+        &mut self,
+        subject: Address,
+        attribute: String,
+        circuit_id: FixedBytes<32>,
+        issued_at: u64,
+        expires_at: u64,
+        proof_hash: FixedBytes<32>,
+        key_fingerprint: FixedBytes<32>,
+        jurisdictions: Vec<FixedBytes<32>>,
+    ) -> Result<(), Vec<u8>> {
+        if msg::sender() != self.gateway.get() {
+            return Err(b"Caller is not the gateway".to_vec());
+        }
+        let jurisdictions = match jurisdictions.is_empty() {
+            true => None,
+            false => {
+                let words: Vec<[u8; 32]> = jurisdictions.iter().map(|word| word.0).collect();
+                Some(jurisdiction::JurisdictionSet::from_words(&words)?)
+            }
+        };
+        if issued_at <= self.records.getter(subject).getter(attribute.clone()).issued_at.get().to::<u64>() {
+            return Err(b"Proof is not newer than the record".to_vec());
+        }
+
+        let mut records = self.records.setter(subject);
+        let mut record = records.setter(attribute.clone());
+        let now = U64::from(block::timestamp());
+        record.verified_at.set(now);
+        record.issued_at.set(U64::from(issued_at));
+        record.expires_at.set(U64::from(expires_at));
+        record.proof_hash.set(proof_hash);
+        record.circuit_id.set(circuit_id);
+        record.key_fingerprint.set(key_fingerprint);
+        record.has_jurisdictions.set(jurisdictions.is_some());
+        if let Some(set) = jurisdictions {
+            write_jurisdictions(&mut record.jurisdictions, &set);
+        }
+        // A second write at the same timestamp replaces the first, as no
+        // timepoint could observe the state between them
+        let last = record.history.len().checked_sub(1);
+        let same_time = last.filter(|last| {
+            record.history.getter(*last).is_some_and(|checkpoint| checkpoint.verified_at.get() == now)
+        });
+        let mut checkpoint = match same_time.and_then(|last| record.history.setter(last)) {
+            Some(checkpoint) => checkpoint,
+            None => record.history.grow(),
+        };
+        checkpoint.verified_at.set(now);
+        checkpoint.block.set(U64::from(block::number()));
+        checkpoint.expires_at.set(U64::from(expires_at));
+        checkpoint.key_fingerprint.set(key_fingerprint);
+
+        let data = (attribute, U256::from(now), U256::from(expires_at)).abi_encode_params();
+        let _ = evm::raw_log(&[B256::from(COMPLIANCE_VERIFIED), subject.into_word()], &data);
+        console!("✓ Attribute recorded until {}", expires_at);
+        Ok(())
+    }
+
+    /// Whether `user` holds an unexpired record for `attribute_type`
+    ///
+    /// Same ABI as `ComplianceRegistry.isCompliant`, so a token can point at
+    /// this contract instead; `ComplianceDelegations.isCompliant` also
+    /// follows implications and delegations
+    pub fn is_compliant(&self, user: Address, attribute_type: String) -> Result<bool, Vec<u8>> {
+        let records = self.records.getter(user);
+        Ok(self.is_current(&records.getter(attribute_type), 0))
+    }
+
+    /// `is_compliant`, counting only records proven at most `max_age`
+    /// seconds ago (0 for no limit), as policy terms read them
+    pub fn is_compliant_within(&self, user: Address, attribute_type: String, max_age: u64) -> Result<bool, Vec<u8>> {
+        let records = self.records.getter(user);
+        Ok(self.is_current(&records.getter(attribute_type), max_age))
+    }
+
+    /// `JurisdictionSet` words of `user`'s current record of `attribute_type`;
+    /// empty if there is none or its circuit exposes no set
+    pub fn get_proven_jurisdictions(
+        &self,
+        user: Address,
+        attribute_type: String,
+    ) -> Result<Vec<FixedBytes<32>>, Vec<u8>> {
+        let records = self.records.getter(user);
+        let record = records.getter(attribute_type);
+        if !record.has_jurisdictions.get() || !self.is_current(&record, 0) {
+            return Ok(Vec::new());
+        }
+        Ok((0..jurisdiction::WORDS).map(|index| record.jurisdictions.get(index).unwrap_or_default()).collect())
+    }
+
+    /// Whether `user`'s current record of `attribute_type` carries a
+    /// jurisdiction set the rules permit, as asset jurisdiction rules read it
+    ///
+    /// @param allowed, denied: `JurisdictionSet::to_bytes` encodings; empty
+    ///                         bytes are the empty set
+    /// @param match_any: `JurisdictionSet::permitted_by_any` rather than
+    ///                   `permitted_by`
+    pub fn jurisdictions_permitted(
+        &self,
+        user: Address,
+        attribute_type: String,
+        allowed: Bytes,
+        denied: Bytes,
+        match_any: bool,
+    ) -> Result<bool, Vec<u8>> {
+        let allowed = jurisdiction::JurisdictionSet::from_bytes(&allowed)?;
+        let denied = jurisdiction::JurisdictionSet::from_bytes(&denied)?;
+        let records = self.records.getter(user);
+        let record = records.getter(attribute_type);
+        if !record.has_jurisdictions.get() || !self.is_current(&record, 0) {
+            return Ok(false);
+        }
+        let proven = read_jurisdictions(&record.jurisdictions)?;
+        Ok(match match_any {
+            true => proven.permitted_by_any(&allowed, &denied),
+            false => proven.permitted_by(&allowed, &denied),
+        })
+    }
+
+    /// Whether `user` held an unexpired record for `attribute_type` at
+    /// `timepoint`, a timestamp as `CLOCK_MODE` reports
+    ///
+    /// Binary search over the record's checkpoints, like ERC20Votes. Only
+    /// past timepoints are answered, since a later write in the current
+    /// block could still change the answer. A checkpoint proven under a key
+    /// since marked compromised never held, as for `is_compliant`.
+    pub fn is_compliant_at(&self, user: Address, attribute_type: String, timepoint: u64) -> Result<bool, Vec<u8>> {
+        if timepoint >= block::timestamp() {
+            return Err(b"Future lookup".to_vec());
+        }
+        let records = self.records.getter(user);
+        let history = &records.getter(attribute_type).history;
+        let Some(latest) = self.checkpoint_before(history, timepoint, |checkpoint| checkpoint.verified_at.get()) else {
+            return Ok(false);
+        };
+        Ok(timepoint < latest.expires_at.get().to::<u64>())
+    }
+
+    /// (verified at, block, expires at) of the checkpoint in effect at the
+    /// end of past block `block`, all zero if there was none
+    ///
+    /// The same binary search as `is_compliant_at`, over the block each
+    /// checkpoint was written in. Expiry is a timestamp and the chain keeps
+    /// no past block timestamps, so the caller compares expires at with
+    /// the block's own timestamp. A checkpoint proven under a key since
+    /// marked compromised reads as none.
+    pub fn checkpoint_at_block(
+        &self,
+        user: Address,
+        attribute_type: String,
+        block: u64,
+    ) -> Result<(u64, u64, u64), Vec<u8>> {
+        if block >= block::number() {
+            return Err(b"Future lookup".to_vec());
+        }
+        let records = self.records.getter(user);
+        let history = &records.getter(attribute_type).history;
+        Ok(self.checkpoint_before(history, block, |checkpoint| checkpoint.block.get()).map_or((0, 0, 0), |checkpoint| {
+            (
+                checkpoint.verified_at.get().to::<u64>(),
+                checkpoint.block.get().to::<u64>(),
+                checkpoint.expires_at.get().to::<u64>(),
+            )
+        }))
+    }
+
+    /// Number of checkpoints `user`'s record for `attribute_type` has
+    pub fn num_checkpoints(&self, user: Address, attribute_type: String) -> Result<u32, Vec<u8>> {
+        let records = self.records.getter(user);
+        let count = records.getter(attribute_type).history.len();
+        Ok(count as u32)
+    }
+
+    /// (verified at, block, expires at) of one checkpoint, oldest first
+    pub fn get_checkpoint(
+        &self,
+        user: Address,
+        attribute_type: String,
+        index: u32,
+    ) -> Result<(u64, u64, u64), Vec<u8>> {
+        let records = self.records.getter(user);
+        let record = records.getter(attribute_type);
+        let checkpoint = record.history.getter(index).ok_or_else(|| b"Checkpoint out of range".to_vec())?;
+        Ok((
+            checkpoint.verified_at.get().to::<u64>(),
+            checkpoint.block.get().to::<u64>(),
+            checkpoint.expires_at.get().to::<u64>(),
+        ))
+    }
+
+    /// ERC-6372 clock: checkpoints are keyed by block timestamp
+    pub fn clock(&self) -> Result<u64, Vec<u8>> {
+        Ok(block::timestamp())
+    }
+
+    /// ERC-6372 clock mode
+    #[selector(name = "CLOCK_MODE")]
+    pub fn clock_mode(&self) -> Result<String, Vec<u8>> {
+        Ok("mode=timestamp".into())
+    }
+
+    /// (verified, verified at, expires at, proof hash), laid out as
+    /// `ComplianceRegistry.getRecord`'s `ComplianceRecord`
+    pub fn get_record(
+        &self,
+        user: Address,
+        attribute_type: String,
+    ) -> Result<(bool, U256, U256, FixedBytes<32>), Vec<u8>> {
+        let records = self.records.getter(user);
+        let record = records.getter(attribute_type);
+        let verified_at = record.verified_at.get();
+        Ok((
+            !verified_at.is_zero(),
+            U256::from(verified_at),
+            U256::from(record.expires_at.get()),
+            record.proof_hash.get(),
+        ))
+    }
+
+    /// (circuit id, key fingerprint) of the proof behind a record
+    pub fn get_record_source(
+        &self,
+        user: Address,
+        attribute_type: String,
+    ) -> Result<(FixedBytes<32>, FixedBytes<32>), Vec<u8>> {
+        let records = self.records.getter(user);
+        let record = records.getter(attribute_type);
+        Ok((record.circuit_id.get(), record.key_fingerprint.get()))
+    }
+}
+
+// ============================================================================
+// RECORD KEEPING
+// ============================================================================
+
+impl ComplianceRecords {
+    /// Whether the verifier has marked a key compromised
+    fn key_compromised(&self, fingerprint: FixedBytes<32>) -> bool {
+        let calldata = IKeyRegistry::isKeyCompromisedCall { fingerprint }.abi_encode();
+        // A verifier that cannot answer trusts no key
+        call::static_call(Call::new(), self.verifier.get(), &calldata)
+            .ok()
+            .and_then(|output| IKeyRegistry::isKeyCompromisedCall::abi_decode_returns(&output, true).ok())
+            .is_none_or(|output| output.compromised)
+    }
+
+    /// Last checkpoint whose `key` is at most `point`, unless proven under a
+    /// key since marked compromised. Checkpoints are appended in time and
+    /// block order, so either key is sorted
+    fn checkpoint_before<'a>(
+        &self,
+        history: &'a StorageVec<RecordCheckpoint>,
+        point: u64,
+        key: impl Fn(&RecordCheckpoint) -> U64,
+    ) -> Option<StorageGuard<'a, RecordCheckpoint>> {
+        let key = |index: usize| history.getter(index).map_or(0, |checkpoint| key(&checkpoint).to::<u64>());
+
+        // First checkpoint written after `point`
+        let (mut low, mut high) = (0, history.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match key(mid) > point {
+                true => high = mid,
+                false => low = mid + 1,
+            }
+        }
+        let latest = history.getter(high.checked_sub(1)?)?;
+        (!self.key_compromised(latest.key_fingerprint.get())).then_some(latest)
+    }
+
+    /// Whether a record is unexpired, at most `max_age` seconds old unless
+    /// `max_age` is 0, and proven under a key still trusted
+    fn is_current(&self, record: &ComplianceRecord, max_age: u64) -> bool {
+        let verified_at = record.verified_at.get().to::<u64>();
+        let now = block::timestamp();
+        let fresh = max_age == 0 || now - verified_at <= max_age;
+        let current = verified_at != 0 && now < record.expires_at.get().to::<u64>() && fresh;
+        current && !self.key_compromised(record.key_fingerprint.get())
+    }
+}
+
+#[cfg(all(test, not(feature = "export-abi")))]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        bind_squares, record, selector, statement, system, DEFAULT_SENDER, GATEWAY, GENESIS_TIMESTAMP, RECORDS,
+    };
+
+    const OWNER: Address = DEFAULT_SENDER;
+    const ALICE: Address = Address::repeat_byte(0xa1);
+    const BOB: Address = Address::repeat_byte(0xb0);
+    const KYC: FixedBytes<32> = FixedBytes([0x4b; 32]);
+
+    #[test]
+    fn test_registry_abi() {
+        let host = system();
+        let pk = bind_squares(&host, KYC, "accredited_investor", 3600, 2, 2);
+        record(&host, KYC, &pk, &statement(ALICE, GENESIS_TIMESTAMP)).unwrap();
+        let logs = host.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics, [B256::from(COMPLIANCE_VERIFIED), ALICE.into_word()]);
+        let data = (
+            "accredited_investor".to_string(),
+            U256::from(GENESIS_TIMESTAMP),
+            U256::from(GENESIS_TIMESTAMP + 3600),
+        );
+        assert_eq!(logs[0].data, data.abi_encode_params());
+
+        // What `MockBUIDL.isCompliant` sends, and `ComplianceRegistry.getRecord`'s layout
+        let args = (ALICE, "accredited_investor".to_string()).abi_encode_params();
+        let call = [&selector("isCompliant(address,string)")[..], &args].concat();
+        assert_eq!(host.call_on(RECORDS, &call), Ok(true.abi_encode()));
+        let call = [&selector("getRecord(address,string)")[..], &args].concat();
+        let record = host.call_on(RECORDS, &call).unwrap();
+        assert_eq!(record.len(), 4 * 32);
+        assert_eq!(record[..64], [true.abi_encode(), U256::from(GENESIS_TIMESTAMP).abi_encode()].concat());
+
+        // Only the gateway writes, once, and nothing is administered
+        assert_eq!(host.records(|records| records.get_gateway()), Ok(GATEWAY));
+        assert_eq!(
+            host.records(|records| records.initialize(ALICE, ALICE)),
+            Err(b"Already initialized".to_vec())
+        );
+        let write = |records: &mut ComplianceRecords| {
+            records.write_record(BOB, "kyc".into(), KYC, 1, u64::MAX, KYC, KYC, Vec::new())
+        };
+        assert_eq!(host.records(write), Err(b"Caller is not the gateway".to_vec()));
+        host.set_sender(GATEWAY);
+        assert_eq!(host.records(write), Ok(()));
+        host.set_sender(OWNER);
+        assert!(host.records(|records| records.is_compliant(BOB, "kyc".into())).unwrap());
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        const T0: u64 = GENESIS_TIMESTAMP;
+        let host = system();
+        let pk = bind_squares(&host, KYC, "kyc", 100, 2, 8);
+        let record = |issued_at| record(&host, KYC, &pk, &statement(ALICE, issued_at));
+        let at = |timepoint| host.records(|records| records.is_compliant_at(ALICE, "kyc".into(), timepoint));
+
+        record(T0).unwrap();
+        assert_eq!(at(T0), Err(b"Future lookup".to_vec()));
+        host.advance(10);
+        assert_eq!(at(T0 - 1), Ok(false));
+        assert_eq!(at(T0), Ok(true));
+        assert_eq!(at(T0 + 9), Ok(true));
+        assert_eq!(host.records(|records| records.is_compliant_at(BOB, "kyc".into(), T0)), Ok(false));
+
+        // Expiry shows in history without a write; writes at one timestamp share a checkpoint
+        host.advance(200);
+        assert_eq!(at(T0 + 99), Ok(true));
+        assert_eq!(at(T0 + 100), Ok(false));
+        record(T0 + 209).unwrap();
+        record(T0 + 210).unwrap();
+        assert_eq!(host.records(|records| records.num_checkpoints(ALICE, "kyc".into())), Ok(2));
+        assert_eq!(
+            host.records(|records| records.get_checkpoint(ALICE, "kyc".into(), 1)),
+            Ok((T0 + 210, 3, T0 + 310))
+        );
+        assert_eq!(
+            host.records(|records| records.get_checkpoint(ALICE, "kyc".into(), 2)),
+            Err(b"Checkpoint out of range".to_vec())
+        );
+
+        // Renewals before expiry: every past timepoint resolves to the write before it
+        for renewal in 1..=5 {
+            host.advance(60);
+            record(T0 + 210 + 60 * renewal).unwrap();
+        }
+        host.advance(1);
+        assert_eq!(host.records(|records| records.num_checkpoints(ALICE, "kyc".into())), Ok(7));
+        assert_eq!(at(T0 + 150), Ok(false));
+        assert_eq!(at(T0 + 209), Ok(false));
+        for timepoint in T0 + 210..T0 + 511 {
+            assert_eq!(at(timepoint), Ok(true), "at {timepoint}");
+        }
+        assert!(host.records(|records| records.is_compliant(ALICE, "kyc".into())).unwrap());
+
+        // By block: the checkpoint in effect at the end of each block, one per block from 3
+        let at_block = |block| host.records(|records| records.checkpoint_at_block(ALICE, "kyc".into(), block));
+        assert_eq!(at_block(9), Err(b"Future lookup".to_vec()));
+        assert_eq!(at_block(0), Ok((0, 0, 0)));
+        assert_eq!(at_block(1), Ok((T0, 1, T0 + 100)));
+        assert_eq!(at_block(2), Ok((T0, 1, T0 + 100)));
+        assert_eq!(at_block(3), Ok((T0 + 210, 3, T0 + 310)));
+        for renewal in 1..=5 {
+            let verified_at = T0 + 210 + 60 * renewal;
+            assert_eq!(at_block(3 + renewal), Ok((verified_at, 3 + renewal, verified_at + 100)));
+        }
+        assert_eq!(host.records(|records| records.checkpoint_at_block(BOB, "kyc".into(), 8)), Ok((0, 0, 0)));
+
+        // ERC-6372 clock, through the ABI
+        let clock_mode = host.call_on(RECORDS, &selector("CLOCK_MODE()")).unwrap();
+        assert_eq!(String::abi_decode(&clock_mode, true).unwrap(), "mode=timestamp");
+        let clock = host.call_on(RECORDS, &selector("clock()")).unwrap();
+        assert_eq!(clock, U256::from(T0 + 511).abi_encode());
+    }
+
+    #[test]
+    fn test_compromised_keys() {
+        const V1: FixedBytes<32> = FixedBytes([0x51; 32]);
+        const V2: FixedBytes<32> = FixedBytes([0x52; 32]);
+        let host = system();
+        let keys = [bind_squares(&host, V1, "kyc", 3600, 2, 9), bind_squares(&host, V2, "kyc", 3600, 2, 10)];
+        record(&host, V1, &keys[0], &statement(ALICE, GENESIS_TIMESTAMP)).unwrap();
+        record(&host, V2, &keys[1], &statement(BOB, GENESIS_TIMESTAMP)).unwrap();
+        host.delegations(|delegations| delegations.add_implication("kyc".into(), "retail".into())).unwrap();
+
+        let fingerprint = host.contract().get_key_fingerprint(V1).unwrap();
+        host.transact(|verifier| verifier.mark_key_compromised(fingerprint)).unwrap();
+
+        // Every record under the key is void at once, including through implications
+        let compliant = |user, attribute: &str| {
+            host.delegations(|delegations| delegations.is_compliant(user, attribute.into())).unwrap()
+        };
+        assert!(!host.records(|records| records.is_compliant(ALICE, "kyc".into())).unwrap());
+        assert!(!compliant(ALICE, "retail"));
+        assert!(compliant(BOB, "kyc"));
+        assert_eq!(host.records(|records| records.get_record_source(ALICE, "kyc".into())), Ok((V1, fingerprint)));
+        assert_eq!(
+            record(&host, V1, &keys[0], &statement(ALICE, GENESIS_TIMESTAMP)),
+            Err(b"Verifying key is compromised".to_vec())
+        );
+
+        // Re-proving under the new setup restores the record
+        host.advance(1);
+        record(&host, V2, &keys[1], &statement(ALICE, GENESIS_TIMESTAMP + 1)).unwrap();
+        assert!(compliant(ALICE, "kyc"));
+        let (_, source) = host.records(|records| records.get_record_source(ALICE, "kyc".into())).unwrap();
+        assert_eq!(host.contract().is_key_compromised(source), Ok(false));
+
+        // History forgets the key's proofs as well, but keeps the new one
+        host.advance(1);
+        let at = |user, timepoint| host.records(|records| records.is_compliant_at(user, "kyc".into(), timepoint));
+        assert_eq!(at(ALICE, GENESIS_TIMESTAMP), Ok(false));
+        assert_eq!(at(BOB, GENESIS_TIMESTAMP), Ok(true));
+        assert_eq!(at(ALICE, GENESIS_TIMESTAMP + 1), Ok(true));
+        let at_block = |user, block| host.records(|records| records.checkpoint_at_block(user, "kyc".into(), block));
+        assert_eq!(at_block(ALICE, 1), Ok((0, 0, 0)));
+        assert_eq!(at_block(BOB, 1), Ok((GENESIS_TIMESTAMP, 1, GENESIS_TIMESTAMP + 3600)));
+    }
+}
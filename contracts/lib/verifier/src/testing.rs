@@ -0,0 +1,911 @@
+//! IN-PROCESS STYLUS HOST FOR `cargo test`
+//!
+//! On native targets the SDK's `vm_hooks` imports are plain unresolved
+//! symbols, so the test binary supplies them here: per-contract storage,
+//! calldata and return data, logs, `msg`/`block` values, the sha256 (0x02)
+//! and BN254 precompiles 0x06 (add), 0x07 (mul) and 0x08 (pairing), computed
+//! with `precompile::Native` and arkworks, and the other accounts a program
+//! calls: programs from this crate deployed with [`TestHost::deploy_program`]
+//! and stand-in contracts deployed with [`TestHost::deploy`].
+//!
+//! Calls between programs run in-process on a frame stack, so each sees its
+//! own storage, `contract::address()` and `msg::sender()`, and a call that
+//! reverts drops its storage writes and logs as the EVM would.
+//!
+//! The SDK caches `msg::sender()` and `block::timestamp()` for the lifetime
+//! of the process (a real program instance lives for one call), so the
+//! contracts read them through the [`msg`] and [`block`] shims below in test
+//! builds. One [`TestHost`] exists at a time; tests using it run serially.
+
+use crate::{
+    assets::AssetCompliance, bridge::ComplianceBridge, delegations::ComplianceDelegations,
+    gateway::ComplianceGateway, policies::CompliancePolicies, precompile::Precompiles as _,
+    records::ComplianceRecords, ZKVerifier,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use arbshield_verifier_core::{
+    jurisdiction::{self, JurisdictionSet},
+    precompile, Groth16Proof, VerifyingKey,
+};
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{BigInt, BigInteger, Field, One, PrimeField, Zero};
+use ark_groth16::{Groth16, ProvingKey};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_snark::SNARK;
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use stylus_sdk::{
+    abi::{Bytes, Router},
+    alloy_primitives::{Address, FixedBytes, B256, U256},
+    storage::{StorageType, TopLevelStorage},
+    ArbResult,
+};
+
+/// Caller address a fresh host starts with, and the owner `system` sets
+pub const DEFAULT_SENDER: Address = Address::repeat_byte(0xaa);
+/// Address the program this build exports is deployed at: `ZKVerifier`,
+/// unless a program feature is enabled
+pub const CONTRACT_ADDRESS: Address = Address::repeat_byte(0xcc);
+/// Where `system` deploys `ComplianceGateway`
+pub const GATEWAY: Address = Address::repeat_byte(0xe1);
+/// Where `system` deploys `ComplianceRecords`
+pub const RECORDS: Address = Address::repeat_byte(0xe2);
+/// Where `system` deploys `ComplianceDelegations`
+pub const DELEGATIONS: Address = Address::repeat_byte(0xe3);
+/// Where `system` deploys `CompliancePolicies`
+pub const POLICIES: Address = Address::repeat_byte(0xe4);
+/// Where `system` deploys `AssetCompliance`
+pub const ASSETS: Address = Address::repeat_byte(0xe5);
+/// Where `system` deploys `ComplianceBridge`
+pub const BRIDGE: Address = Address::repeat_byte(0xe6);
+/// Block timestamp a fresh host starts with
+pub const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+
+/// Code of a contract: calldata in, return or revert data out
+pub type Account = fn(&[u8]) -> ArbResult;
+
+#[derive(Clone, Copy)]
+enum Code {
+    /// A stand-in; calls to it are recorded for `take_calls`
+    Stub(Account),
+    /// A program from this crate, run in its own frame
+    Program(Account),
+}
+
+/// One `emit_log` call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+    /// The contract that emitted it
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Vec<u8>,
+}
+
+/// A program call in progress
+struct Frame {
+    address: Address,
+    sender: Address,
+}
+
+struct State {
+    storage: BTreeMap<(Address, B256), B256>,
+    logs: Vec<Log>,
+    accounts: BTreeMap<Address, Code>,
+    /// Calls that reached a stand-in contract: (address, calldata)
+    calls: Vec<(Address, Vec<u8>)>,
+    /// Innermost last; empty between transactions
+    frames: Vec<Frame>,
+    sender: Address,
+    timestamp: u64,
+    block_number: u64,
+    calldata: Vec<u8>,
+    result: Vec<u8>,
+    return_data: Vec<u8>,
+}
+
+impl State {
+    const EMPTY: Self = Self {
+        storage: BTreeMap::new(),
+        logs: Vec::new(),
+        accounts: BTreeMap::new(),
+        calls: Vec::new(),
+        frames: Vec::new(),
+        sender: DEFAULT_SENDER,
+        timestamp: GENESIS_TIMESTAMP,
+        block_number: 1,
+        calldata: Vec::new(),
+        result: Vec::new(),
+        return_data: Vec::new(),
+    };
+
+    /// Contract whose storage and logs hostios touch; `CONTRACT_ADDRESS`
+    /// outside any call, for reads through `TestHost::contract`
+    fn address(&self) -> Address {
+        self.frames.last().map_or(CONTRACT_ADDRESS, |frame| frame.address)
+    }
+
+    /// `msg::sender()` of the innermost call
+    fn sender(&self) -> Address {
+        self.frames.last().map_or(self.sender, |frame| frame.sender)
+    }
+
+    /// Who a new call comes from: the innermost program, or the transaction sender
+    fn caller(&self) -> Address {
+        self.frames.last().map_or(self.sender, |frame| frame.address)
+    }
+}
+
+static STATE: Mutex<State> = Mutex::new(State::EMPTY);
+static EXCLUSIVE: Mutex<()> = Mutex::new(());
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+type Snapshot = (BTreeMap<(Address, B256), B256>, usize, usize);
+
+fn snapshot() -> Snapshot {
+    let state = state();
+    (state.storage.clone(), state.logs.len(), state.calls.len())
+}
+
+fn restore((storage, logs, calls): Snapshot) {
+    let mut state = state();
+    state.storage = storage;
+    state.logs.truncate(logs);
+    state.calls.truncate(calls);
+}
+
+/// Call the account at `address` from the current frame, as `call_contract`
+/// does: programs run in a frame of their own and revert on failure, stand-ins
+/// are recorded, and accounts without code succeed with no return data
+fn run(address: Address, input: &[u8]) -> ArbResult {
+    let code = state().accounts.get(&address).copied();
+    match code {
+        None => Ok(Vec::new()),
+        Some(Code::Stub(code)) => {
+            state().calls.push((address, input.to_vec()));
+            code(input)
+        }
+        Some(Code::Program(code)) => in_frame(address, || code(input)),
+    }
+}
+
+/// Run `call` as `address`, called by the current frame, undoing its writes if it fails
+fn in_frame<T>(address: Address, call: impl FnOnce() -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+    let snapshot = snapshot();
+    {
+        let mut state = state();
+        let sender = state.caller();
+        state.frames.push(Frame { address, sender });
+    }
+    let result = call();
+    state().frames.pop();
+    if result.is_err() {
+        restore(snapshot);
+    }
+    result
+}
+
+/// A program of this crate as `#[entrypoint]` would route its calldata
+pub fn program<P>(input: &[u8]) -> ArbResult
+where
+    P: StorageType + TopLevelStorage + Router<P, Storage = P>,
+{
+    let Some((selector, args)) = input.split_first_chunk::<4>() else {
+        return Err(Vec::new());
+    };
+    // SAFETY: the program's own frame, so no other handle on its storage is live
+    let mut storage = unsafe { P::new(U256::ZERO, 0) };
+    P::route(&mut storage, u32::from_be_bytes(*selector), args).unwrap_or_else(|| Err(Vec::new()))
+}
+
+/// The program this build exports, through the generated `user_entrypoint`
+fn entrypoint(input: &[u8]) -> ArbResult {
+    state().calldata = input.to_vec();
+    let status = crate::user_entrypoint(input.len());
+    let output = core::mem::take(&mut state().result);
+    match status {
+        0 => Ok(output),
+        _ => Err(output),
+    }
+}
+
+/// Exclusive handle on the emulated chain, reset when created
+pub struct TestHost {
+    _exclusive: MutexGuard<'static, ()>,
+}
+
+impl TestHost {
+    /// A fresh chain with the exported program at `CONTRACT_ADDRESS`
+    pub fn new() -> Self {
+        // A failed test poisons the lock; the next one starts from a clean state anyway
+        let exclusive = EXCLUSIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        *state() = State::EMPTY;
+        state().accounts.insert(CONTRACT_ADDRESS, Code::Program(entrypoint));
+        Self { _exclusive: exclusive }
+    }
+
+    /// The verifier at `CONTRACT_ADDRESS`, as the entrypoint sees it
+    pub fn contract(&self) -> ZKVerifier {
+        // SAFETY: the host is exclusive, so no other storage handle is live
+        unsafe { ZKVerifier::new(U256::ZERO, 0) }
+    }
+
+    pub fn set_sender(&self, sender: Address) {
+        state().sender = sender;
+    }
+
+    /// Move block time forward, starting a new block
+    pub fn advance(&self, secs: u64) {
+        let mut state = state();
+        state.timestamp += secs;
+        state.block_number += 1;
+    }
+
+    /// Run typed calls to the program at `address` as one transaction from
+    /// the sender: storage writes and logs are dropped if it returns an
+    /// error, as a revert would
+    pub fn transact_on<P: StorageType, T>(
+        &self,
+        address: Address,
+        call: impl FnOnce(&mut P) -> Result<T, Vec<u8>>,
+    ) -> Result<T, Vec<u8>> {
+        // SAFETY: the host is exclusive, so no other storage handle is live
+        in_frame(address, || call(&mut unsafe { P::new(U256::ZERO, 0) }))
+    }
+
+    /// `transact_on` the verifier at `CONTRACT_ADDRESS`
+    pub fn transact<T>(&self, call: impl FnOnce(&mut ZKVerifier) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(CONTRACT_ADDRESS, call)
+    }
+
+    /// `transact_on` the gateway at `GATEWAY`
+    pub fn gateway<T>(&self, call: impl FnOnce(&mut ComplianceGateway) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(GATEWAY, call)
+    }
+
+    /// `transact_on` the bridge at `BRIDGE`
+    pub fn bridge<T>(&self, call: impl FnOnce(&mut ComplianceBridge) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(BRIDGE, call)
+    }
+
+    /// `transact_on` the records at `RECORDS`
+    pub fn records<T>(&self, call: impl FnOnce(&mut ComplianceRecords) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(RECORDS, call)
+    }
+
+    /// `transact_on` the delegations at `DELEGATIONS`
+    pub fn delegations<T>(
+        &self,
+        call: impl FnOnce(&mut ComplianceDelegations) -> Result<T, Vec<u8>>,
+    ) -> Result<T, Vec<u8>> {
+        self.transact_on(DELEGATIONS, call)
+    }
+
+    /// `transact_on` the policies at `POLICIES`
+    pub fn policies<T>(&self, call: impl FnOnce(&mut CompliancePolicies) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(POLICIES, call)
+    }
+
+    /// `transact_on` the asset rules at `ASSETS`
+    pub fn assets<T>(&self, call: impl FnOnce(&mut AssetCompliance) -> Result<T, Vec<u8>>) -> Result<T, Vec<u8>> {
+        self.transact_on(ASSETS, call)
+    }
+
+    /// Send ABI calldata to the account at `address` as a transaction,
+    /// reverting on failure
+    pub fn call_on(&self, address: Address, calldata: &[u8]) -> ArbResult {
+        let snapshot = snapshot();
+        let result = run(address, calldata);
+        if result.is_err() {
+            restore(snapshot);
+        }
+        result
+    }
+
+    /// `call_on` the exported program at `CONTRACT_ADDRESS`, through the
+    /// generated `user_entrypoint`
+    pub fn call(&self, calldata: &[u8]) -> ArbResult {
+        self.call_on(CONTRACT_ADDRESS, calldata)
+    }
+
+    /// Raw storage word of the contract at `CONTRACT_ADDRESS`, for checking layout
+    pub fn storage(&self, slot: U256) -> B256 {
+        state().storage.get(&(CONTRACT_ADDRESS, B256::from(slot))).copied().unwrap_or_default()
+    }
+
+    /// Logs emitted since the last call to `take_logs`
+    pub fn take_logs(&self) -> Vec<Log> {
+        core::mem::take(&mut state().logs)
+    }
+
+    /// Put a stand-in contract at `address` that answers every call with `code`
+    pub fn deploy(&self, address: Address, code: Account) {
+        state().accounts.insert(address, Code::Stub(code));
+    }
+
+    /// Put program `P` at `address`, with storage of its own
+    pub fn deploy_program<P>(&self, address: Address)
+    where
+        P: StorageType + TopLevelStorage + Router<P, Storage = P>,
+    {
+        state().accounts.insert(address, Code::Program(program::<P>));
+    }
+
+    /// Calls to stand-in contracts since the last call to `take_calls`
+    pub fn take_calls(&self) -> Vec<(Address, Vec<u8>)> {
+        core::mem::take(&mut state().calls)
+    }
+}
+
+/// `stylus_sdk::msg` as seen by the contracts in test builds
+pub mod msg {
+    use super::*;
+
+    pub fn sender() -> Address {
+        state().sender()
+    }
+}
+
+/// `stylus_sdk::block` as seen by the contracts in test builds
+pub mod block {
+    use super::*;
+
+    pub fn timestamp() -> u64 {
+        state().timestamp
+    }
+
+    pub fn number() -> u64 {
+        state().block_number
+    }
+}
+
+// ============================================================================
+// FIXTURES
+// ============================================================================
+
+/// A host with all seven programs deployed at their addresses and wired up
+/// as `deploy.sh` does, owned by `DEFAULT_SENDER`
+pub fn system() -> TestHost {
+    let host = TestHost::new();
+    host.deploy_program::<ComplianceGateway>(GATEWAY);
+    host.deploy_program::<ComplianceRecords>(RECORDS);
+    host.deploy_program::<ComplianceDelegations>(DELEGATIONS);
+    host.deploy_program::<CompliancePolicies>(POLICIES);
+    host.deploy_program::<AssetCompliance>(ASSETS);
+    host.deploy_program::<ComplianceBridge>(BRIDGE);
+
+    let owner = DEFAULT_SENDER;
+    host.transact(|verifier| verifier.initialize(owner)).unwrap();
+    host.gateway(|gateway| gateway.initialize(owner, CONTRACT_ADDRESS, RECORDS)).unwrap();
+    host.records(|records| records.initialize(CONTRACT_ADDRESS, GATEWAY)).unwrap();
+    host.delegations(|delegations| delegations.initialize(owner, RECORDS)).unwrap();
+    host.policies(|policies| policies.initialize(GATEWAY, DELEGATIONS)).unwrap();
+    host.assets(|assets| assets.initialize(owner, RECORDS, DELEGATIONS, POLICIES)).unwrap();
+    host.bridge(|bridge| bridge.initialize(owner, GATEWAY, RECORDS, ASSETS)).unwrap();
+    host.take_logs();
+    host
+}
+
+/// Register a fresh `Squares` key as `circuit_id` with the verifier and
+/// bind it to `attribute`, with subject input 0 and issued-at input 1
+/// (owner only); returns its proving key
+pub fn bind_squares(
+    host: &TestHost,
+    circuit_id: FixedBytes<32>,
+    attribute: &str,
+    validity: u64,
+    inputs: usize,
+    seed: u64,
+) -> ProvingKey<Bn254> {
+    let (pk, vk) = squares_keys(inputs, seed);
+    host.transact(|verifier| verifier.register_circuit(circuit_id, vk.to_bytes().into(), 0)).unwrap();
+    host.gateway(|gateway| gateway.bind_attribute(circuit_id, attribute.into(), validity, 0, 1)).unwrap();
+    host.take_logs();
+    pk
+}
+
+/// Prove `inputs` under `circuit_id` and submit the proof to the gateway
+pub fn record(
+    host: &TestHost,
+    circuit_id: FixedBytes<32>,
+    pk: &ProvingKey<Bn254>,
+    inputs: &[Fr],
+) -> Result<(Address, u64), Vec<u8>> {
+    let proof = prove_squares(pk, inputs);
+    host.gateway(|gateway| gateway.verify_attribute(circuit_id, proof.into(), packed_inputs(inputs)))
+}
+
+pub fn word(value: u64) -> [u8; 32] {
+    U256::from(value).to_be_bytes()
+}
+
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak_const::Keccak256::new().update(signature.as_bytes()).finalize();
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// x_i · x_i = w_i for each public input x_i
+#[derive(Clone)]
+struct Squares(Vec<Option<Fr>>);
+
+impl ConstraintSynthesizer<Fr> for Squares {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for input in self.0 {
+            let x = cs.new_input_variable(|| input.ok_or(SynthesisError::AssignmentMissing))?;
+            let w = cs.new_witness_variable(|| input.map(|x| x * x).ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce_constraint(x.into(), x.into(), w.into())?;
+        }
+        Ok(())
+    }
+}
+
+/// Proving and verifying key for `Squares` with `inputs` public inputs
+pub fn squares_keys(inputs: usize, seed: u64) -> (ProvingKey<Bn254>, VerifyingKey) {
+    setup(Squares(alloc::vec![None; inputs]), seed)
+}
+
+fn setup(circuit: impl ConstraintSynthesizer<Fr>, seed: u64) -> (ProvingKey<Bn254>, VerifyingKey) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit, &mut rng).unwrap();
+    let vk = VerifyingKey {
+        alpha_g1: vk.alpha_g1,
+        beta_g2: vk.beta_g2,
+        gamma_g2: vk.gamma_g2,
+        delta_g2: vk.delta_g2,
+        ic: vk.gamma_abc_g1,
+    };
+    (pk, vk)
+}
+
+/// Uncompressed proof for `Squares` with the given public inputs
+pub fn prove_squares(pk: &ProvingKey<Bn254>, inputs: &[Fr]) -> Vec<u8> {
+    let mut rng = StdRng::seed_from_u64(inputs.len() as u64);
+    let proof = Groth16::<Bn254>::prove(pk, Squares(inputs.iter().copied().map(Some).collect()), &mut rng).unwrap();
+    Groth16Proof { a: proof.a, b: proof.b, c: proof.c }.to_uncompressed_bytes().to_vec()
+}
+
+/// The constraints of `circuits/jurisdiction.circom`, public signals in
+/// circom's order: the four set words, then subject and issued-at
+#[derive(Clone, Default)]
+struct Residency {
+    subject: Address,
+    issued_at: u64,
+    country: u16,
+    members: Vec<u16>,
+}
+
+impl ConstraintSynthesizer<Fr> for Residency {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let set = JurisdictionSet::from_codes(&self.members).map_err(|_| SynthesisError::Unsatisfiable)?;
+        let words = set
+            .words()
+            .iter()
+            .map(|word| cs.new_input_variable(|| Ok(Fr::from_be_bytes_mod_order(word))))
+            .collect::<Result<Vec<_>, _>>()?;
+        cs.new_input_variable(|| Ok(address_input(self.subject)))?;
+        cs.new_input_variable(|| Ok(Fr::from(self.issued_at)))?;
+        let country = cs.new_witness_variable(|| Ok(Fr::from(self.country)))?;
+
+        let (mut hits, mut index) = (lc!(), lc!());
+        let mut sums = alloc::vec![lc!(); jurisdiction::WORDS];
+        let mut bit = Fr::one();
+        for code in 0..jurisdiction::CODE_COUNT {
+            let member = cs.new_witness_variable(|| Ok(Fr::from(set.contains(code as u16))))?;
+            let hit = cs.new_witness_variable(|| Ok(Fr::from(code == self.country as usize)))?;
+            cs.enforce_constraint(lc!() + hit, lc!() + hit - Variable::One, lc!())?;
+            cs.enforce_constraint(lc!() + member, lc!() + member - Variable::One, lc!())?;
+            cs.enforce_constraint(lc!() + hit, lc!() + Variable::One - member, lc!())?;
+            hits = hits + hit;
+            index += (Fr::from(code as u64), hit);
+            if code % jurisdiction::CODES_PER_WORD == 0 {
+                bit = Fr::one();
+            }
+            sums[code / jurisdiction::CODES_PER_WORD] += (bit, member);
+            bit.double_in_place();
+        }
+        cs.enforce_constraint(hits, lc!() + Variable::One, lc!() + Variable::One)?;
+        cs.enforce_constraint(index, lc!() + Variable::One, lc!() + country)?;
+        for (word, sum) in words.into_iter().zip(sums) {
+            cs.enforce_constraint(sum, lc!() + Variable::One, lc!() + word)?;
+        }
+        Ok(())
+    }
+}
+
+/// Proving and verifying key for `circuits/jurisdiction.circom`
+pub fn residency_keys(seed: u64) -> (ProvingKey<Bn254>, VerifyingKey) {
+    setup(Residency::default(), seed)
+}
+
+/// Whether `country` is in `members`, i.e. the circuit has a witness
+pub fn residency_satisfied(country: u16, members: &[u16]) -> bool {
+    let cs = ConstraintSystem::new_ref();
+    let circuit = Residency { country, members: members.to_vec(), ..Residency::default() };
+    circuit.generate_constraints(cs.clone()).is_ok() && cs.is_satisfied().unwrap()
+}
+
+/// Uncompressed proof that `subject`'s `country` is in `members`, and its
+/// public inputs
+pub fn prove_residency(
+    pk: &ProvingKey<Bn254>,
+    subject: Address,
+    issued_at: u64,
+    country: u16,
+    members: &[u16],
+) -> (Vec<u8>, Vec<Fr>) {
+    let mut rng = StdRng::seed_from_u64(issued_at);
+    let circuit = Residency { subject, issued_at, country, members: members.to_vec() };
+    let proof = Groth16::<Bn254>::prove(pk, circuit, &mut rng).unwrap();
+    let words = JurisdictionSet::from_codes(members).unwrap().to_bytes();
+    let words = words.chunks(32).map(Fr::from_be_bytes_mod_order);
+    let inputs = words.chain(statement(subject, issued_at)).collect();
+    (Groth16Proof { a: proof.a, b: proof.b, c: proof.c }.to_uncompressed_bytes().to_vec(), inputs)
+}
+
+pub fn address_input(address: Address) -> Fr {
+    Fr::from_be_bytes_mod_order(address.as_slice())
+}
+
+/// Public inputs of an attribute statement bound with subject input 0
+/// and issued-at input 1
+pub fn statement(subject: Address, issued_at: u64) -> [Fr; 2] {
+    [address_input(subject), Fr::from(issued_at)]
+}
+
+/// `statement` followed by the four words of the set of `codes`, for a
+/// circuit whose jurisdiction input is 2
+pub fn placed(subject: Address, issued_at: u64, codes: &[u16]) -> Vec<Fr> {
+    let words = JurisdictionSet::from_codes(codes).unwrap().to_bytes();
+    let words = words.chunks(32).map(Fr::from_be_bytes_mod_order);
+    statement(subject, issued_at).into_iter().chain(words).collect()
+}
+
+pub fn packed_inputs(inputs: &[Fr]) -> Bytes {
+    inputs.iter().flat_map(|input| input.into_bigint().to_bytes_be()).collect::<Vec<u8>>().into()
+}
+
+// ============================================================================
+// PRECOMPILES
+// ============================================================================
+
+/// Output of a precompile at `address`, `None` if there is none there;
+/// `Err` for inputs the EVM rejects (the call fails)
+fn precompile(address: Address, input: &[u8]) -> Option<Result<Vec<u8>, ()>> {
+    let id = match address.0[..19].iter().all(|byte| *byte == 0) {
+        true => address.0[19],
+        false => return None,
+    };
+    Some(match id {
+        precompile::SHA256 => precompile::Native.call(id, input).ok_or(()),
+        0x06 => ec_add(&padded::<128>(input)),
+        0x07 => ec_mul(&padded::<96>(input)),
+        0x08 => ec_pairing(input),
+        _ => return None,
+    })
+}
+
+/// EIP-196: missing input bytes read as zero, extra bytes are ignored
+fn padded<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    let len = input.len().min(N);
+    bytes[..len].copy_from_slice(&input[..len]);
+    bytes
+}
+
+fn ec_add(input: &[u8; 128]) -> Result<Vec<u8>, ()> {
+    let sum = g1(&input[..64])? + g1(&input[64..])?;
+    Ok(encode_g1(&sum.into_affine()))
+}
+
+fn ec_mul(input: &[u8; 96]) -> Result<Vec<u8>, ()> {
+    // The scalar is a full 256-bit integer, not reduced mod r
+    let product = g1(&input[..64])?.mul_bigint(bigint(&input[64..]));
+    Ok(encode_g1(&product.into_affine()))
+}
+
+/// EIP-197: (G1 || G2) pairs with G2 as x.c1 || x.c0 || y.c1 || y.c0
+fn ec_pairing(input: &[u8]) -> Result<Vec<u8>, ()> {
+    if !input.len().is_multiple_of(192) {
+        return Err(());
+    }
+    let (g1s, g2s): (Vec<_>, Vec<_>) = input
+        .chunks_exact(192)
+        .map(|pair| Ok((g1(&pair[..64])?, g2(&pair[64..])?)))
+        .collect::<Result<Vec<_>, ()>>()?
+        .into_iter()
+        .unzip();
+    let valid = Bn254::multi_pairing(g1s, g2s).0.is_one();
+    Ok(U256::from(valid as u8).to_be_bytes::<32>().to_vec())
+}
+
+fn bigint(bytes: &[u8]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("8 bytes"));
+    }
+    BigInt::new(limbs)
+}
+
+/// Coordinates at or above p are rejected, not reduced
+fn fq(bytes: &[u8]) -> Result<Fq, ()> {
+    Fq::from_bigint(bigint(bytes)).ok_or(())
+}
+
+fn g1(bytes: &[u8]) -> Result<G1Affine, ()> {
+    let (x, y) = (fq(&bytes[..32])?, fq(&bytes[32..64])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new_unchecked(x, y);
+    point.is_on_curve().then_some(point).ok_or(())
+}
+
+fn g2(bytes: &[u8]) -> Result<G2Affine, ()> {
+    let x = Fq2::new(fq(&bytes[32..64])?, fq(&bytes[..32])?);
+    let y = Fq2::new(fq(&bytes[96..128])?, fq(&bytes[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(());
+    }
+    Ok(point)
+}
+
+fn encode_g1(point: &G1Affine) -> Vec<u8> {
+    match point.xy() {
+        Some((x, y)) => [x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()].concat(),
+        None => alloc::vec![0u8; 64],
+    }
+}
+
+// ============================================================================
+// VM HOOKS
+// ============================================================================
+
+/// Shared body of the call hostios: precompiles, then the account at the address
+unsafe fn call(contract: *const u8, calldata: *const u8, calldata_len: usize, return_data_len: *mut usize) -> u8 {
+    let address = Address::from_slice(core::slice::from_raw_parts(contract, 20));
+    let input = core::slice::from_raw_parts(calldata, calldata_len);
+    let result = match precompile(address, input) {
+        Some(output) => output.map_err(|()| Vec::new()),
+        None => run(address, input),
+    };
+    let (status, output) = match result {
+        Ok(output) => (0, output),
+        Err(revert) => (1, revert),
+    };
+    *return_data_len = output.len();
+    state().return_data = output;
+    status
+}
+
+#[no_mangle]
+unsafe extern "C" fn storage_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let state = state();
+    let value = state.storage.get(&(state.address(), key)).copied().unwrap_or_default();
+    core::ptr::copy_nonoverlapping(value.as_ptr(), dest, 32);
+}
+
+/// Writes land in storage directly; each frame snapshots it to emulate reverts
+#[no_mangle]
+unsafe extern "C" fn storage_cache_bytes32(key: *const u8, value: *const u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = B256::from_slice(core::slice::from_raw_parts(value, 32));
+    let mut state = state();
+    let address = state.address();
+    state.storage.insert((address, key), value);
+}
+
+#[no_mangle]
+extern "C" fn storage_flush_cache(_clear: bool) {}
+
+#[no_mangle]
+unsafe extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
+    let digest = keccak_const::Keccak256::new()
+        .update(core::slice::from_raw_parts(bytes, len))
+        .finalize();
+    core::ptr::copy_nonoverlapping(digest.as_ptr(), output, 32);
+}
+
+#[no_mangle]
+unsafe extern "C" fn read_args(dest: *mut u8) {
+    let state = state();
+    core::ptr::copy_nonoverlapping(state.calldata.as_ptr(), dest, state.calldata.len());
+}
+
+#[no_mangle]
+unsafe extern "C" fn write_result(data: *const u8, len: usize) {
+    state().result = core::slice::from_raw_parts(data, len).to_vec();
+}
+
+#[no_mangle]
+unsafe extern "C" fn emit_log(data: *const u8, len: usize, topics: usize) {
+    let bytes = core::slice::from_raw_parts(data, len);
+    let (topics, data) = bytes.split_at(topics * 32);
+    let mut state = state();
+    let address = state.address();
+    state.logs.push(Log {
+        address,
+        topics: topics.chunks_exact(32).map(B256::from_slice).collect(),
+        data: data.to_vec(),
+    });
+}
+
+/// `console!` output, with the SDK's `debug` feature
+#[no_mangle]
+unsafe extern "C" fn log_txt(text: *const u8, len: usize) {
+    eprintln!("{}", String::from_utf8_lossy(core::slice::from_raw_parts(text, len)));
+}
+
+#[no_mangle]
+unsafe extern "C" fn static_call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+unsafe extern "C" fn call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _value: *const u8,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+unsafe extern "C" fn delegate_call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+unsafe extern "C" fn read_return_data(dest: *mut u8, offset: usize, size: usize) -> usize {
+    let state = state();
+    let available = state.return_data.get(offset..).unwrap_or_default();
+    let len = size.min(available.len());
+    core::ptr::copy_nonoverlapping(available.as_ptr(), dest, len);
+    len
+}
+
+#[no_mangle]
+extern "C" fn return_data_size() -> usize {
+    state().return_data.len()
+}
+
+#[no_mangle]
+unsafe extern "C" fn msg_sender(sender: *mut u8) {
+    core::ptr::copy_nonoverlapping(state().sender().as_ptr(), sender, 20);
+}
+
+#[no_mangle]
+unsafe extern "C" fn msg_value(value: *mut u8) {
+    core::ptr::write_bytes(value, 0, 32);
+}
+
+#[no_mangle]
+extern "C" fn msg_reentrant() -> bool {
+    false
+}
+
+#[no_mangle]
+unsafe extern "C" fn tx_origin(origin: *mut u8) {
+    core::ptr::copy_nonoverlapping(state().sender.as_ptr(), origin, 20);
+}
+
+/// Deployed programs and stand-ins have code; everything else reads as empty
+#[no_mangle]
+unsafe extern "C" fn account_codehash(address: *const u8, dest: *mut u8) {
+    let address = Address::from_slice(core::slice::from_raw_parts(address, 20));
+    let hash = match state().accounts.contains_key(&address) {
+        true => B256::from(keccak_const::Keccak256::new().update(address.as_slice()).finalize()),
+        false => B256::ZERO,
+    };
+    core::ptr::copy_nonoverlapping(hash.as_ptr(), dest, 32);
+}
+
+#[no_mangle]
+unsafe extern "C" fn contract_address(address: *mut u8) {
+    core::ptr::copy_nonoverlapping(state().address().as_ptr(), address, 20);
+}
+
+#[no_mangle]
+extern "C" fn block_timestamp() -> u64 {
+    state().timestamp
+}
+
+#[no_mangle]
+extern "C" fn block_number() -> u64 {
+    state().block_number
+}
+
+#[no_mangle]
+extern "C" fn chainid() -> u64 {
+    // Arbitrum Sepolia
+    421_614
+}
+
+#[no_mangle]
+extern "C" fn pay_for_memory_grow(_pages: u16) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use stylus_sdk::{alloy_primitives::hex, call::RawCall};
+
+    fn precompile_call(id: u8, input: &[u8]) -> ArbResult {
+        let _host = TestHost::new();
+        unsafe { RawCall::new_static().call(Address::with_last_byte(id), input) }
+    }
+
+    #[test]
+    fn test_precompiles() {
+        let g = G1Affine::generator();
+        let g_bytes = encode_g1(&g);
+        let double = encode_g1(&(g + g).into_affine());
+
+        // sha256 of "abc", FIPS 180-2
+        let abc = hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad").to_vec();
+        assert_eq!(precompile_call(2, b"abc"), Ok(abc));
+
+        // ecAdd and ecMul agree, and short inputs are zero-padded
+        assert_eq!(precompile_call(6, &[g_bytes.clone(), g_bytes.clone()].concat()), Ok(double.clone()));
+        let two = U256::from(2).to_be_bytes::<32>();
+        assert_eq!(precompile_call(7, &[g_bytes.clone(), two.to_vec()].concat()), Ok(double));
+        assert_eq!(precompile_call(6, &g_bytes), Ok(g_bytes.clone()));
+        assert_eq!(precompile_call(7, &g_bytes), Ok(alloc::vec![0u8; 64]));
+
+        // r · G is infinity: the scalar is not reduced first
+        let order = Fr::MODULUS.to_bytes_be();
+        assert_eq!(precompile_call(7, &[g_bytes.clone(), order].concat()), Ok(alloc::vec![0u8; 64]));
+
+        // e(G, H) · e(-G, H) = 1, in the EIP-197 coordinate order
+        let h = G2Affine::generator();
+        let (x, y) = h.xy().unwrap();
+        let h_bytes = [x.c1, x.c0, y.c1, y.c0]
+            .iter()
+            .flat_map(|c| c.into_bigint().to_bytes_be())
+            .collect::<Vec<_>>();
+        let minus_g = encode_g1(&(-G1Projective::from(g)).into_affine());
+        let one = U256::from(1).to_be_bytes::<32>().to_vec();
+        let zero = alloc::vec![0u8; 32];
+        let balanced = [g_bytes.clone(), h_bytes.clone(), minus_g, h_bytes.clone()].concat();
+        assert_eq!(precompile_call(8, &balanced), Ok(one.clone()));
+        assert_eq!(precompile_call(8, &balanced[..192]), Ok(zero));
+        assert_eq!(precompile_call(8, &[]), Ok(one));
+
+        // Malformed inputs make the call fail
+        assert!(precompile_call(8, &balanced[..191]).is_err());
+        let mut off_curve = g_bytes.clone();
+        off_curve[63] ^= 1;
+        assert!(precompile_call(6, &[off_curve, g_bytes.clone()].concat()).is_err());
+        let mut non_canonical = g_bytes;
+        non_canonical[..32].copy_from_slice(&Fq::MODULUS.to_bytes_be());
+        assert!(precompile_call(7, &non_canonical).is_err());
+        let mut swapped = balanced.clone();
+        swapped[64..128].rotate_left(32);
+        assert!(precompile_call(8, &swapped).is_err());
+    }
+}
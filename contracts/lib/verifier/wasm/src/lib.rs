@@ -0,0 +1,225 @@
+//! IN-BROWSER PRE-VERIFICATION - wasm-bindgen bindings over the verifier core
+//!
+//! Runs the contract's own parsing and pairing check on snarkjs objects, so
+//! the portal can reject a proof before the user signs a transaction that
+//! was always going to fail.
+//!
+//! ```js
+//! import init, { verifyProof, proofToCalldata } from "arbshield-verifier-wasm";
+//! await init();
+//! const report = verifyProof(vkey, proof, publicSignals);
+//! if (!report.valid) throw new Error(`${report.kind}: ${report.message}`);
+//! const proofBytes = proofToCalldata(proof); // Uint8Array(256)
+//! ```
+
+use arbshield_verifier_core::{
+    snarkjs::{parse_public_signals, ProofJson, SnarkjsError, VerificationKeyJson},
+    verify_groth16,
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Outcome of a pre-verification, returned to JS as a plain object
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub valid: bool,
+    pub kind: DiagnosticKind,
+    /// snarkjs field at fault, e.g. `pi_b`, `IC[2]`, `publicSignals[0]`
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+    Valid,
+    /// Object does not have the snarkjs shape
+    InvalidJson,
+    /// Protocol or curve the contract cannot verify
+    Unsupported,
+    /// Coordinate or signal that is not a canonical field element
+    BadEncoding,
+    OffCurve,
+    NotInSubgroup,
+    /// Public signal count does not match the key's IC length
+    WrongInputCount,
+    /// Well-formed proof that does not satisfy the pairing equation
+    PairingFailed,
+}
+
+impl Diagnostic {
+    fn valid() -> Self {
+        Self {
+            valid: true,
+            kind: DiagnosticKind::Valid,
+            field: None,
+            message: "proof is valid".to_string(),
+        }
+    }
+
+    fn failure(kind: DiagnosticKind, field: Option<String>, message: String) -> Self {
+        Self { valid: false, kind, field, message }
+    }
+}
+
+impl From<SnarkjsError> for Diagnostic {
+    fn from(err: SnarkjsError) -> Self {
+        let message = err.to_string();
+        let (kind, field) = match err {
+            SnarkjsError::Json(_) => (DiagnosticKind::InvalidJson, None),
+            SnarkjsError::Unsupported(_) => (DiagnosticKind::Unsupported, None),
+            SnarkjsError::Encoding(field) => (DiagnosticKind::BadEncoding, Some(field)),
+            SnarkjsError::OffCurve(field) => (DiagnosticKind::OffCurve, Some(field)),
+            SnarkjsError::NotInSubgroup(field) => (DiagnosticKind::NotInSubgroup, Some(field)),
+        };
+        Self::failure(kind, field, message)
+    }
+}
+
+/// Check a proof the way the contract would, stopping at the first problem
+pub fn diagnose(
+    vk: &VerificationKeyJson,
+    proof: &ProofJson,
+    public_signals: &[String],
+) -> Diagnostic {
+    let vk = match vk.to_verifying_key() {
+        Ok(vk) => vk,
+        Err(err) => return err.into(),
+    };
+    let proof = match proof.to_proof() {
+        Ok(proof) => proof,
+        Err(err) => return err.into(),
+    };
+    let inputs = match parse_public_signals(public_signals) {
+        Ok(inputs) => inputs,
+        Err(err) => return err.into(),
+    };
+
+    if inputs.len() + 1 != vk.ic.len() {
+        return Diagnostic::failure(
+            DiagnosticKind::WrongInputCount,
+            Some("publicSignals".to_string()),
+            format!("expected {} public signals, got {}", vk.ic.len() - 1, inputs.len()),
+        );
+    }
+
+    match verify_groth16(&proof, &inputs, &vk) {
+        Ok(true) => Diagnostic::valid(),
+        Ok(false) => Diagnostic::failure(
+            DiagnosticKind::PairingFailed,
+            None,
+            "pairing check failed: proof does not match the key and public signals".to_string(),
+        ),
+        Err(err) => Diagnostic::failure(
+            DiagnosticKind::WrongInputCount,
+            Some("publicSignals".to_string()),
+            String::from_utf8_lossy(&err).into_owned(),
+        ),
+    }
+}
+
+fn from_js<T: serde::de::DeserializeOwned>(value: JsValue, what: &str) -> Result<T, Diagnostic> {
+    serde_wasm_bindgen::from_value(value).map_err(|err| {
+        Diagnostic::failure(DiagnosticKind::InvalidJson, None, format!("{what}: {err}"))
+    })
+}
+
+/// Pre-verify a snarkjs proof: `verifyProof(vkey, proof, publicSignals)`
+///
+/// Never throws for bad input; returns `{ valid, kind, field, message }`
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(vk: JsValue, proof: JsValue, public_signals: JsValue) -> JsValue {
+    let diagnostic = (|| {
+        let vk: VerificationKeyJson = from_js(vk, "verification key")?;
+        let proof: ProofJson = from_js(proof, "proof")?;
+        let signals: Vec<String> = from_js(public_signals, "public signals")?;
+        Ok::<_, Diagnostic>(diagnose(&vk, &proof, &signals))
+    })()
+    .unwrap_or_else(|diagnostic| diagnostic);
+
+    serde_wasm_bindgen::to_value(&diagnostic).expect("diagnostics always serialize")
+}
+
+/// Encode a snarkjs proof as the 256-byte `proof_bytes` argument of `verify`
+///
+/// Throws if the proof does not decode to valid curve points
+#[wasm_bindgen(js_name = proofToCalldata)]
+pub fn proof_to_calldata(proof: JsValue) -> Result<Vec<u8>, JsError> {
+    let proof: ProofJson =
+        serde_wasm_bindgen::from_value(proof).map_err(|err| JsError::new(&format!("proof: {err}")))?;
+    proof.to_proof().map_err(|err| JsError::new(&err.to_string()))?;
+    let bytes = proof.to_uncompressed_bytes().map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROOF_JSON: &str = include_str!("../../core/fixtures/multiplier/proof.json");
+    const VK_JSON: &str = include_str!("../../core/fixtures/multiplier/verification_key.json");
+
+    fn fixtures() -> (VerificationKeyJson, ProofJson, Vec<String>) {
+        (
+            VerificationKeyJson::from_json(VK_JSON).unwrap(),
+            ProofJson::from_json(PROOF_JSON).unwrap(),
+            vec!["15".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_diagnose_valid_proof() {
+        let (vk, proof, signals) = fixtures();
+        let diagnostic = diagnose(&vk, &proof, &signals);
+        assert!(diagnostic.valid);
+        assert_eq!(diagnostic.kind, DiagnosticKind::Valid);
+    }
+
+    #[test]
+    fn test_diagnose_failures() {
+        let (vk, proof, signals) = fixtures();
+        let kind_and_field = |d: Diagnostic| {
+            assert!(!d.valid);
+            (d.kind, d.field)
+        };
+
+        let mut bad = proof.clone();
+        bad.pi_a[0] = "not a number".to_string();
+        assert_eq!(
+            kind_and_field(diagnose(&vk, &bad, &signals)),
+            (DiagnosticKind::BadEncoding, Some("pi_a".to_string()))
+        );
+
+        let mut bad = proof.clone();
+        bad.pi_c[1] = "1".to_string();
+        assert_eq!(
+            kind_and_field(diagnose(&vk, &bad, &signals)),
+            (DiagnosticKind::OffCurve, Some("pi_c".to_string()))
+        );
+
+        let mut bad_vk = vk.clone();
+        bad_vk.ic[1][0] = "2".to_string();
+        assert_eq!(
+            kind_and_field(diagnose(&bad_vk, &proof, &signals)),
+            (DiagnosticKind::OffCurve, Some("IC[1]".to_string()))
+        );
+
+        assert_eq!(
+            kind_and_field(diagnose(&vk, &proof, &["15".to_string(), "1".to_string()])),
+            (DiagnosticKind::WrongInputCount, Some("publicSignals".to_string()))
+        );
+
+        assert_eq!(
+            kind_and_field(diagnose(&vk, &proof, &["16".to_string()])),
+            (DiagnosticKind::PairingFailed, None)
+        );
+
+        let mut bad = proof;
+        bad.curve = "bls12-381".to_string();
+        assert_eq!(
+            kind_and_field(diagnose(&vk, &bad, &signals)),
+            (DiagnosticKind::Unsupported, None)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+import {Script} from "forge-std/Script.sol";
+import {console} from "forge-std/console.sol";
+import {ComplianceRegistry} from "../src/ComplianceRegistry.sol";
+
+/// Make the Stylus verifier the only VERIFIER_ROLE holder on a deployed registry,
+/// so records can only be written by `verify_and_record` after a pairing check.
+/// Run after `set_compliance_registry` on the Stylus contract.
+///
+/// REGISTRY=0x... STYLUS_VERIFIER=0x... ZK_VERIFIER=0x... \
+///     forge script script/HandOverVerifierRole.s.sol --broadcast
+contract HandOverVerifierRole is Script {
+    function run() external {
+        ComplianceRegistry registry = ComplianceRegistry(vm.envAddress("REGISTRY"));
+        address stylusVerifier = vm.envAddress("STYLUS_VERIFIER");
+        // Holders the deploy scripts granted the role to
+        address zkVerifier = vm.envAddress("ZK_VERIFIER");
+        bytes32 role = registry.VERIFIER_ROLE();
+
+        vm.startBroadcast();
+        registry.grantRole(role, stylusVerifier);
+        registry.revokeRole(role, zkVerifier);
+        registry.revokeRole(role, msg.sender);
+        vm.stopBroadcast();
+
+        require(registry.hasRole(role, stylusVerifier), "Stylus verifier lacks VERIFIER_ROLE");
+        require(!registry.hasRole(role, zkVerifier), "ZKVerifier still holds VERIFIER_ROLE");
+        require(!registry.hasRole(role, msg.sender), "Deployer still holds VERIFIER_ROLE");
+        console.log("VERIFIER_ROLE held only by:", stylusVerifier);
+    }
+}
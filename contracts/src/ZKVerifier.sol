@@ -3,13 +3,25 @@ pragma solidity ^0.8.28;
 
 import "@openzeppelin/contracts/access/Ownable.sol";
 
+/// @notice The parts of the Stylus verifier this wrapper uses, both served by its
+///         `ComplianceBridge` program (contracts/lib/verifier/src/bridge.rs)
+interface IStylusVerifier {
+    /// @dev `proof` is the 256-byte uncompressed proof followed by its packed public inputs
+    function verifyProof(bytes calldata proof, string calldata attributeType) external returns (bool);
+
+    function getRecord(
+        address user,
+        string calldata attributeType
+    ) external view returns (bool verified, uint256 verifiedAt, uint256 expiresAt, bytes32 proofHash);
+}
+
 /**
  * @title ZKVerifier
  * @notice Wrapper contract for Stylus Rust ZK proof verifier
  * @dev Delegates verification to Stylus WASM contract for 10x gas efficiency
  */
 contract ZKVerifier is Ownable {
-    /// @notice Address of the Stylus Rust verifier contract
+    /// @notice Address of the Stylus `ComplianceBridge` program
     address public stylusVerifier;
 
     /// @notice Mapping of verified proofs
@@ -34,7 +46,7 @@ contract ZKVerifier is Ownable {
 
     /**
      * @notice Verify a ZK proof using Stylus Rust verifier
-     * @param proof The ZK proof bytes
+     * @param proof The 256-byte Groth16 proof followed by its packed public inputs
      * @param attributeType The compliance attribute being proven
      * @return success Whether the proof was verified
      */
@@ -44,12 +56,20 @@ contract ZKVerifier is Ownable {
     ) external returns (bool success) {
         uint256 gasStart = gasleft();
 
-        // For demo: Mock verification (validates proof structure)
-        // In production: This calls the real Stylus Rust verifier
-        bool verified = _mockVerifyProof(proof);
-        
+        bool verified = IStylusVerifier(stylusVerifier).verifyProof(proof, attributeType);
         require(verified, "Proof verification failed");
 
+        // The Stylus programs record the subject named in the public inputs;
+        // only credit the caller if that subject is them
+        (, uint256 verifiedAt, , bytes32 recordedHash) = IStylusVerifier(stylusVerifier).getRecord(
+            msg.sender,
+            attributeType
+        );
+        require(
+            verifiedAt == block.timestamp && recordedHash == keccak256(proof[:256]),
+            "Proof is not for caller"
+        );
+
         bytes32 proofHash = keccak256(proof);
         verifiedProofs[proofHash] = true;
         userCompliance[msg.sender][attributeType] = true;
@@ -61,28 +81,6 @@ contract ZKVerifier is Ownable {
         return true;
     }
 
-    /**
-     * @notice Mock proof verification (for demo purposes)
-     * @dev Validates proof structure - replace with Stylus call in production
-     * @param proof The ZK proof bytes
-     * @return valid Whether the proof structure is valid
-     */
-    function _mockVerifyProof(bytes calldata proof) internal pure returns (bool) {
-        // Validate proof length (Groth16 proof should be ~256 bytes)
-        if (proof.length < 256) {
-            return false;
-        }
-
-        // Validate proof structure (simple checksum validation)
-        uint256 checksum = 0;
-        for (uint256 i = 0; i < proof.length && i < 32; i++) {
-            checksum += uint8(proof[i]);
-        }
-
-        // Mock validation: proof is valid if checksum is non-zero
-        return checksum > 0;
-    }
-
     /**
      * @notice Check if a user has verified a specific compliance attribute
      * @param user The user address